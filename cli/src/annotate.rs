@@ -0,0 +1,85 @@
+use crate::{config, context, interrupt};
+use std::io;
+
+/// Reads text from stdin, and writes it to stdout with every calculation
+/// between the configured markers (e.g. `{{1 + 1}}`) replaced by its result.
+/// All calculations share a context, so later ones can use variables that
+/// were assigned earlier.
+pub fn run(context: &context::Context<'_>, config: &config::Config) -> i32 {
+    let mut text = String::new();
+    if let Err(e) = io::Read::read_to_string(&mut io::stdin(), &mut text) {
+        eprintln!("Error: {}", e);
+        return 1;
+    }
+    let mut success = true;
+    let (open, close) = &config.annotate_markers;
+    let annotated = annotate(&text, open, close, |expr| {
+        match context.eval(expr, true, &interrupt::Never::default()) {
+            Ok(res) => Some(res.get_main_result().to_string()),
+            Err(msg) => {
+                eprintln!("Error: {}: {}", expr.trim(), msg);
+                success = false;
+                None
+            }
+        }
+    });
+    print!("{}", annotated);
+    i32::from(!success)
+}
+
+/// Replaces every expression between `open` and `close` with the result of
+/// `eval`. Expressions that fail to evaluate are kept unchanged.
+fn annotate(
+    text: &str,
+    open: &str,
+    close: &str,
+    mut eval: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut result = String::new();
+    let mut remaining = text;
+    while let Some((before, after_open)) = remaining.split_once(open) {
+        // text after an opening marker without a closing one is kept as is
+        if let Some((expr, after_close)) = after_open.split_once(close) {
+            result.push_str(before);
+            if let Some(value) = eval(expr) {
+                result.push_str(&value);
+            } else {
+                result.push_str(open);
+                result.push_str(expr);
+                result.push_str(close);
+            }
+            remaining = after_close;
+        } else {
+            break;
+        }
+    }
+    result.push_str(remaining);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::annotate;
+
+    #[test]
+    fn annotate_text() {
+        let eval = |expr: &str| match expr.trim() {
+            "1 + 1" => Some("2".to_string()),
+            "x = 5" => Some("5".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            annotate("a {{1 + 1}} b {{ x = 5 }}.", "{{", "}}", eval),
+            "a 2 b 5."
+        );
+        assert_eq!(
+            annotate("{{invalid}} {{1 + 1", "{{", "}}", eval),
+            "{{invalid}} {{1 + 1"
+        );
+        assert_eq!(annotate("[1 + 1] text", "[", "]", eval), "2 text");
+        assert_eq!(
+            annotate("no calculations", "{{", "}}", eval),
+            "no calculations"
+        );
+    }
+}
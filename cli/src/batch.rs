@@ -0,0 +1,151 @@
+use crate::{clipboard, context, interrupt, json, notify, OutputFormat};
+use std::io::{self, BufRead, Write};
+
+/// Evaluates every line from stdin separately, e.g. when fend is used in a
+/// shell pipeline, and prints one result per line. All lines share a
+/// context, so later lines can use variables that were assigned earlier.
+pub fn run(
+    format: OutputFormat,
+    echo: bool,
+    send_notification: bool,
+    copy: bool,
+    context: &context::Context<'_>,
+) -> i32 {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let (exit_code, last_message, last_result) =
+        match evaluate_lines(stdin.lock(), &mut stdout.lock(), format, echo, context) {
+            Ok(res) => res,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return 1;
+            }
+        };
+    if send_notification {
+        if let Some(message) = last_message.filter(|message| !message.is_empty()) {
+            if let Err(e) = notify::send(&message) {
+                eprintln!("Warning: failed to send notification: {}", e);
+            }
+        }
+    }
+    if copy {
+        if let Some(result) = last_result {
+            if let Err(e) = clipboard::copy(&result) {
+                eprintln!("Warning: failed to copy to the clipboard: {}", e);
+            }
+        }
+    }
+    exit_code
+}
+
+/// Writes the result of each line of `input` to `output`. In the plain
+/// format, empty lines, comments and errors result in an empty line, so that
+/// results stay on the same line number as their input, and with `echo`
+/// every result is preceded by its input and a tab. In the JSON format, each
+/// calculation results in one JSON object. Returns the exit code for the
+/// first line that failed to evaluate (or 0), the last result or error
+/// message, and the last successful result.
+fn evaluate_lines(
+    input: impl BufRead,
+    output: &mut impl Write,
+    format: OutputFormat,
+    echo: bool,
+    context: &context::Context<'_>,
+) -> io::Result<(i32, Option<String>, Option<String>)> {
+    let mut exit_code = 0;
+    let mut last_message = None;
+    let mut last_result = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let statement = line.trim();
+        if statement.is_empty() || statement.starts_with('#') {
+            if format == OutputFormat::Plain {
+                if echo {
+                    writeln!(output, "{}\t", line)?;
+                } else {
+                    writeln!(output)?;
+                }
+            }
+            continue;
+        }
+        let res = context.eval(statement, true, &interrupt::Never::default());
+        if res.is_err() && exit_code == 0 {
+            exit_code = crate::error_exit_code(context.get_error_kind());
+        }
+        last_message = Some(match &res {
+            Ok(res) if res.is_unit_type() => String::new(),
+            Ok(res) => res.get_main_result().to_string(),
+            Err(msg) => format!("Error: {}", msg),
+        });
+        match &res {
+            Ok(res) if !res.is_unit_type() => {
+                last_result = Some(res.get_main_result().to_string());
+            }
+            _ => (),
+        }
+        let result = match (&res, format) {
+            (_, OutputFormat::Json) => {
+                json::evaluation(statement, &res, context.locate_syntax_error(statement))
+            }
+            (Ok(res), OutputFormat::Plain) if res.is_unit_type() => String::new(),
+            (Ok(res), OutputFormat::Plain) => res.get_main_result().to_string(),
+            (Err(msg), OutputFormat::Plain) => {
+                eprintln!("Error: line {}: {}", i + 1, msg);
+                String::new()
+            }
+        };
+        if echo && format == OutputFormat::Plain {
+            writeln!(output, "{}\t{}", line, result)?;
+        } else {
+            writeln!(output, "{}", result)?;
+        }
+        // flush each line so that results appear as soon as the input does
+        output.flush()?;
+    }
+    Ok((exit_code, last_message, last_result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evaluate_lines;
+    use crate::{context::Context, OutputFormat};
+    use std::cell::RefCell;
+
+    fn run(input: &str, format: OutputFormat, echo: bool) -> (String, i32, Option<String>) {
+        let core_context = RefCell::new(fend_core::Context::new());
+        let mut output = vec![];
+        let (exit_code, _, last_result) = evaluate_lines(
+            input.as_bytes(),
+            &mut output,
+            format,
+            echo,
+            &Context::new(&core_context),
+        )
+        .unwrap();
+        (String::from_utf8(output).unwrap(), exit_code, last_result)
+    }
+
+    #[test]
+    fn evaluate_each_line() {
+        let input = "a = 2 m\n\n# comment\n  a * 3\nbogus\n";
+        assert_eq!(
+            run(input, OutputFormat::Plain, false),
+            ("2 m\n\n\n6 m\n\n".to_string(), 1, Some("6 m".to_string()))
+        );
+        assert_eq!(
+            run(input, OutputFormat::Plain, true).0,
+            "a = 2 m\t2 m\n\t\n# comment\t\n  a * 3\t6 m\nbogus\t\n"
+        );
+        assert_eq!(
+            run("1 + 1\n\nx", OutputFormat::Json, true).0,
+            r#"{"input": "1 + 1", "result": "2", "spans": [{"kind": "number", "text": "2"}], "error": null}
+{"input": "x", "result": null, "spans": [], "error": {"message": "unknown identifier 'x'", "range": null}}
+"#
+        );
+        assert_eq!(run("1 +* 2\nbogus", OutputFormat::Plain, false).1, 2);
+        assert_eq!(
+            run("", OutputFormat::Plain, false),
+            (String::new(), 0, None)
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::file_paths;
+use std::fs;
+
+// implements the `:bookmark <name>` and `:bookmarks` commands, and
+// re-evaluating a bookmarked calculation with `@<name>`. Bookmarks are
+// stored in the state directory, next to the history file.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub name: String,
+    pub input: String,
+    pub result: String,
+}
+
+/// Bookmark names may only contain letters, digits and underscores,
+/// so that `@name` can't be confused with an ordinary calculation
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// each bookmark is stored on its own line as `name\tinput\tresult`
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::new();
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(ch) => result.push(ch),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn parse_line(line: &str) -> Option<Bookmark> {
+    let mut fields = line.split('\t');
+    let name = fields.next()?;
+    let input = fields.next()?;
+    let result = fields.next()?;
+    if fields.next().is_some() || !is_valid_name(name) {
+        return None;
+    }
+    Some(Bookmark {
+        name: name.to_string(),
+        input: unescape(input),
+        result: unescape(result),
+    })
+}
+
+fn format_line(bookmark: &Bookmark) -> String {
+    format!(
+        "{}\t{}\t{}",
+        bookmark.name,
+        escape(&bookmark.input),
+        escape(&bookmark.result)
+    )
+}
+
+/// Reads all bookmarks, ignoring any lines that can't be parsed
+pub fn read() -> Vec<Bookmark> {
+    let contents = match file_paths::get_bookmarks_file_location() {
+        Some(path) => fs::read_to_string(path).unwrap_or_default(),
+        None => return vec![],
+    };
+    contents.lines().filter_map(parse_line).collect()
+}
+
+fn write(bookmarks: &[Bookmark]) -> Result<(), String> {
+    let path = file_paths::get_bookmarks_file_location()
+        .ok_or_else(|| "failed to get bookmarks file location".to_string())?;
+    let mut contents = String::new();
+    for bookmark in bookmarks {
+        contents.push_str(&format_line(bookmark));
+        contents.push('\n');
+    }
+    fs::write(path, contents).map_err(|e| format!("failed to save bookmarks: {}", e))
+}
+
+/// Saves a bookmark, replacing any existing bookmark with the same name
+pub fn save(bookmark: Bookmark) -> Result<(), String> {
+    if !is_valid_name(&bookmark.name) {
+        return Err(format!(
+            "invalid bookmark name `{}`: only letters, digits and underscores are allowed",
+            bookmark.name
+        ));
+    }
+    let mut bookmarks = read();
+    match bookmarks.iter_mut().find(|b| b.name == bookmark.name) {
+        Some(existing) => *existing = bookmark,
+        None => bookmarks.push(bookmark),
+    }
+    write(&bookmarks)
+}
+
+/// Implements `:bookmark <name>`, which saves the last successful
+/// calculation
+pub fn save_last(name: &str, last_calculation: Option<&(String, String)>) {
+    let res = match last_calculation {
+        Some((input, result)) => save(Bookmark {
+            name: name.to_string(),
+            input: input.clone(),
+            result: result.clone(),
+        }),
+        None => Err("there is no calculation to bookmark yet".to_string()),
+    };
+    match res {
+        Ok(()) => println!("Saved as @{}", name),
+        Err(msg) => eprintln!("Error: {}", msg),
+    }
+}
+
+pub fn get(name: &str) -> Option<Bookmark> {
+    read().into_iter().find(|b| b.name == name)
+}
+
+/// If the line refers to a bookmark like `@fuel_calc`, returns the
+/// bookmarked input so it can be evaluated again
+pub fn resolve(line: &str) -> Result<Option<String>, String> {
+    let name = match line.trim().strip_prefix('@') {
+        Some(name) if is_valid_name(name) => name,
+        _ => return Ok(None),
+    };
+    match get(name) {
+        Some(bookmark) => Ok(Some(bookmark.input)),
+        None => Err(format!("unknown bookmark `@{}`", name)),
+    }
+}
+
+pub fn print_list() {
+    let bookmarks = read();
+    if bookmarks.is_empty() {
+        println!("No bookmarks yet. Save the last calculation with `:bookmark <name>`.");
+        return;
+    }
+    for bookmark in bookmarks {
+        println!(
+            "@{}: {} = {}",
+            bookmark.name, bookmark.input, bookmark.result
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bookmark = Bookmark {
+            name: "fuel_calc".to_string(),
+            input: "40 L / 500 km to L/100km".to_string(),
+            result: "a\tb\\n\nc".to_string(),
+        };
+        assert_eq!(parse_line(&format_line(&bookmark)), Some(bookmark));
+    }
+
+    #[test]
+    fn names() {
+        assert!(is_valid_name("fuel_calc"));
+        assert!(is_valid_name("x2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("a b"));
+        assert!(!is_valid_name("a+b"));
+        assert_eq!(parse_line("a b\t1\t1"), None);
+        assert_eq!(parse_line("ab\t1"), None);
+    }
+}
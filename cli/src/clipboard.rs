@@ -0,0 +1,72 @@
+use std::{io, io::Write, process};
+
+// The text is always written to the command's stdin, so it never needs
+// to be escaped.
+
+#[cfg(target_os = "macos")]
+fn command() -> (&'static str, &'static [&'static str]) {
+    ("pbcopy", &[])
+}
+
+#[cfg(windows)]
+fn command() -> (&'static str, &'static [&'static str]) {
+    // `clip.exe` doesn't handle non-ASCII text correctly, so use PowerShell
+    (
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+        ],
+    )
+}
+
+// Wayland or X11 (Linux, BSD etc.)
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command() -> (&'static str, &'static [&'static str]) {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("wl-copy", &[])
+    } else {
+        ("xclip", &["-selection", "clipboard"])
+    }
+}
+
+/// Places the given text on the system clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    let (program, args) = command();
+    let mut child = process::Command::new(program)
+        .args(args)
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("could not run `{}`: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // dropping stdin closes it, so the command knows the text is complete
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("clipboard command failed ({})", status),
+        ))
+    }
+}
+
+/// Copies the result of the last calculation in the interactive prompt
+pub fn copy_last(last_calculation: Option<&(String, String)>) {
+    let res = match last_calculation {
+        Some((_, result)) => copy(result)
+            .map(|()| result)
+            .map_err(|e| format!("failed to copy to the clipboard: {}", e)),
+        None => Err("there is no result to copy yet".to_string()),
+    };
+    match res {
+        Ok(result) => println!("Copied {} to the clipboard", result),
+        Err(msg) => eprintln!("Error: {}", msg),
+    }
+}
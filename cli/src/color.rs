@@ -1,5 +1,6 @@
 mod base;
 mod output_colors;
 mod style;
+mod theme;
 
 pub use output_colors::OutputColors;
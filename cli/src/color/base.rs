@@ -10,6 +10,10 @@ pub enum Base {
     Purple,
     Cyan,
     White,
+    // one of the 256 colors of most terminals, e.g. `foreground = 208`
+    Fixed(u8),
+    // a true color like `foreground = '#ff8700'`
+    Rgb(u8, u8, u8),
     Unknown(String),
 }
 
@@ -19,8 +23,24 @@ impl<'de> serde::de::Visitor<'de> for BaseVisitor {
     type Value = Base;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter
-            .write_str("`black`, `red`, `green`, `yellow`, `blue`, `purple`, `cyan` or `white`")
+        formatter.write_str(
+            "`black`, `red`, `green`, `yellow`, `blue`, `purple`, `cyan`, `white`, \
+            a number from 0 to 255 or a hex color like `#ff8700`",
+        )
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        match u8::try_from(v) {
+            Ok(v) => Ok(Base::Fixed(v)),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        match u8::try_from(v) {
+            Ok(v) => Ok(Base::Fixed(v)),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self)),
+        }
     }
 
     fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
@@ -33,14 +53,27 @@ impl<'de> serde::de::Visitor<'de> for BaseVisitor {
             "purple" => Base::Purple,
             "cyan" => Base::Cyan,
             "white" => Base::White,
+            hex if hex.starts_with('#') => {
+                parse_hex(hex).unwrap_or_else(|| Base::Unknown(hex.to_string()))
+            }
             unknown_color_name => Base::Unknown(unknown_color_name.to_string()),
         })
     }
 }
 
+// parses a color like `#ff8700`
+fn parse_hex(hex: &str) -> Option<Base> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 || !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let component = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(Base::Rgb(component(0)?, component(2)?, component(4)?))
+}
+
 impl<'de> serde::Deserialize<'de> for Base {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_str(BaseVisitor)
+        deserializer.deserialize_any(BaseVisitor)
     }
 }
 
@@ -54,6 +87,8 @@ impl Base {
             Self::Blue => ansi_term::Color::Blue,
             Self::Purple => ansi_term::Color::Purple,
             Self::Cyan => ansi_term::Color::Cyan,
+            Self::Fixed(n) => ansi_term::Color::Fixed(*n),
+            Self::Rgb(r, g, b) => ansi_term::Color::RGB(*r, *g, *b),
             Self::White | Self::Unknown(_) => ansi_term::Color::White,
         }
     }
@@ -64,3 +99,28 @@ impl Base {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Base;
+    use std::collections::HashMap;
+
+    fn parse(value: &str) -> Result<Base, toml::de::Error> {
+        toml::de::from_str::<HashMap<String, Base>>(&format!("color = {}", value))
+            .map(|mut colors| colors.remove("color").unwrap())
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse("'blue'").unwrap(), Base::Blue);
+        assert_eq!(parse("208").unwrap(), Base::Fixed(208));
+        assert_eq!(parse("'#FF8700'").unwrap(), Base::Rgb(255, 135, 0));
+        assert_eq!(
+            parse("'#ff87'").unwrap(),
+            Base::Unknown("#ff87".to_string())
+        );
+        assert_eq!(parse("'pink'").unwrap(), Base::Unknown("pink".to_string()));
+        assert!(parse("256").is_err());
+        assert!(parse("-1").is_err());
+    }
+}
@@ -1,28 +1,69 @@
-use super::{base::Base, style::Color};
-use std::collections;
+use super::{style::Color, theme::Theme};
+use std::{collections, fmt};
+
+// the names of all color settings, one for each kind of span plus `error`
+const STYLE_NAMES: &[&str] = &[
+    "number",
+    "string",
+    "identifier",
+    "keyword",
+    "built-in-function",
+    "date",
+    "boolean",
+    "matrix",
+    "other",
+    "secondary",
+    "error",
+];
 
 #[derive(Debug, Default)]
 pub struct OutputColors {
+    theme: Theme,
     styles: collections::HashMap<String, Color>,
 }
 
 impl<'de> serde::Deserialize<'de> for OutputColors {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        Ok(OutputColors {
-            styles: collections::HashMap::deserialize(deserializer)?,
-        })
+        struct OutputColorsVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for OutputColorsVisitor {
+            type Value = OutputColors;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a color theme and colors for each kind of output")
+            }
+
+            fn visit_map<V: serde::de::MapAccess<'de>>(
+                self,
+                mut map: V,
+            ) -> Result<OutputColors, V::Error> {
+                let mut result = OutputColors::default();
+                let mut seen_theme = false;
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "theme" {
+                        if seen_theme {
+                            return Err(serde::de::Error::duplicate_field("theme"));
+                        }
+                        result.theme = map.next_value()?;
+                        seen_theme = true;
+                    } else {
+                        let style = map.next_value()?;
+                        result.styles.insert(key, style);
+                    }
+                }
+                Ok(result)
+            }
+        }
+
+        deserializer.deserialize_map(OutputColorsVisitor)
     }
 }
 
 impl PartialEq for OutputColors {
     fn eq(&self, other: &Self) -> bool {
-        self.get_style("number") == other.get_style("number")
-            && self.get_style("string") == other.get_style("string")
-            && self.get_style("identifier") == other.get_style("identifier")
-            && self.get_style("keyword") == other.get_style("keyword")
-            && self.get_style("built-in-function") == other.get_style("built-in-function")
-            && self.get_style("date") == other.get_style("date")
-            && self.get_style("other") == other.get_style("other")
+        STYLE_NAMES
+            .iter()
+            .all(|name| self.get_style(name) == other.get_style(name))
     }
 }
 
@@ -30,31 +71,16 @@ impl Eq for OutputColors {}
 
 impl OutputColors {
     fn get_style(&self, name: &str) -> Color {
-        self.styles.get(name).cloned().unwrap_or_else(|| {
-            match name {
-                "number" | "date" | "string" | "other" => Color::default(),
-                "identifier" => Color::new(Base::White),
-                "keyword" | "built-in-function" => Color::bold(Base::Blue),
-                _ => {
-                    // this should never happen
-                    Color::default()
-                }
-            }
-        })
+        self.styles
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| self.theme.get_style(name))
     }
 
     pub fn print_warnings_about_unknown_keys(&self) {
+        self.theme.warn_about_unknown_themes();
         for (key, style) in &self.styles {
-            if !matches!(
-                key.as_str(),
-                "number"
-                    | "string"
-                    | "identifier"
-                    | "keyword"
-                    | "built-in-function"
-                    | "date"
-                    | "other"
-            ) {
+            if !STYLE_NAMES.contains(&key.as_str()) {
                 eprintln!(
                     "Warning: ignoring unknown configuration setting `colors.{}`",
                     key
@@ -72,9 +98,46 @@ impl OutputColors {
             SpanKind::String => self.get_style("string").to_ansi(),
             SpanKind::Ident => self.get_style("identifier").to_ansi(),
             SpanKind::Keyword => self.get_style("keyword").to_ansi(),
-            SpanKind::BuiltInFunction => self.get_style("built_in_function").to_ansi(),
+            SpanKind::BuiltInFunction => self.get_style("built-in-function").to_ansi(),
             SpanKind::Date => self.get_style("date").to_ansi(),
+            SpanKind::Boolean => self.get_style("boolean").to_ansi(),
+            SpanKind::Matrix => self.get_style("matrix").to_ansi(),
+            SpanKind::Secondary => self.get_style("secondary").to_ansi(),
             _ => self.get_style("other").to_ansi(),
         }
     }
+
+    pub fn get_error_color(&self) -> ansi_term::Style {
+        self.get_style("error").to_ansi()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputColors;
+    use fend_core::SpanKind;
+
+    #[test]
+    fn themes_and_overrides() {
+        let colors: OutputColors = toml::de::from_str(
+            "theme = 'gruvbox'\nnumber = { foreground = '#ff8700', underline = true }",
+        )
+        .unwrap();
+        assert_eq!(
+            colors.get_color(SpanKind::Number),
+            ansi_term::Color::RGB(255, 135, 0).underline()
+        );
+        assert_eq!(
+            colors.get_color(SpanKind::BuiltInFunction),
+            ansi_term::Color::Fixed(108).bold()
+        );
+        assert_eq!(
+            colors.get_color(SpanKind::Whitespace),
+            ansi_term::Style::default()
+        );
+        assert_eq!(
+            OutputColors::default().get_color(SpanKind::Keyword),
+            ansi_term::Color::Blue.bold()
+        );
+    }
 }
@@ -6,6 +6,7 @@ pub struct Color {
     foreground: Option<Base>,
     underline: bool,
     bold: bool,
+    dim: bool,
     unknown_keys: Vec<String>,
 }
 
@@ -17,7 +18,9 @@ impl<'de> serde::Deserialize<'de> for Color {
             type Value = Color;
 
             fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("a color, with properties `foreground`, `underline` and `bold`")
+                formatter.write_str(
+                    "a color, with properties `foreground`, `underline`, `bold` and `dim`",
+                )
             }
 
             fn visit_map<V: serde::de::MapAccess<'de>>(
@@ -28,6 +31,7 @@ impl<'de> serde::Deserialize<'de> for Color {
                 let mut seen_foreground = false;
                 let mut seen_underline = false;
                 let mut seen_bold = false;
+                let mut seen_dim = false;
                 while let Some(key) = map.next_key()? {
                     match key {
                         "foreground" => {
@@ -51,6 +55,13 @@ impl<'de> serde::Deserialize<'de> for Color {
                             result.bold = map.next_value()?;
                             seen_bold = true;
                         }
+                        "dim" => {
+                            if seen_dim {
+                                return Err(serde::de::Error::duplicate_field("dim"));
+                            }
+                            result.dim = map.next_value()?;
+                            seen_dim = true;
+                        }
                         unknown_key => {
                             map.next_value::<toml::Value>()?;
                             result.unknown_keys.push(unknown_key.to_string());
@@ -61,7 +72,7 @@ impl<'de> serde::Deserialize<'de> for Color {
             }
         }
 
-        const FIELDS: &[&str] = &["foreground", "underline", "bold"];
+        const FIELDS: &[&str] = &["foreground", "underline", "bold", "dim"];
         deserializer.deserialize_struct("Color", FIELDS, ColorVisitor)
     }
 }
@@ -72,6 +83,7 @@ impl Color {
             foreground: Some(foreground),
             underline: false,
             bold: false,
+            dim: false,
             unknown_keys: vec![],
         }
     }
@@ -83,6 +95,20 @@ impl Color {
         }
     }
 
+    pub fn bold_text() -> Self {
+        Self {
+            bold: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn dim() -> Self {
+        Self {
+            dim: true,
+            ..Self::default()
+        }
+    }
+
     pub fn to_ansi(&self) -> ansi_term::Style {
         let mut style = ansi_term::Style::default();
         if let Some(foreground) = &self.foreground {
@@ -94,6 +120,9 @@ impl Color {
         if self.bold {
             style = style.bold();
         }
+        if self.dim {
+            style = style.dimmed();
+        }
         style
     }
 
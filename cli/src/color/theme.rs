@@ -0,0 +1,96 @@
+use super::{base::Base, style::Color};
+use std::fmt;
+
+// the built-in color themes, which can be chosen with e.g.
+// `theme = 'solarized'` in the `[colors]` section. Colors that are
+// set explicitly in the config file take precedence over the theme.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Theme {
+    Default,
+    Monochrome,
+    Solarized,
+    Gruvbox,
+    Unknown(String),
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Theme {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ThemeVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ThemeVisitor {
+            type Value = Theme;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("`default`, `monochrome`, `solarized` or `gruvbox`")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(match v {
+                    "default" => Theme::Default,
+                    "monochrome" => Theme::Monochrome,
+                    "solarized" => Theme::Solarized,
+                    "gruvbox" => Theme::Gruvbox,
+                    unknown_theme => Theme::Unknown(unknown_theme.to_string()),
+                })
+            }
+        }
+
+        deserializer.deserialize_str(ThemeVisitor)
+    }
+}
+
+impl Theme {
+    /// Returns the style of the given color setting, like `number`
+    pub fn get_style(&self, name: &str) -> Color {
+        match self {
+            Self::Default | Self::Unknown(_) => match name {
+                "identifier" => Color::new(Base::White),
+                "keyword" | "built-in-function" => Color::bold(Base::Blue),
+                "secondary" => Color::dim(),
+                "error" => Color::bold(Base::Red),
+                _ => Color::default(),
+            },
+            Self::Monochrome => match name {
+                "keyword" | "built-in-function" | "error" => Color::bold_text(),
+                "secondary" => Color::dim(),
+                _ => Color::default(),
+            },
+            // true colors from the Solarized palette
+            Self::Solarized => match name {
+                "number" => Color::new(Base::Rgb(0x2a, 0xa1, 0x98)),
+                "string" => Color::new(Base::Rgb(0x85, 0x99, 0x00)),
+                "identifier" => Color::new(Base::Rgb(0x83, 0x94, 0x96)),
+                "keyword" | "built-in-function" => Color::bold(Base::Rgb(0x26, 0x8b, 0xd2)),
+                "date" => Color::new(Base::Rgb(0xd3, 0x36, 0x82)),
+                "boolean" => Color::new(Base::Rgb(0xb5, 0x89, 0x00)),
+                "secondary" => Color::dim(),
+                "error" => Color::bold(Base::Rgb(0xdc, 0x32, 0x2f)),
+                _ => Color::default(),
+            },
+            // 256-color approximations of the Gruvbox palette
+            Self::Gruvbox => match name {
+                "number" => Color::new(Base::Fixed(175)),
+                "string" => Color::new(Base::Fixed(142)),
+                "identifier" => Color::new(Base::Fixed(223)),
+                "keyword" | "error" => Color::bold(Base::Fixed(167)),
+                "built-in-function" => Color::bold(Base::Fixed(108)),
+                "date" => Color::new(Base::Fixed(214)),
+                "boolean" => Color::new(Base::Fixed(208)),
+                "secondary" => Color::dim(),
+                _ => Color::default(),
+            },
+        }
+    }
+
+    pub fn warn_about_unknown_themes(&self) {
+        if let Self::Unknown(name) = self {
+            eprintln!("Warning: ignoring unknown color theme `{}`", name);
+        }
+    }
+}
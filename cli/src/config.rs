@@ -1,17 +1,70 @@
 use crate::color;
-use std::{env, fmt, fs, io};
+use std::{env, fmt, fs, io, time};
 
 #[derive(Debug, Eq, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     pub prompt: String,
+    // the prompt for lines that continue the previous one, or `None` to
+    // use dots that are as wide as `prompt`
+    pub continuation_prompt: Option<String>,
+    pub result_preview: ResultPreview,
     pub enable_colors: bool,
     pub coulomb_and_farad: bool,
+    pub dual_units: bool,
+    pub angle_unit: AngleUnit,
+    pub unit_exponent_style: fend_core::UnitExponentStyle,
+    pub significant_figures: bool,
+    pub precision: usize,
     pub colors: color::OutputColors,
     pub max_history_size: usize,
+    pub history_ignore_duplicates: bool,
+    pub save_history: bool,
+    pub screen_reader: bool,
+    pub units_file: Option<String>,
+    pub exchange_rate_max_age: u64,
+    // maximum time for each calculation, which can also be set with
+    // `--timeout`
+    pub timeout: Option<time::Duration>,
+    // approximate maximum size of any number during a calculation, in bytes
+    pub max_memory: Option<usize>,
+    pub annotate_markers: (String, String),
+    pub wrap_long_results: bool,
+    pub show_tips: bool,
+    pub persist_variables: bool,
+    // holidays for `is_holiday` and `add_workdays`, or `None` to use
+    // fend's defaults
+    pub holidays: Option<Vec<String>>,
+    // names and expressions from the `[aliases]` table, in the order in
+    // which they appear in the config file
+    pub aliases: Vec<(String, String)>,
+    // variables from `-D name=value` arguments, which can't be set in the
+    // config file
+    pub definitions: Vec<String>,
+    // whether `init.fend` is skipped because of the `--no-init` argument,
+    // which can't be set in the config file
+    pub skip_init_file: bool,
     unknown_settings: UnknownSettings,
     unknown_keys: Vec<String>,
 }
 
+// unit of angles without an explicit unit, e.g. in `sin 30`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AngleUnit {
+    Radians,
+    Degrees,
+}
+
+// where the result is previewed while typing a calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultPreview {
+    // dimmed, to the right of the cursor
+    Inline,
+    // on the line below the input
+    Below,
+    Never,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum UnknownSettings {
     Ignore,
@@ -19,6 +72,7 @@ enum UnknownSettings {
 }
 
 impl<'de> serde::Deserialize<'de> for Config {
+    #[allow(clippy::too_many_lines)]
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         struct ConfigVisitor;
 
@@ -35,10 +89,30 @@ impl<'de> serde::Deserialize<'de> for Config {
             ) -> Result<Config, V::Error> {
                 let mut result = Config::default();
                 let mut seen_prompt = false;
+                let mut seen_continuation_prompt = false;
+                let mut seen_result_preview = false;
                 let mut seen_enable_colors = false;
                 let mut seen_coulomb_farad = false;
+                let mut seen_dual_units = false;
+                let mut seen_angle_unit = false;
+                let mut seen_unit_exponent_style = false;
+                let mut seen_significant_figures = false;
+                let mut seen_precision = false;
                 let mut seen_colors = false;
                 let mut seen_max_hist_size = false;
+                let mut seen_history_ignore_duplicates = false;
+                let mut seen_save_history = false;
+                let mut seen_screen_reader = false;
+                let mut seen_units_file = false;
+                let mut seen_exchange_rate_max_age = false;
+                let mut seen_timeout = false;
+                let mut seen_max_memory = false;
+                let mut seen_annotate_markers = false;
+                let mut seen_wrap_long_results = false;
+                let mut seen_show_tips = false;
+                let mut seen_persist_variables = false;
+                let mut seen_holidays = false;
+                let mut seen_aliases = false;
                 while let Some(key) = map.next_key()? {
                     match key {
                         "prompt" => {
@@ -48,6 +122,33 @@ impl<'de> serde::Deserialize<'de> for Config {
                             result.prompt = map.next_value()?;
                             seen_prompt = true;
                         }
+                        "continuation-prompt" => {
+                            if seen_continuation_prompt {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "continuation-prompt",
+                                ));
+                            }
+                            result.continuation_prompt = Some(map.next_value()?);
+                            seen_continuation_prompt = true;
+                        }
+                        "result-preview" => {
+                            if seen_result_preview {
+                                return Err(serde::de::Error::duplicate_field("result-preview"));
+                            }
+                            let preview: &str = map.next_value()?;
+                            result.result_preview = match preview {
+                                "inline" => ResultPreview::Inline,
+                                "below" => ResultPreview::Below,
+                                "never" => ResultPreview::Never,
+                                v => {
+                                    return Err(serde::de::Error::invalid_value(
+                                        serde::de::Unexpected::Str(v),
+                                        &"`inline`, `below` or `never`",
+                                    ))
+                                }
+                            };
+                            seen_result_preview = true;
+                        }
                         "enable-colors" | "color" => {
                             if seen_enable_colors {
                                 return Err(serde::de::Error::duplicate_field("enable-colors"));
@@ -72,6 +173,66 @@ impl<'de> serde::Deserialize<'de> for Config {
                             result.coulomb_and_farad = map.next_value()?;
                             seen_coulomb_farad = true;
                         }
+                        "dual-units" => {
+                            if seen_dual_units {
+                                return Err(serde::de::Error::duplicate_field("dual-units"));
+                            }
+                            result.dual_units = map.next_value()?;
+                            seen_dual_units = true;
+                        }
+                        "angle-unit" => {
+                            if seen_angle_unit {
+                                return Err(serde::de::Error::duplicate_field("angle-unit"));
+                            }
+                            let angle_unit: &str = map.next_value()?;
+                            result.angle_unit = match angle_unit {
+                                "radians" => AngleUnit::Radians,
+                                "degrees" => AngleUnit::Degrees,
+                                v => {
+                                    return Err(serde::de::Error::invalid_value(
+                                        serde::de::Unexpected::Str(v),
+                                        &"`radians` or `degrees`",
+                                    ))
+                                }
+                            };
+                            seen_angle_unit = true;
+                        }
+                        "unit-exponent-style" => {
+                            if seen_unit_exponent_style {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "unit-exponent-style",
+                                ));
+                            }
+                            let style: &str = map.next_value()?;
+                            result.unit_exponent_style = match style {
+                                "slash" => fend_core::UnitExponentStyle::Slash,
+                                "negative" => fend_core::UnitExponentStyle::NegativeExponents,
+                                "superscript" => fend_core::UnitExponentStyle::Superscript,
+                                v => {
+                                    return Err(serde::de::Error::invalid_value(
+                                        serde::de::Unexpected::Str(v),
+                                        &"`slash`, `negative` or `superscript`",
+                                    ))
+                                }
+                            };
+                            seen_unit_exponent_style = true;
+                        }
+                        "significant-figures" => {
+                            if seen_significant_figures {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "significant-figures",
+                                ));
+                            }
+                            result.significant_figures = map.next_value()?;
+                            seen_significant_figures = true;
+                        }
+                        "precision" => {
+                            if seen_precision {
+                                return Err(serde::de::Error::duplicate_field("precision"));
+                            }
+                            result.precision = map.next_value()?;
+                            seen_precision = true;
+                        }
                         "colors" => {
                             if seen_colors {
                                 return Err(serde::de::Error::duplicate_field("colors"));
@@ -86,6 +247,106 @@ impl<'de> serde::Deserialize<'de> for Config {
                             result.max_history_size = map.next_value()?;
                             seen_max_hist_size = true;
                         }
+                        "history-ignore-duplicates" => {
+                            if seen_history_ignore_duplicates {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "history-ignore-duplicates",
+                                ));
+                            }
+                            result.history_ignore_duplicates = map.next_value()?;
+                            seen_history_ignore_duplicates = true;
+                        }
+                        "save-history" => {
+                            if seen_save_history {
+                                return Err(serde::de::Error::duplicate_field("save-history"));
+                            }
+                            result.save_history = map.next_value()?;
+                            seen_save_history = true;
+                        }
+                        "screen-reader" => {
+                            if seen_screen_reader {
+                                return Err(serde::de::Error::duplicate_field("screen-reader"));
+                            }
+                            result.screen_reader = map.next_value()?;
+                            seen_screen_reader = true;
+                        }
+                        "units-file" => {
+                            if seen_units_file {
+                                return Err(serde::de::Error::duplicate_field("units-file"));
+                            }
+                            result.units_file = Some(map.next_value()?);
+                            seen_units_file = true;
+                        }
+                        "exchange-rate-max-age" => {
+                            if seen_exchange_rate_max_age {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "exchange-rate-max-age",
+                                ));
+                            }
+                            result.exchange_rate_max_age = map.next_value()?;
+                            seen_exchange_rate_max_age = true;
+                        }
+                        "timeout" => {
+                            if seen_timeout {
+                                return Err(serde::de::Error::duplicate_field("timeout"));
+                            }
+                            let timeout: &str = map.next_value()?;
+                            result.timeout =
+                                Some(parse_timeout(timeout).map_err(serde::de::Error::custom)?);
+                            seen_timeout = true;
+                        }
+                        "max-memory" => {
+                            if seen_max_memory {
+                                return Err(serde::de::Error::duplicate_field("max-memory"));
+                            }
+                            let max_memory: &str = map.next_value()?;
+                            result.max_memory = Some(
+                                parse_memory_size(max_memory).map_err(serde::de::Error::custom)?,
+                            );
+                            seen_max_memory = true;
+                        }
+                        "annotate-markers" => {
+                            if seen_annotate_markers {
+                                return Err(serde::de::Error::duplicate_field("annotate-markers"));
+                            }
+                            result.annotate_markers = map.next_value()?;
+                            seen_annotate_markers = true;
+                        }
+                        "wrap-long-results" => {
+                            if seen_wrap_long_results {
+                                return Err(serde::de::Error::duplicate_field("wrap-long-results"));
+                            }
+                            result.wrap_long_results = map.next_value()?;
+                            seen_wrap_long_results = true;
+                        }
+                        "show-tips" => {
+                            if seen_show_tips {
+                                return Err(serde::de::Error::duplicate_field("show-tips"));
+                            }
+                            result.show_tips = map.next_value()?;
+                            seen_show_tips = true;
+                        }
+                        "persist-variables" => {
+                            if seen_persist_variables {
+                                return Err(serde::de::Error::duplicate_field("persist-variables"));
+                            }
+                            result.persist_variables = map.next_value()?;
+                            seen_persist_variables = true;
+                        }
+                        "holidays" => {
+                            if seen_holidays {
+                                return Err(serde::de::Error::duplicate_field("holidays"));
+                            }
+                            result.holidays = Some(map.next_value()?);
+                            seen_holidays = true;
+                        }
+                        "aliases" => {
+                            if seen_aliases {
+                                return Err(serde::de::Error::duplicate_field("aliases"));
+                            }
+                            result.aliases = map.next_value::<Aliases>()?.0;
+                            seen_aliases = true;
+                        }
                         "unknown-settings" => {
                             let unknown_settings: &str = map.next_value()?;
                             result.unknown_settings = match unknown_settings {
@@ -112,30 +373,165 @@ impl<'de> serde::Deserialize<'de> for Config {
 
         const FIELDS: &[&str] = &[
             "prompt",
+            "continuation-prompt",
+            "result-preview",
             "enable-colors",
             "coulomb-and-farad",
+            "dual-units",
+            "angle-unit",
+            "unit-exponent-style",
+            "significant-figures",
+            "precision",
             "colors",
             "max-history-size",
+            "history-ignore-duplicates",
+            "save-history",
+            "screen-reader",
+            "units-file",
+            "exchange-rate-max-age",
+            "timeout",
+            "max-memory",
+            "annotate-markers",
+            "wrap-long-results",
+            "show-tips",
+            "persist-variables",
+            "holidays",
+            "aliases",
             "unknown-settings",
         ];
         deserializer.deserialize_struct("Config", FIELDS, ConfigVisitor)
     }
 }
 
+// unlike a `HashMap`, this keeps the aliases in order, so
+// that aliases can refer to the ones above them
+struct Aliases(Vec<(String, String)>);
+
+impl<'de> serde::Deserialize<'de> for Aliases {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AliasesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for AliasesVisitor {
+            type Value = Aliases;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a table of aliases like `f2c = 'x => (x - 32) * 5/9'`")
+            }
+
+            fn visit_map<V: serde::de::MapAccess<'de>>(
+                self,
+                mut map: V,
+            ) -> Result<Aliases, V::Error> {
+                let mut aliases = vec![];
+                while let Some((name, expr)) = map.next_entry()? {
+                    aliases.push((name, expr));
+                }
+                Ok(Aliases(aliases))
+            }
+        }
+
+        deserializer.deserialize_map(AliasesVisitor)
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             prompt: "> ".to_string(),
+            continuation_prompt: None,
+            result_preview: ResultPreview::Inline,
             enable_colors: use_colors_if_auto(),
             coulomb_and_farad: false,
+            dual_units: false,
+            angle_unit: AngleUnit::Radians,
+            unit_exponent_style: fend_core::UnitExponentStyle::Slash,
+            significant_figures: false,
+            precision: 10,
             colors: color::OutputColors::default(),
             max_history_size: 1000,
+            history_ignore_duplicates: true,
+            save_history: true,
+            screen_reader: false,
+            units_file: None,
+            exchange_rate_max_age: 86400,
+            timeout: None,
+            max_memory: None,
+            annotate_markers: ("{{".to_string(), "}}".to_string()),
+            wrap_long_results: false,
+            show_tips: false,
+            persist_variables: false,
+            holidays: None,
+            aliases: vec![],
+            definitions: vec![],
+            skip_init_file: false,
             unknown_settings: UnknownSettings::Warn,
             unknown_keys: vec![],
         }
     }
 }
 
+/// Parses a timeout like `2s`, `500ms`, `1.5min` or `1h`. Numbers without
+/// a unit are in seconds.
+pub fn parse_timeout(timeout: &str) -> Result<time::Duration, String> {
+    let timeout = timeout.trim();
+    let unit_start = timeout
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(timeout.len());
+    let (number, unit) = timeout.split_at(unit_start);
+    let seconds_per_unit = match unit.trim() {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" | "min" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(format!("invalid timeout `{}`, expected e.g. `2s`", timeout)),
+    };
+    match number.parse::<f64>() {
+        Ok(number) if number > 0.0 => {
+            let seconds = number * seconds_per_unit;
+            if seconds < time::Duration::MAX.as_secs_f64() {
+                Ok(time::Duration::from_secs_f64(seconds))
+            } else {
+                Err(format!("timeout `{}` is too long", timeout))
+            }
+        }
+        _ => Err(format!("invalid timeout `{}`, expected e.g. `2s`", timeout)),
+    }
+}
+
+/// Parses a size in bytes like `512MB`, `2GiB` or `100kB`
+fn parse_memory_size(size: &str) -> Result<usize, String> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(unit_start);
+    let bytes_per_unit: usize = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "kib" => 1 << 10,
+        "mb" => 1_000_000,
+        "mib" => 1 << 20,
+        "gb" => 1_000_000_000,
+        "gib" => 1 << 30,
+        _ => {
+            return Err(format!(
+                "invalid memory size `{}`, expected e.g. `512MB`",
+                size
+            ))
+        }
+    };
+    match number
+        .parse::<usize>()
+        .map(|n| n.checked_mul(bytes_per_unit))
+    {
+        Ok(Some(bytes)) if bytes > 0 => Ok(bytes),
+        _ => Err(format!(
+            "invalid memory size `{}`, expected e.g. `512MB`",
+            size
+        )),
+    }
+}
+
 pub static DEFAULT_CONFIG_FILE: &str = include_str!("default_config.toml");
 
 fn read_config_file() -> Config {
@@ -208,4 +604,46 @@ mod tests {
         let deserialized: Config = toml::de::from_str(DEFAULT_CONFIG_FILE).unwrap();
         assert_eq!(deserialized, Config::default());
     }
+
+    #[test]
+    fn test_aliases_keep_their_order() {
+        let config: Config =
+            toml::de::from_str("[aliases]\nz = '1'\na = 'z + 1'\nf2c = 'x => (x - 32) * 5/9'\n")
+                .unwrap();
+        assert_eq!(
+            config.aliases,
+            [
+                ("z".to_string(), "1".to_string()),
+                ("a".to_string(), "z + 1".to_string()),
+                ("f2c".to_string(), "x => (x - 32) * 5/9".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("2s"), Ok(time::Duration::from_secs(2)));
+        assert_eq!(parse_timeout("500ms"), Ok(time::Duration::from_millis(500)));
+        assert_eq!(parse_timeout("1.5min"), Ok(time::Duration::from_secs(90)));
+        assert_eq!(parse_timeout("10"), Ok(time::Duration::from_secs(10)));
+        assert!(parse_timeout("0s").is_err());
+        assert!(parse_timeout("2 weeks").is_err());
+        assert!(parse_timeout("s").is_err());
+        assert_eq!(
+            parse_timeout("99999999999999999999999h"),
+            Err("timeout `99999999999999999999999h` is too long".to_string())
+        );
+        assert!(parse_timeout("18000000000000000000s").is_ok());
+    }
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!(parse_memory_size("512MB"), Ok(512_000_000));
+        assert_eq!(parse_memory_size("2 GiB"), Ok(2 << 30));
+        assert_eq!(parse_memory_size("100kb"), Ok(100_000));
+        assert_eq!(parse_memory_size("4096"), Ok(4096));
+        assert!(parse_memory_size("0MB").is_err());
+        assert!(parse_memory_size("1.5GB").is_err());
+        assert!(parse_memory_size("lots").is_err());
+    }
 }
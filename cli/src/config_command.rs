@@ -0,0 +1,86 @@
+use crate::{config, file_paths};
+use std::{env, fs, path, process};
+
+/// A subcommand of `fend config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Print the location of the config file
+    Path,
+    /// Write the default config file, including its comments
+    Init,
+    /// Open the config file in the user's editor
+    Edit,
+}
+
+pub fn run(command: ConfigCommand) -> i32 {
+    let path = match file_paths::get_config_file_location() {
+        Some(path) => path,
+        None => {
+            eprintln!("Error: failed to get config file location");
+            return 1;
+        }
+    };
+    let res = match command {
+        ConfigCommand::Path => {
+            println!("{}", path.display());
+            Ok(())
+        }
+        ConfigCommand::Init => init(&path).map(|()| println!("Created {}", path.display())),
+        ConfigCommand::Edit => edit(&path),
+    };
+    match res {
+        Ok(()) => 0,
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            1
+        }
+    }
+}
+
+/// Writes the default config file to `path`, unless there already is one
+fn init(path: &path::Path) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, config::DEFAULT_CONFIG_FILE)
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// Opens the config file in `$VISUAL` or `$EDITOR`, creating it first if
+/// it doesn't exist yet
+fn edit(path: &path::Path) -> Result<(), String> {
+    if !path.exists() {
+        init(path)?;
+    }
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(|editor| editor.to_string_lossy().into_owned())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| default_editor().to_string());
+    // the editor may include arguments, like `code --wait`
+    let mut args = editor.split_whitespace();
+    let program = args.next().unwrap_or_default();
+    let status = process::Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .map_err(|e| format!("could not run `{}`: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`{}` failed ({})", program, status))
+    }
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
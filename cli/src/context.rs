@@ -1,4 +1,6 @@
 use std::cell::RefCell;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone)]
 pub struct Context<'a> {
@@ -20,16 +22,96 @@ impl<'a> Context<'a> {
             let mut ctx_borrow = self.ctx.borrow_mut();
             ctx_borrow.set_random_u32_fn(random_u32);
             ctx_borrow.set_output_mode_terminal();
+            set_current_time(&mut ctx_borrow);
             fend_core::evaluate_with_interrupt(line, &mut ctx_borrow, int)
         } else {
             let mut ctx_clone = self.ctx.borrow().clone();
             ctx_clone.disable_rng();
             ctx_clone.set_output_mode_terminal();
+            set_current_time(&mut ctx_clone);
             fend_core::evaluate_with_interrupt(line, &mut ctx_clone, int)
         }
     }
 }
 
+impl Context<'_> {
+    pub fn locate_syntax_error(&self, line: &str) -> Option<std::ops::Range<usize>> {
+        fend_core::locate_syntax_error(line, &self.ctx.borrow())
+    }
+
+    pub fn debug_parse(&self, line: &str) -> Result<String, String> {
+        fend_core::debug_parse(line, &self.ctx.borrow())
+    }
+
+    pub fn precision(&self) -> usize {
+        self.ctx.borrow().precision()
+    }
+
+    pub fn is_incomplete(&self, line: &str) -> bool {
+        fend_core::is_incomplete(line, &self.ctx.borrow())
+    }
+
+    pub fn highlight(&self, line: &str, config: &crate::config::Config) -> String {
+        let ctx = self.ctx.borrow();
+        let strings: Vec<_> = fend_core::highlight(line, &ctx)
+            .into_iter()
+            .map(|span| config.colors.get_color(span.kind()).paint(span.string()))
+            .collect();
+        ansi_term::ANSIStrings(strings.as_slice()).to_string()
+    }
+
+    pub fn get_completions(&self, prefix: &str) -> (usize, Vec<fend_core::Completion>) {
+        fend_core::get_completions(prefix, &self.ctx.borrow())
+    }
+
+    pub fn get_error_kind(&self) -> Option<fend_core::ErrorKind> {
+        self.ctx.borrow().get_error_kind()
+    }
+
+    pub fn get_corrected_input(&self) -> Option<String> {
+        self.ctx
+            .borrow()
+            .get_correction()
+            .map(|correction| correction.corrected_input().to_string())
+    }
+}
+
+thread_local! {
+    static TIMEZONE_OFFSET_SECS: i64 = timezone_offset_secs();
+}
+
+fn set_current_time(ctx: &mut fend_core::Context) {
+    let ms_since_1970 = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        Err(_) => return,
+    };
+    let offset = TIMEZONE_OFFSET_SECS.with(|offset| *offset);
+    ctx.set_current_time_v1(ms_since_1970, offset);
+}
+
+/// Asks `date` for the offset of the local time zone (e.g. `+1300`),
+/// falling back to UTC if that doesn't work. Like JavaScript's
+/// `getTimezoneOffset`, the result is positive west of UTC.
+fn timezone_offset_secs() -> i64 {
+    let output = match process::Command::new("date").arg("+%z").output() {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => return 0,
+    };
+    let output = String::from_utf8_lossy(&output);
+    let output = output.trim();
+    let (sign, digits) = match output.strip_prefix('-') {
+        Some(digits) => (1, digits),
+        None => (-1, output.strip_prefix('+').unwrap_or(output)),
+    };
+    if digits.len() != 4 {
+        return 0;
+    }
+    match (digits[..2].parse::<i64>(), digits[2..].parse::<i64>()) {
+        (Ok(hours), Ok(minutes)) => sign * (hours * 3600 + minutes * 60),
+        _ => 0,
+    }
+}
+
 fn random_u32() -> u32 {
     let mut rng = nanorand::WyRand::new();
     nanorand::Rng::generate(&mut rng)
@@ -0,0 +1,97 @@
+use crate::terminal::{PromptState, ReadLineError};
+
+// implements the `:convert` command, which guides users through
+// a unit conversion without them needing to know how units are spelled
+
+/// Parses a choice from a numbered list, given either by its
+/// (1-based) number or by name
+fn parse_choice<'a>(input: &str, options: &[&'a str]) -> Option<&'a str> {
+    let input = input.trim();
+    if let Ok(n) = input.parse::<usize>() {
+        return n.checked_sub(1).and_then(|i| options.get(i)).copied();
+    }
+    options
+        .iter()
+        .find(|option| option.eq_ignore_ascii_case(input))
+        .copied()
+}
+
+fn print_options(options: &[&str]) {
+    for (i, option) in options.iter().enumerate() {
+        println!("{:>3}. {}", i + 1, option.replace('_', " "));
+    }
+}
+
+/// Reads a line, treating an empty line like Ctrl-C
+fn read_non_empty_line(
+    prompt_state: &mut PromptState<'_>,
+    prompt: &str,
+) -> Result<String, ReadLineError> {
+    let line = prompt_state.read_line_with_prompt(prompt)?;
+    if line.trim().is_empty() {
+        return Err(ReadLineError::Interrupted);
+    }
+    Ok(line)
+}
+
+fn choose<'a>(
+    prompt_state: &mut PromptState<'_>,
+    prompt: &str,
+    options: &[&'a str],
+) -> Result<&'a str, ReadLineError> {
+    loop {
+        let line = read_non_empty_line(prompt_state, prompt)?;
+        match parse_choice(&line, options) {
+            Some(choice) => return Ok(choice),
+            None => println!(
+                "Please enter a name or a number between 1 and {}",
+                options.len()
+            ),
+        }
+    }
+}
+
+/// Asks for a category, the units to convert between and a value, and
+/// returns the equivalent fend expression (e.g. `5 mile to kilometer`).
+/// Entering an empty line cancels the conversion, and is reported
+/// in the same way as Ctrl-C (i.e. as [`ReadLineError::Interrupted`]).
+pub fn run(prompt_state: &mut PromptState<'_>) -> Result<String, ReadLineError> {
+    println!("Convert between units (press Enter on an empty line to cancel)");
+    let categories = fend_core::get_unit_categories();
+    let names: Vec<_> = categories
+        .iter()
+        .map(fend_core::UnitCategory::name)
+        .collect();
+    print_options(&names);
+    let name = choose(prompt_state, "Category: ", &names)?;
+    let category = categories
+        .into_iter()
+        .find(|category| category.name() == name)
+        .expect("choice must be one of the categories");
+    print_options(category.units());
+    let from = choose(prompt_state, "From: ", category.units())?;
+    let to = choose(prompt_state, "To: ", category.units())?;
+    let value = read_non_empty_line(prompt_state, "Value: ")?;
+    Ok(format!("{} {} to {}", value.trim(), from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_choice;
+
+    #[test]
+    fn choose_by_number() {
+        let options = ["meter", "foot", "inch"];
+        assert_eq!(parse_choice("2", &options), Some("foot"));
+        assert_eq!(parse_choice(" 3 ", &options), Some("inch"));
+        assert_eq!(parse_choice("0", &options), None);
+        assert_eq!(parse_choice("4", &options), None);
+    }
+
+    #[test]
+    fn choose_by_name() {
+        let options = ["meter", "foot", "inch"];
+        assert_eq!(parse_choice("Foot", &options), Some("foot"));
+        assert_eq!(parse_choice("yard", &options), None);
+    }
+}
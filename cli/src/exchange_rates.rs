@@ -0,0 +1,217 @@
+use crate::file_paths;
+use std::{cell::Cell, collections::HashMap, fs, io, path, process, sync, thread, time};
+
+const URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+thread_local! {
+    // false while evaluating e.g. the live preview, which must never
+    // wait for the network
+    static WAIT_FOR_DOWNLOAD: Cell<bool> = Cell::new(true);
+}
+
+/// Runs `f` without waiting for exchange rates that are still being
+/// downloaded, so that the cached (or built-in) rates are used instead
+pub fn without_waiting<T>(f: impl FnOnce() -> T) -> T {
+    let previous = WAIT_FOR_DOWNLOAD.with(|wait| wait.replace(false));
+    let result = f();
+    WAIT_FOR_DOWNLOAD.with(|wait| wait.set(previous));
+    result
+}
+
+/// Exchange rates from the European Central Bank. The first time a
+/// currency is used, the rates are read from the cache on disk, and if
+/// they are missing or outdated, new rates are downloaded on a background
+/// thread. The cached rates are also used if fend is offline.
+#[derive(Clone)]
+pub struct ExchangeRates {
+    max_age: time::Duration,
+    state: sync::Arc<(sync::Mutex<State>, sync::Condvar)>,
+}
+
+#[derive(Default)]
+struct State {
+    rates: Option<HashMap<String, f64>>,
+    download: Download,
+    // printed (at most once) if downloading failed and there are no
+    // cached rates either, unless the previous download failed as well
+    warning: Option<String>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Download {
+    #[default]
+    NotStarted,
+    InProgress,
+    Finished,
+}
+
+impl ExchangeRates {
+    pub fn new(max_age_secs: u64) -> Self {
+        Self {
+            max_age: time::Duration::from_secs(max_age_secs),
+            state: sync::Arc::default(),
+        }
+    }
+
+    /// Reads the cached rates, and starts downloading new rates if
+    /// they are missing or outdated
+    fn load(&self, state: &mut State) {
+        let cache_path = file_paths::get_exchange_rates_cache_location();
+        let is_fresh = cache_path.as_deref().map_or(false, |path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .map_or(false, |age| age < self.max_age)
+        });
+        state.rates = cache_path.as_deref().and_then(read_cache);
+        if is_fresh && state.rates.is_some() {
+            state.download = Download::Finished;
+            return;
+        }
+        state.download = Download::InProgress;
+        let shared = sync::Arc::clone(&self.state);
+        thread::spawn(move || {
+            let result = download_rates(cache_path.as_deref());
+            let failed_before = record_failure(cache_path.as_deref(), result.is_err());
+            let (lock, finished) = &*shared;
+            if let Ok(mut state) = lock.lock() {
+                match result {
+                    Ok(rates) => state.rates = Some(rates),
+                    Err(e) if state.rates.is_none() && !failed_before => state.warning = Some(e),
+                    // offline: keep using the cached rates, even if they are outdated
+                    Err(_) => (),
+                }
+                state.download = Download::Finished;
+            }
+            finished.notify_all();
+        });
+    }
+}
+
+impl fend_core::ExchangeRateSource for ExchangeRates {
+    fn units_per_euro(&self, currency: &str) -> Option<f64> {
+        // the ECB only publishes rates for currencies that fend already
+        // knows about, so there's no need to download them for e.g. `BTC`
+        fend_core::get_currency_info(currency)?;
+        let (lock, finished) = &*self.state;
+        let mut state = lock.lock().ok()?;
+        if state.download == Download::NotStarted {
+            self.load(&mut state);
+        }
+        if WAIT_FOR_DOWNLOAD.with(Cell::get) {
+            while state.download == Download::InProgress {
+                state = finished.wait(state).ok()?;
+            }
+            if let Some(warning) = state.warning.take() {
+                eprintln!("Warning: {}, using built-in rates instead", warning);
+            }
+        }
+        state.rates.as_ref()?.get(currency).copied()
+    }
+}
+
+/// Downloads the latest rates and writes them to the cache
+fn download_rates(cache_path: Option<&path::Path>) -> Result<HashMap<String, f64>, String> {
+    let xml = download().map_err(|e| format!("failed to download exchange rates: {}", e))?;
+    let rates =
+        parse_rates(&xml).ok_or_else(|| format!("received invalid exchange rates from {}", URL))?;
+    if let Some(path) = cache_path {
+        // write to a temporary file first, so that the cache is never
+        // left incomplete if fend exits during the download
+        let temp_path = path.with_extension("xml.tmp");
+        // failing to cache the rates is not a problem
+        let _ = fs::write(&temp_path, &xml).and_then(|()| fs::rename(&temp_path, path));
+    }
+    Ok(rates)
+}
+
+/// Remembers whether the last download failed, so that e.g. running fend
+/// offline only prints a warning the first time. Returns whether the
+/// previous download failed as well.
+fn record_failure(cache_path: Option<&path::Path>, failed: bool) -> bool {
+    let path = match cache_path {
+        Some(path) => path.with_extension("failed"),
+        None => return false,
+    };
+    let failed_before = path.exists();
+    // failing to record this only means that the warning is repeated
+    let _ = if failed {
+        fs::write(&path, "")
+    } else {
+        fs::remove_file(&path)
+    };
+    failed_before
+}
+
+fn read_cache(path: &path::Path) -> Option<HashMap<String, f64>> {
+    parse_rates(&fs::read_to_string(path).ok()?)
+}
+
+fn download() -> io::Result<String> {
+    let output = process::Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--max-time", "10", URL])
+        .stdin(process::Stdio::null())
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("could not run `curl`: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            stderr.trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+}
+
+/// Parses the ECB's daily reference rates, which contain
+/// elements like `<Cube currency='USD' rate='1.2162'/>`
+fn parse_rates(xml: &str) -> Option<HashMap<String, f64>> {
+    let mut rates = HashMap::new();
+    for element in xml.split('<').skip(1) {
+        let currency = attribute(element, "currency");
+        let rate = attribute(element, "rate").and_then(|rate| rate.parse().ok());
+        if let (Some(currency), Some(rate)) = (currency, rate) {
+            rates.insert(currency.to_string(), rate);
+        }
+    }
+    if rates.is_empty() {
+        None
+    } else {
+        Some(rates)
+    }
+}
+
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let start = element.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = element[start..].chars().next()?;
+    if quote != '\'' && quote != '"' {
+        return None;
+    }
+    let value = &element[start + 1..];
+    Some(&value[..value.find(quote)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ecb_rates() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01">
+	<Cube>
+		<Cube time='2021-06-07'>
+			<Cube currency='USD' rate='1.2162'/>
+			<Cube currency="JPY" rate="132.98"/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+        let rates = parse_rates(xml).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert!((rates["USD"] - 1.2162).abs() < f64::EPSILON);
+        assert!((rates["JPY"] - 132.98).abs() < f64::EPSILON);
+        assert_eq!(parse_rates("<html>error</html>"), None);
+    }
+}
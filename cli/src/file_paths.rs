@@ -31,6 +31,18 @@ pub fn get_config_file_location() -> Option<path::PathBuf> {
     Some(config_path)
 }
 
+pub fn get_units_file_location() -> Option<path::PathBuf> {
+    let mut units_path = get_config_dir()?;
+    units_path.push("units.fend");
+    Some(units_path)
+}
+
+pub fn get_init_file_location() -> Option<path::PathBuf> {
+    let mut init_path = get_config_dir()?;
+    init_path.push("init.fend");
+    Some(init_path)
+}
+
 fn get_history_dir() -> Option<path::PathBuf> {
     // first try $FEND_STATE_DIR
     if let Some(env_var_history_dir) = env::var_os("FEND_STATE_DIR") {
@@ -52,12 +64,58 @@ fn get_history_dir() -> Option<path::PathBuf> {
     Some(res)
 }
 
+fn get_state_file_location(name: &str) -> Option<path::PathBuf> {
+    let mut path = get_history_dir()?;
+    match fs::create_dir_all(path.as_path()) {
+        Ok(_) => (),
+        Err(_) => return None,
+    }
+    path.push(name);
+    Some(path)
+}
+
 pub fn get_history_file_location() -> Option<path::PathBuf> {
-    let mut history_path = get_history_dir()?;
-    match fs::create_dir_all(history_path.as_path()) {
+    get_state_file_location("history")
+}
+
+pub fn get_bookmarks_file_location() -> Option<path::PathBuf> {
+    get_state_file_location("bookmarks")
+}
+
+pub fn get_variables_file_location() -> Option<path::PathBuf> {
+    get_state_file_location("variables.fend")
+}
+
+pub fn get_tips_file_location() -> Option<path::PathBuf> {
+    get_state_file_location("tips")
+}
+
+fn get_cache_dir() -> Option<path::PathBuf> {
+    // first try $FEND_CACHE_DIR
+    if let Some(env_var_cache_dir) = env::var_os("FEND_CACHE_DIR") {
+        return Some(path::PathBuf::from(env_var_cache_dir));
+    }
+
+    // otherwise try $XDG_CACHE_HOME/fend/
+    if let Some(env_var_xdg_cache_dir) = env::var_os("XDG_CACHE_HOME") {
+        let mut res = path::PathBuf::from(env_var_xdg_cache_dir);
+        res.push("fend");
+        return Some(res);
+    }
+
+    // otherwise use $HOME/.cache/fend/
+    let mut res = get_home_dir()?;
+    res.push(".cache");
+    res.push("fend");
+    Some(res)
+}
+
+pub fn get_exchange_rates_cache_location() -> Option<path::PathBuf> {
+    let mut path = get_cache_dir()?;
+    match fs::create_dir_all(path.as_path()) {
         Ok(_) => (),
         Err(_) => return None,
     }
-    history_path.push("history");
-    Some(history_path)
+    path.push("exchange-rates.xml");
+    Some(path)
 }
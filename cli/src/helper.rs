@@ -1,5 +1,5 @@
-use crate::{config, context::Context};
-use std::time;
+use crate::{config, context::Context, exchange_rates};
+use std::{borrow, time};
 
 pub struct HintInterrupt {
     start: time::Instant,
@@ -47,9 +47,20 @@ impl<'a> Helper<'a> {
 impl rustyline::hint::Hinter for Helper<'_> {
     type Hint = Hint;
 
-    fn hint(&self, line: &str, _pos: usize, _ctx: &rustyline::Context<'_>) -> Option<Hint> {
+    fn hint(&self, line: &str, pos: usize, _ctx: &rustyline::Context<'_>) -> Option<Hint> {
+        let preview = self.config.result_preview;
+        // like autosuggestions, inline previews are only shown while typing
+        // at the end of the line, so they don't get in the way of editing
+        if preview == config::ResultPreview::Never
+            || preview == config::ResultPreview::Inline && pos < line.len()
+        {
+            return None;
+        }
+        // slow calculations are interrupted so that they don't block typing,
+        // and exchange rates that are still being downloaded aren't awaited
         let int = HintInterrupt::default();
-        Some(match self.ctx.eval(line, false, &int) {
+        let result = exchange_rates::without_waiting(|| self.ctx.eval(line, false, &int));
+        Some(match result {
             Ok(result) => {
                 let res = result.get_main_result();
                 if res.is_empty()
@@ -60,7 +71,10 @@ impl rustyline::hint::Hinter for Helper<'_> {
                 {
                     return None;
                 }
-                if self.config.enable_colors {
+                if preview == config::ResultPreview::Inline {
+                    // colors are added by `highlight_hint`
+                    Hint(format!(" = {}", res))
+                } else if self.config.enable_colors {
                     Hint(format!(
                         "\n{}",
                         crate::print_spans(result.get_main_result_spans().collect(), self.config)
@@ -74,7 +88,28 @@ impl rustyline::hint::Hinter for Helper<'_> {
     }
 }
 
-impl rustyline::highlight::Highlighter for Helper<'_> {}
+impl rustyline::highlight::Highlighter for Helper<'_> {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> borrow::Cow<'l, str> {
+        if self.config.enable_colors {
+            borrow::Cow::Owned(self.ctx.highlight(line, self.config))
+        } else {
+            borrow::Cow::Borrowed(line)
+        }
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> borrow::Cow<'h, str> {
+        if self.config.enable_colors && self.config.result_preview == config::ResultPreview::Inline
+        {
+            borrow::Cow::Owned(ansi_term::Style::new().dimmed().paint(hint).to_string())
+        } else {
+            borrow::Cow::Borrowed(hint)
+        }
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        self.config.enable_colors
+    }
+}
 
 impl rustyline::validate::Validator for Helper<'_> {}
 
@@ -100,7 +135,7 @@ impl rustyline::completion::Completer for Helper<'_> {
         pos: usize,
         _ctx: &rustyline::Context<'_>,
     ) -> rustyline::Result<(usize, Vec<Self::Candidate>)> {
-        let (pos, completions) = fend_core::get_completions_for_prefix(&line[..pos]);
+        let (pos, completions) = self.ctx.get_completions(&line[..pos]);
         let v: Vec<_> = completions
             .into_iter()
             .map(|c| FendCandidate { completion: c })
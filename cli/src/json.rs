@@ -0,0 +1,108 @@
+use std::{fmt::Write, ops::Range};
+
+// formats results as JSON for `--format json` and `--output ... --json`,
+// so that editors and scripts don't need to parse fend's plain output
+
+pub fn string(s: &str) -> String {
+    let mut result = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => {
+                // writing to a `String` can't fail
+                let _ = write!(result, "\\u{:04x}", u32::from(ch));
+            }
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// The name of a span kind, which matches the name of its color setting
+fn kind_name(kind: fend_core::SpanKind) -> &'static str {
+    match kind {
+        fend_core::SpanKind::Number => "number",
+        fend_core::SpanKind::BuiltInFunction => "built-in-function",
+        fend_core::SpanKind::Keyword => "keyword",
+        fend_core::SpanKind::String => "string",
+        fend_core::SpanKind::Date => "date",
+        fend_core::SpanKind::Whitespace => "whitespace",
+        fend_core::SpanKind::Ident => "identifier",
+        fend_core::SpanKind::Boolean => "boolean",
+        fend_core::SpanKind::Matrix => "matrix",
+        fend_core::SpanKind::Secondary => "secondary",
+        _ => "other",
+    }
+}
+
+/// Formats a single evaluation as a JSON object on one line, like
+/// `{"input": "1 + 1", "result": "2", "spans": [...], "error": null}`.
+/// Errors have a `null` result, and include the byte range of the input
+/// that caused them if it is known.
+pub fn evaluation(
+    input: &str,
+    result: &Result<fend_core::FendResult, String>,
+    error_range: Option<Range<usize>>,
+) -> String {
+    match result {
+        Ok(res) => {
+            let spans: Vec<_> = res
+                .get_main_result_spans()
+                .filter(|span| !span.string().is_empty())
+                .map(|span| {
+                    format!(
+                        "{{\"kind\": {}, \"text\": {}}}",
+                        string(kind_name(span.kind())),
+                        string(span.string())
+                    )
+                })
+                .collect();
+            format!(
+                "{{\"input\": {}, \"result\": {}, \"spans\": [{}], \"error\": null}}",
+                string(input),
+                string(res.get_main_result()),
+                spans.join(", ")
+            )
+        }
+        Err(msg) => {
+            let range = error_range.map_or("null".to_string(), |range| {
+                format!("{{\"start\": {}, \"end\": {}}}", range.start, range.end)
+            });
+            format!(
+                "{{\"input\": {}, \"result\": null, \"spans\": [], \"error\": {{\"message\": {}, \"range\": {}}}}}",
+                string(input),
+                string(msg),
+                range
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evaluation;
+
+    #[test]
+    fn json_evaluations() {
+        let mut ctx = fend_core::Context::new();
+        let res = fend_core::evaluate("2 m", &mut ctx);
+        assert_eq!(
+            evaluation("2 m", &res, None),
+            r#"{"input": "2 m", "result": "2 m", "spans": [{"kind": "number", "text": "2"}, {"kind": "identifier", "text": " m"}], "error": null}"#
+        );
+        let res = fend_core::evaluate("2 * * 3", &mut ctx);
+        assert_eq!(
+            evaluation(
+                "2 * * 3",
+                &res,
+                fend_core::locate_syntax_error("2 * * 3", &ctx)
+            ),
+            r#"{"input": "2 * * 3", "result": null, "spans": [], "error": {"message": "unexpected input found", "range": {"start": 2, "end": 3}}}"#
+        );
+    }
+}
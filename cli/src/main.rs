@@ -3,24 +3,44 @@
 #![deny(clippy::pedantic)]
 #![deny(elided_lifetimes_in_paths)]
 
-use std::{env, io, process};
+use std::{env, fs, path, process};
 
+mod annotate;
+mod batch;
+mod bookmarks;
+mod clipboard;
 mod color;
 mod config;
+mod config_command;
 mod context;
+mod convert;
+mod exchange_rates;
 mod file_paths;
 mod helper;
 mod interrupt;
+mod json;
+mod notify;
+mod report;
+mod script;
 mod terminal;
+mod tips;
+mod wrap;
 
 use context::Context;
 
 enum EvalResult {
-    Ok,
-    Err,
+    Ok(String),
+    Err(String),
     NoInput,
 }
 
+/// How results are printed, which is set with `--format` (or `--json`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Plain,
+    Json,
+}
+
 /// Which action should be executed?
 ///
 /// This implements [`FromIterator`] and can be `collect`ed from
@@ -37,13 +57,47 @@ enum ArgsAction {
     Eval(String),
     /// Show the default config file
     DefaultConfig,
+    /// Evaluate calculations in text from stdin
+    Annotate,
+    /// Evaluate a script file and print a Markdown report
+    Report(String),
+    /// Evaluate a script file (or stdin) and print the values of the given
+    /// comma-separated variables
+    Output {
+        variables: Option<String>,
+        script: Option<String>,
+    },
+    /// Show, create or edit the config file
+    Config(config_command::ConfigCommand),
+    /// Print the tokens and expression tree of the arguments
+    DebugParse(String),
 }
 
 fn print_spans(spans: Vec<fend_core::SpanRef<'_>>, config: &config::Config) -> String {
+    let text: String = spans.iter().map(|span| span.string()).collect();
+    let breaks = match terminal::width() {
+        Some(width) if config.wrap_long_results => wrap::line_breaks(&text, width),
+        _ => vec![],
+    };
+    let mut breaks = breaks.into_iter().peekable();
+    let mut offset = 0;
     let mut strings = vec![];
     for span in spans {
-        let style = config.colors.get_color(span.kind());
-        strings.push(style.paint(span.string()));
+        let style = if config.enable_colors {
+            config.colors.get_color(span.kind())
+        } else {
+            ansi_term::Style::default()
+        };
+        let mut string = span.string();
+        while let Some(line_break) = breaks.next_if(|&b| b < offset + string.len()) {
+            let (before, after) = string.split_at(line_break - offset);
+            strings.push(style.paint(before));
+            strings.push(ansi_term::Style::default().paint(wrap::LINE_BREAK));
+            string = after;
+            offset = line_break;
+        }
+        strings.push(style.paint(string));
+        offset += string.len();
     }
     ansi_term::ANSIStrings(strings.as_slice()).to_string()
 }
@@ -60,16 +114,147 @@ fn eval_and_print_res(
             if result.is_empty() || res.is_unit_type() {
                 return EvalResult::NoInput;
             }
-            if config.enable_colors {
+            if config.screen_reader {
+                println!("{}", res.get_main_result_verbose());
+            } else if config.enable_colors || config.wrap_long_results {
                 println!("{}", print_spans(result, config));
             } else {
                 println!("{}", res.get_main_result());
             }
-            EvalResult::Ok
+            EvalResult::Ok(res.get_main_result().to_string())
         }
         Err(msg) => {
-            eprintln!("Error: {}", msg);
-            EvalResult::Err
+            print_error(line, &msg, context.locate_syntax_error(line), config);
+            EvalResult::Err(msg)
+        }
+    }
+}
+
+/// Prints an error message, underlining the part of the input
+/// that caused it (if known) like this:
+///
+/// ```text
+///   5 @ 3
+///     ^
+/// Error: unexpected character '@'
+/// ```
+fn print_error(
+    line: &str,
+    msg: &str,
+    range: Option<std::ops::Range<usize>>,
+    config: &config::Config,
+) {
+    let style = if config.enable_colors {
+        config.colors.get_error_color()
+    } else {
+        ansi_term::Style::default()
+    };
+    if let Some(range) = range {
+        if !line.contains('\n') {
+            let offset = line[..range.start].chars().count();
+            let width = line[range].chars().count().max(1);
+            eprintln!("  {}", line);
+            eprintln!("  {}{}", " ".repeat(offset), style.paint("^".repeat(width)));
+        }
+    }
+    eprintln!("{} {}", style.paint("Error:"), msg);
+}
+
+/// Loads custom units from the `units-file` setting, or otherwise from
+/// `units.fend` in the config directory (if it exists)
+fn load_custom_units(context: &mut fend_core::Context, config: &config::Config) {
+    let path = match &config.units_file {
+        Some(path) => path::PathBuf::from(path),
+        None => match file_paths::get_units_file_location() {
+            Some(path) if path.exists() => path,
+            _ => return,
+        },
+    };
+    let definitions = match fs::read_to_string(&path) {
+        Ok(definitions) => definitions,
+        Err(e) => {
+            eprintln!("Error: failed to read units file {}: {}", path.display(), e);
+            return;
+        }
+    };
+    if let Err(msg) = context.define_units(&definitions) {
+        eprintln!("Error: invalid units file {}: {}", path.display(), msg);
+    }
+}
+
+/// Restores the variables that were saved when the interactive prompt
+/// last exited, if the `persist-variables` setting is enabled
+fn restore_variables(context: &mut fend_core::Context, config: &config::Config) {
+    if !config.persist_variables {
+        return;
+    }
+    let path = match file_paths::get_variables_file_location() {
+        Some(path) if path.exists() => path,
+        _ => return,
+    };
+    let saved = match fs::read_to_string(&path) {
+        Ok(saved) => saved,
+        Err(e) => {
+            eprintln!(
+                "Error: failed to read variables file {}: {}",
+                path.display(),
+                e
+            );
+            return;
+        }
+    };
+    if let Err(msg) = context.restore_variables(&saved) {
+        eprintln!("Error: invalid variables file {}: {}", path.display(), msg);
+    }
+}
+
+/// Saves variables and custom units so that they can be restored by
+/// `restore_variables`, if the `persist-variables` setting is enabled
+fn save_variables(context: &fend_core::Context, config: &config::Config) {
+    if !config.persist_variables {
+        return;
+    }
+    let path = match file_paths::get_variables_file_location() {
+        Some(path) => path,
+        None => return,
+    };
+    let result = context
+        .save_variables()
+        .and_then(|saved| fs::write(&path, saved).map_err(|e| e.to_string()));
+    if let Err(msg) = result {
+        eprintln!(
+            "Error: failed to save variables to {}: {}",
+            path.display(),
+            msg
+        );
+    }
+}
+
+/// Evaluates each line of `init.fend` in the config directory (if it
+/// exists), e.g. to define variables and functions that are always
+/// available, unless fend was started with `--no-init`
+fn load_init_file(context: &mut fend_core::Context, config: &config::Config) {
+    if config.skip_init_file {
+        return;
+    }
+    let path = match file_paths::get_init_file_location() {
+        Some(path) if path.exists() => path,
+        _ => return,
+    };
+    let statements = match fs::read_to_string(&path) {
+        Ok(statements) => statements,
+        Err(e) => {
+            eprintln!("Error: failed to read init file {}: {}", path.display(), e);
+            return;
+        }
+    };
+    for (i, line) in statements.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Err(msg) = fend_core::evaluate(line, context) {
+            eprintln!("Error: {} line {}: {}", path.display(), i + 1, msg);
         }
     }
 }
@@ -84,46 +269,206 @@ fn print_help(explain_quitting: bool) {
     } else {
         println!("Failed to get config file location");
     }
+    if let Some(units_path) = file_paths::get_units_file_location() {
+        println!("Units file: {}", units_path.to_string_lossy());
+    } else {
+        println!("Failed to get units file location");
+    }
+    if let Some(init_path) = file_paths::get_init_file_location() {
+        println!("Init file: {}", init_path.to_string_lossy());
+    } else {
+        println!("Failed to get init file location");
+    }
     if let Some(history_path) = file_paths::get_history_file_location() {
         println!("History file: {}", history_path.to_string_lossy());
     } else {
         println!("Failed to get history file location");
     }
     if explain_quitting {
-        println!("\nTo quit, type `quit`.");
+        println!("\nTo search previous calculations, press Ctrl-R.");
+        println!("To convert between units step by step, type `:convert`.");
+        println!("To bookmark the last calculation, type `:bookmark <name>`.");
+        println!("To list bookmarks, type `:bookmarks`, and use `@<name>` to recalculate one.");
+        println!("To copy the last result to the clipboard, type `:copy`.");
+        println!("To see tips about fend's features, type `:tips`.");
+        println!("To quit, type `quit`.");
     }
 }
 
-fn repl_loop(config: &config::Config) -> i32 {
-    let core_context = std::cell::RefCell::new(fend_core::Context::new());
+/// Creates a context with the settings from the config file and
+/// the variables defined on the command line
+fn new_core_context(config: &config::Config) -> Result<fend_core::Context, String> {
+    let mut context = fend_core::Context::new();
     if config.coulomb_and_farad {
-        core_context.borrow_mut().use_coulomb_and_farad();
+        context.use_coulomb_and_farad();
+    }
+    if config.dual_units {
+        context.show_dual_units();
     }
+    if config.angle_unit == config::AngleUnit::Degrees {
+        context.use_degrees();
+    }
+    context.set_unit_exponent_style(config.unit_exponent_style);
+    if config.significant_figures {
+        context.track_significant_figures();
+    }
+    context.set_precision(config.precision);
+    context.set_limits(fend_core::Limits {
+        max_time: config.timeout,
+        max_memory: config.max_memory,
+        ..fend_core::Limits::default()
+    });
+    // custom units, aliases and `init.fend` may use holidays or currencies
+    if let Some(holidays) = &config.holidays {
+        if let Err(msg) = context.set_holidays(holidays) {
+            eprintln!("Error: {}", msg);
+        }
+    }
+    context.set_exchange_rate_source(exchange_rates::ExchangeRates::new(
+        config.exchange_rate_max_age,
+    ));
+    load_custom_units(&mut context, config);
+    if let Err(msg) = context.define_variables(&config.aliases) {
+        eprintln!("Error: invalid alias {}", msg);
+    }
+    load_init_file(&mut context, config);
+    for definition in &config.definitions {
+        if !definition.contains('=') {
+            return Err(format!(
+                "invalid definition `{}`, expected `-D name=value`",
+                definition
+            ));
+        }
+        if let Err(msg) = fend_core::evaluate(definition, &mut context) {
+            return Err(format!("-D {}: {}", definition, msg));
+        }
+    }
+    Ok(context)
+}
+
+/// Keeps reading lines while the input ends with an operator or has
+/// unclosed parentheses, and joins them into a single input. An empty
+/// line finishes the input early, e.g. to rely on fend closing any
+/// remaining parentheses.
+fn read_continuation_lines(
+    first_line: &str,
+    prompt_state: &mut terminal::PromptState<'_>,
+    context: &Context<'_>,
+) -> Result<String, terminal::ReadLineError> {
+    let mut input = first_line.to_string();
+    while context.is_incomplete(&input) {
+        let line = prompt_state.read_continuation_line()?;
+        if line.trim().is_empty() {
+            break;
+        }
+        input.push(' ');
+        input.push_str(line.trim());
+    }
+    Ok(input)
+}
+
+#[allow(clippy::too_many_lines)]
+fn repl_loop(config: &config::Config) -> i32 {
+    let core_context = match new_core_context(config) {
+        Ok(mut core_context) => {
+            restore_variables(&mut core_context, config);
+            std::cell::RefCell::new(core_context)
+        }
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            return 1;
+        }
+    };
     let mut context = Context::new(&core_context);
     let mut prompt_state = terminal::init_prompt(config, &context);
+    if config.show_tips {
+        tips::show_next();
+    }
     let mut initial_run = true; // set to false after first successful command
     let mut last_command_success = true;
+    // the last successful input and its result, for `:bookmark` and `:copy`
+    let mut last_calculation: Option<(String, String)> = None;
     let interrupt = interrupt::register_handler();
+    // corrected input for the last error, which is filled in so
+    // that it can be accepted by pressing Enter
+    let mut correction: Option<String> = None;
     loop {
-        match prompt_state.read_line() {
+        let line = match correction.take() {
+            Some(corrected_input) => prompt_state.read_line_with_initial(&corrected_input),
+            None => prompt_state.read_line(),
+        };
+        let line = line.and_then(|line| {
+            if line.trim() != ":convert" {
+                return Ok(line);
+            }
+            // the wizard's answers are turned into a regular expression,
+            // which is shown so users can type it directly next time
+            match convert::run(&mut prompt_state) {
+                Ok(expr) => {
+                    println!("> {}", expr);
+                    Ok(expr)
+                }
+                // Ctrl-C or an empty line only cancels the conversion
+                Err(terminal::ReadLineError::Interrupted) => Ok(String::new()),
+                Err(e) => Err(e),
+            }
+        });
+        match line {
             Ok(line) => match line.as_str() {
                 "exit" | "exit()" | ".exit" | ":exit" | "quit" | "quit()" | ":quit" | ":q"
                 | ":wq" | ":q!" | ":wq!" | ":qa" | ":wqa" | ":qa!" | ":wqa!" => break,
                 "help" | "?" => {
                     print_help(true);
                 }
+                ":bookmarks" => {
+                    bookmarks::print_list();
+                }
+                ":tips" => {
+                    tips::print_list();
+                }
+                ":copy" => {
+                    clipboard::copy_last(last_calculation.as_ref());
+                }
+                line if line.starts_with(":bookmark ") => {
+                    let name = line.trim_start_matches(":bookmark ").trim();
+                    bookmarks::save_last(name, last_calculation.as_ref());
+                }
                 line => {
+                    let line = match read_continuation_lines(line, &mut prompt_state, &context) {
+                        Ok(line) => line,
+                        Err(terminal::ReadLineError::Interrupted) => continue,
+                        Err(terminal::ReadLineError::Eof) => break,
+                        Err(terminal::ReadLineError::Error(err)) => {
+                            println!("Error: {}", err);
+                            break;
+                        }
+                    };
+                    let line = match bookmarks::resolve(&line) {
+                        Ok(Some(input)) => {
+                            println!("> {}", input);
+                            input
+                        }
+                        Ok(None) => line,
+                        Err(msg) => {
+                            eprintln!("Error: {}", msg);
+                            last_command_success = false;
+                            continue;
+                        }
+                    };
                     interrupt.reset();
-                    match eval_and_print_res(line, &mut context, &interrupt, config) {
-                        EvalResult::Ok => {
+                    match eval_and_print_res(&line, &mut context, &interrupt, config) {
+                        EvalResult::Ok(result) => {
                             last_command_success = true;
                             initial_run = false;
+                            prompt_state.count_result();
+                            last_calculation = Some((line.trim().to_string(), result));
                         }
                         EvalResult::NoInput => {
                             last_command_success = true;
                         }
-                        EvalResult::Err => {
+                        EvalResult::Err(_) => {
                             last_command_success = false;
+                            correction = context.get_corrected_input();
                         }
                     }
                 }
@@ -141,6 +486,7 @@ fn repl_loop(config: &config::Config) -> i32 {
             }
         }
     }
+    save_variables(&core_context.borrow(), config);
     if last_command_success {
         0
     } else {
@@ -148,26 +494,220 @@ fn repl_loop(config: &config::Config) -> i32 {
     }
 }
 
-fn eval_expr(expr: &str) -> i32 {
-    let config = config::read();
-    let core_context = std::cell::RefCell::new(fend_core::Context::new());
-    if config.coulomb_and_farad {
-        core_context.borrow_mut().use_coulomb_and_farad();
+/// Evaluates `line` and prints the input, result and any error as a
+/// JSON object
+fn eval_and_print_json(
+    line: &str,
+    context: &Context<'_>,
+    int: &impl fend_core::Interrupt,
+) -> EvalResult {
+    let res = context.eval(line, true, int);
+    let error_range = res
+        .as_ref()
+        .err()
+        .and_then(|_| context.locate_syntax_error(line));
+    println!("{}", json::evaluation(line, &res, error_range));
+    match res {
+        Ok(res) if res.is_unit_type() => EvalResult::NoInput,
+        Ok(res) => EvalResult::Ok(res.get_main_result().to_string()),
+        Err(msg) => EvalResult::Err(msg),
+    }
+}
+
+/// The exit code for a calculation that failed, so that scripts can tell
+/// syntax errors (2) and interrupted calculations (3) apart from other
+/// errors (1)
+fn error_exit_code(kind: Option<fend_core::ErrorKind>) -> i32 {
+    match kind {
+        Some(fend_core::ErrorKind::Syntax) => 2,
+        Some(fend_core::ErrorKind::Interrupted) => 3,
+        _ => 1,
     }
-    match eval_and_print_res(
-        expr,
-        &mut Context::new(&core_context),
-        &interrupt::Never::default(),
-        &config,
-    ) {
-        EvalResult::Ok | EvalResult::NoInput => 0,
-        EvalResult::Err => 1,
+}
+
+fn eval_expr(
+    expr: &str,
+    config: &config::Config,
+    notify: bool,
+    copy: bool,
+    format: OutputFormat,
+) -> i32 {
+    let core_context = match new_core_context(config) {
+        Ok(core_context) => std::cell::RefCell::new(core_context),
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            return 1;
+        }
+    };
+    let mut context = Context::new(&core_context);
+    // Ctrl-C interrupts long calculations, which exit with their own code
+    let interrupt = interrupt::register_handler();
+    let res = match format {
+        OutputFormat::Plain => eval_and_print_res(expr, &mut context, &interrupt, config),
+        OutputFormat::Json => eval_and_print_json(expr, &context, &interrupt),
+    };
+    if notify {
+        let message = match &res {
+            EvalResult::Ok(result) => Some(result.clone()),
+            EvalResult::Err(msg) => Some(format!("Error: {}", msg)),
+            EvalResult::NoInput => None,
+        };
+        if let Some(message) = message {
+            if let Err(e) = notify::send(&message) {
+                eprintln!("Warning: failed to send notification: {}", e);
+            }
+        }
+    }
+    if copy {
+        if let EvalResult::Ok(result) = &res {
+            if let Err(e) = clipboard::copy(result) {
+                eprintln!("Warning: failed to copy to the clipboard: {}", e);
+            }
+        }
+    }
+    match res {
+        EvalResult::Ok(_) | EvalResult::NoInput => 0,
+        EvalResult::Err(_) => error_exit_code(context.get_error_kind()),
     }
 }
 
+/// Prints how `input` is split into tokens and parsed, e.g. for bug reports
+/// about operator precedence
+fn debug_parse(input: &str, context: &Context<'_>) -> i32 {
+    match context.debug_parse(input) {
+        Ok(description) => {
+            print!("{}", description);
+            0
+        }
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            error_exit_code(Some(fend_core::ErrorKind::Syntax))
+        }
+    }
+}
+
+/// Runs `f` with a new context, or prints an error if any of the
+/// variables defined on the command line are invalid
+fn with_core_context(config: &config::Config, f: impl FnOnce(&Context<'_>) -> i32) -> i32 {
+    match new_core_context(config) {
+        Ok(core_context) => f(&Context::new(&std::cell::RefCell::new(core_context))),
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            1
+        }
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 fn real_main() -> i32 {
+    // These flags can be combined with any action, so handle them separately
+    let mut screen_reader = false;
+    let mut raw = false;
+    let mut notify = false;
+    let mut copy = false;
+    let mut echo = false;
+    let mut skip_init_file = false;
+    let mut format = None;
+    let mut precision = None;
+    let mut timeout = None;
+    let mut definitions = vec![];
+    // whether the previous argument was `-D` (or `--define`), `--format`
+    // or `--timeout`
+    let mut expect_definition = false;
+    let mut expect_format = false;
+    let mut expect_timeout = false;
     // Assemble the action from all but the first argument.
-    let action: ArgsAction = env::args().skip(1).collect();
+    let action: ArgsAction = env::args()
+        .skip(1)
+        .filter(|arg| {
+            if expect_definition {
+                definitions.push(arg.clone());
+                expect_definition = false;
+                return false;
+            }
+            if expect_format {
+                format = Some(arg.clone());
+                expect_format = false;
+                return false;
+            }
+            if expect_timeout {
+                timeout = Some(arg.clone());
+                expect_timeout = false;
+                return false;
+            }
+            match arg.as_str() {
+                "--screen-reader" => screen_reader = true,
+                "--raw" | "--plain" => raw = true,
+                "--notify" => notify = true,
+                "--copy" => copy = true,
+                // `--json` is short for `--format json`
+                "--json" => format = Some("json".to_string()),
+                "--echo" => echo = true,
+                "--no-init" => skip_init_file = true,
+                "-D" | "--define" => expect_definition = true,
+                "--format" => expect_format = true,
+                "--timeout" => expect_timeout = true,
+                arg => {
+                    if let Some(definition) = arg
+                        .strip_prefix("--define=")
+                        .or_else(|| arg.strip_prefix("-D"))
+                    {
+                        definitions.push(definition.to_string());
+                    } else if let Some(value) = arg.strip_prefix("--format=") {
+                        format = Some(value.to_string());
+                    } else if let Some(value) = arg.strip_prefix("--timeout=") {
+                        timeout = Some(value.to_string());
+                    } else {
+                        match arg.strip_prefix("--precision=").map(str::parse) {
+                            Some(Ok(digits)) => precision = Some(digits),
+                            _ => return true,
+                        }
+                    }
+                }
+            }
+            false
+        })
+        .collect();
+    let format = match format.as_deref() {
+        None | Some("plain") => OutputFormat::Plain,
+        Some("json") => OutputFormat::Json,
+        Some(format) => {
+            eprintln!(
+                "Error: unknown output format `{}`, expected `plain` or `json`",
+                format
+            );
+            return 1;
+        }
+    };
+    let timeout = match timeout.as_deref().map(config::parse_timeout) {
+        None => None,
+        Some(Ok(timeout)) => Some(timeout),
+        Some(Err(msg)) => {
+            eprintln!("Error: {}", msg);
+            return 1;
+        }
+    };
+    // the config file is only read by actions that need it
+    let read_config = move || {
+        let mut config = config::read();
+        config.screen_reader |= screen_reader;
+        if raw {
+            // only print the result itself, so that scripts can rely on it
+            config.enable_colors = false;
+            config.wrap_long_results = false;
+            config.screen_reader = false;
+            config.dual_units = false;
+        }
+        if let Some(precision) = precision {
+            config.precision = precision;
+        }
+        if timeout.is_some() {
+            config.timeout = timeout;
+        }
+        config.definitions = definitions;
+        config.skip_init_file = skip_init_file;
+        config
+    };
     match action {
         ArgsAction::Help => {
             print_help(false);
@@ -181,21 +721,39 @@ fn real_main() -> i32 {
             println!("{}", config::DEFAULT_CONFIG_FILE);
             0
         }
-        ArgsAction::Eval(expr) => eval_expr(expr.as_str()),
+        ArgsAction::Config(command) => config_command::run(command),
+        ArgsAction::DebugParse(input) => {
+            let config = read_config();
+            with_core_context(&config, |context| debug_parse(&input, context))
+        }
+        ArgsAction::Annotate => {
+            let config = read_config();
+            with_core_context(&config, |context| annotate::run(context, &config))
+        }
+        ArgsAction::Report(path) => {
+            let config = read_config();
+            with_core_context(&config, |context| report::run(&path, context))
+        }
+        ArgsAction::Output { variables, script } => {
+            let config = read_config();
+            with_core_context(&config, |context| {
+                script::run(
+                    script.as_deref().unwrap_or_default(),
+                    variables.as_deref().unwrap_or_default(),
+                    format,
+                    context,
+                )
+            })
+        }
+        ArgsAction::Eval(expr) => eval_expr(expr.as_str(), &read_config(), notify, copy, format),
         ArgsAction::Repl => {
+            let config = read_config();
             if terminal::atty_stdin() {
-                let config = config::read();
                 repl_loop(&config)
             } else {
-                let mut input = String::new();
-                match io::Read::read_to_string(&mut io::stdin(), &mut input) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        return 1;
-                    }
-                }
-                eval_expr(input.as_str())
+                with_core_context(&config, |context| {
+                    batch::run(format, echo, notify, copy, context)
+                })
             }
         }
     }
@@ -208,7 +766,11 @@ fn main() {
 impl FromIterator<String> for ArgsAction {
     fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
         iter.into_iter().fold(ArgsAction::Repl, |action, arg| {
-            use ArgsAction::{DefaultConfig, Eval, Help, Repl, Version};
+            use config_command::ConfigCommand;
+            use ArgsAction::{
+                Annotate, Config, DebugParse, DefaultConfig, Eval, Help, Output, Repl, Report,
+                Version,
+            };
             match (action, arg.as_str()) {
                 // If any argument is shouting for help, print help!
                 (_, "help" | "--help" | "-h") | (Help, _) => Help,
@@ -216,11 +778,64 @@ impl FromIterator<String> for ArgsAction {
                 // Once we're set on printing the version, only a request for help
                 // can overwrite that
                 // NOTE: 'version' is already handled by fend itself
-                (Repl | Eval(_) | DefaultConfig, "--version" | "-v" | "-V") | (Version, _) => {
-                    Version
-                }
+                (
+                    Repl
+                    | Eval(_)
+                    | DefaultConfig
+                    | Annotate
+                    | Report(_)
+                    | Output { .. }
+                    | Config(_)
+                    | DebugParse(_),
+                    "--version" | "-v" | "-V",
+                )
+                | (Version, _) => Version,
 
-                (Repl | Eval(_), "--default-config") | (DefaultConfig, _) => DefaultConfig,
+                (Repl | Eval(_) | Annotate | Report(_) | Output { .. }, "--default-config")
+                | (DefaultConfig, _) => DefaultConfig,
+                (Repl | Eval(_) | Report(_) | Output { .. }, "--annotate") | (Annotate, _) => {
+                    Annotate
+                }
+                // The argument after `--report` is the script file
+                (Repl | Eval(_), "--report") => Report(String::new()),
+                (Report(path), arg) if path.is_empty() => Report(String::from(arg)),
+                (Report(path), _) => Report(path),
+                // `--output` is followed by the variable names and the script file
+                (Repl | Eval(_), "--output") => Output {
+                    variables: None,
+                    script: None,
+                },
+                (
+                    Output {
+                        variables: None,
+                        script,
+                    },
+                    arg,
+                ) => Output {
+                    variables: Some(String::from(arg)),
+                    script,
+                },
+                (
+                    Output {
+                        variables,
+                        script: None,
+                    },
+                    arg,
+                ) => Output {
+                    variables,
+                    script: Some(String::from(arg)),
+                },
+                (Output { variables, script }, _) => Output { variables, script },
+                // `fend config path`, `fend config init` or `fend config edit`
+                (Eval(eval), "path") if eval == "config" => Config(ConfigCommand::Path),
+                (Eval(eval), "init") if eval == "config" => Config(ConfigCommand::Init),
+                (Eval(eval), "edit") if eval == "config" => Config(ConfigCommand::Edit),
+                (Config(command), _) => Config(command),
+                // the input can come before or after `--debug-parse`
+                (Repl, "--debug-parse") => DebugParse(String::new()),
+                (Eval(eval), "--debug-parse") => DebugParse(eval),
+                (DebugParse(input), arg) if input.is_empty() => DebugParse(String::from(arg)),
+                (DebugParse(input), arg) => DebugParse(input + " " + arg),
                 // If neither help nor version is requested, evaluate the arguments
                 // Ignore empty arguments, so that `$ fend "" ""` will enter the repl.
                 (Repl, arg) if !arg.trim().is_empty() => Eval(String::from(arg)),
@@ -281,6 +896,77 @@ mod tests {
         assert_eq!(Eval(String::from("1 '+' 1 ")), action!["1 '+' 1 "]);
     }
 
+    #[test]
+    fn annotate_argument_works() {
+        assert_eq!(ArgsAction::Annotate, action!["--annotate"]);
+        assert_eq!(ArgsAction::Annotate, action!["", "--annotate"]);
+        assert_eq!(ArgsAction::Help, action!["--annotate", "--help"]);
+        assert_eq!(ArgsAction::Version, action!["--annotate", "-v"]);
+    }
+
+    #[test]
+    fn report_argument_works() {
+        use ArgsAction::Report;
+        assert_eq!(
+            Report(String::from("a.fend")),
+            action!["--report", "a.fend"]
+        );
+        assert_eq!(
+            Report(String::from("a.fend")),
+            action!["1", "--report", "a.fend"]
+        );
+        assert_eq!(Report(String::new()), action!["--report"]);
+        assert_eq!(ArgsAction::Help, action!["--report", "a.fend", "-h"]);
+    }
+
+    #[test]
+    fn output_argument_works() {
+        let output = |variables: Option<&str>, script: Option<&str>| ArgsAction::Output {
+            variables: variables.map(String::from),
+            script: script.map(String::from),
+        };
+        assert_eq!(
+            output(Some("a,b"), Some("s.fend")),
+            action!["--output", "a,b", "s.fend"]
+        );
+        assert_eq!(output(Some("total"), None), action!["--output", "total"]);
+        assert_eq!(output(None, None), action!["--output"]);
+        assert_eq!(
+            output(Some(""), Some("s.fend")),
+            action!["--output", "", "s.fend"]
+        );
+        assert_eq!(
+            output(Some("total"), Some("s.fend")),
+            action!["--output", "total", "s.fend", "ignored"]
+        );
+        assert_eq!(ArgsAction::Help, action!["--output", "total", "--help"]);
+    }
+
+    #[test]
+    fn config_subcommand_works() {
+        use crate::config_command::ConfigCommand;
+
+        let config = ArgsAction::Config;
+        assert_eq!(config(ConfigCommand::Path), action!["config", "path"]);
+        assert_eq!(config(ConfigCommand::Init), action!["config", "init"]);
+        assert_eq!(config(ConfigCommand::Edit), action!["config", "edit", "x"]);
+        assert_eq!(ArgsAction::Help, action!["config", "path", "--help"]);
+        assert_eq!(
+            ArgsAction::Eval(String::from("1 config path")),
+            action!["1", "config", "path"]
+        );
+        assert_eq!(ArgsAction::Eval(String::from("config")), action!["config"]);
+    }
+
+    #[test]
+    fn debug_parse_argument_works() {
+        let debug_parse = |input: &str| ArgsAction::DebugParse(String::from(input));
+        assert_eq!(debug_parse("2x + 3"), action!["--debug-parse", "2x", "+ 3"]);
+        assert_eq!(debug_parse("1 2/3"), action!["1 2/3", "--debug-parse"]);
+        assert_eq!(debug_parse(""), action!["--debug-parse"]);
+        assert_eq!(ArgsAction::Help, action!["--debug-parse", "1", "--help"]);
+    }
+
     #[test]
     fn empty_arguments() {
         assert_eq!(ArgsAction::Repl, action![]);
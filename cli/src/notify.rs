@@ -0,0 +1,76 @@
+use std::{io, process};
+
+const TITLE: &str = "fend";
+
+// The message is always passed as a separate argument or
+// environment variable, so it never needs to be escaped.
+
+#[cfg(target_os = "macos")]
+const PROGRAM: &str = "osascript";
+
+#[cfg(target_os = "macos")]
+fn command(message: &str) -> process::Command {
+    let mut command = process::Command::new(PROGRAM);
+    command
+        .arg("-e")
+        .arg("on run argv")
+        .arg("-e")
+        .arg(format!(
+            "display notification (item 1 of argv) with title \"{}\"",
+            TITLE
+        ))
+        .arg("-e")
+        .arg("end run")
+        .arg(message);
+    command
+}
+
+#[cfg(windows)]
+const PROGRAM: &str = "powershell";
+
+#[cfg(windows)]
+fn command(message: &str) -> process::Command {
+    const SCRIPT: &str = "\
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null
+$template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$text = $template.GetElementsByTagName('text')
+$text.Item(0).AppendChild($template.CreateTextNode($env:FEND_NOTIFICATION_TITLE)) > $null
+$text.Item(1).AppendChild($template.CreateTextNode($env:FEND_NOTIFICATION_MESSAGE)) > $null
+$toast = [Windows.UI.Notifications.ToastNotification]::new($template)
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:FEND_NOTIFICATION_TITLE).Show($toast)";
+    let mut command = process::Command::new(PROGRAM);
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("FEND_NOTIFICATION_TITLE", TITLE)
+        .env("FEND_NOTIFICATION_MESSAGE", message);
+    command
+}
+
+// freedesktop.org notifications (Linux, BSD etc.)
+#[cfg(not(any(target_os = "macos", windows)))]
+const PROGRAM: &str = "notify-send";
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command(message: &str) -> process::Command {
+    let mut command = process::Command::new(PROGRAM);
+    command.args(["--app-name", TITLE, TITLE, message]);
+    command
+}
+
+/// Shows a desktop notification with the given message
+pub fn send(message: &str) -> io::Result<()> {
+    let status = command(message)
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("could not run `{}`: {}", PROGRAM, e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("notification command failed ({})", status),
+        ))
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{context, interrupt};
+use std::fs;
+
+/// Evaluates every statement in the given script file, and prints a Markdown
+/// document containing each statement and its result. Comments on their own
+/// line become prose between the calculations.
+pub fn run(path: &str, context: &context::Context<'_>) -> i32 {
+    if path.is_empty() {
+        eprintln!("Error: --report requires a file name");
+        return 1;
+    }
+    let script = match fs::read_to_string(path) {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("Error: failed to read {}: {}", path, e);
+            return 1;
+        }
+    };
+    let mut success = true;
+    let markdown = report(&script, |statement| {
+        match context.eval(statement, true, &interrupt::Never::default()) {
+            Ok(res) => Ok(res.get_main_result().to_string()),
+            Err(msg) => {
+                success = false;
+                Err(msg)
+            }
+        }
+    });
+    print!("{}", markdown);
+    i32::from(!success)
+}
+
+/// Converts a script into Markdown. Consecutive statements are grouped into
+/// a code block, with the result of each statement on the following line.
+fn report(script: &str, mut eval: impl FnMut(&str) -> Result<String, String>) -> String {
+    let mut result = String::new();
+    let mut in_code_block = false;
+    let mut previous_line_blank = true;
+    for line in script.lines() {
+        let line = line.trim_end();
+        if let Some(comment) = line.trim_start().strip_prefix('#') {
+            if in_code_block {
+                result.push_str("```\n\n");
+                in_code_block = false;
+            }
+            // consecutive comments are joined into a single paragraph
+            result.push_str(comment.strip_prefix(' ').unwrap_or(comment));
+            result.push('\n');
+            previous_line_blank = false;
+        } else if line.trim().is_empty() {
+            if in_code_block {
+                result.push_str("```\n");
+                in_code_block = false;
+            }
+            if !previous_line_blank {
+                result.push('\n');
+            }
+            previous_line_blank = true;
+        } else {
+            if !in_code_block {
+                if !previous_line_blank {
+                    result.push('\n');
+                }
+                result.push_str("```\n");
+                in_code_block = true;
+            }
+            result.push_str(line);
+            result.push('\n');
+            match eval(line) {
+                Ok(value) => {
+                    result.push_str("= ");
+                    result.push_str(&value);
+                }
+                Err(msg) => {
+                    result.push_str("Error: ");
+                    result.push_str(&msg);
+                }
+            }
+            result.push('\n');
+            previous_line_blank = false;
+        }
+    }
+    if in_code_block {
+        result.push_str("```\n");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::report;
+
+    #[test]
+    fn report_script() {
+        let eval = |statement: &str| match statement {
+            "a = 2 m" => Ok("2 m".to_string()),
+            "a * 3" => Ok("6 m".to_string()),
+            _ => Err("unknown identifier 'b'".to_string()),
+        };
+        assert_eq!(
+            report(
+                "# Beam length\n# in metres\na = 2 m\na * 3\n\n# Unknown:\nb\n",
+                eval
+            ),
+            "Beam length\nin metres\n\n```\na = 2 m\n= 2 m\na * 3\n= 6 m\n```\n\nUnknown:\n\n```\nb\nError: unknown identifier 'b'\n```\n"
+        );
+        assert_eq!(report("", eval), "");
+        assert_eq!(report("#Only prose", eval), "Only prose\n");
+    }
+}
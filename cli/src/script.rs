@@ -0,0 +1,91 @@
+use crate::{context, interrupt, json, OutputFormat};
+use std::{fs, io};
+
+/// Evaluates a script file line by line (or stdin if `path` is empty), then
+/// prints the final values of the given comma-separated variables, either one
+/// per line or as a JSON object.
+pub fn run(
+    path: &str,
+    variables: &str,
+    format: OutputFormat,
+    context: &context::Context<'_>,
+) -> i32 {
+    let variables: Vec<_> = variables
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if variables.is_empty() {
+        eprintln!("Error: --output requires a comma-separated list of variables");
+        return 1;
+    }
+    let script = if path.is_empty() {
+        let mut script = String::new();
+        io::Read::read_to_string(&mut io::stdin(), &mut script).map(|_| script)
+    } else {
+        fs::read_to_string(path)
+    };
+    let script = match script {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("Error: failed to read {}: {}", path, e);
+            return 1;
+        }
+    };
+    let int = interrupt::Never::default();
+    for (i, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Err(msg) = context.eval(line, true, &int) {
+            eprintln!("Error: line {}: {}", i + 1, msg);
+            return 1;
+        }
+    }
+    let mut values = vec![];
+    for name in &variables {
+        match context.eval(name, false, &int) {
+            Ok(res) => values.push(res.get_main_result().to_string()),
+            Err(msg) => {
+                eprintln!("Error: {}: {}", name, msg);
+                return 1;
+            }
+        }
+    }
+    if format == OutputFormat::Json {
+        println!("{}", to_json(&variables, &values));
+    } else {
+        for value in values {
+            println!("{}", value);
+        }
+    }
+    0
+}
+
+/// Formats the variables as a JSON object with string values
+fn to_json(names: &[&str], values: &[String]) -> String {
+    let members: Vec<_> = names
+        .iter()
+        .zip(values)
+        .map(|(name, value)| format!("{}: {}", json::string(name), json::string(value)))
+        .collect();
+    format!("{{{}}}", members.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_json;
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            to_json(
+                &["total", "name"],
+                &["8 kN".to_string(), "\"a\\b\"\n\u{1}".to_string()]
+            ),
+            r#"{"total": "8 kN", "name": "\"a\\b\"\n\u0001"}"#
+        );
+        assert_eq!(to_json(&[], &[]), "{}");
+    }
+}
@@ -15,10 +15,20 @@ pub fn atty_stdin() -> bool {
     atty::is(atty::Stream::Stdin)
 }
 
+pub fn width() -> Option<usize> {
+    // returns the width of the terminal (if stdout is a tty)
+    rustyline::Editor::<()>::new()
+        .dimensions()
+        .map(|(columns, _)| columns)
+}
+
 pub struct PromptState<'a> {
     rl: rustyline::Editor<helper::Helper<'a>>,
     config: &'a config::Config,
+    context: context::Context<'a>,
     history_path: Option<path::PathBuf>,
+    // the number of results so far, for the `{n}` placeholder
+    results: usize,
 }
 
 pub fn init_prompt<'a>(
@@ -30,10 +40,20 @@ pub fn init_prompt<'a>(
             .history_ignore_space(true)
             .auto_add_history(true)
             .max_history_size(config.max_history_size)
+            .history_ignore_dups(config.history_ignore_duplicates)
             .build(),
     );
+    // incremental search through previous calculations
+    rl.bind_sequence(
+        rustyline::KeyEvent::ctrl('R'),
+        rustyline::Cmd::ReverseSearchHistory,
+    );
     rl.set_helper(Some(helper::Helper::new(context.clone(), config)));
-    let history_path = file_paths::get_history_file_location();
+    let history_path = if config.save_history {
+        file_paths::get_history_file_location()
+    } else {
+        None
+    };
     if let Some(history_path) = &history_path {
         if rl.load_history(history_path.as_path()).is_err() {
             // No previous history
@@ -42,7 +62,9 @@ pub fn init_prompt<'a>(
     PromptState {
         rl,
         config,
+        context: context.clone(),
         history_path,
+        results: 0,
     }
 }
 
@@ -60,9 +82,66 @@ fn save_history(rl: &mut rustyline::Editor<helper::Helper<'_>>, path: &Option<pa
     }
 }
 
+/// Replaces the placeholders `{precision}` and `{n}` in a prompt
+fn expand_prompt(prompt: &str, precision: usize, n: usize) -> String {
+    prompt
+        .replace("{precision}", &precision.to_string())
+        .replace("{n}", &n.to_string())
+}
+
 impl PromptState<'_> {
+    /// Updates the `{n}` placeholder after a calculation has a result
+    pub fn count_result(&mut self) {
+        self.results += 1;
+    }
+
+    fn prompt(&self) -> String {
+        expand_prompt(
+            &self.config.prompt,
+            self.context.precision(),
+            self.results + 1,
+        )
+    }
+
     pub fn read_line(&mut self) -> Result<String, ReadLineError> {
-        let res = self.rl.readline(self.config.prompt.as_str());
+        let prompt = self.prompt();
+        self.read_line_with_prompt(prompt.as_str())
+    }
+
+    pub fn read_line_with_prompt(&mut self, prompt: &str) -> Result<String, ReadLineError> {
+        let res = self.rl.readline(prompt);
+        self.handle_result(res)
+    }
+
+    /// Reads the next line of an input that continues over several lines,
+    /// with the `continuation-prompt` setting, or otherwise a prompt like
+    /// `. ` that is as wide as the normal prompt
+    pub fn read_continuation_line(&mut self) -> Result<String, ReadLineError> {
+        let prompt = if let Some(prompt) = &self.config.continuation_prompt {
+            expand_prompt(prompt, self.context.precision(), self.results + 1)
+        } else {
+            let prompt = self.prompt();
+            let width = prompt.trim_end().chars().count();
+            format!(
+                "{}{}",
+                ".".repeat(width),
+                &prompt[prompt.trim_end().len()..]
+            )
+        };
+        self.read_line_with_prompt(prompt.as_str())
+    }
+
+    /// Reads a line that is already filled in with `initial`, which the
+    /// user can accept by pressing Enter or edit first
+    pub fn read_line_with_initial(&mut self, initial: &str) -> Result<String, ReadLineError> {
+        let prompt = self.prompt();
+        let res = self
+            .rl
+            .readline_with_initial(prompt.as_str(), (initial, ""));
+        self.handle_result(res)
+    }
+
+    fn handle_result(&mut self, res: rustyline::Result<String>) -> Result<String, ReadLineError> {
         save_history(&mut self.rl, &self.history_path);
         match res {
             Ok(line) => Ok(line),
@@ -72,3 +151,16 @@ impl PromptState<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::expand_prompt;
+
+    #[test]
+    fn prompt_placeholders() {
+        assert_eq!(expand_prompt("> ", 10, 1), "> ");
+        assert_eq!(expand_prompt("In [{n}]: ", 10, 12), "In [12]: ");
+        assert_eq!(expand_prompt("[{precision}] {n}> ", 30, 2), "[30] 2> ");
+        assert_eq!(expand_prompt("{other} > ", 10, 1), "{other} > ");
+    }
+}
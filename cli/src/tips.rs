@@ -0,0 +1,125 @@
+use crate::file_paths;
+use std::fs;
+
+// implements the tips that are shown when the REPL starts (if the `show-tips`
+// setting is enabled) and the `:tips` command. The index of the next tip is
+// stored in the state directory, so that each session shows a different tip.
+
+pub struct Tip {
+    pub topic: &'static str,
+    pub text: &'static str,
+    pub example: &'static str,
+}
+
+pub const TIPS: &[Tip] = &[
+    Tip {
+        topic: "units",
+        text: "Convert between units with `to`, `as` or `in`",
+        example: "5 feet to m",
+    },
+    Tip {
+        topic: "mixed units",
+        text: "Convert to several units at once by joining them with `+`",
+        example: "1.87 m to ft+in",
+    },
+    Tip {
+        topic: "readable units",
+        text: "`to human` picks the most readable unit for a number",
+        example: "123456789 bytes to human",
+    },
+    Tip {
+        topic: "variables",
+        text: "Assign variables with `=`, and separate statements with `;`",
+        example: "a = 4 kg; a * 9.81 m/s^2",
+    },
+    Tip {
+        topic: "lambdas",
+        text: "Define your own functions with `:` or `=>`",
+        example: "f = x: x^2; f 3",
+    },
+    Tip {
+        topic: "dates",
+        text: "Convert strings to dates, and get their day of the week",
+        example: "day_of_week of ('2024-03-01' to date)",
+    },
+    Tip {
+        topic: "bases",
+        text: "Use `0x`, `0o` and `0b` for other bases, and `to base <n>` to convert",
+        example: "0xff to base 2",
+    },
+    Tip {
+        topic: "fractions",
+        text: "Write recurring digits in parentheses, and convert them with `to fraction`",
+        example: "0.(3) to fraction",
+    },
+    Tip {
+        topic: "dice",
+        text: "Roll dice with D&D-style syntax to see the distribution of results",
+        example: "2d6",
+    },
+    Tip {
+        topic: "temperatures",
+        text: "Subtracting temperatures gives a difference in `deltaC` or `deltaF`",
+        example: "20 \u{b0}C - 68 \u{b0}F",
+    },
+    Tip {
+        topic: "conversion wizard",
+        text: "Convert between units step by step with the conversion wizard",
+        example: ":convert",
+    },
+    Tip {
+        topic: "bookmarks",
+        text: "Save the last calculation with `:bookmark <name>`, and rerun it with `@<name>`",
+        example: ":bookmark fuel",
+    },
+];
+
+fn format_tip(tip: &Tip) -> String {
+    format!("{}, e.g. `{}`", tip.text, tip.example)
+}
+
+fn read_next_index() -> usize {
+    file_paths::get_tips_file_location()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| contents.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Prints the next tip, and advances to the following tip for the
+/// next session
+pub fn show_next() {
+    let index = read_next_index() % TIPS.len();
+    println!("Tip: {}", format_tip(&TIPS[index]));
+    println!("Type `:tips` to see all tips.");
+    if let Some(path) = file_paths::get_tips_file_location() {
+        // failing to save the index only means the same tip is shown again
+        let _ = fs::write(path, ((index + 1) % TIPS.len()).to_string());
+    }
+}
+
+/// Implements the `:tips` command
+pub fn print_list() {
+    for (i, tip) in TIPS.iter().enumerate() {
+        println!("{:>2}. {}: {}", i + 1, tip.topic, format_tip(tip));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TIPS;
+
+    #[test]
+    fn tip_examples_are_valid() {
+        for tip in TIPS {
+            if tip.example.starts_with(':') {
+                continue;
+            }
+            let mut context = fend_core::Context::new();
+            assert!(
+                fend_core::evaluate(tip.example, &mut context).is_ok(),
+                "failed to evaluate `{}`",
+                tip.example
+            );
+        }
+    }
+}
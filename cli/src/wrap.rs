@@ -0,0 +1,72 @@
+use unicode_width::UnicodeWidthChar;
+
+/// Inserted at every line break, so that continuation lines are indented
+pub const LINE_BREAK: &str = "\n  ";
+
+/// Returns the byte offsets at which `text` needs to be broken so that
+/// no line is wider than `width` terminal columns, taking the indentation
+/// of continuation lines into account. Lines are broken after a space where
+/// possible, and otherwise between any two characters. Wide characters
+/// count as two columns, and combining characters are never separated from
+/// the character before them.
+pub fn line_breaks(text: &str, width: usize) -> Vec<usize> {
+    let indent = LINE_BREAK.len() - 1;
+    let mut breaks = vec![];
+    if width < indent + 2 {
+        return breaks;
+    }
+    let mut max_width = width;
+    let mut line_width = 0;
+    // the byte offset after the last space in the current line, and the
+    // width of the line up to that point
+    let mut last_space: Option<(usize, usize)> = None;
+    for (i, ch) in text.char_indices() {
+        if ch == '\n' {
+            max_width = width;
+            line_width = 0;
+            last_space = None;
+            continue;
+        }
+        let ch_width = ch.width().unwrap_or(0);
+        while ch_width > 0 && line_width + ch_width > max_width {
+            if let Some((offset, width_before)) = last_space.take() {
+                breaks.push(offset);
+                line_width -= width_before;
+            } else {
+                breaks.push(i);
+                line_width = 0;
+            }
+            max_width = width - indent;
+        }
+        line_width += ch_width;
+        if ch == ' ' {
+            last_space = Some((i + 1, line_width));
+        }
+    }
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::line_breaks;
+
+    #[test]
+    fn break_long_lines() {
+        assert_eq!(line_breaks("12345", 10), vec![]);
+        assert_eq!(line_breaks("1234567890", 10), vec![]);
+        // continuation lines are indented by two spaces
+        assert_eq!(line_breaks("12345678901234567890", 10), vec![10, 18]);
+        assert_eq!(line_breaks("1234 6789 1234", 10), vec![10]);
+        assert_eq!(line_breaks("12 45678 0123", 10), vec![9]);
+        assert_eq!(line_breaks("12345\n12345678901", 10), vec![16]);
+        assert_eq!(line_breaks("12345", 2), vec![]);
+    }
+
+    #[test]
+    fn break_wide_and_combining_characters() {
+        // each of these characters takes up two columns
+        assert_eq!(line_breaks("日本語日本語", 6), vec![9, 15]);
+        // the combining accent stays on the first line
+        assert_eq!(line_breaks("12345e\u{301}6", 6), vec![8]);
+    }
+}
@@ -0,0 +1,26 @@
+//! Timings for the factorial and integer power fast paths.
+//!
+//! Run with `cargo bench -p fend-core`. The same file can be run against an
+//! older checkout to compare the results. `(3/2)^1000` is converted to a
+//! fraction, since printing it as a decimal takes far longer than computing it.
+
+use std::time::{Duration, Instant};
+
+const CASES: &[&str] = &["1000!", "100!/98!", "(3/2)^1000 to fraction", "300!/299!"];
+
+fn time_evaluation(input: &str) -> Duration {
+    let mut context = fend_core::Context::new();
+    let start = Instant::now();
+    fend_core::evaluate(input, &mut context).unwrap();
+    start.elapsed()
+}
+
+fn main() {
+    for input in CASES {
+        // warm up
+        time_evaluation(input);
+        let mut timings = (0..10).map(|_| time_evaluation(input)).collect::<Vec<_>>();
+        timings.sort();
+        println!("{:<24} median {:?}", input, timings[timings.len() / 2]);
+    }
+}
@@ -2,9 +2,21 @@ use crate::error::{FendError, Interrupt};
 use crate::eval::evaluate_to_value;
 use crate::ident::Ident;
 use crate::interrupt::test_int;
-use crate::num::{Base, FormattingStyle, Number};
+use crate::limits;
+use crate::num::{Base, FormattingStyle, Number, DEFAULT_PRECISION, MAX_PRECISION};
 use crate::scope::Scope;
+use crate::units::{self, PrefixRule};
+use crate::value::dimension::Dimension;
+use crate::value::duration::Duration;
+use crate::value::matrix::Matrix;
+use crate::value::mixed_units::MixedUnits;
+use crate::value::polar::PolarForm;
+use crate::value::polynomial::Polynomial;
+use crate::value::quaternion::Quaternion;
+use crate::value::uncertain::UncertainValue;
 use crate::value::{ApplyMulHandling, BuiltInFunction, Value};
+use crate::LanguageFeature;
+use std::cmp::Ordering;
 use std::fmt;
 use std::sync::Arc;
 
@@ -17,6 +29,8 @@ pub(crate) enum Bop {
     Div,
     Mod,
     Pow,
+    Polar,
+    PlusMinus,
 }
 
 impl fmt::Display for Bop {
@@ -29,15 +43,32 @@ impl fmt::Display for Bop {
             Self::Div => write!(f, "/"),
             Self::Mod => write!(f, " mod "),
             Self::Pow => write!(f, "^"),
+            Self::Polar => write!(f, " \u{2220} "),
+            Self::PlusMinus => write!(f, " \u{b1} "),
         }
     }
 }
 
+/// A lambda parameter and its default value, e.g. `y=2` in `\(x, y=2).x^y`
+pub(crate) type Param = (Ident, Option<Expr>);
+
 #[derive(Clone, Debug)]
 pub(crate) enum Expr {
     Literal(Value),
     Ident(Ident),
+    // a template placeholder like `?a`, see `Expr::into_template`
+    Placeholder(Ident),
     Parens(Box<Expr>),
+    List(Vec<Expr>),
+    // a list of lists, e.g. `[[1, 2], [3, 4]]`, which is evaluated
+    // as a matrix if every row contains the same number of numbers
+    Matrix(Vec<Expr>),
+    // an inclusive range of numbers, e.g. `1..10`
+    Range(Box<Expr>, Box<Expr>),
+    // `a == b` (true) or `a != b` (false)
+    Equality(bool, Box<Expr>, Box<Expr>),
+    // `a < b` (Less, false), `a <= b` (Less, true), `a > b` or `a >= b`
+    Comparison(Ordering, bool, Box<Expr>, Box<Expr>),
     UnaryMinus(Box<Expr>),
     UnaryPlus(Box<Expr>),
     UnaryDiv(Box<Expr>),
@@ -52,10 +83,22 @@ pub(crate) enum Expr {
 
     As(Box<Expr>, Box<Expr>),
     Fn(Ident, Box<Expr>),
+    // the body of a lambda with a parameter list like `\(x, y=2).x^y`, which
+    // is stored as nested lambdas `\x.\y.body` ending in this expression,
+    // recording every parameter and its default value (if any)
+    Params(Vec<Param>, Box<Expr>),
 
     Of(Ident, Box<Expr>),
 
     Assign(Ident, Box<Expr>),
+    // `set <setting> <value>`, e.g. `set precision 100`
+    Set(Ident, Box<Expr>),
+    // `unit <singular> [<plural>] [= <definition>]`, optionally preceded
+    // by `long` or `short` to allow prefixes, e.g. `unit furlong = 220 yards`
+    DefineUnit(PrefixRule, Ident, Ident, Option<Box<Expr>>),
+    // `takes <rate>`, e.g. `takes 45 min per item`, which multiplies the
+    // final result by the rate if it is a matching count like `32 items`
+    Takes(Box<Expr>),
     Statements(Box<Expr>, Box<Expr>),
 }
 
@@ -69,7 +112,37 @@ impl<'a> Expr {
             Self::Literal(Value::String(s)) => format!(r#""{}""#, s.as_ref()),
             Self::Literal(v) => v.format_to_plain_string(0, ctx, int)?,
             Self::Ident(ident) => ident.to_string(),
+            Self::Placeholder(ident) => format!("?{}", ident),
             Self::Parens(x) => format!("({})", x.format(ctx, int)?),
+            Self::List(elements) | Self::Matrix(elements) => {
+                let mut res = "[".to_string();
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        res.push_str(", ");
+                    }
+                    res.push_str(&element.format(ctx, int)?);
+                }
+                res.push(']');
+                res
+            }
+            Self::Range(a, b) => format!("({}..{})", a.format(ctx, int)?, b.format(ctx, int)?),
+            Self::Equality(equal, a, b) => format!(
+                "({} {} {})",
+                a.format(ctx, int)?,
+                if *equal { "==" } else { "!=" },
+                b.format(ctx, int)?
+            ),
+            Self::Comparison(ordering, or_equal, a, b) => format!(
+                "({} {}{} {})",
+                a.format(ctx, int)?,
+                if *ordering == Ordering::Less {
+                    "<"
+                } else {
+                    ">"
+                },
+                if *or_equal { "=" } else { "" },
+                b.format(ctx, int)?
+            ),
             Self::UnaryMinus(x) => format!("(-{})", x.format(ctx, int)?),
             Self::UnaryPlus(x) => format!("(+{})", x.format(ctx, int)?),
             Self::UnaryDiv(x) => format!("(/{})", x.format(ctx, int)?),
@@ -82,8 +155,11 @@ impl<'a> Expr {
                 format!("({} {})", a.format(ctx, int)?, b.format(ctx, int)?)
             }
             Self::As(a, b) => format!("({} as {})", a.format(ctx, int)?, b.format(ctx, int)?),
+            Self::Params(params, body) => Self::format_params(params, body, ctx, int)?,
             Self::Fn(a, b) => {
-                if a.as_str().contains('.') {
+                if let Some((params, body)) = b.params() {
+                    Self::format_params(params, body, ctx, int)?
+                } else if a.as_str().contains('.') {
                     format!("({}:{})", a, b.format(ctx, int)?)
                 } else {
                     format!("\\{}.{}", a, b.format(ctx, int)?)
@@ -91,9 +167,307 @@ impl<'a> Expr {
             }
             Self::Of(a, b) => format!("{} of {}", a, b.format(ctx, int)?),
             Self::Assign(a, b) => format!("{} = {}", a, b.format(ctx, int)?),
+            Self::Set(a, b) => format!("set {} {}", a, b.format(ctx, int)?),
+            Self::Takes(rate) => format!("takes {}", rate.format(ctx, int)?),
+            Self::DefineUnit(rule, singular, plural, definition) => {
+                let mut res = match rule {
+                    PrefixRule::LongPrefix => "prefix ",
+                    PrefixRule::ShortPrefix => "prefix short ",
+                    _ => "unit ",
+                }
+                .to_string();
+                if let Some(keyword) = rule.keyword() {
+                    res.push_str(keyword);
+                    res.push(' ');
+                }
+                res.push_str(singular.as_str());
+                if plural.as_str() != singular.as_str() {
+                    res.push(' ');
+                    res.push_str(plural.as_str());
+                }
+                if let Some(definition) = definition {
+                    res.push_str(" = ");
+                    res.push_str(&definition.format(ctx, int)?);
+                }
+                res
+            }
             Self::Statements(a, b) => format!("{}; {}", a.format(ctx, int)?, b.format(ctx, int)?),
         })
     }
+
+    /// Writes this expression as a tree with one node per line, indented by
+    /// `depth` levels, e.g. to see how operators and implicit
+    /// multiplication were parsed. Used by `debug_parse`.
+    pub(crate) fn describe<I: Interrupt>(
+        &self,
+        depth: usize,
+        res: &mut String,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<(), FendError> {
+        let (label, children): (String, Vec<&Self>) = match self {
+            Self::Literal(v) => (
+                format!("Literal {}", Self::Literal(v.clone()).format(ctx, int)?),
+                vec![],
+            ),
+            Self::Ident(ident) => (format!("Ident {}", ident.as_str()), vec![]),
+            Self::Placeholder(ident) => (format!("Placeholder ?{}", ident.as_str()), vec![]),
+            Self::Parens(x) => ("Parens".to_string(), vec![x]),
+            Self::List(elements) => ("List".to_string(), elements.iter().collect()),
+            Self::Matrix(rows) => ("Matrix".to_string(), rows.iter().collect()),
+            Self::Range(a, b) => ("Range".to_string(), vec![a, b]),
+            Self::Equality(equal, a, b) => (
+                format!("Equality {}", if *equal { "==" } else { "!=" }),
+                vec![a, b],
+            ),
+            Self::Comparison(ordering, or_equal, a, b) => (
+                format!(
+                    "Comparison {}{}",
+                    if *ordering == Ordering::Less {
+                        "<"
+                    } else {
+                        ">"
+                    },
+                    if *or_equal { "=" } else { "" }
+                ),
+                vec![a, b],
+            ),
+            Self::UnaryMinus(x) => ("UnaryMinus".to_string(), vec![x]),
+            Self::UnaryPlus(x) => ("UnaryPlus".to_string(), vec![x]),
+            Self::UnaryDiv(x) => ("UnaryDiv".to_string(), vec![x]),
+            Self::Factorial(x) => ("Factorial".to_string(), vec![x]),
+            Self::Bop(op, a, b) => (format!("Bop {:?}", op), vec![a, b]),
+            Self::Apply(a, b) => ("Apply".to_string(), vec![a, b]),
+            Self::ApplyFunctionCall(a, b) => ("ApplyFunctionCall".to_string(), vec![a, b]),
+            Self::ApplyMul(a, b) => ("ApplyMul".to_string(), vec![a, b]),
+            Self::As(a, b) => ("As".to_string(), vec![a, b]),
+            Self::Fn(param, body) => (format!("Fn {}", param.as_str()), vec![body]),
+            Self::Params(params, body) => {
+                let mut label = "Params".to_string();
+                for (i, (param, default)) in params.iter().enumerate() {
+                    label.push_str(if i == 0 { " " } else { ", " });
+                    label.push_str(param.as_str());
+                    if let Some(default) = default {
+                        label.push('=');
+                        label.push_str(&default.format(ctx, int)?);
+                    }
+                }
+                (label, vec![body])
+            }
+            Self::Of(ident, x) => (format!("Of {}", ident.as_str()), vec![x]),
+            Self::Assign(ident, x) => (format!("Assign {}", ident.as_str()), vec![x]),
+            Self::Set(setting, x) => (format!("Set {}", setting.as_str()), vec![x]),
+            Self::DefineUnit(rule, singular, plural, definition) => (
+                format!(
+                    "DefineUnit {:?} {} {}",
+                    rule,
+                    singular.as_str(),
+                    plural.as_str()
+                ),
+                definition.iter().map(AsRef::as_ref).collect(),
+            ),
+            Self::Takes(rate) => ("Takes".to_string(), vec![rate]),
+            Self::Statements(a, b) => ("Statements".to_string(), vec![a, b]),
+        };
+        res.push_str(&"  ".repeat(depth));
+        res.push_str(&label);
+        res.push('\n');
+        for child in children {
+            child.describe(depth + 1, res, ctx, int)?;
+        }
+        Ok(())
+    }
+
+    /// If this is (the body of) a lambda with a parameter list, like
+    /// `\(x, y=2).x^y`, returns the parameters and the innermost body
+    pub(crate) fn params(&self) -> Option<(&[Param], &Expr)> {
+        let mut expr = self;
+        while let Self::Fn(_, body) = expr {
+            expr = body;
+        }
+        match expr {
+            Self::Params(params, body) => Some((params, body)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn format_params<I: Interrupt>(
+        params: &[Param],
+        body: &Expr,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<String, FendError> {
+        let mut res = "\\(".to_string();
+        for (i, (param, default)) in params.iter().enumerate() {
+            if i != 0 {
+                res.push_str(", ");
+            }
+            res.push_str(param.as_str());
+            if let Some(default) = default {
+                res.push('=');
+                res.push_str(&default.format(ctx, int)?);
+            }
+        }
+        res.push_str(").");
+        res.push_str(&body.format(ctx, int)?);
+        Ok(res)
+    }
+
+    /// Turns an expression containing placeholders, like `?a * ?b / 100`,
+    /// into nested lambdas with one parameter per placeholder (in order of
+    /// first appearance), i.e. `\a.\b.a * b / 100`. These can then be
+    /// called with named arguments, e.g. `tpl(a=250, b=12)`.
+    pub(crate) fn into_template(mut self) -> Self {
+        let mut params = vec![];
+        self.replace_placeholders(&mut params);
+        params
+            .into_iter()
+            .rev()
+            .fold(self, |body, param| Self::Fn(param, Box::new(body)))
+    }
+
+    fn replace_placeholders(&mut self, params: &mut Vec<Ident>) {
+        match self {
+            Self::Placeholder(ident) => {
+                if !params.iter().any(|p| p.as_str() == ident.as_str()) {
+                    params.push(ident.clone());
+                }
+                *self = Self::Ident(ident.clone());
+            }
+            Self::Literal(_) | Self::Ident(_) => (),
+            Self::Parens(x)
+            | Self::UnaryMinus(x)
+            | Self::UnaryPlus(x)
+            | Self::UnaryDiv(x)
+            | Self::Factorial(x)
+            | Self::Fn(_, x)
+            | Self::Of(_, x)
+            | Self::Assign(_, x)
+            | Self::Set(_, x)
+            | Self::Takes(x)
+            | Self::Params(_, x) => x.replace_placeholders(params),
+            Self::DefineUnit(_, _, _, definition) => {
+                if let Some(definition) = definition {
+                    definition.replace_placeholders(params);
+                }
+            }
+            Self::List(elements) | Self::Matrix(elements) => {
+                for element in elements {
+                    element.replace_placeholders(params);
+                }
+            }
+            Self::Range(a, b)
+            | Self::Equality(_, a, b)
+            | Self::Comparison(_, _, a, b)
+            | Self::Bop(_, a, b)
+            | Self::Apply(a, b)
+            | Self::ApplyFunctionCall(a, b)
+            | Self::ApplyMul(a, b)
+            | Self::As(a, b)
+            | Self::Statements(a, b) => {
+                a.replace_placeholders(params);
+                b.replace_placeholders(params);
+            }
+        }
+    }
+
+    /// Returns an error if this expression uses a language feature that
+    /// has been disabled with `Context::disable_language_feature`
+    pub(crate) fn check_language_features(
+        &self,
+        context: &crate::Context,
+    ) -> Result<(), FendError> {
+        let feature = match self {
+            Self::Fn(_, _) | Self::Params(_, _) | Self::Placeholder(_) => {
+                Some(LanguageFeature::Lambdas)
+            }
+            Self::Assign(_, _) | Self::DefineUnit(_, _, _, _) => Some(LanguageFeature::Assignments),
+            Self::Literal(Value::String(_)) => Some(LanguageFeature::Strings),
+            Self::Ident(ident)
+                if matches!(
+                    ident.as_str(),
+                    "today"
+                        | "tomorrow"
+                        | "yesterday"
+                        | "now"
+                        | "date"
+                        | "between"
+                        | "easter"
+                        | "is_holiday"
+                        | "add_workdays"
+                        | "moon_phase"
+                        | "julian_day"
+                ) =>
+            {
+                Some(LanguageFeature::Dates)
+            }
+            Self::As(_, b)
+                if matches!(&**b, Self::Ident(ident)
+                    if matches!(ident.as_str(), "date" | "time" | "datetime" | "iso" | "gregorian" | "julian"
+                        | "hebrew" | "islamic")) =>
+            {
+                Some(LanguageFeature::Dates)
+            }
+            Self::Literal(value) if value.as_dyn::<crate::date::Time>().is_some() => {
+                Some(LanguageFeature::Dates)
+            }
+            Self::Apply(a, b) | Self::ApplyMul(a, b)
+                if matches!((&**a, &**b), (Self::Ident(a), Self::Ident(b))
+                    if crate::date::is_relative_weekday(a.as_str(), b.as_str())) =>
+            {
+                Some(LanguageFeature::Dates)
+            }
+            _ => None,
+        };
+        if let Some(feature) = feature {
+            if context.disabled_language_features.contains(&feature) {
+                return Err(FendError::LanguageFeatureDisabled(feature));
+            }
+        }
+        match self {
+            Self::Literal(_) | Self::Ident(_) | Self::Placeholder(_) => Ok(()),
+            Self::Parens(x)
+            | Self::UnaryMinus(x)
+            | Self::UnaryPlus(x)
+            | Self::UnaryDiv(x)
+            | Self::Factorial(x)
+            | Self::Fn(_, x)
+            | Self::Of(_, x)
+            | Self::Assign(_, x)
+            | Self::Set(_, x)
+            | Self::Takes(x) => x.check_language_features(context),
+            Self::Params(params, body) => {
+                for (_, default) in params {
+                    if let Some(default) = default {
+                        default.check_language_features(context)?;
+                    }
+                }
+                body.check_language_features(context)
+            }
+            Self::DefineUnit(_, _, _, definition) => match definition {
+                Some(definition) => definition.check_language_features(context),
+                None => Ok(()),
+            },
+            Self::List(elements) | Self::Matrix(elements) => {
+                for element in elements {
+                    element.check_language_features(context)?;
+                }
+                Ok(())
+            }
+            Self::Range(a, b)
+            | Self::Equality(_, a, b)
+            | Self::Comparison(_, _, a, b)
+            | Self::Bop(_, a, b)
+            | Self::Apply(a, b)
+            | Self::ApplyFunctionCall(a, b)
+            | Self::ApplyMul(a, b)
+            | Self::As(a, b)
+            | Self::Statements(a, b) => {
+                a.check_language_features(context)?;
+                b.check_language_features(context)
+            }
+        }
+    }
 }
 
 /// returns true if rhs is '-1' or '(-1)'
@@ -116,12 +490,29 @@ fn should_compute_inverse(rhs: &Expr) -> bool {
     false
 }
 
-#[allow(clippy::too_many_lines)]
 pub(crate) fn evaluate<I: Interrupt>(
     expr: Expr,
     scope: Option<Arc<Scope>>,
     context: &mut crate::Context,
     int: &I,
+) -> Result<Value, FendError> {
+    if let Some(max_recursion) = context.limits.max_recursion {
+        if context.recursion_depth >= max_recursion {
+            return Err(FendError::RecursionLimitExceeded);
+        }
+    }
+    context.recursion_depth += 1;
+    let result = evaluate_expr(expr, scope, context, int);
+    context.recursion_depth -= 1;
+    result
+}
+
+#[allow(clippy::too_many_lines)]
+fn evaluate_expr<I: Interrupt>(
+    expr: Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
 ) -> Result<Value, FendError> {
     macro_rules! eval {
         ($e:expr) => {
@@ -130,10 +521,75 @@ pub(crate) fn evaluate<I: Interrupt>(
     }
     test_int(int)?;
     Ok(match expr {
+        // number literals remember their significant figures, which are
+        // only needed if they're being tracked
+        Expr::Literal(Value::Num(n)) if !context.track_significant_figures => {
+            Value::Num(Box::new(n.with_significant_figures(None)))
+        }
         Expr::Literal(v) => v,
-        Expr::Ident(ident) => resolve_identifier(&ident, scope, context, int)?,
+        Expr::Ident(ident) | Expr::Placeholder(ident) => {
+            resolve_identifier(&ident, scope, context, int)?
+        }
         Expr::Parens(x) => eval!(*x)?,
-        Expr::UnaryMinus(x) => eval!(*x)?.handle_num(|x| Ok(-x), Expr::UnaryMinus, scope)?,
+        Expr::List(elements) => {
+            limits::allocate_list_elements(elements.len())?;
+            let mut values = vec![];
+            for element in elements {
+                values.push(eval!(element)?);
+            }
+            Value::List(values)
+        }
+        Expr::Matrix(rows) => {
+            let mut values = vec![];
+            for row in rows {
+                values.push(eval!(row)?);
+            }
+            Matrix::from_list(values, context, int)?
+        }
+        Expr::Range(a, b) => {
+            let start = eval!(*a)?.expect_num()?;
+            let end = eval!(*b)?.expect_num()?;
+            let mut values = vec![];
+            let mut current = start;
+            while current.compare(&end, int)? != std::cmp::Ordering::Greater {
+                test_int(int)?;
+                limits::allocate_list_elements(1)?;
+                values.push(Value::Num(Box::new(current.clone())));
+                current = current.add(1.into(), int)?;
+            }
+            Value::List(values)
+        }
+        Expr::Equality(equal, a, b) => {
+            let lhs = eval!(*a)?;
+            let rhs = eval!(*b)?;
+            (lhs.equals(&rhs, int)? == equal).into()
+        }
+        Expr::Comparison(ordering, or_equal, a, b) => {
+            let lhs = eval!(*a)?;
+            let rhs = eval!(*b)?;
+            let result = lhs.compare(&rhs, int)?;
+            (result == ordering || or_equal && result == Ordering::Equal).into()
+        }
+        Expr::UnaryMinus(x) => {
+            let value = eval!(*x)?;
+            if value.as_dyn::<Polynomial>().is_some() {
+                let minus_one = Value::Num(Box::new(-Number::from(1)));
+                return Polynomial::bop(Bop::Mul, value, minus_one, context, int);
+            }
+            if value.as_dyn::<Quaternion>().is_some() {
+                let minus_one = Value::Num(Box::new(-Number::from(1)));
+                return Quaternion::bop(Bop::Mul, value, minus_one, context, int);
+            }
+            if value.as_dyn::<UncertainValue>().is_some() {
+                let minus_one = Value::Num(Box::new(-Number::from(1)));
+                return UncertainValue::bop(Bop::Mul, value, minus_one, context, int);
+            }
+            if value.as_dyn::<Duration>().is_some() {
+                let minus_one = Value::Num(Box::new(-Number::from(1)));
+                return Duration::bop(Bop::Mul, value, minus_one, context, int);
+            }
+            value.handle_num(|x| Ok(-x), Expr::UnaryMinus, scope)?
+        }
         Expr::UnaryPlus(x) => eval!(*x)?.handle_num(Ok, Expr::UnaryPlus, scope)?,
         Expr::UnaryDiv(x) => {
             eval!(*x)?.handle_num(|x| Number::from(1).div(x, int), Expr::UnaryDiv, scope)?
@@ -141,11 +597,61 @@ pub(crate) fn evaluate<I: Interrupt>(
         Expr::Factorial(x) => {
             eval!(*x)?.handle_num(|x| x.factorial(int), Expr::Factorial, scope)?
         }
-        Expr::Bop(Bop::Plus, a, b) => evaluate_add(eval!(*a)?, eval!(*b)?, scope, int)?,
+        Expr::Bop(Bop::Plus, a, b) => {
+            let (a, b) = (eval!(*a)?, eval!(*b)?);
+            if Matrix::is_operand(&a, &b) {
+                return Matrix::bop(Bop::Plus, a, b, context, int);
+            }
+            if Polynomial::is_operand(&a, &b) {
+                return Polynomial::bop(Bop::Plus, a, b, context, int);
+            }
+            if Quaternion::is_operand(&a, &b) {
+                return Quaternion::bop(Bop::Plus, a, b, context, int);
+            }
+            if UncertainValue::is_operand(&a, &b) {
+                return UncertainValue::bop(Bop::Plus, a, b, context, int);
+            }
+            if crate::date::is_operand(&a, &b) {
+                return crate::date::add(a, b, context, int);
+            }
+            if Duration::is_operand(&a, &b) {
+                return Duration::bop(Bop::Plus, a, b, context, int);
+            }
+            evaluate_add(a, b, scope, context, int)?
+        }
         Expr::Bop(Bop::Minus, a, b) => {
             let a = eval!(*a)?;
             match a {
-                Value::Num(a) => Value::Num(Box::new(a.sub(eval!(*b)?.expect_num()?, int)?)),
+                a @ Value::Dynamic(_) if a.as_dyn::<Matrix>().is_some() => {
+                    Matrix::bop(Bop::Minus, a, eval!(*b)?, context, int)?
+                }
+                a @ Value::Dynamic(_) if a.as_dyn::<Polynomial>().is_some() => {
+                    Polynomial::bop(Bop::Minus, a, eval!(*b)?, context, int)?
+                }
+                a @ Value::Dynamic(_) if a.as_dyn::<Quaternion>().is_some() => {
+                    Quaternion::bop(Bop::Minus, a, eval!(*b)?, context, int)?
+                }
+                a @ Value::Dynamic(_) if a.as_dyn::<UncertainValue>().is_some() => {
+                    UncertainValue::bop(Bop::Minus, a, eval!(*b)?, context, int)?
+                }
+                a @ Value::Dynamic(_) if a.as_dyn::<Duration>().is_some() => {
+                    Duration::bop(Bop::Minus, a, eval!(*b)?, context, int)?
+                }
+                Value::Num(a) => match eval!(*b)? {
+                    b @ Value::Dynamic(_) if b.as_dyn::<Polynomial>().is_some() => {
+                        Polynomial::bop(Bop::Minus, Value::Num(a), b, context, int)?
+                    }
+                    b @ Value::Dynamic(_) if b.as_dyn::<Quaternion>().is_some() => {
+                        Quaternion::bop(Bop::Minus, Value::Num(a), b, context, int)?
+                    }
+                    b @ Value::Dynamic(_) if b.as_dyn::<UncertainValue>().is_some() => {
+                        UncertainValue::bop(Bop::Minus, Value::Num(a), b, context, int)?
+                    }
+                    b @ Value::Dynamic(_) if b.as_dyn::<Duration>().is_some() => {
+                        Duration::bop(Bop::Minus, Value::Num(a), b, context, int)?
+                    }
+                    b => Value::Num(Box::new(evaluate_sub(*a, b.expect_num()?, context, int)?)),
+                },
                 f @ (Value::BuiltInFunction(_) | Value::Fn(_, _, _)) => f.apply(
                     Expr::UnaryMinus(b),
                     ApplyMulHandling::OnlyApply,
@@ -153,11 +659,50 @@ pub(crate) fn evaluate<I: Interrupt>(
                     context,
                     int,
                 )?,
+                // subtracting two date strings like `'2025-01-01' - '2024-03-15'`
+                // gives the number of days between them
+                Value::String(a) => match eval!(*b)? {
+                    b @ (Value::String(_) | Value::Dynamic(_)) => {
+                        if context
+                            .disabled_language_features
+                            .contains(&LanguageFeature::Dates)
+                        {
+                            return Err(FendError::LanguageFeatureDisabled(LanguageFeature::Dates));
+                        }
+                        Value::from(crate::date::Date::parse(a.as_ref())?).sub_dyn(b, context)?
+                    }
+                    _ => return Err(FendError::InvalidOperandsForSubtraction),
+                },
+                a @ Value::Dynamic(_) => a.sub_dyn(eval!(*b)?, context)?,
                 _ => return Err(FendError::InvalidOperandsForSubtraction),
             }
         }
+        Expr::Bop(Bop::Div, a, b)
+            if matches!((&*a, &*b), (Expr::Factorial(_), Expr::Factorial(_))) =>
+        {
+            evaluate_factorial_quotient(*a, *b, scope, context, int)?
+        }
         Expr::Bop(Bop::Pow, a, b) => {
+            // complex exponents are only supported for `e`, e.g. for
+            // phasors like `2 e^(i pi/2)`
+            let is_base_e = is_builtin_e(&a, scope.clone(), context, int);
             let lhs = eval!(*a)?;
+            if lhs.as_dyn::<Matrix>().is_some() {
+                return Matrix::bop(Bop::Pow, lhs, eval!(*b)?, context, int);
+            }
+            if lhs.as_dyn::<Polynomial>().is_some() {
+                return Polynomial::bop(Bop::Pow, lhs, eval!(*b)?, context, int);
+            }
+            if lhs.as_dyn::<Quaternion>().is_some() {
+                return Quaternion::bop(Bop::Pow, lhs, eval!(*b)?, context, int);
+            }
+            if lhs.as_dyn::<UncertainValue>().is_some() {
+                return UncertainValue::bop(Bop::Pow, lhs, eval!(*b)?, context, int);
+            }
+            if let Value::Dynamic(d) = &lhs {
+                return d.pow(eval!(*b)?.expect_num()?.try_as_i64(int)?);
+            }
+            let precision = context.precision;
             if should_compute_inverse(&*b) {
                 let result = match &lhs {
                     Value::BuiltInFunction(f) => Some(f.invert()?),
@@ -168,9 +713,13 @@ pub(crate) fn evaluate<I: Interrupt>(
                     return Ok(res);
                 }
             }
+            let rhs = eval!(*b)?;
+            if !is_base_e && matches!(&rhs, Value::Num(n) if n.is_complex()) {
+                return Err(FendError::ExpComplex);
+            }
             lhs.handle_two_nums(
-                eval!(*b)?,
-                |a, b| a.pow(b, int),
+                rhs,
+                |a, b| a.pow_with_precision(b, precision, int),
                 |a| {
                     |f| {
                         Expr::Bop(
@@ -192,13 +741,83 @@ pub(crate) fn evaluate<I: Interrupt>(
                 scope,
             )?
         }
-        Expr::Bop(bop, a, b) => eval!(*a)?.handle_two_nums(
-            eval!(*b)?,
-            |a, b| a.bop(bop, b, context, int),
-            |a| |f| Expr::Bop(bop, f, Box::new(Expr::Literal(Value::Num(Box::new(a))))),
-            |a| |f| Expr::Bop(bop, Box::new(Expr::Literal(Value::Num(Box::new(a)))), f),
-            scope,
-        )?,
+        Expr::Bop(Bop::PlusMinus, a, b) => {
+            let (value, uncertainty) = (eval!(*a)?, eval!(*b)?);
+            UncertainValue::plus_minus(value, uncertainty, context, int)?
+        }
+        Expr::Bop(bop, a, b) => {
+            let lhs = eval!(*a)?;
+            let rhs = eval!(*b)?;
+            if Matrix::is_operand(&lhs, &rhs) {
+                return Matrix::bop(bop, lhs, rhs, context, int);
+            }
+            if Polynomial::is_operand(&lhs, &rhs) {
+                return Polynomial::bop(bop, lhs, rhs, context, int);
+            }
+            if Quaternion::is_operand(&lhs, &rhs) {
+                // e.g. `quat(i) * quat(j)`, which is parsed as `(quat(i) * quat)(j)`
+                match rhs {
+                    Value::BuiltInFunction(f) => {
+                        return Ok(f.wrap_with_expr(
+                            |f| Expr::Bop(bop, Box::new(Expr::Literal(lhs)), f),
+                            scope,
+                        ));
+                    }
+                    Value::Fn(param, expr, fn_scope) => {
+                        let expr = Expr::Bop(bop, Box::new(Expr::Literal(lhs)), expr);
+                        return Ok(Value::Fn(param, Box::new(expr), fn_scope));
+                    }
+                    rhs => return Quaternion::bop(bop, lhs, rhs, context, int),
+                }
+            }
+            if UncertainValue::is_operand(&lhs, &rhs) {
+                // e.g. `(5 ± 0.1) * sqrt(2)`, which is parsed as `((5 ± 0.1) * sqrt)(2)`
+                match rhs {
+                    Value::BuiltInFunction(f) => {
+                        return Ok(f.wrap_with_expr(
+                            |f| Expr::Bop(bop, Box::new(Expr::Literal(lhs)), f),
+                            scope,
+                        ));
+                    }
+                    Value::Fn(param, expr, fn_scope) => {
+                        let expr = Expr::Bop(bop, Box::new(Expr::Literal(lhs)), expr);
+                        return Ok(Value::Fn(param, Box::new(expr), fn_scope));
+                    }
+                    rhs => return UncertainValue::bop(bop, lhs, rhs, context, int),
+                }
+            }
+            // e.g. `years between('1990-06-15', today)`
+            if let (Bop::Mul, Value::Num(unit)) = (bop, &lhs) {
+                if let Some(interval) = rhs.as_dyn::<crate::date::DateInterval>() {
+                    return interval.convert_to((**unit).clone(), context, int);
+                }
+            }
+            if Duration::is_operand(&lhs, &rhs) {
+                return Duration::bop(bop, lhs, rhs, context, int);
+            }
+            if let (Bop::Mul, Value::Num(_), Value::List(_))
+            | (Bop::Mul | Bop::Div, Value::List(_), Value::Num(_)) = (bop, &lhs, &rhs)
+            {
+                return lhs.scale_list(bop, rhs, int);
+            }
+            match (bop, &lhs) {
+                (Bop::Mul, Value::Dynamic(d)) => return d.mul(rhs),
+                (Bop::Div, Value::Dynamic(d)) => return d.div(rhs),
+                (Bop::Div, Value::Num(n)) if n.is_unitless_one() => {
+                    if let Value::Dynamic(d) = &rhs {
+                        return d.pow(-1);
+                    }
+                }
+                _ => (),
+            }
+            lhs.handle_two_nums(
+                rhs,
+                |a, b| a.bop(bop, b, context, int),
+                |a| |f| Expr::Bop(bop, f, Box::new(Expr::Literal(Value::Num(Box::new(a))))),
+                |a| |f| Expr::Bop(bop, Box::new(Expr::Literal(Value::Num(Box::new(a)))), f),
+                scope,
+            )?
+        }
         Expr::Apply(a, b) | Expr::ApplyMul(a, b) => {
             if let (Expr::Ident(a), Expr::Ident(b)) = (&*a, &*b) {
                 let ident = format!("{}_{}", a, b);
@@ -206,6 +825,26 @@ pub(crate) fn evaluate<I: Interrupt>(
                     return Ok(val);
                 }
             }
+            if let (Expr::Ident(a), Expr::Ident(b)) = (&*a, &*b) {
+                // e.g. `next friday` or `last monday`
+                if let Some(date) = crate::date::relative_weekday(a.as_str(), b.as_str(), context) {
+                    return date;
+                }
+            }
+            if let Expr::Ident(ident) = &*a {
+                // `min` is a unit (minutes), but applying it to
+                // a list (e.g. `min(3, 4)`) finds the minimum instead
+                if ident.as_str() == "min" {
+                    let rhs = eval!(*b)?;
+                    let lhs = if matches!(rhs, Value::List(_)) || rhs.is_unit() {
+                        Value::BuiltInFunction(BuiltInFunction::Min)
+                    } else {
+                        eval!(*a)?
+                    };
+                    let rhs = Expr::Literal(rhs);
+                    return lhs.apply(rhs, ApplyMulHandling::Both, scope, context, int);
+                }
+            }
             eval!(*a)?.apply(*b, ApplyMulHandling::Both, scope, context, int)?
         }
         Expr::ApplyFunctionCall(a, b) => {
@@ -213,12 +852,57 @@ pub(crate) fn evaluate<I: Interrupt>(
         }
         Expr::As(a, b) => evaluate_as(*a, *b, scope, context, int)?,
         Expr::Fn(a, b) => Value::Fn(a, b, scope),
+        // all parameters have been bound by the time the body is evaluated
+        Expr::Params(_, body) => eval!(*body)?,
+        Expr::Of(a, b) if a.as_str() == "dimension" => {
+            let num = eval!(*b)?.expect_num()?;
+            Dimension::new(num.base_unit_exponents(int)?).into()
+        }
+        Expr::Of(a, b) if a.as_str() == "units" => {
+            // e.g. `units of power` or `units of (km/h)`
+            let dimension = match &*b {
+                Expr::Ident(name) if Dimension::from_any_name(name.as_str()).is_some() => {
+                    Dimension::from_any_name(name.as_str()).unwrap()
+                }
+                _ => Dimension::of_value(&eval!(*b)?, int)?,
+            };
+            Value::String(
+                units::units_of_dimension(&dimension, context, int)?
+                    .join(", ")
+                    .into(),
+            )
+        }
         Expr::Of(a, b) => eval!(*b)?.get_object_member(&a)?,
         Expr::Assign(a, b) => {
             let rhs = evaluate(*b, scope, context, int)?;
-            context.variables.insert(a.to_string(), rhs.clone());
+            context.set_variable(a.as_str().to_string(), rhs.clone());
             rhs
         }
+        Expr::Set(setting, value) => {
+            let value = evaluate(*value, scope, context, int)?;
+            apply_setting(&setting, value, context, int)?;
+            Value::from(())
+        }
+        Expr::Takes(rate) => {
+            let rate = evaluate(*rate, scope, context, int)?;
+            context.rate = Some(rate.clone());
+            rate
+        }
+        Expr::DefineUnit(rule, singular, plural, definition) => {
+            let definition = match definition {
+                Some(definition) => Some(evaluate(*definition, scope, context, int)?),
+                None => None,
+            };
+            units::define_custom_unit(
+                singular.as_str(),
+                plural.as_str(),
+                rule,
+                definition,
+                context,
+                int,
+            )?;
+            Value::from(())
+        }
         Expr::Statements(a, b) => {
             let _lhs = evaluate(*a, scope.clone(), context, int)?;
             evaluate(*b, scope, context, int)?
@@ -226,14 +910,106 @@ pub(crate) fn evaluate<I: Interrupt>(
     })
 }
 
+fn apply_setting<I: Interrupt>(
+    setting: &Ident,
+    value: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    match setting.as_str() {
+        "precision" => {
+            let digits = value
+                .expect_num()?
+                .try_as_usize(int)
+                .map_err(|_| FendError::InvalidPrecision)?;
+            if digits > MAX_PRECISION {
+                return Err(FendError::InvalidPrecision);
+            }
+            context.set_precision(digits);
+        }
+        _ => return Err(FendError::UnknownSetting(setting.to_string())),
+    }
+    Ok(())
+}
+
+/// Returns true if `expr` is the constant `e`, and not a variable
+/// with the same name
+fn is_builtin_e<I: Interrupt>(
+    expr: &Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> bool {
+    let ident = match expr {
+        Expr::Ident(ident) if ident.as_str() == "e" => ident,
+        _ => return false,
+    };
+    if context.variables.contains_key("e") {
+        return false;
+    }
+    scope.map_or(true, |scope| {
+        matches!(
+            scope.get(ident, context, int),
+            Err(FendError::IdentifierNotFound(_))
+        )
+    })
+}
+
+/// Evaluates `a! / b!` without computing both factorials in full
+fn evaluate_factorial_quotient<I: Interrupt>(
+    a: Expr,
+    b: Expr,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let (a, b) = match (a, b) {
+        (Expr::Factorial(a), Expr::Factorial(b)) => (*a, *b),
+        (a, b) => {
+            return evaluate(
+                Expr::Bop(Bop::Div, Box::new(a), Box::new(b)),
+                scope,
+                context,
+                int,
+            )
+        }
+    };
+    let a = evaluate(a, scope.clone(), context, int)?;
+    let b = evaluate(b, scope.clone(), context, int)?;
+    Ok(match (a, b) {
+        (Value::Num(a), Value::Num(b)) => Value::Num(Box::new(a.factorial_quotient(*b, int)?)),
+        // e.g. functions, which are evaluated lazily
+        (a, b) => {
+            let a = a.handle_num(|x| x.factorial(int), Expr::Factorial, scope.clone())?;
+            let b = b.handle_num(|x| x.factorial(int), Expr::Factorial, scope.clone())?;
+            evaluate(
+                Expr::Bop(
+                    Bop::Div,
+                    Box::new(Expr::Literal(a)),
+                    Box::new(Expr::Literal(b)),
+                ),
+                scope,
+                context,
+                int,
+            )?
+        }
+    })
+}
+
 fn evaluate_add<I: Interrupt>(
     a: Value,
     b: Value,
     scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
     int: &I,
 ) -> Result<Value, FendError> {
     Ok(match (a, b) {
-        (Value::Num(a), Value::Num(b)) => Value::Num(Box::new(a.add(*b, int)?)),
+        (Value::Num(a), Value::Num(b)) => {
+            match units::combine_power_levels(&a, &b, false, context, int)? {
+                Some(sum) => Value::Num(Box::new(sum)),
+                None => Value::Num(Box::new(a.add(*b, int)?)),
+            }
+        }
         (Value::String(a), Value::String(b)) => {
             Value::String(format!("{}{}", a.as_ref(), b.as_ref()).into())
         }
@@ -267,6 +1043,87 @@ fn evaluate_add<I: Interrupt>(
     })
 }
 
+fn evaluate_sub<I: Interrupt>(
+    a: Number,
+    b: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    match units::combine_power_levels(&a, &b, true, context, int)? {
+        Some(difference) => Ok(difference),
+        None => a.sub(b, int),
+    }
+}
+
+/// After a statement like `takes 45 min per item`, turns a count like
+/// `32 items` into a total by multiplying it by the rate, e.g. `24 hours`
+pub(crate) fn apply_rate<I: Interrupt>(
+    value: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if let (Some(Value::Num(rate)), Value::Num(count)) = (&context.rate, &value) {
+        let count_units = count.unit_names();
+        let rate_units = rate.unit_names();
+        if !count_units.is_empty()
+            && count_units.iter().all(|name| rate_units.contains(name))
+            && count.base_unit_exponents(int)?.is_empty()
+        {
+            let total = (**rate).clone().mul((**count).clone(), int)?;
+            let total = units::convert_total_to_human_units(total, context, int)?;
+            return Ok(Value::Num(Box::new(total)));
+        }
+    }
+    Ok(value)
+}
+
+/// Returns the parts of a conversion target like `L/100km`, which is parsed
+/// as `(L/100) km` but means litres per 100 kilometres
+fn unit_per_multiple(expr: &Expr) -> Option<(&Ident, &Expr, &Ident)> {
+    if let Expr::Apply(lhs, rhs) | Expr::ApplyMul(lhs, rhs) = expr {
+        if let (Expr::Bop(Bop::Div, numerator, count), Expr::Ident(denominator)) = (&**lhs, &**rhs)
+        {
+            if let (Expr::Ident(numerator), Expr::Literal(Value::Num(_))) = (&**numerator, &**count)
+            {
+                return Some((numerator, count, denominator));
+            }
+        }
+    }
+    None
+}
+
+/// Returns the units of a mixed unit conversion like `to ft+in`
+fn mixed_units(expr: &Expr) -> Option<Vec<&Ident>> {
+    match expr {
+        Expr::Bop(Bop::Plus, a, b) => match &**b {
+            Expr::Ident(unit) => {
+                let mut units = match &**a {
+                    Expr::Ident(first) => vec![first],
+                    a => mixed_units(a)?,
+                };
+                units.push(unit);
+                Some(units)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the time zone named by a tz database name like `Asia/Tokyo`,
+/// which is parsed as a division of identifiers
+fn time_zone_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.as_str().to_string()),
+        Expr::Bop(Bop::Div, a, b) => match &**b {
+            Expr::Ident(b) => Some(format!("{}/{}", time_zone_name(a)?, b.as_str())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 fn evaluate_as<I: Interrupt>(
     a: Expr,
     b: Expr,
@@ -274,20 +1131,81 @@ fn evaluate_as<I: Interrupt>(
     context: &mut crate::Context,
     int: &I,
 ) -> Result<Value, FendError> {
+    // e.g. `takes 45 min per item; 32 items to hours`
+    let a = if context.rate.is_some() {
+        let value = evaluate(a, scope.clone(), context, int)?;
+        Expr::Literal(apply_rate(value, context, int)?)
+    } else {
+        a
+    };
     if let Expr::Ident(ident) = &b {
         match ident.as_str() {
             "bool" | "boolean" => {
                 let num = evaluate(a, scope, context, int)?.expect_num()?;
                 return Ok((!num.is_zero()).into());
             }
-            "date" => {
+            "duration" => {
                 let a = evaluate(a, scope, context, int)?;
+                return if a.as_dyn::<Duration>().is_some() {
+                    Ok(a)
+                } else {
+                    Ok(Duration::new(a.expect_num()?, context, int)?.into())
+                };
+            }
+            "date" | "gregorian" => {
+                let a = evaluate(a, scope, context, int)?;
+                if let Some(date_time) = a.as_dyn::<crate::date::DateTime>() {
+                    return Ok(date_time.date.into());
+                }
+                if let Some(calendar_date) = a.as_dyn::<crate::date::CalendarDate>() {
+                    return Ok(calendar_date.to_date()?.into());
+                }
                 return if let Value::String(s) = a {
                     Ok(crate::date::Date::parse(s.as_ref())?.into())
                 } else {
                     Err(FendError::ExpectedAString)
                 };
             }
+            "time" => {
+                let a = evaluate(a, scope, context, int)?;
+                if let Some(date_time) = a.as_dyn::<crate::date::DateTime>() {
+                    return Ok(date_time.time.into());
+                }
+                return match a {
+                    Value::String(s) => Ok(crate::date::Time::parse(s.as_ref())?.into()),
+                    a if a.as_dyn::<crate::date::Time>().is_some() => Ok(a),
+                    _ => Err(FendError::ExpectedAString),
+                };
+            }
+            "datetime" => {
+                let a = evaluate(a, scope, context, int)?;
+                if let Some(&date) = a.as_dyn::<crate::date::Date>() {
+                    let time = crate::date::Time::MIDNIGHT;
+                    return Ok(crate::date::DateTime { date, time }.into());
+                }
+                return match a {
+                    Value::String(s) => match crate::date::DateTime::parse(s.as_ref()) {
+                        Ok(date_time) => Ok(date_time.into()),
+                        // e.g. `2025-01-01T14:30:00Z`
+                        Err(e) => match crate::date::ZonedTime::parse(s.as_ref()) {
+                            Ok(zoned) => Ok(zoned.into()),
+                            Err(_) => Err(e),
+                        },
+                    },
+                    a if a.as_dyn::<crate::date::DateTime>().is_some() => Ok(a),
+                    a if a.as_dyn::<crate::date::ZonedTime>().is_some() => Ok(a),
+                    _ => Err(FendError::ExpectedAString),
+                };
+            }
+            "julian" | "hebrew" | "islamic" => {
+                let calendar = crate::date::Calendar::parse(ident.as_str()).unwrap();
+                let a = evaluate(a, scope, context, int)?;
+                return crate::date::to_calendar(&a, calendar);
+            }
+            "iso" => {
+                let a = evaluate(a, scope, context, int)?;
+                return Ok(Value::String(crate::date::to_iso(&a)?.into()));
+            }
             "string" => {
                 return Ok(Value::String(
                     evaluate(a, scope, context, int)?
@@ -295,6 +1213,61 @@ fn evaluate_as<I: Interrupt>(
                         .into(),
                 ));
             }
+            "human" => {
+                let num = evaluate(a, scope, context, int)?.expect_num()?;
+                return Ok(Value::Num(Box::new(crate::units::convert_to_human_units(
+                    num, context, int,
+                )?)));
+            }
+            "si" | "SI" | "imperial" => {
+                let system = if ident.as_str() == "imperial" {
+                    crate::units::UnitSystem::Imperial
+                } else {
+                    crate::units::UnitSystem::Si
+                };
+                let num = evaluate(a, scope, context, int)?.expect_num()?;
+                return Ok(Value::Num(Box::new(crate::units::convert_to_system(
+                    num, system, context, int,
+                )?)));
+            }
+            "dms" => {
+                // degrees, minutes and seconds, e.g. `48° 51' 24"`
+                let degrees =
+                    resolve_identifier(&Ident::new_str("°"), scope.clone(), context, int)?
+                        .expect_num()?;
+                let mut num = evaluate(a, scope.clone(), context, int)?.expect_num()?;
+                if num.is_unitless() {
+                    // decimal degrees like GPS coordinates
+                    num = num.mul(degrees.clone(), int)?;
+                }
+                let mut quote_unit = |unit, name: &'static str| {
+                    let value =
+                        resolve_identifier(&Ident::new_str(unit), scope.clone(), context, int)?
+                            .expect_num()?;
+                    Number::create_unit_value_from_value(
+                        &value,
+                        "".into(),
+                        name.into(),
+                        name.into(),
+                        int,
+                    )
+                };
+                let minutes = quote_unit("arcmin", "'")?;
+                let seconds = quote_unit("arcsec", "\"")?;
+                return Ok(
+                    MixedUnits::new(&num, &[degrees, minutes], &seconds, context, int)?.into(),
+                );
+            }
+            "simplest" => {
+                let num = evaluate(a, scope, context, int)?.expect_num()?;
+                return Ok(Value::Num(Box::new(
+                    crate::units::convert_to_simplest_unit(num, context, int)?,
+                )));
+            }
+            "polar" => {
+                let num = evaluate(a, scope.clone(), context, int)?.expect_num()?;
+                return Ok(PolarForm::new(num, scope, context, int)?.into());
+            }
             "codepoint" => {
                 let a = evaluate(a, scope, context, int)?;
                 if let Value::String(s) = a {
@@ -316,12 +1289,68 @@ fn evaluate_as<I: Interrupt>(
             _ => (),
         }
     }
+    if matches!(b, Expr::Bop(Bop::Div, _, _)) {
+        if let Some(zone) = time_zone_name(&b).and_then(|name| crate::date::TimeZone::find(&name)) {
+            let a = evaluate(a, scope, context, int)?;
+            return crate::date::ZonedTime::convert(&a, zone, context);
+        }
+    }
+    if let Some(units) = mixed_units(&b) {
+        let num = evaluate(a, scope.clone(), context, int)?.expect_num()?;
+        let mut units = units
+            .into_iter()
+            .map(|unit| resolve_identifier(unit, scope.clone(), context, int)?.expect_num())
+            .collect::<Result<Vec<_>, _>>()?;
+        let smallest_unit = units.pop().ok_or(FendError::ExpectedANumber)?;
+        return Ok(MixedUnits::new(&num, &units, &smallest_unit, context, int)?.into());
+    }
+    if let Some((numerator, count, denominator)) = unit_per_multiple(&b) {
+        let name = format!(
+            "{}/{}{}",
+            numerator,
+            count.format(context, int)?,
+            denominator
+        );
+        let count = evaluate(count.clone(), scope.clone(), context, int)?.expect_num()?;
+        let per = resolve_identifier(numerator, scope.clone(), context, int)?
+            .expect_num()?
+            .div(
+                count.mul(
+                    resolve_identifier(denominator, scope.clone(), context, int)?.expect_num()?,
+                    int,
+                )?,
+                int,
+            )?;
+        let unit = Number::create_unit_value_from_value(
+            &per,
+            "".into(),
+            name.clone().into(),
+            name.into(),
+            int,
+        )?;
+        let num = evaluate(a, scope, context, int)?.expect_num()?;
+        return Ok(Value::Num(Box::new(
+            num.convert_to_or_reciprocal(unit, int)?,
+        )));
+    }
     Ok(match evaluate(b, scope.clone(), context, int)? {
-        Value::Num(b) => Value::Num(Box::new(
-            evaluate(a, scope, context, int)?
-                .expect_num()?
-                .convert_to(*b, int)?,
-        )),
+        Value::Num(b) => {
+            let a = evaluate(a, scope, context, int)?;
+            if let Some(u) = a.as_dyn::<UncertainValue>() {
+                return u.convert_to(*b, context, int);
+            }
+            if let Some(d) = a.as_dyn::<Duration>() {
+                return d.convert_to(*b, int);
+            }
+            if let Some(interval) = a.as_dyn::<crate::date::DateInterval>() {
+                return interval.convert_to(*b, context, int);
+            }
+            let a = a.expect_num()?;
+            if let Some(converted) = units::convert_logarithmic(&a, &b, context, int)? {
+                return Ok(Value::Num(Box::new(converted)));
+            }
+            Value::Num(Box::new(a.convert_to_or_reciprocal(*b, int)?))
+        }
         Value::Format(fmt) => Value::Num(Box::new(
             evaluate(a, scope, context, int)?
                 .expect_num()?
@@ -333,9 +1362,12 @@ fn evaluate_as<I: Interrupt>(
         Value::Sf => {
             return Err(FendError::SpecifyNumSf);
         }
+        // converting to a base like `to decimal` shows digits instead of
+        // symbolic forms like `π/3` or `2√2`
         Value::Base(base) => Value::Num(Box::new(
             evaluate(a, scope, context, int)?
                 .expect_num()?
+                .approximate_symbolic(context.precision, int)?
                 .with_base(base),
         )),
         Value::BuiltInFunction(_) | Value::Fn(_, _, _) => {
@@ -344,15 +1376,36 @@ fn evaluate_as<I: Interrupt>(
         Value::Object(_) => {
             return Err(FendError::CannotConvertValueTo("object"));
         }
+        Value::List(_) => {
+            return Err(FendError::CannotConvertValueTo("list"));
+        }
         Value::String(_) => {
             return Err(FendError::CannotConvertValueTo("string"));
         }
         Value::Dynamic(d) => {
+            if let Some(&zone) = d.as_any().downcast_ref::<crate::date::TimeZone>() {
+                let a = evaluate(a, scope, context, int)?;
+                return crate::date::ZonedTime::convert(&a, zone, context);
+            }
             return Err(FendError::CannotConvertValueTo(d.type_name()));
         }
     })
 }
 
+/// Returns pi, which is kept exact at the default precision so that e.g.
+/// `sin(pi/6)` is exactly `0.5` and `pi/3` is shown as `π/3`. At any other
+/// precision it is approximated, because mixing exact multiples of pi with
+/// other numbers would otherwise only be accurate to the default precision.
+fn pi<I: Interrupt>(precision: usize, int: &I) -> Result<Number, FendError> {
+    let pi = Number::pi();
+    if precision == DEFAULT_PRECISION {
+        Ok(pi)
+    } else {
+        pi.approximate_pi(precision, int)
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 pub(crate) fn resolve_identifier<I: Interrupt>(
     ident: &Ident,
     scope: Option<Arc<Scope>>,
@@ -374,9 +1427,20 @@ pub(crate) fn resolve_identifier<I: Interrupt>(
     if let Some(val) = context.variables.get(ident.as_str()) {
         return Ok(val.clone());
     }
+    if context.quaternion_units {
+        if let Some(unit) = Quaternion::unit_value(ident.as_str(), context, int)? {
+            return Ok(unit);
+        }
+    }
+    if let Some(dimension) = Dimension::from_name(ident.as_str()) {
+        return Ok(dimension.into());
+    }
     Ok(match ident.as_str() {
-        "pi" | "\u{3c0}" => Value::Num(Box::new(Number::pi())),
-        "tau" | "\u{3c4}" => Value::Num(Box::new(Number::pi().mul(2.into(), int)?)),
+        "pi" | "\u{3c0}" => Value::Num(Box::new(pi(context.precision, int)?)),
+        "tau" | "\u{3c4}" => Value::Num(Box::new(pi(context.precision, int)?.mul(2.into(), int)?)),
+        "e" if context.precision > DEFAULT_PRECISION => {
+            Value::Num(Box::new(Number::e(context.precision, int)?))
+        }
         "e" => evaluate_to_value("approx. 2.718281828459045235", scope, context, int)?,
         "phi" => evaluate_to_value("(1 + sqrt(5))/2", scope, context, int)?,
         "i" => Value::Num(Box::new(Number::i())),
@@ -385,8 +1449,56 @@ pub(crate) fn resolve_identifier<I: Interrupt>(
         "sample" | "roll" => Value::BuiltInFunction(BuiltInFunction::Sample),
         "sqrt" => evaluate_to_value("x: x^(1/2)", scope, context, int)?,
         "cbrt" => evaluate_to_value("x: x^(1/3)", scope, context, int)?,
-        "conjugate" => crate::value::func::CONJUGATE.into(),
+        "conjugate" | "conj" => Value::BuiltInFunction(BuiltInFunction::Conjugate),
         "abs" => Value::BuiltInFunction(BuiltInFunction::Abs),
+        "arg" => Value::BuiltInFunction(BuiltInFunction::Arg),
+        "max" => Value::BuiltInFunction(BuiltInFunction::Max),
+        "clamp" => Value::BuiltInFunction(BuiltInFunction::Clamp),
+        "round" => Value::BuiltInFunction(BuiltInFunction::Round),
+        "floor" => Value::BuiltInFunction(BuiltInFunction::Floor),
+        "ceil" => Value::BuiltInFunction(BuiltInFunction::Ceil),
+        "trunc" | "truncate" => Value::BuiltInFunction(BuiltInFunction::Trunc),
+        "table" => Value::BuiltInFunction(BuiltInFunction::Table),
+        "random" => Value::BuiltInFunction(BuiltInFunction::Random),
+        "randint" => Value::BuiltInFunction(BuiltInFunction::RandInt),
+        "random_choice" => Value::BuiltInFunction(BuiltInFunction::RandomChoice),
+        "random_seed" => Value::BuiltInFunction(BuiltInFunction::RandomSeed),
+        "totient" | "phi_function" => Value::BuiltInFunction(BuiltInFunction::Totient),
+        "divisors" => Value::BuiltInFunction(BuiltInFunction::Divisors),
+        "num_divisors" => Value::BuiltInFunction(BuiltInFunction::NumDivisors),
+        "sigma" => Value::BuiltInFunction(BuiltInFunction::Sigma),
+        "fib" | "fibonacci" => Value::BuiltInFunction(BuiltInFunction::Fib),
+        "mean" | "average" => Value::BuiltInFunction(BuiltInFunction::Mean),
+        "median" => Value::BuiltInFunction(BuiltInFunction::Median),
+        "mode" => Value::BuiltInFunction(BuiltInFunction::Mode),
+        "stdev" => Value::BuiltInFunction(BuiltInFunction::Stdev),
+        "variance" => Value::BuiltInFunction(BuiltInFunction::Variance),
+        "percentile" => Value::BuiltInFunction(BuiltInFunction::Percentile),
+        "normalize" => Value::BuiltInFunction(BuiltInFunction::Normalize),
+        "percentoftotal" | "percent_of_total" => {
+            Value::BuiltInFunction(BuiltInFunction::PercentOfTotal)
+        }
+        "dot" => Value::BuiltInFunction(BuiltInFunction::Dot),
+        "cross" => Value::BuiltInFunction(BuiltInFunction::Cross),
+        "norm" => Value::BuiltInFunction(BuiltInFunction::Norm),
+        "transpose" => Value::BuiltInFunction(BuiltInFunction::Transpose),
+        "det" | "determinant" => Value::BuiltInFunction(BuiltInFunction::Determinant),
+        "inverse" | "inv" => Value::BuiltInFunction(BuiltInFunction::Inverse),
+        "poly" | "polynomial" => Value::BuiltInFunction(BuiltInFunction::Polynomial),
+        "quat" | "quaternion" => Value::BuiltInFunction(BuiltInFunction::Quaternion),
+        "rotate" => Value::BuiltInFunction(BuiltInFunction::Rotate),
+        "currencyinfo" => Value::BuiltInFunction(BuiltInFunction::CurrencyInfo),
+        "download_time" => Value::BuiltInFunction(BuiltInFunction::DownloadTime),
+        "molar_mass" => Value::BuiltInFunction(BuiltInFunction::MolarMass),
+        "format" => Value::BuiltInFunction(BuiltInFunction::FormatDate),
+        "date" => Value::BuiltInFunction(BuiltInFunction::Date),
+        "between" => Value::BuiltInFunction(BuiltInFunction::Between),
+        "easter" => Value::BuiltInFunction(BuiltInFunction::Easter),
+        "is_holiday" => Value::BuiltInFunction(BuiltInFunction::IsHoliday),
+        "add_workdays" => Value::BuiltInFunction(BuiltInFunction::AddWorkdays),
+        "moon_phase" => Value::BuiltInFunction(BuiltInFunction::MoonPhase),
+        "julian_day" => Value::BuiltInFunction(BuiltInFunction::JulianDay),
+        "roots" => Value::BuiltInFunction(BuiltInFunction::Roots),
         "sin" => Value::BuiltInFunction(BuiltInFunction::Sin),
         "cos" => Value::BuiltInFunction(BuiltInFunction::Cos),
         "tan" => Value::BuiltInFunction(BuiltInFunction::Tan),
@@ -434,6 +1546,20 @@ pub(crate) fn resolve_identifier<I: Interrupt>(
         "today" => crate::date::Date::today(context)?.into(),
         "tomorrow" => crate::date::Date::today(context)?.next().into(),
         "yesterday" => crate::date::Date::today(context)?.prev().into(),
-        _ => return crate::units::query_unit(ident.as_str(), context, int),
+        "now" => crate::date::DateTime::now(context)?.into(),
+        _ => {
+            return match crate::units::query_unit(ident.as_str(), context, int) {
+                Err(FendError::IdentifierNotFound(name)) => {
+                    if let Some(zone) = crate::date::TimeZone::find(ident.as_str()) {
+                        Ok(zone.into())
+                    } else if let Some(day) = crate::date::DayOfWeek::parse_plural(ident.as_str()) {
+                        Ok(day.into())
+                    } else {
+                        Err(FendError::IdentifierNotFound(name))
+                    }
+                }
+                result => result,
+            }
+        }
     })
 }
@@ -0,0 +1,222 @@
+use crate::error::FendError;
+use std::collections::BTreeMap;
+
+/// Standard atomic weights in g/mol (abridged). Elements without stable
+/// isotopes use the mass number of their longest-lived isotope.
+const ATOMIC_WEIGHTS: &[(&str, &str)] = &[
+    ("H", "1.008"),
+    ("He", "4.0026"),
+    ("Li", "6.94"),
+    ("Be", "9.0122"),
+    ("B", "10.81"),
+    ("C", "12.011"),
+    ("N", "14.007"),
+    ("O", "15.999"),
+    ("F", "18.998"),
+    ("Ne", "20.180"),
+    ("Na", "22.990"),
+    ("Mg", "24.305"),
+    ("Al", "26.982"),
+    ("Si", "28.085"),
+    ("P", "30.974"),
+    ("S", "32.06"),
+    ("Cl", "35.45"),
+    ("Ar", "39.95"),
+    ("K", "39.098"),
+    ("Ca", "40.078"),
+    ("Sc", "44.956"),
+    ("Ti", "47.867"),
+    ("V", "50.942"),
+    ("Cr", "51.996"),
+    ("Mn", "54.938"),
+    ("Fe", "55.845"),
+    ("Co", "58.933"),
+    ("Ni", "58.693"),
+    ("Cu", "63.546"),
+    ("Zn", "65.38"),
+    ("Ga", "69.723"),
+    ("Ge", "72.630"),
+    ("As", "74.922"),
+    ("Se", "78.971"),
+    ("Br", "79.904"),
+    ("Kr", "83.798"),
+    ("Rb", "85.468"),
+    ("Sr", "87.62"),
+    ("Y", "88.906"),
+    ("Zr", "91.224"),
+    ("Nb", "92.906"),
+    ("Mo", "95.95"),
+    ("Tc", "97"),
+    ("Ru", "101.07"),
+    ("Rh", "102.91"),
+    ("Pd", "106.42"),
+    ("Ag", "107.87"),
+    ("Cd", "112.41"),
+    ("In", "114.82"),
+    ("Sn", "118.71"),
+    ("Sb", "121.76"),
+    ("Te", "127.60"),
+    ("I", "126.90"),
+    ("Xe", "131.29"),
+    ("Cs", "132.91"),
+    ("Ba", "137.33"),
+    ("La", "138.91"),
+    ("Ce", "140.12"),
+    ("Pr", "140.91"),
+    ("Nd", "144.24"),
+    ("Pm", "145"),
+    ("Sm", "150.36"),
+    ("Eu", "151.96"),
+    ("Gd", "157.25"),
+    ("Tb", "158.93"),
+    ("Dy", "162.50"),
+    ("Ho", "164.93"),
+    ("Er", "167.26"),
+    ("Tm", "168.93"),
+    ("Yb", "173.05"),
+    ("Lu", "174.97"),
+    ("Hf", "178.49"),
+    ("Ta", "180.95"),
+    ("W", "183.84"),
+    ("Re", "186.21"),
+    ("Os", "190.23"),
+    ("Ir", "192.22"),
+    ("Pt", "195.08"),
+    ("Au", "196.97"),
+    ("Hg", "200.59"),
+    ("Tl", "204.38"),
+    ("Pb", "207.2"),
+    ("Bi", "208.98"),
+    ("Po", "209"),
+    ("At", "210"),
+    ("Rn", "222"),
+    ("Fr", "223"),
+    ("Ra", "226"),
+    ("Ac", "227"),
+    ("Th", "232.04"),
+    ("Pa", "231.04"),
+    ("U", "238.03"),
+    ("Np", "237"),
+    ("Pu", "244"),
+    ("Am", "243"),
+    ("Cm", "247"),
+    ("Bk", "247"),
+    ("Cf", "251"),
+    ("Es", "252"),
+    ("Fm", "257"),
+    ("Md", "258"),
+    ("No", "259"),
+    ("Lr", "266"),
+    ("Rf", "267"),
+    ("Db", "268"),
+    ("Sg", "269"),
+    ("Bh", "270"),
+    ("Hs", "269"),
+    ("Mt", "278"),
+    ("Ds", "281"),
+    ("Rg", "282"),
+    ("Cn", "285"),
+    ("Nh", "286"),
+    ("Fl", "289"),
+    ("Mc", "290"),
+    ("Lv", "293"),
+    ("Ts", "294"),
+    ("Og", "294"),
+];
+
+type ElementCounts = BTreeMap<&'static str, u64>;
+
+/// Returns an expression for the molar mass of a chemical formula like
+/// `C6H12O6`, `Ca(OH)2` or `CuSO4·5H2O`, e.g. `(12.011*6+1.008*12) g/mol`
+pub(crate) fn molar_mass_expression(formula: &str) -> Result<String, FendError> {
+    let invalid = || FendError::InvalidChemicalFormula(formula.to_string());
+    let mut counts = ElementCounts::new();
+    // hydrates like `CuSO4·5H2O` consist of several parts
+    for part in formula.split(|ch| ch == '·' || ch == '*') {
+        let mut chars = part.trim().chars().peekable();
+        let coefficient = parse_count(&mut chars).unwrap_or(1);
+        let part_counts = parse_group(&mut chars, formula)?;
+        if chars.next().is_some() || part_counts.is_empty() {
+            return Err(invalid());
+        }
+        add_counts(&mut counts, part_counts, coefficient).ok_or_else(invalid)?;
+    }
+    let terms: Vec<_> = counts
+        .into_iter()
+        .map(|(symbol, count)| {
+            let weight = ATOMIC_WEIGHTS
+                .iter()
+                .find(|(s, _)| *s == symbol)
+                .map(|(_, weight)| *weight)
+                .unwrap_or_default();
+            format!("{}*{}", weight, count)
+        })
+        .collect();
+    Ok(format!("({}) g/mol", terms.join("+")))
+}
+
+/// Parses elements and parenthesised groups until the end of the input
+/// or a closing parenthesis
+fn parse_group(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    formula: &str,
+) -> Result<ElementCounts, FendError> {
+    let invalid = || FendError::InvalidChemicalFormula(formula.to_string());
+    let mut counts = ElementCounts::new();
+    while let Some(&ch) = chars.peek() {
+        let group = if ch == '(' || ch == '[' {
+            chars.next();
+            let group = parse_group(chars, formula)?;
+            let closing = if ch == '(' { ')' } else { ']' };
+            if chars.next() != Some(closing) || group.is_empty() {
+                return Err(invalid());
+            }
+            group
+        } else if ch.is_ascii_uppercase() {
+            let mut symbol = String::from(ch);
+            chars.next();
+            while let Some(&lower) = chars.peek() {
+                if !lower.is_ascii_lowercase() {
+                    break;
+                }
+                symbol.push(lower);
+                chars.next();
+            }
+            let symbol = ATOMIC_WEIGHTS
+                .iter()
+                .find(|(s, _)| *s == symbol)
+                .map(|(s, _)| *s)
+                .ok_or(FendError::UnknownChemicalElement(symbol))?;
+            ElementCounts::from([(symbol, 1)])
+        } else {
+            break;
+        };
+        let count = parse_count(chars).unwrap_or(1);
+        add_counts(&mut counts, group, count).ok_or_else(invalid)?;
+    }
+    Ok(counts)
+}
+
+fn parse_count(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<u64> {
+    let mut count: Option<u64> = None;
+    while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+        chars.next();
+        count = Some(
+            count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(u64::from(digit)),
+        );
+    }
+    count
+}
+
+/// Adds `multiplier` times each count in `group` to `counts`, returning
+/// `None` on overflow
+fn add_counts(counts: &mut ElementCounts, group: ElementCounts, multiplier: u64) -> Option<()> {
+    for (symbol, count) in group {
+        let total = counts.entry(symbol).or_insert(0);
+        *total = total.checked_add(count.checked_mul(multiplier)?)?;
+    }
+    Some(())
+}
@@ -1,19 +1,37 @@
+use std::cmp::Ordering;
 use std::fmt;
 
+mod astronomy;
+mod calendars;
+mod date_time;
 mod day;
 mod day_of_week;
+mod format;
+mod holidays;
+mod interval;
 mod month;
 mod parser;
+mod time;
+mod time_zone;
 mod year;
 
+pub(crate) use astronomy::{julian_day, MoonPhase};
+pub(crate) use calendars::{Calendar, CalendarDate};
+pub(crate) use date_time::DateTime;
 use day::Day;
-use day_of_week::DayOfWeek;
+pub(crate) use day_of_week::DayOfWeek;
+pub(crate) use format::format;
+pub(crate) use holidays::{add_workdays, easter, is_holiday, Holiday};
+pub(crate) use interval::DateInterval;
 use month::Month;
+pub(crate) use time::Time;
+pub(crate) use time_zone::{TimeZone, ZonedTime};
 use year::Year;
 
 use crate::{
-    error::FendError,
-    value::{Value, ValueTrait},
+    error::{FendError, Interrupt},
+    num::Number,
+    value::{duration::Duration, Value, ValueTrait},
 };
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -24,56 +42,67 @@ pub(crate) struct Date {
 }
 
 impl Date {
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss,
-        clippy::cast_possible_wrap
-    )]
     pub(crate) fn today(context: &mut crate::Context) -> Result<Self, FendError> {
-        let current_time_info = if let Some(t) = &context.current_time {
-            t
+        let ms_since_epoch = context
+            .current_time
+            .as_ref()
+            .ok_or(FendError::UnableToGetCurrentDate)?
+            .local_ms_since_epoch()?;
+        let days = ms_since_epoch.div_euclid(86_400_000); // no leap seconds
+        Self::from_days_since_epoch(days)
+    }
+
+    /// Returns the number of days since 1970-01-01 (which may be negative),
+    /// using the algorithm from
+    /// <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+    fn days_since_epoch(self) -> i64 {
+        let month = i64::from(self.month.number());
+        // years start in March, so that leap days are at the end of the year
+        let year = i64::from(self.year.value()) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day.value()) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The inverse of `days_since_epoch`, see
+    /// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+    fn from_days_since_epoch(days: i64) -> Result<Self, FendError> {
+        let days = days.checked_add(719_468).ok_or(FendError::DateOutOfRange)?;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
         } else {
-            return Err(FendError::UnableToGetCurrentDate);
+            shifted_month - 9
         };
-        let mut ms_since_epoch = current_time_info.elapsed_unix_time_ms as i64;
-        ms_since_epoch -= current_time_info.timezone_offset_secs * 1000;
-        let mut days = ms_since_epoch / 86_400_000; // no leap seconds
-        let mut year = Year::new(1970);
-        while days >= year.number_of_days().into() {
-            year = year.next();
-            days -= i64::from(year.number_of_days());
-        }
-        let mut month = Month::January;
-        while days >= month.number_of_days(year).into() {
-            month = month.next();
-            days -= i64::from(month.number_of_days(year));
-        }
+        let year = era * 400 + year_of_era + i64::from(month <= 2);
+        let out_of_range = |_| FendError::DateOutOfRange;
         Ok(Self {
-            year,
-            month,
-            day: Day::new(days as u8),
+            year: Year::new(i32::try_from(year).map_err(out_of_range)?),
+            month: Month::try_from(i32::try_from(month).map_err(out_of_range)?)
+                .map_err(|_| FendError::DateOutOfRange)?,
+            day: Day::new(u8::try_from(day).map_err(out_of_range)?),
         })
     }
 
+    fn add_days(self, num_days: i64) -> Result<Self, FendError> {
+        let days = self
+            .days_since_epoch()
+            .checked_add(num_days)
+            .ok_or(FendError::DateOutOfRange)?;
+        Self::from_days_since_epoch(days)
+    }
+
     fn day_of_week(self) -> DayOfWeek {
-        let d1 = (1
-            + 5 * ((self.year.value() - 1) % 4)
-            + 4 * ((self.year.value() - 1) % 100)
-            + 6 * ((self.year.value() - 1) % 400))
-            % 7;
-        let ms = match self.month {
-            Month::January => (0, 0),
-            Month::February => (3, 3),
-            Month::March | Month::November => (3, 4),
-            Month::April | Month::July => (6, 0),
-            Month::May => (1, 2),
-            Month::June => (4, 5),
-            Month::August => (2, 3),
-            Month::September | Month::December => (5, 6),
-            Month::October => (0, 1),
-        };
-        let m = if self.year.is_leap_year() { ms.1 } else { ms.0 };
-        match (d1 + m + i32::from(self.day.value() - 1)) % 7 {
+        // 1970-01-01 was a Thursday
+        match (self.days_since_epoch() + 4).rem_euclid(7) {
             0 => DayOfWeek::Sunday,
             1 => DayOfWeek::Monday,
             2 => DayOfWeek::Tuesday,
@@ -130,8 +159,118 @@ impl Date {
         }
     }
 
+    /// Creates a date from a year, month and day, e.g. for `date(2024, 5, 1)`
+    pub(crate) fn from_ymd(year: i64, month: i64, day: i64) -> Result<Self, FendError> {
+        let invalid = || FendError::InvalidDate(year, month, day);
+        let year = Year::new(i32::try_from(year).map_err(|_| invalid())?);
+        let month = i32::try_from(month)
+            .ok()
+            .and_then(|month| Month::try_from(month).ok())
+            .ok_or_else(invalid)?;
+        let day = u8::try_from(day).map_err(|_| invalid())?;
+        if day < 1 || day > month.number_of_days(year) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            year,
+            month,
+            day: Day::new(day),
+        })
+    }
+
+    /// Adds a number of calendar months (which may be negative). If the
+    /// day doesn't exist in the resulting month, the last day of the month
+    /// is used instead, e.g. 31 January plus one month is 28 or 29 February.
+    fn add_months(self, months: i64) -> Result<Self, FendError> {
+        let total = i64::from(self.year.value()) * 12 + i64::from(self.month.number()) - 1;
+        let total = total.checked_add(months).ok_or(FendError::DateOutOfRange)?;
+        let year =
+            Year::new(i32::try_from(total.div_euclid(12)).map_err(|_| FendError::DateOutOfRange)?);
+        let month = Month::try_from(i32::try_from(total.rem_euclid(12)).unwrap() + 1)
+            .map_err(|_| FendError::DateOutOfRange)?;
+        let day = Day::new(self.day.value().min(month.number_of_days(year)));
+        Ok(Self { year, month, day })
+    }
+
+    /// Returns the day of the year, from 1 (1 January) to 366
+    fn day_of_year(self) -> i64 {
+        let first_day = Self {
+            year: self.year,
+            month: Month::January,
+            day: Day::new(1),
+        };
+        self.days_since_epoch() - first_day.days_since_epoch() + 1
+    }
+
+    /// Returns the ISO 8601 week number, from 1 to 53. Weeks start on
+    /// Monday, and week 1 is the week containing the first Thursday of the
+    /// year, so early January may be part of the last week of the
+    /// previous year.
+    fn iso_week(self) -> i64 {
+        // 1970-01-01 was a Thursday, and Monday is day 1
+        let weekday = |date: Self| (date.days_since_epoch() + 3).rem_euclid(7) + 1;
+        let weeks_in_year = |year: Year| {
+            let first_day = Self {
+                year,
+                month: Month::January,
+                day: Day::new(1),
+            };
+            match weekday(first_day) {
+                4 => 53,
+                3 if year.is_leap_year() => 53,
+                _ => 52,
+            }
+        };
+        let week = (self.day_of_year() - weekday(self) + 10) / 7;
+        if week < 1 {
+            weeks_in_year(self.year.prev())
+        } else if week > weeks_in_year(self.year) {
+            1
+        } else {
+            week
+        }
+    }
+
+    /// Returns the first date after this one that falls on the given day
+    /// of the week, e.g. for `next friday`
+    fn next_weekday(self, day_of_week: DayOfWeek) -> Result<Self, FendError> {
+        let days = (day_of_week.number() - self.day_of_week().number() - 1).rem_euclid(7) + 1;
+        self.add_days(days)
+    }
+
+    /// Returns the last date before this one that falls on the given day
+    /// of the week, e.g. for `last monday`
+    fn prev_weekday(self, day_of_week: DayOfWeek) -> Result<Self, FendError> {
+        let days = (self.day_of_week().number() - day_of_week.number() - 1).rem_euclid(7) + 1;
+        self.add_days(-days)
+    }
+
+    /// Parses a date like `2024-05-01`, or a date in another calendar like
+    /// `15 Nisan 5784`
     pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
-        parser::parse_date(s)
+        match parser::parse_date(s) {
+            Ok(date) => Ok(date),
+            Err(e) => CalendarDate::parse(s)
+                .and_then(CalendarDate::to_date)
+                .map_err(|_| e),
+        }
+    }
+
+    /// Formats this date like `2025-01-01`. Years outside of 0 to 9999 have
+    /// a sign, like `-0044` or `+12345`.
+    fn to_iso(self) -> String {
+        let year = self.year.value();
+        let year = if (0..=9999).contains(&year) {
+            format!("{:04}", year)
+        } else {
+            format!("{:+05}", year)
+        };
+        format!(
+            "{}-{:02}-{:02}",
+            year,
+            self.month.number(),
+            self.day.value()
+        )
     }
 }
 
@@ -177,22 +316,393 @@ impl ValueTrait for Date {
         Some(match key {
             "month" => self.month.into(),
             "day_of_week" => self.day_of_week().into(),
+            "year" => Value::Num(Box::new(signed_number(self.year.value().into()))),
+            "day" => Value::Num(Box::new(Number::from(u64::from(self.day.value())))),
+            "day_of_year" => Value::Num(Box::new(signed_number(self.day_of_year()))),
+            "week_number" => Value::Num(Box::new(signed_number(self.iso_week()))),
+            "is_leap_year" => self.year.is_leap_year().into(),
             _ => return None,
         })
     }
 
-    fn add(&self, rhs: Value) -> Result<Value, FendError> {
-        let rhs = rhs.expect_num()?;
-        let int = &crate::interrupt::Never::default();
-        if rhs.unit_equal_to("day") {
-            let num_days = rhs.try_as_usize_unit(int)?;
-            let mut result = *self;
-            for _ in 0..num_days {
-                result = result.next();
-            }
-            Ok(result.into())
+    fn sub(&self, rhs: Value, context: &mut crate::Context) -> Result<Value, FendError> {
+        let rhs = match rhs {
+            Value::String(s) => Self::parse(s.as_ref())?.into(),
+            rhs => rhs,
+        };
+        sub(
+            &(*self).into(),
+            rhs,
+            context,
+            &crate::interrupt::Never::default(),
+        )
+    }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
+
+    fn compare(&self, rhs: &Value) -> Option<Ordering> {
+        compare(&(*self).into(), rhs)
+    }
+}
+
+/// Orders two dates, dates and times, or times of day, e.g. for
+/// `date(2024, 1, 1) < date(2024, 5, 1)`. A date counts as midnight when
+/// compared to a date and time.
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    if let (Some(a), Some(b)) = (lhs.as_dyn::<Time>(), rhs.as_dyn::<Time>()) {
+        return Some(a.seconds_since_midnight().cmp(&b.seconds_since_midnight()));
+    }
+    Some(seconds_since_epoch(lhs)?.cmp(&seconds_since_epoch(rhs)?))
+}
+
+/// Returns true if evaluating `lhs + rhs` should be handled by `date::add`
+pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+    is_moment(lhs) || is_moment(rhs)
+}
+
+fn is_moment(value: &Value) -> bool {
+    value.as_dyn::<Date>().is_some()
+        || value.as_dyn::<Time>().is_some()
+        || value.as_dyn::<DateTime>().is_some()
+}
+
+/// Returns the whole number of seconds in a length of time like `36 hours`
+/// or a duration
+fn seconds_in<I: Interrupt>(
+    span: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<i64, FendError> {
+    let span = match span.as_dyn::<Duration>() {
+        Some(duration) => duration.to_number(),
+        None => span.expect_num()?,
+    };
+    // e.g. `('2025-01-01' to date) - 5`
+    if span.is_unitless() {
+        return Err(FendError::ExpectedANumber);
+    }
+    let seconds = crate::units::query_unit("seconds", context, int)?.expect_num()?;
+    span.convert_to(seconds, int)?
+        .without_unit()
+        .try_as_i64(int)
+}
+
+/// Returns the number of calendar months in a length of time like
+/// `3 months` or `2 years`, or `None` if it isn't a whole number of months
+/// or years and should be added as a fixed number of seconds instead
+fn months_in<I: Interrupt>(
+    span: &Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<i64>, FendError> {
+    let is_calendar_unit = matches!(span, Value::Num(n) if matches!(
+        n.unit_names().as_slice(),
+        [unit] if matches!(
+            *unit,
+            "month" | "mo" | "year" | "yr" | "decade" | "century" | "millennium"
+        )
+    ));
+    if !is_calendar_unit {
+        return Ok(None);
+    }
+    let month = crate::units::query_unit("months", context, int)?.expect_num()?;
+    Ok(span
+        .clone()
+        .expect_num()?
+        .convert_to(month, int)?
+        .without_unit()
+        .try_as_i64(int)
+        .ok())
+}
+
+/// Adds a number of calendar months to a date or a date and time
+fn add_months(moment: &Value, months: i64) -> Option<Result<Value, FendError>> {
+    if let Some(&date) = moment.as_dyn::<Date>() {
+        Some(date.add_months(months).map(Into::into))
+    } else {
+        let &DateTime { date, time } = moment.as_dyn::<DateTime>()?;
+        Some(
+            date.add_months(months)
+                .map(|date| DateTime { date, time }.into()),
+        )
+    }
+}
+
+/// Adds a length of time to a date, a time of day or a date and time.
+/// Dates stay dates when adding whole days, e.g. `today + 2 days`, but
+/// become a date and time otherwise, e.g. `today + 36 hours`. Months and
+/// years are calendar months and years, e.g. `today + 3 months`. A date and
+/// a time of day can also be combined with `+`.
+pub(crate) fn add<I: Interrupt>(
+    lhs: Value,
+    rhs: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let (moment, span) = if is_moment(&lhs) {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    if let (Some(&date), Some(&time)) = (moment.as_dyn::<Date>(), span.as_dyn::<Time>()) {
+        return Ok(DateTime { date, time }.into());
+    }
+    if let (Some(&date), Some(&time)) = (span.as_dyn::<Date>(), moment.as_dyn::<Time>()) {
+        return Ok(DateTime { date, time }.into());
+    }
+    if let Some(months) = months_in(&span, context, int)? {
+        if let Some(result) = add_months(&moment, months) {
+            return result;
+        }
+    }
+    let seconds = seconds_in(span, context, int)?;
+    add_seconds(&moment, seconds)
+}
+
+fn add_seconds(moment: &Value, seconds: i64) -> Result<Value, FendError> {
+    if let Some(&date) = moment.as_dyn::<Date>() {
+        Ok(if seconds % 86400 == 0 {
+            date.add_days(seconds / 86400)?.into()
         } else {
-            Err(FendError::ExpectedANumber)
+            DateTime {
+                date,
+                time: Time::MIDNIGHT,
+            }
+            .add_seconds(seconds)?
+            .into()
+        })
+    } else if let Some(&time) = moment.as_dyn::<Time>() {
+        Ok(time.add_seconds(seconds)?.1.into())
+    } else if let Some(&date_time) = moment.as_dyn::<DateTime>() {
+        Ok(date_time.add_seconds(seconds)?.into())
+    } else {
+        Err(FendError::ExpectedANumber)
+    }
+}
+
+/// Returns the date of a date, a date and time, a date in another calendar
+/// or a string like `2024-05-01`
+pub(crate) fn expect_date(value: &Value) -> Result<Date, FendError> {
+    if let Value::String(s) = value {
+        return expect_date(&parse_moment(s.as_ref())?);
+    }
+    if let Some(&date) = value.as_dyn::<Date>() {
+        Ok(date)
+    } else if let Some(date_time) = value.as_dyn::<DateTime>() {
+        Ok(date_time.date)
+    } else if let Some(calendar_date) = value.as_dyn::<CalendarDate>() {
+        calendar_date.to_date()
+    } else {
+        Err(FendError::ExpectedADate)
+    }
+}
+
+/// Converts a date to another calendar, e.g. for `today to hebrew`
+pub(crate) fn to_calendar(value: &Value, calendar: Calendar) -> Result<Value, FendError> {
+    Ok(CalendarDate::from_date(expect_date(value)?, calendar).into())
+}
+
+/// Returns the number of seconds since 1970-01-01 00:00 of a date or a
+/// date and time
+fn seconds_since_epoch(moment: &Value) -> Option<i64> {
+    if let Some(date) = moment.as_dyn::<Date>() {
+        Some(date.days_since_epoch() * 86400)
+    } else {
+        moment
+            .as_dyn::<DateTime>()
+            .map(|date_time| date_time.seconds_since_epoch())
+    }
+}
+
+fn signed_number(n: i64) -> Number {
+    let result = Number::from(n.unsigned_abs());
+    if n < 0 {
+        -result
+    } else {
+        result
+    }
+}
+
+/// Subtracts a length of time from a date or time, or returns the
+/// difference between two of them. The difference between two dates is a
+/// number of days, while other differences are durations.
+pub(crate) fn sub<I: Interrupt>(
+    lhs: &Value,
+    rhs: Value,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if !is_moment(&rhs) {
+        if let Some(months) = months_in(&rhs, context, int)? {
+            if let Some(result) = add_months(lhs, -months) {
+                return result;
+            }
         }
+        let seconds = seconds_in(rhs, context, int)?
+            .checked_neg()
+            .ok_or(FendError::DateOutOfRange)?;
+        return add_seconds(lhs, seconds);
+    }
+    if let (Some(a), Some(b)) = (lhs.as_dyn::<Date>(), rhs.as_dyn::<Date>()) {
+        let days = a.days_since_epoch() - b.days_since_epoch();
+        let day = crate::units::query_unit("days", context, int)?.expect_num()?;
+        return Ok(Value::Num(Box::new(signed_number(days).mul(day, int)?)));
+    }
+    let seconds = match (lhs.as_dyn::<Time>(), rhs.as_dyn::<Time>()) {
+        (Some(a), Some(b)) => a.seconds_since_midnight() - b.seconds_since_midnight(),
+        _ => match (seconds_since_epoch(lhs), seconds_since_epoch(&rhs)) {
+            (Some(a), Some(b)) => a - b,
+            _ => return Err(FendError::InvalidOperandsForSubtraction),
+        },
+    };
+    let second = crate::units::query_unit("seconds", context, int)?.expect_num()?;
+    let time = signed_number(seconds).mul(second, int)?;
+    Ok(Duration::new(time, context, int)?.into())
+}
+
+/// Returns true if `direction day` is a relative date like `next friday`
+pub(crate) fn is_relative_weekday(direction: &str, day: &str) -> bool {
+    matches!(direction, "next" | "last") && DayOfWeek::parse(day).is_some()
+}
+
+/// Evaluates relative dates like `next friday` or `last monday`, returning
+/// `None` if `direction` or `day` aren't recognised
+pub(crate) fn relative_weekday(
+    direction: &str,
+    day: &str,
+    context: &mut crate::Context,
+) -> Option<Result<Value, FendError>> {
+    if !is_relative_weekday(direction, day) {
+        return None;
+    }
+    let day_of_week = DayOfWeek::parse(day)?;
+    let next = direction == "next";
+    Some(Date::today(context).and_then(|today| {
+        Ok(if next {
+            today.next_weekday(day_of_week)?.into()
+        } else {
+            today.prev_weekday(day_of_week)?.into()
+        })
+    }))
+}
+
+/// Parses a string containing a date, a time, or a date and time with an
+/// optional UTC offset
+fn parse_moment(s: &str) -> Result<Value, FendError> {
+    if let Ok(date) = Date::parse(s) {
+        Ok(date.into())
+    } else if let Ok(date_time) = DateTime::parse(s) {
+        Ok(date_time.into())
+    } else if let Ok(time) = Time::parse(s) {
+        Ok(time.into())
+    } else {
+        Ok(ZonedTime::parse(s)?.into())
+    }
+}
+
+/// Formats a date or time in ISO 8601 format, e.g. `2025-01-01T14:30:00Z`.
+/// Strings are parsed first, so that dates from other tools can be checked
+/// and normalised.
+pub(crate) fn to_iso(value: &Value) -> Result<String, FendError> {
+    if let Value::String(s) = value {
+        return to_iso(&parse_moment(s.as_ref())?);
+    }
+    if let Some(date) = value.as_dyn::<Date>() {
+        Ok(date.to_iso())
+    } else if let Some(date_time) = value.as_dyn::<DateTime>() {
+        Ok(date_time.to_iso())
+    } else if let Some(time) = value.as_dyn::<Time>() {
+        Ok(time.to_iso())
+    } else if let Some(zoned) = value.as_dyn::<ZonedTime>() {
+        Ok(zoned.to_iso())
+    } else {
+        Err(FendError::ExpectedADateOrTime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_weekdays() {
+        // 2024-05-01 was a Wednesday
+        let date = Date::parse("2024-05-01").unwrap();
+        let friday = Date::parse("2024-05-03").unwrap();
+        let monday = Date::parse("2024-04-29").unwrap();
+        assert_eq!(date.next_weekday(DayOfWeek::Friday).unwrap(), friday);
+        assert_eq!(date.prev_weekday(DayOfWeek::Monday).unwrap(), monday);
+        let next_week = Date::parse("2024-05-08").unwrap();
+        assert_eq!(date.next_weekday(DayOfWeek::Wednesday).unwrap(), next_week);
+        let last_week = Date::parse("2024-04-24").unwrap();
+        assert_eq!(date.prev_weekday(DayOfWeek::Wednesday).unwrap(), last_week);
+    }
+
+    fn context_at(elapsed_unix_time_ms: u64, timezone_offset_secs: i64) -> crate::Context {
+        let mut context = crate::Context::new();
+        context.current_time = Some(crate::CurrentTimeInfo {
+            elapsed_unix_time_ms,
+            timezone_offset_secs,
+        });
+        context
+    }
+
+    #[test]
+    fn today() {
+        let today = |ms, offset| Date::today(&mut context_at(ms, offset)).unwrap();
+        assert_eq!(today(0, 0), Date::parse("1970-01-01").unwrap());
+        // 1970-01-01 01:00 UTC is still 1969 in New York
+        assert_eq!(
+            today(3_600_000, 5 * 3600),
+            Date::parse("1969-12-31").unwrap()
+        );
+        assert_eq!(
+            today(1_714_521_600_000, 0),
+            Date::parse("2024-05-01").unwrap()
+        );
+        // far in the future
+        assert_eq!(
+            today(253_402_300_800_000, 0),
+            Date::parse("+10000-01-01").unwrap()
+        );
+        assert!(Date::today(&mut context_at(u64::MAX, 0)).is_err());
+        let now = DateTime::now(&mut context_at(3_600_000, 5 * 3600)).unwrap();
+        assert_eq!(now.to_string(), "Wednesday, 31 December 1969 20:00");
+    }
+
+    #[test]
+    fn iso_week_numbers() {
+        let week = |s| Date::parse(s).unwrap().iso_week();
+        assert_eq!(week("2024-05-01"), 18);
+        // 2021-01-03 is part of the last week of 2020
+        assert_eq!(week("2021-01-03"), 53);
+        assert_eq!(week("2021-01-04"), 1);
+        // 2024-12-30 is part of the first week of 2025
+        assert_eq!(week("2024-12-30"), 1);
+        assert_eq!(week("2026-12-31"), 53);
+        assert_eq!(Date::parse("2024-12-31").unwrap().day_of_year(), 366);
+    }
+
+    #[test]
+    fn add_months() {
+        let add = |s, months| Date::parse(s).unwrap().add_months(months).unwrap();
+        assert_eq!(add("2024-05-01", 3), Date::parse("2024-08-01").unwrap());
+        assert_eq!(add("2024-01-31", 1), Date::parse("2024-02-29").unwrap());
+        assert_eq!(add("2023-01-31", 1), Date::parse("2023-02-28").unwrap());
+        assert_eq!(add("2024-03-31", -1), Date::parse("2024-02-29").unwrap());
+        assert_eq!(add("2024-11-15", 14), Date::parse("2026-01-15").unwrap());
+        assert_eq!(add("2024-02-29", -12), Date::parse("2023-02-28").unwrap());
+    }
+
+    #[test]
+    fn dates_from_year_month_and_day() {
+        let date = Date::from_ymd(2024, 5, 1).unwrap();
+        assert_eq!(date, Date::parse("2024-05-01").unwrap());
+        Date::from_ymd(2024, 2, 29).unwrap();
+        Date::from_ymd(2023, 2, 29).unwrap_err();
+        Date::from_ymd(2024, 13, 1).unwrap_err();
+        Date::from_ymd(2024, 5, 0).unwrap_err();
     }
 }
@@ -0,0 +1,124 @@
+use super::{DateTime, ZonedTime};
+use crate::error::{FendError, Interrupt};
+use crate::num::Number;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+// the average time from one new moon to the next, 29.530588853 days
+const SYNODIC_MONTH_MS: i64 = 2_551_442_877;
+// 2000-01-06 18:14 UTC
+const REFERENCE_NEW_MOON_MS: i64 = 947_182_440_000;
+// the Julian day at 1970-01-01 00:00 UTC is 2440587.5
+const JULIAN_DAY_AT_EPOCH_SECONDS: i64 = 210_866_760_000;
+
+const PHASES: [&str; 8] = [
+    "New Moon",
+    "Waxing Crescent",
+    "First Quarter",
+    "Waxing Gibbous",
+    "Full Moon",
+    "Waning Gibbous",
+    "Last Quarter",
+    "Waning Crescent",
+];
+
+/// Returns the number of seconds since 1970-01-01 00:00 UTC. Dates are
+/// taken to be at midnight, and values without a time zone are in UTC.
+fn utc_seconds(value: &Value) -> Result<i64, FendError> {
+    if let Value::String(s) = value {
+        return utc_seconds(&super::parse_moment(s.as_ref())?);
+    }
+    if let Some(zoned) = value.as_dyn::<ZonedTime>() {
+        let (date, time, offset, _) = zoned.parts();
+        let date = date.ok_or(FendError::ExpectedADate)?;
+        return Ok(DateTime { date, time }.seconds_since_epoch() - offset);
+    }
+    if let Some(date_time) = value.as_dyn::<DateTime>() {
+        return Ok(date_time.seconds_since_epoch());
+    }
+    Ok(super::expect_date(value)?.days_since_epoch() * 86400)
+}
+
+/// Returns the Julian day, i.e. the number of days since noon UTC on
+/// 1 January 4713 BC in the Julian calendar, as used in astronomy
+pub(crate) fn julian_day<I: Interrupt>(value: &Value, int: &I) -> Result<Number, FendError> {
+    let seconds = utc_seconds(value)? + JULIAN_DAY_AT_EPOCH_SECONDS;
+    super::signed_number(seconds).div(86400.into(), int)
+}
+
+/// The phase of the moon at a given date and time, based on the average
+/// length of a lunar month. This is accurate to within a few hours, which
+/// is enough to tell e.g. whether the moon is full.
+#[derive(Clone, Debug)]
+pub(crate) struct MoonPhase {
+    name: &'static str,
+    // the time since the last new moon, in days
+    age: Number,
+    // the illuminated fraction of the moon, in percent
+    illumination: Number,
+}
+
+impl MoonPhase {
+    pub(crate) fn new<I: Interrupt>(
+        value: &Value,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let ms = utc_seconds(value)?
+            .checked_mul(1000)
+            .ok_or(FendError::DateOutOfRange)?;
+        let age_ms = (ms - REFERENCE_NEW_MOON_MS).rem_euclid(SYNODIC_MONTH_MS);
+        let index = (age_ms * 8 + SYNODIC_MONTH_MS / 2) / SYNODIC_MONTH_MS % 8;
+        let days = crate::units::query_unit("days", context, int)?.expect_num()?;
+        let age = super::signed_number(age_ms)
+            .div(86_400_000.into(), int)?
+            .mul(days.clone(), int)?
+            .convert_to(days, int)?;
+        // (1 - cos(2 pi * fraction of the month)) / 2
+        let radians = crate::units::query_unit("radians", context, int)?.expect_num()?;
+        let angle = Number::pi()
+            .mul(2.into(), int)?
+            .mul(super::signed_number(age_ms), int)?
+            .div(super::signed_number(SYNODIC_MONTH_MS), int)?
+            .mul(radians, int)?;
+        let cos = angle.cos(None, context, int)?;
+        let percent = crate::units::query_unit("%", context, int)?.expect_num()?;
+        let illumination = Number::from(1)
+            .sub(cos, int)?
+            .div(2.into(), int)?
+            .convert_to(percent, int)?;
+        Ok(Self {
+            name: PHASES[usize::try_from(index).unwrap()],
+            age,
+            illumination,
+        })
+    }
+}
+
+impl fmt::Display for MoonPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl ValueTrait for MoonPhase {
+    fn type_name(&self) -> &'static str {
+        "moon phase"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+
+    fn get_object_member(&self, key: &str) -> Option<Value> {
+        Some(match key {
+            "name" => Value::String(self.name.into()),
+            "age" => Value::Num(Box::new(self.age.clone())),
+            "illumination" => Value::Num(Box::new(self.illumination.clone())),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,403 @@
+use super::{Date, Month};
+use crate::error::FendError;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+// The conversions below use "fixed" day numbers, where day 1 is
+// 0001-01-01 in the proleptic Gregorian calendar, following the
+// algorithms from "Calendrical Calculations" by Reingold and Dershowitz.
+const UNIX_EPOCH: i64 = 719_163;
+const JULIAN_EPOCH: i64 = -1;
+const HEBREW_EPOCH: i64 = -1_373_427;
+const ISLAMIC_EPOCH: i64 = 227_015;
+
+const HEBREW_MONTHS: [&str; 13] = [
+    "Nisan", "Iyar", "Sivan", "Tammuz", "Av", "Elul", "Tishrei", "Cheshvan", "Kislev", "Tevet",
+    "Shevat", "Adar", "Adar II",
+];
+const ISLAMIC_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi al-Awwal",
+    "Rabi al-Thani",
+    "Jumada al-Awwal",
+    "Jumada al-Thani",
+    "Rajab",
+    "Shaban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qadah",
+    "Dhu al-Hijjah",
+];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Calendar {
+    Julian,
+    Hebrew,
+    // the tabular (arithmetic) Islamic calendar, which can differ by a day
+    // or two from calendars based on observing the moon
+    Islamic,
+}
+
+impl Calendar {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "julian" => Self::Julian,
+            "hebrew" => Self::Hebrew,
+            "islamic" => Self::Islamic,
+            _ => return None,
+        })
+    }
+}
+
+/// A date in the Julian, Hebrew or Islamic calendar, e.g. `15 Nisan 5784`.
+/// Years use astronomical numbering like `Date`. Hebrew months are numbered
+/// from Nisan (1) to Adar (12) and Adar II (13), so years start with
+/// Tishrei (7).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct CalendarDate {
+    calendar: Calendar,
+    year: i64,
+    month: i64,
+    day: i64,
+}
+
+impl CalendarDate {
+    pub(crate) fn from_date(date: Date, calendar: Calendar) -> Self {
+        let fixed = date.days_since_epoch() + UNIX_EPOCH;
+        let (year, month, day) = match calendar {
+            Calendar::Julian => julian_from_fixed(fixed),
+            Calendar::Hebrew => hebrew_from_fixed(fixed),
+            Calendar::Islamic => islamic_from_fixed(fixed),
+        };
+        Self {
+            calendar,
+            year,
+            month,
+            day,
+        }
+    }
+
+    fn to_fixed(self) -> i64 {
+        match self.calendar {
+            Calendar::Julian => fixed_from_julian(self.year, self.month, self.day),
+            Calendar::Hebrew => fixed_from_hebrew(self.year, self.month, self.day),
+            Calendar::Islamic => fixed_from_islamic(self.year, self.month, self.day),
+        }
+    }
+
+    pub(crate) fn to_date(self) -> Result<Date, FendError> {
+        Date::from_days_since_epoch(self.to_fixed() - UNIX_EPOCH)
+    }
+
+    /// Parses dates like `18 April 2024 (Julian)`, `15 Nisan 5784` or
+    /// `1 Ramadan 1445 AH`, i.e. in the same format they are shown in
+    pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
+        let error = || FendError::ParseDateError(s.to_string());
+        let mut words = s.split_whitespace().collect::<Vec<_>>();
+        let mut calendar = None;
+        match words.last() {
+            Some(&"(Julian)") => calendar = Some(Calendar::Julian),
+            Some(&"AH") => calendar = Some(Calendar::Islamic),
+            _ => (),
+        }
+        if calendar.is_some() {
+            words.pop();
+        }
+        if words.len() < 3 {
+            return Err(error());
+        }
+        let day = words[0].parse::<u8>().map_err(|_| error())?;
+        let year = words[words.len() - 1].parse::<i32>().map_err(|_| error())?;
+        let month_name = words[1..words.len() - 1].join(" ");
+        let find = |names: &[&str]| {
+            names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(&month_name))
+                .map(|index| i64::try_from(index).unwrap() + 1)
+        };
+        let julian_month = || {
+            (1..=12).find(|&month| {
+                Month::try_from(month).map_or(false, |month| {
+                    month.to_string().eq_ignore_ascii_case(&month_name)
+                })
+            })
+        };
+        let (calendar, month) = match calendar {
+            Some(Calendar::Julian) => (Calendar::Julian, julian_month().map(i64::from)),
+            Some(calendar) => (calendar, find(&ISLAMIC_MONTHS)),
+            None if month_name.eq_ignore_ascii_case("Adar I") => (Calendar::Hebrew, Some(12)),
+            None => match find(&HEBREW_MONTHS) {
+                Some(month) => (Calendar::Hebrew, Some(month)),
+                None => (Calendar::Islamic, find(&ISLAMIC_MONTHS)),
+            },
+        };
+        let result = Self {
+            calendar,
+            year: year.into(),
+            month: month.ok_or_else(error)?,
+            day: day.into(),
+        };
+        // invalid dates like `30 Iyar 5784` would end up in the next month
+        if result.day == 0 || Self::from_date(result.to_date()?, calendar) != result {
+            return Err(error());
+        }
+        Ok(result)
+    }
+
+    fn month_name(self) -> String {
+        let index = usize::try_from(self.month - 1).unwrap();
+        match self.calendar {
+            Calendar::Julian => Month::try_from(i32::try_from(self.month).unwrap())
+                .map_or_else(|_| String::new(), |month| month.to_string()),
+            Calendar::Hebrew if self.month == 12 && hebrew_leap_year(self.year) => {
+                "Adar I".to_string()
+            }
+            Calendar::Hebrew => HEBREW_MONTHS[index].to_string(),
+            Calendar::Islamic => ISLAMIC_MONTHS[index].to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.day, self.month_name(), self.year)?;
+        match self.calendar {
+            Calendar::Julian => write!(f, " (Julian)"),
+            Calendar::Hebrew => Ok(()),
+            Calendar::Islamic => write!(f, " AH"),
+        }
+    }
+}
+
+impl ValueTrait for CalendarDate {
+    fn type_name(&self) -> &'static str {
+        "date"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
+}
+
+fn julian_leap_year(year: i64) -> bool {
+    year.rem_euclid(4) == 0
+}
+
+fn fixed_from_julian(year: i64, month: i64, day: i64) -> i64 {
+    let correction = if month <= 2 {
+        0
+    } else if julian_leap_year(year) {
+        -1
+    } else {
+        -2
+    };
+    JULIAN_EPOCH - 1
+        + 365 * (year - 1)
+        + (year - 1).div_euclid(4)
+        + (367 * month - 362).div_euclid(12)
+        + correction
+        + day
+}
+
+fn julian_from_fixed(fixed: i64) -> (i64, i64, i64) {
+    let year = (4 * (fixed - JULIAN_EPOCH) + 1464).div_euclid(1461);
+    let prior_days = fixed - fixed_from_julian(year, 1, 1);
+    let correction = if fixed < fixed_from_julian(year, 3, 1) {
+        0
+    } else if julian_leap_year(year) {
+        1
+    } else {
+        2
+    };
+    let month = (12 * (prior_days + correction) + 373).div_euclid(367);
+    let day = fixed - fixed_from_julian(year, month, 1) + 1;
+    (year, month, day)
+}
+
+fn fixed_from_islamic(year: i64, month: i64, day: i64) -> i64 {
+    ISLAMIC_EPOCH - 1
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + 29 * (month - 1)
+        + (6 * month - 1).div_euclid(11)
+        + day
+}
+
+fn islamic_from_fixed(fixed: i64) -> (i64, i64, i64) {
+    let year = (30 * (fixed - ISLAMIC_EPOCH) + 10646).div_euclid(10631);
+    let prior_days = fixed - fixed_from_islamic(year, 1, 1);
+    let month = (11 * prior_days + 330).div_euclid(325);
+    let day = fixed - fixed_from_islamic(year, month, 1) + 1;
+    (year, month, day)
+}
+
+fn hebrew_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn last_hebrew_month(year: i64) -> i64 {
+    if hebrew_leap_year(year) {
+        13
+    } else {
+        12
+    }
+}
+
+/// Returns the number of days from the epoch to the molad (new moon) of
+/// Tishrei in `year`, delayed by a day if it would fall on a Sunday,
+/// Wednesday or Friday
+fn hebrew_elapsed_days(year: i64) -> i64 {
+    let months_elapsed = (235 * year - 234).div_euclid(19);
+    let parts_elapsed = 12084 + 13753 * months_elapsed;
+    let days = 29 * months_elapsed + parts_elapsed.div_euclid(25920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    let previous = hebrew_elapsed_days(year - 1);
+    let current = hebrew_elapsed_days(year);
+    let next = hebrew_elapsed_days(year + 1);
+    // avoids years with an invalid length
+    let correction = if next - current == 356 {
+        2
+    } else {
+        i64::from(current - previous == 382)
+    };
+    HEBREW_EPOCH + current + correction
+}
+
+fn hebrew_month_length(year: i64, month: i64) -> i64 {
+    let year_length = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = match month {
+        2 | 4 | 6 | 10 | 13 => true,
+        12 => !hebrew_leap_year(year),
+        // Cheshvan and Kislev vary to keep years at a valid length
+        8 => !matches!(year_length, 355 | 385),
+        9 => matches!(year_length, 353 | 383),
+        _ => false,
+    };
+    if short {
+        29
+    } else {
+        30
+    }
+}
+
+fn fixed_from_hebrew(year: i64, month: i64, day: i64) -> i64 {
+    let months_before = if month < 7 {
+        (7..=last_hebrew_month(year))
+            .chain(1..month)
+            .collect::<Vec<_>>()
+    } else {
+        (7..month).collect()
+    };
+    let days_before = months_before
+        .into_iter()
+        .map(|month| hebrew_month_length(year, month))
+        .sum::<i64>();
+    hebrew_new_year(year) + days_before + day - 1
+}
+
+fn hebrew_from_fixed(fixed: i64) -> (i64, i64, i64) {
+    // the average length of a year is 35975351/98496 days
+    let approx = (98496 * (fixed - HEBREW_EPOCH)).div_euclid(35_975_351) + 1;
+    let mut year = approx - 1;
+    while hebrew_new_year(year + 1) <= fixed {
+        year += 1;
+    }
+    let start = if fixed < fixed_from_hebrew(year, 1, 1) {
+        7
+    } else {
+        1
+    };
+    let month = (start..=13)
+        .find(|&month| fixed <= fixed_from_hebrew(year, month, hebrew_month_length(year, month)))
+        .unwrap();
+    let day = fixed - fixed_from_hebrew(year, month, 1) + 1;
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(date: &str, calendar: Calendar) -> String {
+        CalendarDate::from_date(Date::parse(date).unwrap(), calendar).to_string()
+    }
+
+    #[test]
+    fn convert_to_calendars() {
+        // sample dates from "Calendrical Calculations"
+        assert_eq!(
+            convert("1945-11-12", Calendar::Julian),
+            "30 October 1945 (Julian)"
+        );
+        assert_eq!(convert("1945-11-12", Calendar::Hebrew), "7 Kislev 5706");
+        assert_eq!(
+            convert("1945-11-12", Calendar::Islamic),
+            "6 Dhu al-Hijjah 1364 AH"
+        );
+        assert_eq!(
+            convert("2024-05-01", Calendar::Julian),
+            "18 April 2024 (Julian)"
+        );
+        assert_eq!(
+            convert("1582-10-15", Calendar::Julian),
+            "5 October 1582 (Julian)"
+        );
+        assert_eq!(convert("2024-04-23", Calendar::Hebrew), "15 Nisan 5784");
+        assert_eq!(convert("2023-09-16", Calendar::Hebrew), "1 Tishrei 5784");
+        assert_eq!(convert("2024-02-10", Calendar::Hebrew), "1 Adar I 5784");
+        assert_eq!(convert("2024-03-11", Calendar::Hebrew), "1 Adar II 5784");
+        assert_eq!(convert("2025-03-01", Calendar::Hebrew), "1 Adar 5785");
+        assert_eq!(
+            convert("2024-03-11", Calendar::Islamic),
+            "1 Ramadan 1445 AH"
+        );
+        assert_eq!(convert("0622-07-19", Calendar::Islamic), "1 Muharram 1 AH");
+    }
+
+    #[test]
+    fn parse_calendar_dates() {
+        for (s, date) in [
+            ("18 April 2024 (Julian)", "2024-05-01"),
+            ("15 Nisan 5784", "2024-04-23"),
+            ("1 adar ii 5784", "2024-03-11"),
+            ("1 Ramadan 1445 AH", "2024-03-11"),
+            ("10 Dhu al-Hijjah 1445", "2024-06-17"),
+        ] {
+            let parsed = CalendarDate::parse(s).unwrap().to_date().unwrap();
+            assert_eq!(parsed, Date::parse(date).unwrap());
+        }
+        assert!(CalendarDate::parse("30 Iyar 5784").is_err());
+        assert!(CalendarDate::parse("1 Adar II 5785").is_err());
+        assert!(CalendarDate::parse("0 Nisan 5784").is_err());
+        assert!(CalendarDate::parse("1 Nisan").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut date = Date::parse("1900-01-01").unwrap();
+        for _ in 0..100 {
+            for calendar in [Calendar::Julian, Calendar::Hebrew, Calendar::Islamic] {
+                let converted = CalendarDate::from_date(date, calendar);
+                assert_eq!(converted.to_date().unwrap(), date);
+                let parsed = CalendarDate::parse(&converted.to_string()).unwrap();
+                assert_eq!(parsed, converted);
+            }
+            date = date.add_days(457).unwrap();
+        }
+    }
+}
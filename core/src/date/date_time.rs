@@ -0,0 +1,104 @@
+use super::{Date, Time};
+use crate::error::FendError;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+/// A date with a time of day, e.g. the result of `today + 36 hours`
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub(crate) struct DateTime {
+    pub(crate) date: Date,
+    pub(crate) time: Time,
+}
+
+impl DateTime {
+    pub(crate) fn now(context: &mut crate::Context) -> Result<Self, FendError> {
+        let ms_since_epoch = context
+            .current_time
+            .as_ref()
+            .ok_or(FendError::UnableToGetCurrentDate)?
+            .local_ms_since_epoch()?;
+        let (_, time) = Time::MIDNIGHT.add_seconds(ms_since_epoch.div_euclid(1000))?;
+        Ok(Self {
+            date: Date::from_days_since_epoch(ms_since_epoch.div_euclid(86_400_000))?,
+            time,
+        })
+    }
+
+    /// Returns the number of seconds since 1970-01-01 00:00
+    pub(crate) fn seconds_since_epoch(self) -> i64 {
+        self.date.days_since_epoch() * 86400 + self.time.seconds_since_midnight()
+    }
+
+    pub(crate) fn add_seconds(self, seconds: i64) -> Result<Self, FendError> {
+        let (days, time) = self.time.add_seconds(seconds)?;
+        Ok(Self {
+            date: self.date.add_days(days)?,
+            time,
+        })
+    }
+
+    pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
+        super::parser::parse_date_time(s)
+    }
+
+    /// Formats this date and time like `2025-01-01T14:30:00`
+    pub(crate) fn to_iso(self) -> String {
+        format!("{}T{}", self.date.to_iso(), self.time.to_iso())
+    }
+}
+
+impl fmt::Debug for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+impl ValueTrait for DateTime {
+    fn type_name(&self) -> &'static str {
+        "date and time"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+
+    fn get_object_member(&self, key: &str) -> Option<Value> {
+        match key {
+            "date" => Some(self.date.into()),
+            "time" => Some(self.time.into()),
+            key => self.date.get_object_member(key),
+        }
+    }
+
+    // e.g. `3pm EST`
+    fn apply(&self, arg: Value) -> Option<Result<Value, FendError>> {
+        let zone = *arg.as_dyn::<super::TimeZone>()?;
+        super::ZonedTime::new(&(*self).into(), zone).map(|zoned| Ok(zoned.into()))
+    }
+
+    fn sub(&self, rhs: Value, context: &mut crate::Context) -> Result<Value, FendError> {
+        super::sub(
+            &(*self).into(),
+            rhs,
+            context,
+            &crate::interrupt::Never::default(),
+        )
+    }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
+
+    fn compare(&self, rhs: &Value) -> Option<std::cmp::Ordering> {
+        super::compare(&(*self).into(), rhs)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::value::ValueTrait;
+use crate::error::FendError;
+use crate::num::Number;
+use crate::value::{Value, ValueTrait};
 use std::fmt;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -12,6 +14,41 @@ pub(crate) enum DayOfWeek {
     Saturday,
 }
 
+impl DayOfWeek {
+    /// Parses the name of a day like `friday` or `Friday`
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        Some(match s.to_ascii_lowercase().as_str() {
+            "sunday" => Self::Sunday,
+            "monday" => Self::Monday,
+            "tuesday" => Self::Tuesday,
+            "wednesday" => Self::Wednesday,
+            "thursday" => Self::Thursday,
+            "friday" => Self::Friday,
+            "saturday" => Self::Saturday,
+            _ => return None,
+        })
+    }
+
+    /// Parses plural day names like `fridays`, e.g. for
+    /// `fridays between('2025-01-01', '2025-12-31')`
+    pub(crate) fn parse_plural(s: &str) -> Option<Self> {
+        Self::parse(s.strip_suffix('s')?)
+    }
+
+    /// Returns the number of the day, from 0 (Sunday) to 6 (Saturday)
+    pub(crate) fn number(self) -> i64 {
+        match self {
+            Self::Sunday => 0,
+            Self::Monday => 1,
+            Self::Tuesday => 2,
+            Self::Wednesday => 3,
+            Self::Thursday => 4,
+            Self::Friday => 5,
+            Self::Saturday => 6,
+        }
+    }
+}
+
 impl fmt::Debug for DayOfWeek {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
@@ -44,4 +81,10 @@ impl ValueTrait for DayOfWeek {
             kind: crate::SpanKind::Date,
         });
     }
+
+    // e.g. `fridays between('2025-01-01', '2025-12-31')`
+    fn apply(&self, arg: Value) -> Option<Result<Value, FendError>> {
+        let count = arg.as_dyn::<super::DateInterval>()?.count_weekday(*self);
+        Some(Ok(Value::Num(Box::new(Number::from(count)))))
+    }
 }
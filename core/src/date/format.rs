@@ -0,0 +1,203 @@
+use super::{Date, DateTime, Time, ZonedTime};
+use crate::error::FendError;
+use crate::value::Value;
+use std::fmt::Write;
+
+/// The parts of a date or time that a format pattern can refer to
+struct Parts {
+    date: Option<Date>,
+    time: Option<Time>,
+    // offset from UTC in seconds, and the time zone abbreviation
+    zone: Option<(i64, &'static str)>,
+}
+
+impl Parts {
+    fn new(value: &Value) -> Result<Self, FendError> {
+        if let Value::String(s) = value {
+            return Self::new(&super::parse_moment(s.as_ref())?);
+        }
+        if let Some(&date) = value.as_dyn::<Date>() {
+            return Ok(Self {
+                date: Some(date),
+                time: None,
+                zone: None,
+            });
+        }
+        if let Some(&time) = value.as_dyn::<Time>() {
+            return Ok(Self {
+                date: None,
+                time: Some(time),
+                zone: None,
+            });
+        }
+        if let Some(&DateTime { date, time }) = value.as_dyn::<DateTime>() {
+            return Ok(Self {
+                date: Some(date),
+                time: Some(time),
+                zone: None,
+            });
+        }
+        if let Some(zoned) = value.as_dyn::<ZonedTime>() {
+            let (date, time, offset, abbreviation) = zoned.parts();
+            return Ok(Self {
+                date,
+                time: Some(time),
+                zone: Some((offset, abbreviation)),
+            });
+        }
+        Err(FendError::ExpectedADateOrTime)
+    }
+
+    fn date(&self, specifier: char) -> Result<Date, FendError> {
+        self.date
+            .ok_or(FendError::DateFormatSpecifierRequires(specifier, "date"))
+    }
+
+    fn time(&self, specifier: char) -> Result<(u32, u32, u32), FendError> {
+        let seconds = self
+            .time
+            .ok_or(FendError::DateFormatSpecifierRequires(specifier, "time"))?
+            .seconds_since_midnight();
+        let seconds = u32::try_from(seconds).unwrap();
+        Ok((seconds / 3600, seconds / 60 % 60, seconds % 60))
+    }
+
+    fn zone(&self, specifier: char) -> Result<(i64, &'static str), FendError> {
+        self.zone.ok_or(FendError::DateFormatSpecifierRequires(
+            specifier,
+            "time zone",
+        ))
+    }
+}
+
+fn abbreviate(name: &str) -> &str {
+    name.get(..3).unwrap_or(name)
+}
+
+/// Formats a date or time using a `strftime`-style pattern like
+/// `%Y-%m-%d %H:%M`. Strings are parsed as dates or times first.
+pub(crate) fn format(value: &Value, pattern: &str) -> Result<String, FendError> {
+    let parts = Parts::new(value)?;
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            result.push(ch);
+            continue;
+        }
+        let specifier = chars
+            .next()
+            .ok_or_else(|| FendError::UnknownDateFormatSpecifier("%".to_string()))?;
+        match specifier {
+            'Y' => {
+                let year = parts.date(specifier)?.year.value();
+                if (0..=9999).contains(&year) {
+                    write!(result, "{:04}", year)?;
+                } else {
+                    write!(result, "{:+05}", year)?;
+                }
+            }
+            'y' => {
+                let year = parts.date(specifier)?.year.value();
+                write!(result, "{:02}", year.rem_euclid(100))?;
+            }
+            'm' => write!(result, "{:02}", parts.date(specifier)?.month.number())?,
+            'd' => write!(result, "{:02}", parts.date(specifier)?.day.value())?,
+            'e' => write!(result, "{:2}", parts.date(specifier)?.day.value())?,
+            'j' => write!(result, "{:03}", parts.date(specifier)?.day_of_year())?,
+            'V' => write!(result, "{:02}", parts.date(specifier)?.iso_week())?,
+            'B' => write!(result, "{}", parts.date(specifier)?.month)?,
+            'b' => {
+                let month = parts.date(specifier)?.month.to_string();
+                result.push_str(abbreviate(&month));
+            }
+            'A' => write!(result, "{}", parts.date(specifier)?.day_of_week())?,
+            'a' => {
+                let day = parts.date(specifier)?.day_of_week().to_string();
+                result.push_str(abbreviate(&day));
+            }
+            'u' => {
+                // 1970-01-01 was a Thursday, and Monday is day 1
+                let days = parts.date(specifier)?.days_since_epoch();
+                write!(result, "{}", (days + 3).rem_euclid(7) + 1)?;
+            }
+            'F' => {
+                let date = parts.date(specifier)?;
+                result.push_str(&format(&date.into(), "%Y-%m-%d")?);
+            }
+            'H' => write!(result, "{:02}", parts.time(specifier)?.0)?,
+            'I' => {
+                let hour = parts.time(specifier)?.0 % 12;
+                write!(result, "{:02}", if hour == 0 { 12 } else { hour })?;
+            }
+            'M' => write!(result, "{:02}", parts.time(specifier)?.1)?,
+            'S' => write!(result, "{:02}", parts.time(specifier)?.2)?,
+            'p' => result.push_str(if parts.time(specifier)?.0 < 12 {
+                "AM"
+            } else {
+                "PM"
+            }),
+            'R' => {
+                let (hour, minute, _) = parts.time(specifier)?;
+                write!(result, "{:02}:{:02}", hour, minute)?;
+            }
+            'T' => {
+                let (hour, minute, second) = parts.time(specifier)?;
+                write!(result, "{:02}:{:02}:{:02}", hour, minute, second)?;
+            }
+            'Z' => result.push_str(parts.zone(specifier)?.1),
+            'z' => {
+                let offset = parts.zone(specifier)?.0;
+                let sign = if offset < 0 { '-' } else { '+' };
+                let minutes = offset.abs() / 60;
+                write!(result, "{}{:02}{:02}", sign, minutes / 60, minutes % 60)?;
+            }
+            '%' => result.push('%'),
+            _ => {
+                return Err(FendError::UnknownDateFormatSpecifier(format!(
+                    "%{}",
+                    specifier
+                )))
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Value {
+        Date::parse(s).unwrap().into()
+    }
+
+    #[test]
+    fn format_dates() {
+        let value = date("2024-05-01");
+        assert_eq!(format(&value, "%Y-%m-%d").unwrap(), "2024-05-01");
+        assert_eq!(format(&value, "%d/%m/%y").unwrap(), "01/05/24");
+        assert_eq!(format(&value, "%a %e %b").unwrap(), "Wed  1 May");
+        assert_eq!(format(&value, "%A, %B %d").unwrap(), "Wednesday, May 01");
+        assert_eq!(
+            format(&value, "day %j, weekday %u").unwrap(),
+            "day 122, weekday 3"
+        );
+        assert_eq!(format(&value, "week %V").unwrap(), "week 18");
+        assert_eq!(format(&date("-44-03-15"), "%F").unwrap(), "-0044-03-15");
+        assert_eq!(format(&value, "100%%").unwrap(), "100%");
+        assert!(format(&value, "%H").is_err());
+        assert!(format(&value, "%Q").is_err());
+        assert!(format(&value, "%").is_err());
+    }
+
+    #[test]
+    fn format_times() {
+        let value = Time::new(15, 4, 5).unwrap().into();
+        assert_eq!(format(&value, "%H:%M:%S").unwrap(), "15:04:05");
+        assert_eq!(format(&value, "%I:%M %p").unwrap(), "03:04 PM");
+        assert_eq!(format(&Time::MIDNIGHT.into(), "%I %p").unwrap(), "12 AM");
+        assert!(format(&value, "%Y").is_err());
+        assert!(format(&value, "%Z").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use super::{Date, DayOfWeek, Month, Year};
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+
+/// A holiday that `is_holiday` and `add_workdays` take into account, set
+/// with `Context::set_holidays`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Holiday {
+    // the same day every year, e.g. `12-25`
+    Annual(Month, u8),
+    // a single date, e.g. `2025-05-05`
+    Once(Date),
+    // a number of days after Easter Sunday, e.g. `easter-2` for Good Friday
+    Easter(i64),
+}
+
+impl Holiday {
+    /// New Year's Day and Christmas Day
+    pub(crate) fn defaults() -> Vec<Self> {
+        vec![
+            Self::Annual(Month::January, 1),
+            Self::Annual(Month::December, 25),
+        ]
+    }
+
+    pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
+        let error = || FendError::InvalidHoliday(s.to_string());
+        let s = s.trim();
+        if let Some(offset) = s.strip_prefix("easter") {
+            let offset = match offset.strip_prefix('+') {
+                Some(offset) => offset.parse::<i64>(),
+                None if offset.is_empty() => Ok(0),
+                None => offset.parse::<i64>(),
+            };
+            return offset.map(Self::Easter).map_err(|_| error());
+        }
+        if let Ok(date) = super::parser::parse_date(s) {
+            return Ok(Self::Once(date));
+        }
+        let (month, day) = s.split_once('-').ok_or_else(error)?;
+        let month = month.parse::<i32>().map_err(|_| error())?;
+        let month = Month::try_from(month).map_err(|_| error())?;
+        let day = day.parse::<u8>().map_err(|_| error())?;
+        // a leap year, so that `02-29` is allowed
+        if day == 0 || day > month.number_of_days(Year::new(2000)) {
+            return Err(error());
+        }
+        Ok(Self::Annual(month, day))
+    }
+
+    fn matches(self, date: Date) -> bool {
+        match self {
+            Self::Annual(month, day) => date.month == month && date.day.value() == day,
+            Self::Once(holiday) => date == holiday,
+            Self::Easter(offset) => match date.add_days(-offset) {
+                Ok(sunday) => matches!(easter(sunday.year.value().into()), Ok(e) if e == sunday),
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+/// Returns the date of (Western) Easter Sunday in the given year, using
+/// the anonymous Gregorian algorithm
+pub(crate) fn easter(year: i64) -> Result<Date, FendError> {
+    if i32::try_from(year).is_err() {
+        return Err(FendError::DateOutOfRange);
+    }
+    let golden = year.rem_euclid(19);
+    let century = year.div_euclid(100);
+    let year_of_century = year.rem_euclid(100);
+    let leap_centuries = century.div_euclid(4);
+    let skipped_leap = century.rem_euclid(4);
+    let moon_correction = (century - (century + 8).div_euclid(25) + 1).div_euclid(3);
+    let epact = (19 * golden + century - leap_centuries - moon_correction + 15).rem_euclid(30);
+    let weekday = (32 + 2 * skipped_leap + 2 * (year_of_century / 4) - epact - year_of_century % 4)
+        .rem_euclid(7);
+    let correction = (golden + 11 * epact + 22 * weekday) / 451;
+    let month = (epact + weekday - 7 * correction + 114) / 31;
+    let day = (epact + weekday - 7 * correction + 114) % 31 + 1;
+    Date::from_ymd(year, month, day)
+}
+
+pub(crate) fn is_holiday(date: Date, holidays: &[Holiday]) -> bool {
+    holidays.iter().any(|holiday| holiday.matches(date))
+}
+
+fn is_workday(date: Date, holidays: &[Holiday]) -> bool {
+    !matches!(date.day_of_week(), DayOfWeek::Saturday | DayOfWeek::Sunday)
+        && !is_holiday(date, holidays)
+}
+
+/// Moves a date forward (or back, if `workdays` is negative) by a number of
+/// workdays, skipping weekends and holidays
+pub(crate) fn add_workdays<I: Interrupt>(
+    mut date: Date,
+    workdays: i64,
+    holidays: &[Holiday],
+    int: &I,
+) -> Result<Date, FendError> {
+    let step = workdays.signum();
+    for _ in 0..workdays.unsigned_abs() {
+        date = date.add_days(step)?;
+        // this can only loop forever if every day is a holiday
+        while !is_workday(date, holidays) {
+            test_int(int)?;
+            date = date.add_days(step)?;
+        }
+        test_int(int)?;
+    }
+    Ok(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        Date::parse(s).unwrap()
+    }
+
+    #[test]
+    fn easter_sundays() {
+        assert_eq!(easter(2024).unwrap(), date("2024-03-31"));
+        assert_eq!(easter(2025).unwrap(), date("2025-04-20"));
+        assert_eq!(easter(2038).unwrap(), date("2038-04-25"));
+        assert_eq!(easter(1818).unwrap(), date("1818-03-22"));
+        assert_eq!(easter(1961).unwrap(), date("1961-04-02"));
+    }
+
+    #[test]
+    fn easter_sundays_before_year_1() {
+        // the Gregorian Easter dates repeat every 5,700,000 years
+        for year in -500..=0 {
+            let sunday = easter(year).unwrap();
+            let later = easter(year + 5_700_000).unwrap();
+            assert_eq!(sunday.day_of_week(), DayOfWeek::Sunday, "{}", year);
+            assert_eq!((sunday.month, sunday.day), (later.month, later.day));
+        }
+        assert_eq!(easter(-1).unwrap(), date("-0001-04-18"));
+        assert_eq!(easter(-5).unwrap(), date("-0005-04-02"));
+    }
+
+    #[test]
+    fn parse_holidays() {
+        assert_eq!(
+            Holiday::parse("12-25").unwrap(),
+            Holiday::Annual(Month::December, 25)
+        );
+        assert_eq!(
+            Holiday::parse("2025-05-05").unwrap(),
+            Holiday::Once(date("2025-05-05"))
+        );
+        assert_eq!(Holiday::parse("easter").unwrap(), Holiday::Easter(0));
+        assert_eq!(Holiday::parse("easter-2").unwrap(), Holiday::Easter(-2));
+        assert_eq!(Holiday::parse("easter+1").unwrap(), Holiday::Easter(1));
+        assert!(Holiday::parse("02-30").is_err());
+        assert!(Holiday::parse("christmas").is_err());
+        assert!(Holiday::parse("easter+").is_err());
+    }
+
+    #[test]
+    fn workdays() {
+        let holidays = [Holiday::Easter(-2), Holiday::Easter(1)];
+        let int = crate::interrupt::Never::default();
+        // Thursday before Easter 2024, then Tuesday after Easter Monday
+        let result = add_workdays(date("2024-03-28"), 1, &holidays, &int).unwrap();
+        assert_eq!(result, date("2024-04-02"));
+        let result = add_workdays(date("2024-04-02"), -1, &holidays, &int).unwrap();
+        assert_eq!(result, date("2024-03-28"));
+        let result = add_workdays(date("2024-05-03"), 0, &holidays, &int).unwrap();
+        assert_eq!(result, date("2024-05-03"));
+        let result = add_workdays(date("2024-05-03"), 5, &holidays, &int).unwrap();
+        assert_eq!(result, date("2024-05-10"));
+    }
+}
@@ -0,0 +1,211 @@
+use super::{Date, DayOfWeek};
+use crate::error::{FendError, Interrupt};
+use crate::num::Number;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+/// The calendar interval between two dates, e.g. `between('1990-06-15',
+/// today)`, which is shown as whole years, months and days. It can also be
+/// converted to a (fractional) number of years, months or days, e.g. for
+/// ages.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DateInterval {
+    start: Date,
+    end: Date,
+    negative: bool,
+}
+
+impl DateInterval {
+    pub(crate) fn new(start: Date, end: Date) -> Self {
+        if end.days_since_epoch() < start.days_since_epoch() {
+            Self {
+                start: end,
+                end: start,
+                negative: true,
+            }
+        } else {
+            Self {
+                start,
+                end,
+                negative: false,
+            }
+        }
+    }
+
+    /// Creates the interval between two dates or strings, e.g. for
+    /// `between('1990-06-15', '2024-05-01')`
+    pub(crate) fn between(start: &Value, end: &Value) -> Result<Self, FendError> {
+        Ok(Self::new(
+            super::expect_date(start)?,
+            super::expect_date(end)?,
+        ))
+    }
+
+    /// Returns the number of whole months in this interval
+    fn whole_months(self) -> i64 {
+        let month_number =
+            |date: Date| i64::from(date.year.value()) * 12 + i64::from(date.month.number());
+        let mut months = month_number(self.end) - month_number(self.start);
+        if self.end.day.value() < self.start.day.value() {
+            months -= 1;
+        }
+        months
+    }
+
+    /// Returns the number of days in this interval after the last whole
+    /// month
+    fn remaining_days(self) -> Result<i64, FendError> {
+        let last_month = self.start.add_months(self.whole_months())?;
+        Ok(self.end.days_since_epoch() - last_month.days_since_epoch())
+    }
+
+    /// Counts the dates in this interval (including the first and last
+    /// date) that fall on the given day of the week
+    pub(crate) fn count_weekday(self, day_of_week: DayOfWeek) -> u64 {
+        let days = self.end.days_since_epoch() - self.start.days_since_epoch() + 1;
+        let first = self.start.day_of_week().number();
+        let extra = (day_of_week.number() - first).rem_euclid(7) < days % 7;
+        u64::try_from(days / 7 + i64::from(extra)).unwrap()
+    }
+
+    /// Returns the length of this interval in units of `step` calendar
+    /// months, e.g. 12 for years. The fractional part is the part of the
+    /// next step that has passed, so e.g. half a year after a birthday is
+    /// about 0.5 years.
+    fn fractional_months<I: Interrupt>(self, step: i64, int: &I) -> Result<Number, FendError> {
+        let whole = self.whole_months().div_euclid(step);
+        let last = self.start.add_months(whole * step)?.days_since_epoch();
+        let next = self
+            .start
+            .add_months((whole + 1) * step)?
+            .days_since_epoch();
+        let fraction = super::signed_number(self.end.days_since_epoch() - last)
+            .div(super::signed_number(next - last), int)?;
+        super::signed_number(whole).add(fraction, int)
+    }
+
+    fn sign(self, n: Number) -> Number {
+        if self.negative {
+            -n
+        } else {
+            n
+        }
+    }
+
+    /// Converts this interval to a unit of time like `years` or `days`.
+    /// Months and years are calendar months and years, while other units
+    /// are based on the number of days.
+    pub(crate) fn convert_to<I: Interrupt>(
+        &self,
+        unit: Number,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let (value, base_unit) = match unit.unit_names().as_slice() {
+            ["month" | "mo"] => (self.fractional_months(1, int)?, "months"),
+            ["year" | "yr" | "decade" | "century" | "millennium"] => {
+                (self.fractional_months(12, int)?, "years")
+            }
+            _ => (
+                super::signed_number(self.end.days_since_epoch() - self.start.days_since_epoch()),
+                "days",
+            ),
+        };
+        let base_unit = crate::units::query_unit(base_unit, context, int)?.expect_num()?;
+        let value = self.sign(value).mul(base_unit, int)?;
+        Ok(Value::Num(Box::new(value.convert_to(unit, int)?)))
+    }
+}
+
+impl fmt::Display for DateInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let months = self.whole_months();
+        let days = self.remaining_days().map_err(|_| fmt::Error)?;
+        let parts = [
+            (months / 12, "year", "years"),
+            (months % 12, "month", "months"),
+            (days, "day", "days"),
+        ];
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut first = true;
+        for (count, singular, plural) in parts {
+            if count == 0 {
+                continue;
+            }
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+            write!(
+                f,
+                "{} {}",
+                count,
+                if count == 1 { singular } else { plural }
+            )?;
+        }
+        if first {
+            write!(f, "0 days")?;
+        }
+        Ok(())
+    }
+}
+
+impl ValueTrait for DateInterval {
+    fn type_name(&self) -> &'static str {
+        "date interval"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+
+    fn get_object_member(&self, key: &str) -> Option<Value> {
+        let months = self.whole_months();
+        let value = match key {
+            "years" => months / 12,
+            "months" => months % 12,
+            "days" => self.remaining_days().ok()?,
+            _ => return None,
+        };
+        let value = if self.negative { -value } else { value };
+        Some(Value::Num(Box::new(super::signed_number(value))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: &str, end: &str) -> DateInterval {
+        DateInterval::new(Date::parse(start).unwrap(), Date::parse(end).unwrap())
+    }
+
+    #[test]
+    fn intervals_between_dates() {
+        let age = interval("1990-06-15", "2024-05-01");
+        assert_eq!(age.to_string(), "33 years 10 months 16 days");
+        assert_eq!(
+            interval("2024-01-31", "2024-03-01").to_string(),
+            "1 month 1 day"
+        );
+        assert_eq!(interval("2024-05-01", "2025-05-01").to_string(), "1 year");
+        assert_eq!(interval("2024-05-01", "2024-05-01").to_string(), "0 days");
+        assert_eq!(interval("2024-05-01", "2024-04-01").to_string(), "-1 month");
+    }
+
+    #[test]
+    fn count_weekdays() {
+        let year = interval("2025-01-01", "2025-12-31");
+        assert_eq!(year.count_weekday(DayOfWeek::Friday), 52);
+        // 2025 starts and ends on a Wednesday
+        assert_eq!(year.count_weekday(DayOfWeek::Wednesday), 53);
+        let day = interval("2024-05-06", "2024-05-06");
+        assert_eq!(day.count_weekday(DayOfWeek::Monday), 1);
+        assert_eq!(day.count_weekday(DayOfWeek::Tuesday), 0);
+    }
+}
@@ -67,6 +67,24 @@ impl Month {
         }
     }
 
+    /// Returns the number of the month, from 1 (January) to 12 (December)
+    pub(crate) fn number(self) -> u8 {
+        match self {
+            Self::January => 1,
+            Self::February => 2,
+            Self::March => 3,
+            Self::April => 4,
+            Self::May => 5,
+            Self::June => 6,
+            Self::July => 7,
+            Self::August => 8,
+            Self::September => 9,
+            Self::October => 10,
+            Self::November => 11,
+            Self::December => 12,
+        }
+    }
+
     fn as_str(self) -> &'static str {
         match self {
             Self::January => "January",
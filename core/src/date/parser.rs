@@ -1,5 +1,5 @@
 use crate::{
-    date::{Date, Day, Month, Year},
+    date::{Date, DateTime, Day, Month, Time, Year},
     error::FendError,
 };
 use std::convert;
@@ -39,13 +39,28 @@ fn parse_num(s: &str, leading_zeroes: bool) -> Result<(i32, &str), ()> {
     Ok((num, s))
 }
 
-fn parse_yyyymmdd(s: &str) -> Result<(Date, &str), ()> {
-    let (year, s) = parse_num(s, false)?;
-    let s = parse_specific_char(s, '-')?;
-    if year < 1000 {
+// Years use astronomical year numbering as in ISO 8601, so e.g. 45 BC can
+// be written as `-0044` or `-44`. Unsigned years need at least four digits,
+// e.g. `0044` or `2021`.
+fn parse_year(s: &str) -> Result<(Year, &str), ()> {
+    let (sign, unsigned) = if let Ok(remaining) = parse_specific_char(s, '-') {
+        (-1, remaining)
+    } else if let Ok(remaining) = parse_specific_char(s, '+') {
+        (1, remaining)
+    } else {
+        (1, s)
+    };
+    let (year, remaining) = parse_num(unsigned, true)?;
+    let digits = unsigned.len() - remaining.len();
+    if digits < 4 && unsigned.len() == s.len() {
         return Err(());
     }
-    let year = Year::new(year);
+    Ok((Year::new(sign * year), remaining))
+}
+
+fn parse_yyyymmdd(s: &str) -> Result<(Date, &str), ()> {
+    let (year, s) = parse_year(s)?;
+    let s = parse_specific_char(s, '-')?;
     let (month, s) = parse_num(s, true)?;
     let s = parse_specific_char(s, '-')?;
     let month: Month = convert::TryInto::try_into(month).map_err(|_| ())?;
@@ -68,6 +83,119 @@ pub(crate) fn parse_date(s: &str) -> Result<Date, FendError> {
     Err(FendError::ParseDateError(s.to_string()))
 }
 
+fn parse_two_digits(s: &str) -> Result<(u32, &str), ()> {
+    let (a, s) = parse_digit(s)?;
+    let (b, s) = parse_digit(s)?;
+    Ok((u32::try_from(a * 10 + b).map_err(|_| ())?, s))
+}
+
+/// Parses a time like `9:30`, `09:30`, `14:30:15`, `3pm` or `9:30am` at
+/// the start of `s`
+pub(crate) fn parse_time_prefix(s: &str) -> Result<(Time, &str), ()> {
+    let (mut hour, mut s) = parse_digit(s)?;
+    if let Ok((digit, remaining)) = parse_digit(s) {
+        hour = hour * 10 + digit;
+        s = remaining;
+    }
+    let mut hour = u32::try_from(hour).map_err(|_| ())?;
+    let mut minute = 0;
+    let mut second = 0;
+    let has_minutes = if let Ok(remaining) = parse_specific_char(s, ':') {
+        let (minutes, remaining) = parse_two_digits(remaining)?;
+        minute = minutes;
+        s = remaining;
+        if let Ok(remaining) = parse_specific_char(s, ':') {
+            let (seconds, remaining) = parse_two_digits(remaining)?;
+            second = seconds;
+            s = remaining;
+        }
+        true
+    } else {
+        false
+    };
+    let suffix = s.get(..2).map(str::to_ascii_lowercase);
+    if let Some(pm) = suffix.as_deref().and_then(|suffix| match suffix {
+        "am" => Some(false),
+        "pm" => Some(true),
+        _ => None,
+    }) {
+        if !(1..=12).contains(&hour) {
+            return Err(());
+        }
+        s = &s[2..];
+        if s.starts_with(|ch: char| ch.is_alphanumeric() || ch == '_') {
+            return Err(());
+        }
+        hour %= 12;
+        if pm {
+            hour += 12;
+        }
+    } else if !has_minutes {
+        return Err(());
+    }
+    if s.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return Err(());
+    }
+    Ok((Time::new(hour, minute, second).ok_or(())?, s))
+}
+
+pub(crate) fn parse_time(s: &str) -> Result<Time, FendError> {
+    match parse_time_prefix(s.trim()) {
+        Ok((time, "")) => Ok(time),
+        _ => Err(FendError::ParseTimeError(s.to_string())),
+    }
+}
+
+fn parse_date_time_prefix(s: &str) -> Result<(DateTime, &str), ()> {
+    let (date, s) = parse_yyyymmdd(s)?;
+    let s = s
+        .strip_prefix('T')
+        .or_else(|| s.strip_prefix(' '))
+        .ok_or(())?;
+    let (time, s) = parse_time_prefix(s.trim_start())?;
+    Ok((DateTime { date, time }, s))
+}
+
+/// Parses a date and time like `2025-01-01 14:30` or `2025-01-01T14:30`
+pub(crate) fn parse_date_time(s: &str) -> Result<DateTime, FendError> {
+    match parse_date_time_prefix(s.trim()) {
+        Ok((date_time, "")) => Ok(date_time),
+        _ => Err(FendError::ParseDateTimeError(s.to_string())),
+    }
+}
+
+/// Parses an ISO 8601 UTC offset like `Z`, `+09:00` or `-0500`, returning
+/// the offset in seconds
+fn parse_utc_offset(s: &str) -> Result<(i64, &str), ()> {
+    if let Some(remaining) = s.strip_prefix('Z').or_else(|| s.strip_prefix('z')) {
+        return Ok((0, remaining));
+    }
+    let (sign, s) = if let Ok(remaining) = parse_specific_char(s, '+') {
+        (1, remaining)
+    } else {
+        (-1, parse_specific_char(s, '-')?)
+    };
+    let (hours, s) = parse_two_digits(s)?;
+    let s = parse_specific_char(s, ':').unwrap_or(s);
+    let (minutes, s) = parse_two_digits(s).unwrap_or((0, s));
+    if hours > 23 || minutes > 59 {
+        return Err(());
+    }
+    Ok((sign * i64::from(hours * 3600 + minutes * 60), s))
+}
+
+/// Parses an ISO 8601 date and time with a UTC offset like
+/// `2025-01-01T14:30:00Z`, returning the offset in seconds
+pub(crate) fn parse_zoned_date_time(s: &str) -> Result<(DateTime, i64), FendError> {
+    let trimmed = s.trim();
+    if let Ok((date_time, remaining)) = parse_date_time_prefix(trimmed) {
+        if let Ok((offset, "")) = parse_utc_offset(remaining) {
+            return Ok((date_time, offset));
+        }
+    }
+    Err(FendError::ParseDateTimeError(s.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,10 +211,56 @@ mod tests {
         parse_date("214748363-1-1").unwrap();
         parse_date("2147483647-1-1").unwrap();
 
+        parse_date("0999-01-01").unwrap();
+        parse_date("-44-03-15").unwrap();
+        parse_date("-0044-03-15").unwrap();
+        parse_date("+12345-01-01").unwrap();
+        parse_date("0000-02-29").unwrap();
+
         parse_date("999-01-01").unwrap_err();
+        parse_date("-1-02-29").unwrap_err();
+        parse_date("--1-01-01").unwrap_err();
         parse_date("2021-02-29").unwrap_err();
         parse_date("2100-02-29").unwrap_err();
         parse_date("7453-13-01").unwrap_err();
         parse_date("2147483648-1-1").unwrap_err();
     }
+
+    #[test]
+    fn parse_time_tests() {
+        parse_time("9:30").unwrap();
+        parse_time("09:30").unwrap();
+        parse_time("23:59:59").unwrap();
+        parse_time("24:00").unwrap_err();
+        parse_time("12:60").unwrap_err();
+        parse_time("12:5").unwrap_err();
+        parse_time("123:45").unwrap_err();
+        parse_time("12:345").unwrap_err();
+        assert_eq!(parse_time("3pm").unwrap(), Time::new(15, 0, 0).unwrap());
+        assert_eq!(parse_time("12am").unwrap(), Time::MIDNIGHT);
+        assert_eq!(parse_time("9:30AM").unwrap(), Time::new(9, 30, 0).unwrap());
+        parse_time("13pm").unwrap_err();
+        parse_time("0am").unwrap_err();
+        parse_time("3").unwrap_err();
+        parse_time("3pmx").unwrap_err();
+        parse_date_time("2025-01-01 14:30").unwrap();
+        parse_date_time("2025-01-01T14:30:15").unwrap();
+        parse_date_time("2025-01-01").unwrap_err();
+        parse_date_time("2025-01-01T14:30Z").unwrap_err();
+    }
+
+    #[test]
+    fn parse_zoned_date_time_tests() {
+        assert_eq!(parse_zoned_date_time("2025-01-01T14:30:00Z").unwrap().1, 0);
+        assert_eq!(
+            parse_zoned_date_time("2025-01-01T14:30+09:00").unwrap().1,
+            9 * 3600
+        );
+        assert_eq!(
+            parse_zoned_date_time("2025-01-01T14:30-0530").unwrap().1,
+            -(5 * 3600 + 30 * 60)
+        );
+        parse_zoned_date_time("2025-01-01T14:30").unwrap_err();
+        parse_zoned_date_time("2025-01-01T14:30+25:00").unwrap_err();
+    }
 }
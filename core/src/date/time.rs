@@ -0,0 +1,155 @@
+use crate::error::FendError;
+use crate::num::Number;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// A time of day like `14:30`, stored as the number of seconds since
+/// midnight
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Time {
+    seconds: u32,
+}
+
+impl Time {
+    pub(crate) const MIDNIGHT: Self = Self { seconds: 0 };
+
+    pub(crate) fn new(hour: u32, minute: u32, second: u32) -> Option<Self> {
+        if hour >= 24 || minute >= 60 || second >= 60 {
+            return None;
+        }
+        Some(Self {
+            seconds: hour * 3600 + minute * 60 + second,
+        })
+    }
+
+    pub(crate) fn seconds_since_midnight(self) -> i64 {
+        i64::from(self.seconds)
+    }
+
+    /// Adds a number of seconds (which may be negative), returning the
+    /// new time along with the number of days it moved forward or back
+    pub(crate) fn add_seconds(self, seconds: i64) -> Result<(i64, Self), FendError> {
+        let total = self
+            .seconds_since_midnight()
+            .checked_add(seconds)
+            .ok_or(FendError::DateOutOfRange)?;
+        let seconds = u32::try_from(total.rem_euclid(SECONDS_PER_DAY)).unwrap();
+        Ok((total.div_euclid(SECONDS_PER_DAY), Self { seconds }))
+    }
+
+    pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
+        super::parser::parse_time(s)
+    }
+
+    /// Parses a time at the start of `s`, returning the remaining input
+    pub(crate) fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+        super::parser::parse_time_prefix(s).ok()
+    }
+
+    /// Formats this time as `HH:MM:SS`, as in ISO 8601
+    pub(crate) fn to_iso(self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.hour(),
+            self.minute(),
+            self.second()
+        )
+    }
+
+    fn hour(self) -> u32 {
+        self.seconds / 3600
+    }
+
+    fn minute(self) -> u32 {
+        self.seconds / 60 % 60
+    }
+
+    fn second(self) -> u32 {
+        self.seconds % 60
+    }
+}
+
+impl fmt::Debug for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())?;
+        if self.second() != 0 {
+            write!(f, ":{:02}", self.second())?;
+        }
+        Ok(())
+    }
+}
+
+impl ValueTrait for Time {
+    fn type_name(&self) -> &'static str {
+        "time"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+
+    fn get_object_member(&self, key: &str) -> Option<Value> {
+        let value = match key {
+            "hour" => self.hour(),
+            "minute" => self.minute(),
+            "second" => self.second(),
+            _ => return None,
+        };
+        Some(Value::Num(Box::new(Number::from(u64::from(value)))))
+    }
+
+    // e.g. `3pm EST`
+    fn apply(&self, arg: Value) -> Option<Result<Value, FendError>> {
+        let zone = *arg.as_dyn::<super::TimeZone>()?;
+        super::ZonedTime::new(&(*self).into(), zone).map(|zoned| Ok(zoned.into()))
+    }
+
+    fn sub(&self, rhs: Value, context: &mut crate::Context) -> Result<Value, FendError> {
+        super::sub(
+            &(*self).into(),
+            rhs,
+            context,
+            &crate::interrupt::Never::default(),
+        )
+    }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
+
+    fn compare(&self, rhs: &Value) -> Option<std::cmp::Ordering> {
+        super::compare(&(*self).into(), rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_to_string() {
+        assert_eq!(Time::new(9, 5, 0).unwrap().to_string(), "09:05");
+        assert_eq!(Time::new(23, 59, 30).unwrap().to_string(), "23:59:30");
+        assert!(Time::new(24, 0, 0).is_none());
+        assert_eq!(Time::new(9, 5, 0).unwrap().to_iso(), "09:05:00");
+    }
+
+    #[test]
+    fn add_seconds_wraps_around() {
+        let (days, time) = Time::new(23, 0, 0).unwrap().add_seconds(7200).unwrap();
+        assert_eq!((days, time), (1, Time::new(1, 0, 0).unwrap()));
+        let (days, time) = Time::MIDNIGHT.add_seconds(-60).unwrap();
+        assert_eq!((days, time), (-1, Time::new(23, 59, 0).unwrap()));
+    }
+}
@@ -0,0 +1,476 @@
+use super::{Date, DateTime, Day, Month, Time, Year};
+use crate::error::FendError;
+use crate::value::{Value, ValueTrait};
+use std::fmt;
+
+/// Daylight saving time rules, which are applied to every year
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum DstRule {
+    None,
+    // from the second Sunday in March to the first Sunday in November
+    UnitedStates,
+    // from the last Sunday in March to the last Sunday in October
+    Europe,
+    // from the first Sunday in October to the first Sunday in April
+    Australia,
+    // from the last Sunday in September to the first Sunday in April
+    NewZealand,
+}
+
+/// A time zone, either with a fixed offset like `EST` or with daylight
+/// saving time like `America/New_York`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TimeZone {
+    name: &'static str,
+    offset_minutes: i32,
+    rule: DstRule,
+    abbreviation: &'static str,
+    dst_abbreviation: &'static str,
+}
+
+const fn fixed(name: &'static str, offset_minutes: i32) -> TimeZone {
+    TimeZone {
+        name,
+        offset_minutes,
+        rule: DstRule::None,
+        abbreviation: name,
+        dst_abbreviation: name,
+    }
+}
+
+const fn zone(
+    name: &'static str,
+    offset_minutes: i32,
+    rule: DstRule,
+    abbreviation: &'static str,
+    dst_abbreviation: &'static str,
+) -> TimeZone {
+    TimeZone {
+        name,
+        offset_minutes,
+        rule,
+        abbreviation,
+        dst_abbreviation,
+    }
+}
+
+const TIME_ZONES: &[TimeZone] = &[
+    fixed("UTC", 0),
+    fixed("GMT", 0),
+    fixed("EST", -300),
+    fixed("EDT", -240),
+    fixed("CST", -360),
+    fixed("CDT", -300),
+    fixed("MST", -420),
+    fixed("MDT", -360),
+    fixed("PST", -480),
+    fixed("PDT", -420),
+    fixed("AKST", -540),
+    fixed("AKDT", -480),
+    fixed("HST", -600),
+    fixed("WET", 0),
+    fixed("WEST", 60),
+    fixed("BST", 60),
+    fixed("CET", 60),
+    fixed("CEST", 120),
+    fixed("EET", 120),
+    fixed("EEST", 180),
+    fixed("MSK", 180),
+    fixed("IST", 330),
+    fixed("SGT", 480),
+    fixed("HKT", 480),
+    fixed("AWST", 480),
+    fixed("JST", 540),
+    fixed("KST", 540),
+    fixed("ACST", 570),
+    fixed("ACDT", 630),
+    fixed("AEST", 600),
+    fixed("AEDT", 660),
+    fixed("NZST", 720),
+    fixed("NZDT", 780),
+    zone("Etc/UTC", 0, DstRule::None, "UTC", "UTC"),
+    zone(
+        "America/New_York",
+        -300,
+        DstRule::UnitedStates,
+        "EST",
+        "EDT",
+    ),
+    zone("America/Toronto", -300, DstRule::UnitedStates, "EST", "EDT"),
+    zone("America/Chicago", -360, DstRule::UnitedStates, "CST", "CDT"),
+    zone("America/Mexico_City", -360, DstRule::None, "CST", "CST"),
+    zone("America/Denver", -420, DstRule::UnitedStates, "MST", "MDT"),
+    zone("America/Phoenix", -420, DstRule::None, "MST", "MST"),
+    zone(
+        "America/Los_Angeles",
+        -480,
+        DstRule::UnitedStates,
+        "PST",
+        "PDT",
+    ),
+    zone(
+        "America/Vancouver",
+        -480,
+        DstRule::UnitedStates,
+        "PST",
+        "PDT",
+    ),
+    zone(
+        "America/Anchorage",
+        -540,
+        DstRule::UnitedStates,
+        "AKST",
+        "AKDT",
+    ),
+    zone("America/Sao_Paulo", -180, DstRule::None, "BRT", "BRT"),
+    zone(
+        "America/Argentina/Buenos_Aires",
+        -180,
+        DstRule::None,
+        "ART",
+        "ART",
+    ),
+    zone("Pacific/Honolulu", -600, DstRule::None, "HST", "HST"),
+    zone("Europe/London", 0, DstRule::Europe, "GMT", "BST"),
+    zone("Europe/Dublin", 0, DstRule::Europe, "GMT", "IST"),
+    zone("Europe/Lisbon", 0, DstRule::Europe, "WET", "WEST"),
+    zone("Europe/Paris", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Berlin", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Madrid", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Rome", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Amsterdam", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Brussels", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Vienna", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Zurich", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Stockholm", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Oslo", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Copenhagen", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Warsaw", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Prague", 60, DstRule::Europe, "CET", "CEST"),
+    zone("Europe/Athens", 120, DstRule::Europe, "EET", "EEST"),
+    zone("Europe/Helsinki", 120, DstRule::Europe, "EET", "EEST"),
+    zone("Europe/Kyiv", 120, DstRule::Europe, "EET", "EEST"),
+    zone("Europe/Bucharest", 120, DstRule::Europe, "EET", "EEST"),
+    zone("Europe/Istanbul", 180, DstRule::None, "TRT", "TRT"),
+    zone("Europe/Moscow", 180, DstRule::None, "MSK", "MSK"),
+    zone("Africa/Lagos", 60, DstRule::None, "WAT", "WAT"),
+    zone("Africa/Cairo", 120, DstRule::None, "EET", "EET"),
+    zone("Africa/Johannesburg", 120, DstRule::None, "SAST", "SAST"),
+    zone("Africa/Nairobi", 180, DstRule::None, "EAT", "EAT"),
+    zone("Asia/Dubai", 240, DstRule::None, "GST", "GST"),
+    zone("Asia/Karachi", 300, DstRule::None, "PKT", "PKT"),
+    zone("Asia/Kolkata", 330, DstRule::None, "IST", "IST"),
+    zone("Asia/Bangkok", 420, DstRule::None, "ICT", "ICT"),
+    zone("Asia/Jakarta", 420, DstRule::None, "WIB", "WIB"),
+    zone("Asia/Shanghai", 480, DstRule::None, "CST", "CST"),
+    zone("Asia/Hong_Kong", 480, DstRule::None, "HKT", "HKT"),
+    zone("Asia/Singapore", 480, DstRule::None, "SGT", "SGT"),
+    zone("Asia/Taipei", 480, DstRule::None, "CST", "CST"),
+    zone("Asia/Seoul", 540, DstRule::None, "KST", "KST"),
+    zone("Asia/Tokyo", 540, DstRule::None, "JST", "JST"),
+    zone("Australia/Perth", 480, DstRule::None, "AWST", "AWST"),
+    zone("Australia/Darwin", 570, DstRule::None, "ACST", "ACST"),
+    zone(
+        "Australia/Adelaide",
+        570,
+        DstRule::Australia,
+        "ACST",
+        "ACDT",
+    ),
+    zone("Australia/Brisbane", 600, DstRule::None, "AEST", "AEST"),
+    zone("Australia/Sydney", 600, DstRule::Australia, "AEST", "AEDT"),
+    zone(
+        "Australia/Melbourne",
+        600,
+        DstRule::Australia,
+        "AEST",
+        "AEDT",
+    ),
+    zone("Australia/Hobart", 600, DstRule::Australia, "AEST", "AEDT"),
+    zone("Pacific/Auckland", 720, DstRule::NewZealand, "NZST", "NZDT"),
+];
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// Returns the day (as days since 1970-01-01) of the `n`th Sunday of a
+/// month, or of the last Sunday if `n` is zero
+fn sunday(year: i32, month: Month, n: i64) -> i64 {
+    let year = Year::new(year);
+    let first = Date {
+        year,
+        month,
+        day: Day::new(1),
+    }
+    .days_since_epoch();
+    // 1970-01-01 was a Thursday
+    let weekday = |day: i64| (day + 4).rem_euclid(7);
+    if n == 0 {
+        let last = first + i64::from(month.number_of_days(year)) - 1;
+        last - weekday(last)
+    } else {
+        first + (7 - weekday(first)) % 7 + 7 * (n - 1)
+    }
+}
+
+impl TimeZone {
+    /// Looks up a time zone abbreviation like `PST`, or a name from the tz
+    /// database like `America/Los_Angeles`
+    pub(crate) fn find(name: &str) -> Option<Self> {
+        TIME_ZONES.iter().find(|zone| zone.name == name).copied()
+    }
+
+    fn standard_offset(self) -> i64 {
+        i64::from(self.offset_minutes) * 60
+    }
+
+    fn is_dst(self, utc_seconds: i64) -> bool {
+        let std = self.standard_offset();
+        let dst = std + 3600;
+        let year = Date::from_days_since_epoch(utc_seconds.div_euclid(SECONDS_PER_DAY))
+            .map_or(1970, |date| date.year.value());
+        // transitions happen at a local time, given as seconds since midnight
+        let at =
+            |day: i64, local_time: i64, offset: i64| day * SECONDS_PER_DAY + local_time - offset;
+        match self.rule {
+            DstRule::None => false,
+            DstRule::UnitedStates => {
+                let start = at(sunday(year, Month::March, 2), 2 * 3600, std);
+                let end = at(sunday(year, Month::November, 1), 2 * 3600, dst);
+                start <= utc_seconds && utc_seconds < end
+            }
+            DstRule::Europe => {
+                let start = at(sunday(year, Month::March, 0), 3600, 0);
+                let end = at(sunday(year, Month::October, 0), 3600, 0);
+                start <= utc_seconds && utc_seconds < end
+            }
+            DstRule::Australia => {
+                let end = at(sunday(year, Month::April, 1), 3 * 3600, dst);
+                let start = at(sunday(year, Month::October, 1), 2 * 3600, std);
+                utc_seconds < end || start <= utc_seconds
+            }
+            DstRule::NewZealand => {
+                let end = at(sunday(year, Month::April, 1), 3 * 3600, dst);
+                let start = at(sunday(year, Month::September, 0), 2 * 3600, std);
+                utc_seconds < end || start <= utc_seconds
+            }
+        }
+    }
+
+    /// Returns the offset from UTC in seconds and the abbreviation that is
+    /// used at the given instant
+    fn offset_at(self, utc_seconds: i64) -> (i64, &'static str) {
+        if self.is_dst(utc_seconds) {
+            (self.standard_offset() + 3600, self.dst_abbreviation)
+        } else {
+            (self.standard_offset(), self.abbreviation)
+        }
+    }
+
+    /// Returns the offset from UTC that applies to a local time in this
+    /// time zone
+    fn offset_at_local(self, local_seconds: i64) -> (i64, &'static str) {
+        self.offset_at(local_seconds - self.standard_offset())
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl ValueTrait for TimeZone {
+    fn type_name(&self) -> &'static str {
+        "time zone"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+}
+
+/// A time of day, or a date and time, in a specific time zone, e.g.
+/// `3pm EST`
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ZonedTime {
+    date: Option<Date>,
+    time: Time,
+    offset_seconds: i64,
+    abbreviation: &'static str,
+}
+
+impl ZonedTime {
+    /// Parses an ISO 8601 date and time with a UTC offset, like
+    /// `2025-01-01T14:30:00Z` or `2025-01-01T14:30:00+09:00`
+    pub(crate) fn parse(s: &str) -> Result<Self, FendError> {
+        let (date_time, offset_seconds) = super::parser::parse_zoned_date_time(s)?;
+        Ok(Self {
+            date: Some(date_time.date),
+            time: date_time.time,
+            offset_seconds,
+            abbreviation: if offset_seconds == 0 { "UTC" } else { "" },
+        })
+    }
+
+    /// Returns the date (if known), the time, the offset from UTC in seconds
+    /// and the time zone abbreviation
+    pub(crate) fn parts(&self) -> (Option<Date>, Time, i64, &'static str) {
+        (self.date, self.time, self.offset_seconds, self.abbreviation)
+    }
+
+    /// Formats the UTC offset like `+09:00`
+    fn format_offset(&self) -> String {
+        let sign = if self.offset_seconds < 0 { '-' } else { '+' };
+        let minutes = self.offset_seconds.abs() / 60;
+        format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+
+    /// Formats this value like `2025-01-01T14:30:00Z`, or like
+    /// `14:30:00-05:00` if there is no date
+    pub(crate) fn to_iso(self) -> String {
+        let offset = if self.offset_seconds == 0 {
+            "Z".to_string()
+        } else {
+            self.format_offset()
+        };
+        match self.date {
+            Some(date) => format!("{}T{}{}", date.to_iso(), self.time.to_iso(), offset),
+            None => format!("{}{}", self.time.to_iso(), offset),
+        }
+    }
+
+    /// Places a time of day or a date and time in a time zone
+    pub(crate) fn new(value: &Value, zone: TimeZone) -> Option<Self> {
+        let (date, time) = if let Some(&time) = value.as_dyn::<Time>() {
+            (None, time)
+        } else {
+            let date_time = value.as_dyn::<DateTime>()?;
+            (Some(date_time.date), date_time.time)
+        };
+        let (offset_seconds, abbreviation) = match date {
+            Some(date) => zone.offset_at_local(
+                date.days_since_epoch() * SECONDS_PER_DAY + time.seconds_since_midnight(),
+            ),
+            None => (zone.standard_offset(), zone.abbreviation),
+        };
+        Some(Self {
+            date,
+            time,
+            offset_seconds,
+            abbreviation,
+        })
+    }
+
+    /// Converts a date or time to the given time zone. Values without a
+    /// time zone are in the local time zone, or in UTC if it is unknown.
+    pub(crate) fn convert(
+        value: &Value,
+        zone: TimeZone,
+        context: &mut crate::Context,
+    ) -> Result<Value, FendError> {
+        let local_offset = context
+            .current_time
+            .as_ref()
+            .map_or(0, |t| -t.timezone_offset_secs);
+        let (date, time, offset) = if let Some(zoned) = value.as_dyn::<Self>() {
+            (zoned.date, zoned.time, zoned.offset_seconds)
+        } else if let Some(date_time) = value.as_dyn::<DateTime>() {
+            (Some(date_time.date), date_time.time, local_offset)
+        } else if let Some(&date) = value.as_dyn::<Date>() {
+            (Some(date), Time::MIDNIGHT, local_offset)
+        } else if let Some(&time) = value.as_dyn::<Time>() {
+            (None, time, local_offset)
+        } else {
+            return Err(FendError::ExpectedADateOrTime);
+        };
+        // times of day use today's date to decide whether daylight saving
+        // time applies, or standard time if the date is unknown
+        let reference_date = match date {
+            Some(date) => Some(date),
+            None => Date::today(context).ok(),
+        };
+        let utc_seconds = reference_date.map_or(0, Date::days_since_epoch) * SECONDS_PER_DAY
+            + time.seconds_since_midnight()
+            - offset;
+        let (offset_seconds, abbreviation) = if reference_date.is_some() {
+            zone.offset_at(utc_seconds)
+        } else {
+            (zone.standard_offset(), zone.abbreviation)
+        };
+        let local_seconds = utc_seconds + offset_seconds;
+        let (_, time) = Time::MIDNIGHT.add_seconds(local_seconds)?;
+        let date = match date {
+            Some(_) => Some(Date::from_days_since_epoch(
+                local_seconds.div_euclid(SECONDS_PER_DAY),
+            )?),
+            None => None,
+        };
+        Ok(Self {
+            date,
+            time,
+            offset_seconds,
+            abbreviation,
+        }
+        .into())
+    }
+}
+
+impl fmt::Display for ZonedTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(date) = self.date {
+            write!(f, "{} ", date)?;
+        }
+        if self.abbreviation.is_empty() {
+            write!(f, "{} UTC{}", self.time, self.format_offset())
+        } else {
+            write!(f, "{} {}", self.time, self.abbreviation)
+        }
+    }
+}
+
+impl ValueTrait for ZonedTime {
+    fn type_name(&self) -> &'static str {
+        "time"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<crate::Span>) {
+        spans.push(crate::Span {
+            string: self.to_string(),
+            kind: crate::SpanKind::Date,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(date: &str, hour: i64) -> i64 {
+        Date::parse(date).unwrap().days_since_epoch() * SECONDS_PER_DAY + hour * 3600
+    }
+
+    #[test]
+    fn daylight_saving_time_rules() {
+        let new_york = TimeZone::find("America/New_York").unwrap();
+        assert!(!new_york.is_dst(utc("2025-03-09", 6)));
+        assert!(new_york.is_dst(utc("2025-03-09", 7)));
+        assert!(new_york.is_dst(utc("2025-11-02", 5)));
+        assert!(!new_york.is_dst(utc("2025-11-02", 6)));
+
+        let london = TimeZone::find("Europe/London").unwrap();
+        assert!(!london.is_dst(utc("2025-03-30", 0)));
+        assert!(london.is_dst(utc("2025-03-30", 1)));
+        assert!(!london.is_dst(utc("2025-10-26", 1)));
+
+        let sydney = TimeZone::find("Australia/Sydney").unwrap();
+        assert!(sydney.is_dst(utc("2025-01-15", 0)));
+        assert!(!sydney.is_dst(utc("2025-07-15", 0)));
+
+        let auckland = TimeZone::find("Pacific/Auckland").unwrap();
+        assert!(auckland.is_dst(utc("2025-12-15", 0)));
+        assert!(!auckland.is_dst(utc("2025-06-15", 0)));
+    }
+}
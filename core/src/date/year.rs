@@ -1,11 +1,13 @@
-use std::{convert, fmt};
+use std::fmt;
 
+/// A year in astronomical year numbering, where year 0 is 1 BC and year -1
+/// is 2 BC. Leap years follow the proleptic Gregorian calendar, i.e. the
+/// Gregorian rules are also applied to years before 1582.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub(crate) struct Year(i32);
 
 impl Year {
     pub(crate) fn new(year: i32) -> Self {
-        assert!(year != 0, "year 0 is invalid");
         Self(year)
     }
 
@@ -14,68 +16,34 @@ impl Year {
     }
 
     pub(crate) fn next(self) -> Self {
-        if self.value() == -1 {
-            Self::new(1)
-        } else {
-            Self::new(self.value() + 1)
-        }
+        Self::new(self.value() + 1)
     }
 
     pub(crate) fn prev(self) -> Self {
-        if self.value() == 1 {
-            Self::new(-1)
-        } else {
-            Self::new(self.value() - 1)
-        }
+        Self::new(self.value() - 1)
     }
 
     pub(crate) fn is_leap_year(self) -> bool {
-        if self.value() % 400 == 0 {
+        if self.value().rem_euclid(400) == 0 {
             true
-        } else if self.value() % 100 == 0 {
+        } else if self.value().rem_euclid(100) == 0 {
             false
         } else {
-            self.value() % 4 == 0
-        }
-    }
-
-    pub(crate) fn number_of_days(self) -> u16 {
-        if self.is_leap_year() {
-            366
-        } else {
-            365
-        }
-    }
-}
-
-pub(crate) struct InvalidYearError;
-
-impl convert::TryFrom<i32> for Year {
-    type Error = InvalidYearError;
-
-    fn try_from(year: i32) -> Result<Self, Self::Error> {
-        if year == 0 {
-            Err(InvalidYearError)
-        } else {
-            Ok(Self(year))
+            self.value().rem_euclid(4) == 0
         }
     }
 }
 
 impl fmt::Debug for Year {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.value() < 0 {
-            write!(f, "{} BC", -self.0)
-        } else {
-            write!(f, "{}", self.0)
-        }
+        fmt::Display::fmt(self, f)
     }
 }
 
 impl fmt::Display for Year {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.value() < 0 {
-            write!(f, "{} BC", -self.0)
+        if self.value() <= 0 {
+            write!(f, "{} BC", 1 - i64::from(self.0))
         } else {
             write!(f, "{}", self.0)
         }
@@ -87,13 +55,18 @@ mod tests {
     use super::*;
 
     #[test]
-    #[should_panic]
-    fn year_0() {
-        Year::new(0);
+    fn negative_year_string() {
+        assert_eq!(Year::new(-822).to_string(), "823 BC");
+        assert_eq!(Year::new(0).to_string(), "1 BC");
     }
 
     #[test]
-    fn negative_year_string() {
-        assert_eq!(Year::new(-823).to_string(), "823 BC");
+    fn leap_years() {
+        assert!(Year::new(2000).is_leap_year());
+        assert!(!Year::new(1900).is_leap_year());
+        assert!(Year::new(0).is_leap_year());
+        assert!(Year::new(-4).is_leap_year());
+        assert!(!Year::new(-100).is_leap_year());
+        assert!(!Year::new(-1).is_leap_year());
     }
 }
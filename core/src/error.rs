@@ -37,7 +37,13 @@ pub(crate) enum FendError {
     UnableToInvertFunction(&'static str),
     InvalidType,
     InvalidOperandsForSubtraction,
+    InvalidOperandsForComparison,
     InversesOfLambdasUnsupported,
+    UnknownArgument(String),
+    MissingArgument(String),
+    DuplicateArgument(String),
+    TooManyArguments(usize),
+    PositionalAfterNamedArgument,
     CouldNotFindKeyInObject,
     CouldNotFindKey(String),
     CannotFormatWithZeroSf,
@@ -64,6 +70,15 @@ pub(crate) enum FendError {
     InvalidUnicodeEscapeSequence,
     FormattingError(fmt::Error),
     ParseDateError(String),
+    ParseTimeError(String),
+    ParseDateTimeError(String),
+    DateOutOfRange,
+    ExpectedADate,
+    ExpectedADateOrTime,
+    InvalidDate(i64, i64, i64),
+    UnknownDateFormatSpecifier(String),
+    DateFormatSpecifierRequires(char, &'static str),
+    InvalidHoliday(String),
     ParseError(crate::parser::ParseError),
     ExpectedAString,
     ExpComplex,
@@ -76,6 +91,8 @@ pub(crate) enum FendError {
     IncompatibleConversion {
         from: String,
         to: String,
+        // boxed to keep `FendError` small
+        dimensions: Option<Box<(String, String)>>,
         from_base: String,
         to_base: String,
     },
@@ -83,6 +100,51 @@ pub(crate) enum FendError {
     RootsOfNegativeNumbers,
     NonIntegerNegRoots,
     CannotConvertValueTo(&'static str),
+    ExpectedAtLeastOneArgument,
+    WrongNumberOfArguments {
+        function: &'static str,
+        expected: &'static str,
+        found: usize,
+    },
+    ClampBoundsOutOfOrder,
+    MixedUnitsOutOfOrder,
+    ExpectedAPositiveInteger,
+    ExpectedADimension,
+    InvalidPercentile,
+    ExpectedAMatrix,
+    ExpectedASquareMatrix,
+    MatrixDimensionMismatch,
+    SingularMatrix,
+    UnsupportedMatrixOperation,
+    ExpectedAVector,
+    VectorLengthMismatch,
+    ExpectedA3dVector,
+    ExpectedAPolynomial,
+    ZeroPolynomialRoots,
+    PolynomialVariableMismatch,
+    UnsupportedPolynomialOperation,
+    ArgumentOfZero,
+    ExpectedAQuaternion,
+    UnsupportedQuaternionOperation,
+    UnknownCurrency(String),
+    NoExchangeRate(String),
+    NoUnitsOfDimension(String),
+    UnknownChemicalElement(String),
+    InvalidChemicalFormula(String),
+    UnsupportedUncertaintyOperation,
+    UnsupportedDurationOperation,
+    UnknownSetting(String),
+    InvalidPrecision,
+    ExpectedAUnitDefinition,
+    InvalidUnitDefinition(usize, Box<Self>),
+    InvalidSavedVariables(usize, Box<Self>),
+    InvalidVariableName,
+    InvalidVariableDefinition(String, Box<Self>),
+    LanguageFeatureDisabled(crate::LanguageFeature),
+    TimeLimitExceeded,
+    MemoryLimitExceeded,
+    RecursionLimitExceeded,
+    OutputTooLong,
 }
 
 impl fmt::Display for FendError {
@@ -103,11 +165,31 @@ impl fmt::Display for FendError {
                 f,
                 "unable to parse a valid base prefix, expected 0b, 0o, or 0x"
             ),
+            Self::IncompatibleConversion {
+                from,
+                to,
+                dimensions: Some(dimensions),
+                from_base,
+                to_base,
+            } => {
+                let (from_dimension, to_dimension) = dimensions.as_ref();
+                write!(
+                    f,
+                    "cannot convert from {} to {}: {} ({}) and {} ({}) are incompatible",
+                    from,
+                    to,
+                    from_dimension,
+                    from_base.trim(),
+                    to_dimension,
+                    to_base.trim()
+                )
+            }
             Self::IncompatibleConversion {
                 from,
                 to,
                 from_base,
                 to_base,
+                ..
             } => {
                 write!(
                     f,
@@ -158,6 +240,25 @@ impl fmt::Display for FendError {
                 )
             }
             Self::ParseDateError(s) => write!(f, "failed to convert '{}' to a date", s),
+            Self::ParseTimeError(s) => write!(f, "failed to convert '{}' to a time", s),
+            Self::ParseDateTimeError(s) => {
+                write!(f, "failed to convert '{}' to a date and time", s)
+            }
+            Self::DateOutOfRange => write!(f, "date is out of range"),
+            Self::ExpectedADate => write!(f, "expected a date"),
+            Self::ExpectedADateOrTime => write!(f, "expected a date or time"),
+            Self::InvalidDate(year, month, day) => {
+                write!(f, "{}-{:02}-{:02} is not a valid date", year, month, day)
+            }
+            Self::UnknownDateFormatSpecifier(s) => write!(f, "unknown date format '{}'", s),
+            Self::InvalidHoliday(s) => write!(
+                f,
+                "invalid holiday '{}', expected e.g. '12-25', '2025-05-05' or 'easter+1'",
+                s
+            ),
+            Self::DateFormatSpecifierRequires(specifier, part) => {
+                write!(f, "'%{}' can only be used to format a {}", specifier, part)
+            }
             Self::ExpectedAString => write!(f, "expected a string"),
             Self::UnableToInvertFunction(name) => write!(f, "unable to invert function {}", name),
             Self::FractionToInteger => write!(f, "cannot convert fraction to integer"),
@@ -170,6 +271,17 @@ impl fmt::Display for FendError {
                 f,
                 "inverses of lambda functions are not currently supported"
             ),
+            Self::UnknownArgument(name) => write!(f, "unknown argument '{}'", name),
+            Self::MissingArgument(name) => write!(f, "missing argument '{}'", name),
+            Self::DuplicateArgument(name) => {
+                write!(f, "argument '{}' was given more than once", name)
+            }
+            Self::TooManyArguments(max) => {
+                write!(f, "too many arguments: expected at most {}", max)
+            }
+            Self::PositionalAfterNamedArgument => {
+                write!(f, "positional arguments must come before named arguments")
+            }
             Self::ExpectedARationalNumber => write!(f, "expected a rational number"),
             Self::CannotConvertToInteger => write!(f, "number cannot be converted to an integer"),
             Self::ComplexToInteger => write!(f, "cannot convert complex number to integer"),
@@ -179,6 +291,7 @@ impl fmt::Display for FendError {
             Self::InvalidDiceSyntax => write!(f, "invalid dice syntax, try e.g. `4d6`"),
             Self::InvalidType => write!(f, "invalid type"),
             Self::InvalidOperandsForSubtraction => write!(f, "invalid operands for subtraction"),
+            Self::InvalidOperandsForComparison => write!(f, "invalid operands for comparison"),
             Self::CannotFormatWithZeroSf => {
                 write!(f, "cannot format a number with zero significant figures")
             }
@@ -220,6 +333,91 @@ impl fmt::Display for FendError {
                 )
             }
             Self::FormattingError(_) => write!(f, "error during formatting"),
+            Self::ExpectedAtLeastOneArgument => write!(f, "expected at least one argument"),
+            Self::WrongNumberOfArguments {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} expects {} arguments, but {} were given",
+                function, expected, found
+            ),
+            Self::ExpectedAPositiveInteger => write!(f, "expected a positive integer"),
+            Self::ExpectedADimension => write!(f, "expected a dimension"),
+            Self::InvalidPercentile => write!(f, "percentile must be between 0 and 100"),
+            Self::ExpectedAMatrix => write!(f, "expected a matrix"),
+            Self::ExpectedASquareMatrix => write!(f, "expected a square matrix"),
+            Self::MatrixDimensionMismatch => write!(f, "matrix dimensions do not match"),
+            Self::SingularMatrix => write!(f, "matrix is not invertible"),
+            Self::ExpectedAVector => write!(f, "expected a vector"),
+            Self::VectorLengthMismatch => write!(f, "vectors must have the same length"),
+            Self::ExpectedA3dVector => write!(f, "expected a 3-dimensional vector"),
+            Self::UnsupportedMatrixOperation => {
+                write!(f, "this operation is not supported for matrices")
+            }
+            Self::ExpectedAPolynomial => write!(f, "expected a polynomial"),
+            Self::ZeroPolynomialRoots => {
+                write!(f, "every number is a root of the zero polynomial")
+            }
+            Self::PolynomialVariableMismatch => {
+                write!(f, "polynomials must be in the same variable")
+            }
+            Self::UnsupportedPolynomialOperation => {
+                write!(f, "this operation is not supported for polynomials")
+            }
+            Self::ExpectedAQuaternion => write!(f, "expected a quaternion"),
+            Self::UnsupportedQuaternionOperation => {
+                write!(f, "this operation is not supported for quaternions")
+            }
+            Self::UnknownCurrency(code) => write!(f, "unknown currency code '{}'", code),
+            Self::NoExchangeRate(code) => write!(f, "no exchange rate available for {}", code),
+            Self::UnknownChemicalElement(symbol) => {
+                write!(f, "unknown chemical element '{}'", symbol)
+            }
+            Self::InvalidChemicalFormula(formula) => {
+                write!(f, "invalid chemical formula '{}'", formula)
+            }
+            Self::NoUnitsOfDimension(dimension) => {
+                write!(f, "there are no known units of {}", dimension)
+            }
+            Self::UnsupportedDurationOperation => {
+                write!(f, "this operation is not supported for durations")
+            }
+            Self::UnsupportedUncertaintyOperation => {
+                write!(
+                    f,
+                    "this operation is not supported for values with uncertainties"
+                )
+            }
+            Self::UnknownSetting(name) => write!(f, "unknown setting '{}'", name),
+            Self::InvalidPrecision => write!(
+                f,
+                "precision must be an integer between 0 and {}",
+                crate::num::MAX_PRECISION
+            ),
+            Self::ExpectedAUnitDefinition => write!(
+                f,
+                "expected a unit definition like `unit furlong = 220 yards`"
+            ),
+            Self::InvalidUnitDefinition(line, e) | Self::InvalidSavedVariables(line, e) => {
+                write!(f, "line {}: {}", line, e)
+            }
+            Self::InvalidVariableName => write!(f, "not a valid variable name"),
+            Self::InvalidVariableDefinition(name, e) => write!(f, "{}: {}", name, e),
+            Self::LanguageFeatureDisabled(feature) => write!(f, "{} are disabled", feature),
+            Self::TimeLimitExceeded => write!(f, "time limit exceeded"),
+            Self::MemoryLimitExceeded => write!(f, "memory limit exceeded"),
+            Self::RecursionLimitExceeded => write!(f, "maximum recursion depth exceeded"),
+            Self::OutputTooLong => write!(f, "the result is too long"),
+            Self::ArgumentOfZero => write!(f, "the argument of zero is undefined"),
+            Self::ClampBoundsOutOfOrder => {
+                write!(f, "the lower bound cannot be larger than the upper bound")
+            }
+            Self::MixedUnitsOutOfOrder => write!(
+                f,
+                "mixed units must go from largest to smallest without repeating, e.g. `ft+in`"
+            ),
         }
     }
 }
@@ -228,6 +426,9 @@ impl error::Error for FendError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             Self::FormattingError(e) => Some(e),
+            Self::InvalidUnitDefinition(_, e)
+            | Self::InvalidSavedVariables(_, e)
+            | Self::InvalidVariableDefinition(_, e) => Some(e.as_ref()),
             _ => None,
         }
     }
@@ -1,12 +1,14 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use crate::{
     ast,
     error::{FendError, Interrupt},
-    lexer, parser,
+    lexer,
+    num::FormattingStyle,
+    parser,
     scope::Scope,
     value::Value,
-    Span,
+    Span, SpanKind,
 };
 
 pub(crate) fn evaluate_to_value<'a, I: Interrupt>(
@@ -15,22 +17,193 @@ pub(crate) fn evaluate_to_value<'a, I: Interrupt>(
     context: &mut crate::Context,
     int: &I,
 ) -> Result<Value, FendError> {
-    let lex = lexer::lex(input, int);
-    let mut tokens = vec![];
-    let mut missing_open_parens: i32 = 0;
-    for token in lex {
-        let token = token?;
-        if let lexer::Token::Symbol(lexer::Symbol::CloseParens) = token {
-            missing_open_parens += 1;
+    let lex = lexer::lex(input, &context.interner, int);
+    let tokens = lex.collect::<Result<Vec<_>, _>>()?;
+    evaluate_tokens(tokens, scope, context, int)
+}
+
+fn evaluate_tokens<I: Interrupt>(
+    tokens: Vec<lexer::Token>,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    let parsed = parse_tokens(tokens)?;
+    let result = ast::evaluate(parsed, scope, context, int)?;
+    Ok(result)
+}
+
+fn parse_tokens(mut tokens: Vec<lexer::Token>) -> Result<ast::Expr, FendError> {
+    insert_missing_open_parens(&mut tokens);
+    Ok(parser::parse_tokens(&tokens)?)
+}
+
+/// Describes the tokens of `input` and the expression that they are parsed
+/// into, one per line. Parser errors are described instead of the expression.
+pub(crate) fn debug_parse<I: Interrupt>(
+    input: &str,
+    context: &crate::Context,
+    int: &I,
+) -> Result<String, FendError> {
+    let tokens = lexer::lex(input, &context.interner, int).collect::<Result<Vec<_>, _>>()?;
+    let mut res = "tokens:\n".to_string();
+    for token in &tokens {
+        res.push_str("  ");
+        res.push_str(&token.describe(context, int)?);
+        res.push('\n');
+    }
+    res.push_str("expression:\n");
+    match parse_tokens(tokens) {
+        Ok(expr) => expr.describe(1, &mut res, context, int)?,
+        Err(e) => {
+            res.push_str("  error: ");
+            res.push_str(&e.to_string());
+            res.push('\n');
         }
-        tokens.push(token);
     }
+    Ok(res)
+}
+
+/// Returns the number of opening parentheses that were inserted
+fn insert_missing_open_parens(tokens: &mut Vec<lexer::Token>) -> usize {
+    let missing_open_parens = tokens
+        .iter()
+        .filter(|t| matches!(t, lexer::Token::Symbol(lexer::Symbol::CloseParens)))
+        .count();
     for _ in 0..missing_open_parens {
         tokens.insert(0, lexer::Token::Symbol(lexer::Symbol::OpenParens));
     }
+    missing_open_parens
+}
+
+/// Returns the byte range of the part of the input that causes a lexer
+/// or parser error, if any
+pub(crate) fn locate_syntax_error(input: &str, lexed: &lexer::LexedTokens) -> Option<Range<usize>> {
+    match lexed.tokens() {
+        Ok(mut tokens) => {
+            let inserted = insert_missing_open_parens(&mut tokens);
+            let mut index = parser::find_error_position(&tokens)?.checked_sub(inserted)?;
+            while matches!(tokens.get(index + inserted), Some(lexer::Token::Whitespace)) {
+                index += 1;
+            }
+            Some(lexed.token_range(input, index))
+        }
+        // number literals like `1e1000000` can exceed the memory limit,
+        // which isn't a syntax error
+        Err(FendError::MemoryLimitExceeded) => None,
+        // the error is where lexing stopped
+        Err(_) => Some(lexed.token_range(input, lexed.len())),
+    }
+}
+
+/// Returns whether the input ends with an operator or has unclosed
+/// parentheses or brackets, so that it is likely to continue on the
+/// next line
+pub(crate) fn is_incomplete(lexed: &lexer::LexedTokens) -> bool {
+    use lexer::{Symbol, Token};
+
+    let tokens = match lexed.tokens() {
+        Ok(tokens) => tokens,
+        Err(_) => return false,
+    };
+    let mut depth = 0_usize;
+    for token in &tokens {
+        match token {
+            Token::Symbol(Symbol::OpenParens | Symbol::OpenBracket) => depth += 1,
+            Token::Symbol(Symbol::CloseParens | Symbol::CloseBracket) => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => (),
+        }
+    }
+    if depth > 0 {
+        return true;
+    }
+    matches!(
+        tokens
+            .iter()
+            .rev()
+            .find(|t| !matches!(t, Token::Whitespace)),
+        Some(Token::Symbol(
+            Symbol::Add
+                | Symbol::Sub
+                | Symbol::Mul
+                | Symbol::Div
+                | Symbol::Mod
+                | Symbol::Pow
+                | Symbol::UnitConversion
+                | Symbol::Fn
+                | Symbol::Backslash
+                | Symbol::Dot
+                | Symbol::Of
+                | Symbol::ShiftLeft
+                | Symbol::ShiftRight
+                | Symbol::Equals
+                | Symbol::DoubleEquals
+                | Symbol::NotEquals
+                | Symbol::Less
+                | Symbol::LessOrEqual
+                | Symbol::Greater
+                | Symbol::GreaterOrEqual
+                | Symbol::Comma
+                | Symbol::DotDot
+                | Symbol::PlusMinus
+                | Symbol::Sqrt
+        ))
+    )
+}
+
+/// Evaluates input from the user, which may not use any
+/// disabled language features
+fn evaluate_user_tokens<I: Interrupt>(
+    tokens: Vec<lexer::Token>,
+    scope: Option<Arc<Scope>>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Value, FendError> {
+    if let Some(max_recursion) = context.limits.max_recursion {
+        if nesting_depth(&tokens) > max_recursion {
+            return Err(FendError::RecursionLimitExceeded);
+        }
+    }
+    let parsed = parse_tokens(tokens)?;
+    parsed.check_language_features(context)?;
+    ast::evaluate(parsed, scope, context, int)
+}
+
+fn nesting_depth(tokens: &[lexer::Token]) -> usize {
+    let mut depth = 0_usize;
+    let mut max_depth = 0;
+    for token in tokens {
+        match token {
+            lexer::Token::Symbol(lexer::Symbol::OpenParens | lexer::Symbol::OpenBracket) => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            lexer::Token::Symbol(lexer::Symbol::CloseParens | lexer::Symbol::CloseBracket) => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => (),
+        }
+    }
+    max_depth
+}
+
+/// Evaluates a single statement like `unit furlong = 220 yards`, failing
+/// if the input is anything other than a unit definition
+pub(crate) fn evaluate_unit_definition<I: Interrupt>(
+    input: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    let lex = lexer::lex(input, &context.interner, int);
+    let tokens = lex.collect::<Result<Vec<_>, _>>()?;
     let parsed = parser::parse_tokens(&tokens)?;
-    let result = ast::evaluate(parsed, scope, context, int)?;
-    Ok(result)
+    if !matches!(parsed, ast::Expr::DefineUnit(..)) {
+        return Err(FendError::ExpectedAUnitDefinition);
+    }
+    ast::evaluate(parsed, None, context, int)?;
+    Ok(())
 }
 
 /// This also saves the calculation result in a variable `_` and `ans`
@@ -44,17 +217,75 @@ pub(crate) fn evaluate_to_spans<'a, I: Interrupt>(
         input = remaining;
         true
     });
-    let value = evaluate_to_value(input, scope, context, int)?;
-    context.variables.insert("_".to_string(), value.clone());
-    context.variables.insert("ans".to_string(), value.clone());
+    context.rate = None;
+    let lex = lexer::lex(input, &context.interner, int);
+    let tokens = lex.collect::<Result<Vec<_>, _>>()?;
+    let value = evaluate_user_tokens(tokens, scope, context, int)?;
+    let value = ast::apply_rate(value, context, int)?;
+    context.rate = None;
+    value_to_spans(&value, debug, context, int)
+}
+
+/// Like `evaluate_to_spans`, but uses tokens that have already been lexed
+pub(crate) fn evaluate_lexed_to_spans<I: Interrupt>(
+    tokens: Vec<lexer::Token>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(Vec<Span>, bool), FendError> {
+    context.rate = None;
+    let value = evaluate_user_tokens(tokens, None, context, int)?;
+    let value = ast::apply_rate(value, context, int)?;
+    context.rate = None;
+    value_to_spans(&value, false, context, int)
+}
+
+fn value_to_spans<I: Interrupt>(
+    value: &Value,
+    debug: bool,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(Vec<Span>, bool), FendError> {
+    context.set_variable("_".to_string(), value.clone());
+    context.set_variable("ans".to_string(), value.clone());
     Ok((
         if debug {
             vec![Span::from_string(format!("{:?}", value))]
         } else {
             let mut spans = vec![];
             value.format(0, &mut spans, context, int)?;
+            if context.dual_units {
+                push_secondary_units(value, &mut spans, context, int)?;
+            }
+            if let Some(max_output_len) = context.limits.max_output_len {
+                let len: usize = spans.iter().map(|s| s.string.chars().count()).sum();
+                if len > max_output_len {
+                    return Err(FendError::OutputTooLong);
+                }
+            }
             spans
         },
         value.is_unit(),
     ))
 }
+
+/// Shows numbers with units in the other measurement system as well,
+/// e.g. `5 m (approx. 16.4 feet)`
+fn push_secondary_units<I: Interrupt>(
+    value: &Value,
+    spans: &mut Vec<Span>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    if let Value::Num(num) = value {
+        if let Some(converted) = crate::units::convert_to_other_system(num, context, int)? {
+            let converted = Value::Num(Box::new(
+                converted.with_format(FormattingStyle::DecimalPlaces(2)),
+            ));
+            spans.push(Span {
+                string: format!(" ({})", converted.format_to_plain_string(0, context, int)?),
+                kind: SpanKind::Secondary,
+            });
+        }
+    }
+    Ok(())
+}
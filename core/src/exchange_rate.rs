@@ -0,0 +1,37 @@
+use std::{fmt, rc::Rc};
+
+/// A source of currency exchange rates that is supplied by the application
+/// embedding fend, e.g. with live data from an online service. Set it with
+/// [`Context::set_exchange_rate_source`](crate::Context::set_exchange_rate_source).
+///
+/// Rates are requested every time a currency is used, so implementations
+/// should cache them if fetching them is slow.
+pub trait ExchangeRateSource {
+    /// Returns how many units of the given currency (an ISO 4217 code like
+    /// `"USD"`, or a cryptocurrency like `"BTC"`) one euro is worth, or
+    /// `None` if no rate is available, in which case fend uses its built-in
+    /// rate (if any).
+    fn units_per_euro(&self, currency: &str) -> Option<f64>;
+}
+
+#[derive(Clone)]
+pub(crate) struct SharedExchangeRateSource(Rc<dyn ExchangeRateSource>);
+
+impl SharedExchangeRateSource {
+    pub(crate) fn new(source: impl ExchangeRateSource + 'static) -> Self {
+        Self(Rc::new(source))
+    }
+
+    /// Returns the rate for a currency code, ignoring invalid rates
+    pub(crate) fn units_per_euro(&self, currency: &str) -> Option<f64> {
+        self.0
+            .units_per_euro(currency)
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+    }
+}
+
+impl fmt::Debug for SharedExchangeRateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ExchangeRateSource")
+    }
+}
@@ -0,0 +1,104 @@
+use crate::lexer::{LexedTokens, Token};
+use crate::{interrupt, limits, suggestions, Context, SpanKind, SpanRef};
+
+/// Splits `input` into spans that can be coloured like the output, e.g.
+/// while the input is being typed. Anything after a lexer error is a
+/// single span of kind `Other`.
+pub(crate) fn highlight<'a>(input: &'a str, context: &Context) -> Vec<SpanRef<'a>> {
+    let lexed = limits::with_memory_limit(context.limits, || {
+        LexedTokens::new(input, &context.interner, &interrupt::Never::default())
+    });
+    let mut spans = vec![];
+    let mut end = 0;
+    for (i, token) in lexed.tokens_before_error().enumerate() {
+        let range = lexed.token_range(input, i);
+        push(&mut spans, &input[end..range.start], SpanKind::Whitespace);
+        let string = &input[range.clone()];
+        let kind = match token {
+            Token::Num(_) => SpanKind::Number,
+            Token::Ident(ident) => match ident.as_str() {
+                "true" | "false" => SpanKind::Boolean,
+                name if !context.variables.contains_key(name)
+                    && suggestions::is_builtin_name(name) =>
+                {
+                    SpanKind::BuiltInFunction
+                }
+                _ => SpanKind::Ident,
+            },
+            // words like `to` and `of`
+            Token::Symbol(_) if string.starts_with(char::is_alphabetic) => SpanKind::Keyword,
+            Token::Symbol(_) => SpanKind::Other,
+            Token::Whitespace => SpanKind::Whitespace,
+            Token::StringLiteral(_) => SpanKind::String,
+            Token::Time(_) => SpanKind::Date,
+        };
+        push(&mut spans, string, kind);
+        end = range.end;
+    }
+    let rest = &input[end..];
+    let trimmed = rest.trim_start();
+    push(
+        &mut spans,
+        &rest[..rest.len() - trimmed.len()],
+        SpanKind::Whitespace,
+    );
+    push(&mut spans, trimmed, SpanKind::Other);
+    spans
+}
+
+fn push<'a>(spans: &mut Vec<SpanRef<'a>>, string: &'a str, kind: SpanKind) {
+    if !string.is_empty() {
+        spans.push(SpanRef { string, kind });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(&str, SpanKind)> {
+        highlight(input, &Context::new())
+            .into_iter()
+            .map(|span| (span.string(), span.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn highlight_input() {
+        assert_eq!(
+            kinds("sqrt(4) km to m"),
+            [
+                ("sqrt", SpanKind::BuiltInFunction),
+                ("(", SpanKind::Other),
+                ("4", SpanKind::Number),
+                (")", SpanKind::Other),
+                (" ", SpanKind::Whitespace),
+                ("km", SpanKind::Ident),
+                (" ", SpanKind::Whitespace),
+                ("to", SpanKind::Keyword),
+                (" ", SpanKind::Whitespace),
+                ("m", SpanKind::Ident),
+            ]
+        );
+        assert_eq!(
+            kinds("'a' == true"),
+            [
+                ("'a'", SpanKind::String),
+                (" ", SpanKind::Whitespace),
+                ("==", SpanKind::Other),
+                (" ", SpanKind::Whitespace),
+                ("true", SpanKind::Boolean),
+            ]
+        );
+        assert_eq!(
+            kinds("1 + 'abc"),
+            [
+                ("1", SpanKind::Number),
+                (" ", SpanKind::Whitespace),
+                ("+", SpanKind::Other),
+                (" ", SpanKind::Whitespace),
+                ("'abc", SpanKind::Other),
+            ]
+        );
+    }
+}
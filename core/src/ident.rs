@@ -1,19 +1,16 @@
-use std::{borrow::Cow, fmt};
+use crate::interner::SharedStr;
+use std::fmt;
 
 #[derive(Clone, Debug)]
-pub(crate) struct Ident(Cow<'static, str>);
+pub(crate) struct Ident(SharedStr);
 
 impl Ident {
     pub(crate) fn new_str(s: &'static str) -> Self {
-        Self(Cow::Borrowed(s))
-    }
-
-    pub(crate) fn new_string(s: String) -> Self {
-        Self(Cow::Owned(s))
+        Self(SharedStr::Static(s))
     }
 
     pub(crate) fn as_str(&self) -> &str {
-        self.0.as_ref()
+        self.0.as_str()
     }
 
     pub(crate) fn is_prefix_unit(&self) -> bool {
@@ -23,15 +20,21 @@ impl Ident {
     }
 }
 
+impl From<SharedStr> for Ident {
+    fn from(value: SharedStr) -> Self {
+        Self(value)
+    }
+}
+
 impl From<String> for Ident {
     fn from(value: String) -> Self {
-        Self(Cow::Owned(value))
+        Self(value.into())
     }
 }
 
 impl From<&'static str> for Ident {
     fn from(value: &'static str) -> Self {
-        Self(Cow::Borrowed(value))
+        Self(SharedStr::Static(value))
     }
 }
 
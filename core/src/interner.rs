@@ -0,0 +1,167 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::{borrow::Borrow, fmt, ops::Deref, sync::Arc};
+
+/// An immutable string that is cheap to clone. Static strings (like
+/// built-in unit names) are stored as-is, while other strings are
+/// reference-counted and usually come from an `Interner`.
+#[derive(Clone)]
+pub(crate) enum SharedStr {
+    Static(&'static str),
+    Shared(Arc<str>),
+}
+
+impl SharedStr {
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            Self::Static(s) => s,
+            Self::Shared(s) => s,
+        }
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SharedStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedStr {}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for SharedStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&'static str> for SharedStr {
+    fn from(s: &'static str) -> Self {
+        Self::Static(s)
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        Self::Shared(s.into())
+    }
+}
+
+/// Stores one shared copy of every identifier and custom unit name that
+/// is currently in use. This is kept in the `Context`, so evaluating
+/// similar inputs over and over (e.g. while the user is typing) doesn't
+/// need to allocate the same names again. Clones of an interner share the
+/// same table, so names are also reused by `evaluate_pure`, which works on
+/// a copy of the context.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Interner {
+    strings: Rc<RefCell<Strings>>,
+}
+
+// names that are no longer used anywhere else are only removed once
+// there are at least this many, so that e.g. the names in the previous
+// input can still be reused
+const MIN_PRUNE_LEN: usize = 1024;
+
+#[derive(Debug, Default)]
+struct Strings {
+    set: HashSet<SharedStr>,
+    // size of the set at which unused names are removed
+    prune_len: usize,
+}
+
+impl Strings {
+    /// Removes the names that are only referenced by the interner, so that
+    /// e.g. untrusted input can't fill up memory with unique names
+    fn prune(&mut self) {
+        self.set.retain(|s| match s {
+            SharedStr::Shared(s) => Arc::strong_count(s) > 1,
+            SharedStr::Static(_) => false,
+        });
+        self.prune_len = (2 * self.set.len()).max(MIN_PRUNE_LEN);
+    }
+}
+
+impl Interner {
+    pub(crate) fn intern(&self, s: &str) -> SharedStr {
+        let mut strings = self.strings.borrow_mut();
+        if let Some(existing) = strings.set.get(s) {
+            return existing.clone();
+        }
+        if strings.set.len() >= strings.prune_len.max(MIN_PRUNE_LEN) {
+            strings.prune();
+        }
+        let shared = SharedStr::Shared(s.into());
+        strings.set.insert(shared.clone());
+        shared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned_strings_are_shared() {
+        let interner = Interner::default();
+        let a = interner.clone().intern("meter");
+        let b = interner.intern("meter");
+        match (a, b) {
+            (SharedStr::Shared(a), SharedStr::Shared(b)) => assert!(Arc::ptr_eq(&a, &b)),
+            _ => panic!("expected shared strings"),
+        }
+        assert_eq!(interner.intern("foot"), "foot");
+    }
+
+    #[test]
+    fn unused_strings_are_removed() {
+        let interner = Interner::default();
+        let meter = interner.intern("meter");
+        for i in 0..10 * MIN_PRUNE_LEN {
+            interner.intern(&format!("name{}", i));
+        }
+        let strings = RefCell::borrow(&interner.strings);
+        assert!(strings.set.len() <= MIN_PRUNE_LEN);
+        assert!(strings.set.contains(&meter));
+        drop(meter);
+    }
+}
@@ -1,7 +1,9 @@
+use crate::date::Time;
 use crate::error::{FendError, Interrupt};
 use crate::ident::Ident;
+use crate::interner::Interner;
 use crate::num::{Base, Number};
-use std::{borrow, convert, fmt};
+use std::{borrow, convert, fmt, ops::Range};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Token {
@@ -10,6 +12,29 @@ pub(crate) enum Token {
     Symbol(Symbol),
     Whitespace,
     StringLiteral(borrow::Cow<'static, str>),
+    Time(Time),
+}
+
+impl Token {
+    /// Describes this token for `debug_parse`, e.g. `Num 2` or `Symbol +`
+    pub(crate) fn describe<I: Interrupt>(
+        &self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<String, FendError> {
+        Ok(match self {
+            Self::Num(n) => format!(
+                "Num {}",
+                crate::value::Value::Num(Box::new(n.clone()))
+                    .format_to_plain_string(0, ctx, int)?
+            ),
+            Self::Ident(ident) => format!("Ident {}", ident.as_str()),
+            Self::Symbol(symbol) => format!("Symbol {}", symbol),
+            Self::Whitespace => "Whitespace".to_string(),
+            Self::StringLiteral(s) => format!("StringLiteral {:?}", s),
+            Self::Time(time) => format!("Time {}", time),
+        })
+    }
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
@@ -32,6 +57,20 @@ pub(crate) enum Symbol {
     ShiftRight,
     Semicolon,
     Equals, // used for assignment
+    DoubleEquals,
+    NotEquals,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Comma,
+    OpenBracket,
+    CloseBracket,
+    DotDot,
+    Angle,
+    PlusMinus,
+    Question,
+    Sqrt,
 }
 
 impl fmt::Display for Symbol {
@@ -48,13 +87,27 @@ impl fmt::Display for Symbol {
             Self::UnitConversion => "to",
             Self::Factorial => "!",
             Self::Fn => ":",
-            Self::Backslash => "\"",
+            Self::Backslash => "\\",
             Self::Dot => ".",
             Self::Of => "of",
             Self::ShiftLeft => "<<",
             Self::ShiftRight => ">>",
             Self::Semicolon => ";",
             Self::Equals => "=",
+            Self::DoubleEquals => "==",
+            Self::NotEquals => "!=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Comma => ",",
+            Self::OpenBracket => "[",
+            Self::CloseBracket => "]",
+            Self::DotDot => "..",
+            Self::Angle => "\u{2220}",
+            Self::PlusMinus => "\u{b1}",
+            Self::Question => "?",
+            Self::Sqrt => "\u{221a}",
         };
         write!(f, "{}", s)?;
         Ok(())
@@ -112,6 +165,11 @@ fn parse_integer<'a, E: From<FendError>>(
     let mut parsed_digit_separator;
     loop {
         if let Ok((_, remaining)) = parse_digit_separator(input) {
+            // a comma that isn't followed by a digit separates list elements,
+            // e.g. `max(1, 2)`
+            if input.starts_with(',') && parse_ascii_digit(remaining, base).is_err() {
+                break;
+            }
             input = remaining;
             parsed_digit_separator = true;
             if !allow_digit_separator {
@@ -249,7 +307,9 @@ fn parse_basic_number<'a, I: Interrupt>(
     }
 
     // parse decimal point and at least one digit
-    if let Ok((_, remaining)) = parse_fixed_char(input, '.') {
+    // (unless this is a range like `1..10`)
+    if input.starts_with("..") {
+    } else if let Ok((_, remaining)) = parse_fixed_char(input, '.') {
         is_integer = false;
         let mut num_nonrec_digits = 0;
         let mut numerator = Number::zero_with_base(base);
@@ -367,25 +427,57 @@ fn parse_basic_number<'a, I: Interrupt>(
 }
 
 fn parse_number<'a, I: Interrupt>(input: &'a str, int: &I) -> Result<(Number, &'a str), FendError> {
-    let (base, input) = parse_base_prefix(input).unwrap_or((Base::default(), input));
-    let (res, input) = parse_basic_number(input, base, int)?;
-    Ok((res, input))
+    let (base, remaining) = parse_base_prefix(input).unwrap_or((Base::default(), input));
+    let (res, remaining) = parse_basic_number(remaining, base, int)?;
+    if base != Base::default() {
+        return Ok((res, remaining));
+    }
+    let literal = &input[..input.len() - remaining.len()];
+    Ok((
+        res.with_significant_figures(count_significant_figures(literal)),
+        remaining,
+    ))
+}
+
+/// Counts the significant figures in a decimal literal, e.g. 3 for `2.50`
+/// or 2 for `0.0012`. Trailing zeroes in integers like `1200` are not
+/// counted. Returns `None` for zero, recurring decimals and dice.
+fn count_significant_figures(literal: &str) -> Option<usize> {
+    let mantissa = literal
+        .split(|ch| ch == 'e' || ch == 'E')
+        .next()
+        .unwrap_or(literal);
+    if mantissa.contains(|ch| ch == '(' || ch == 'd') {
+        return None;
+    }
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let digits = digits.trim_start_matches('0');
+    let digits = if mantissa.contains('.') {
+        digits
+    } else {
+        digits.trim_end_matches('0')
+    };
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits.len())
+    }
 }
 
 fn is_valid_in_ident(ch: char, prev: Option<char>) -> bool {
     let allowed_chars = [
-        ',', '&', '_', '⅛', '¼', '⅜', '½', '⅝', '¾', '⅞', '⅙', '⅓', '⅔', '⅚', '⅕', '⅖', '⅗', '⅘',
-        '°', '$', '℃', '℉', '℧', '℈', '℥', '℔', '¢', '£', '¥', '€', '₩', '₪', '₤', '₨', '฿', '₡',
-        '₣', '₦', '₧', '₫', '₭', '₮', '₯', '₱', '﷼', '﹩', '￠', '￡', '￥', '￦', '㍱', '㍲',
-        '㍳', '㍴', '㍶', '㎀', '㎁', '㎂', '㎃', '㎄', '㎅', '㎆', '㎇', '㎈', '㎉', '㎊', '㎋',
-        '㎌', '㎍', '㎎', '㎏', '㎐', '㎑', '㎒', '㎓', '㎔', '㎕', '㎖', '㎗', '㎘', '㎙', '㎚',
-        '㎛', '㎜', '㎝', '㎞', '㎟', '㎠', '㎡', '㎢', '㎣', '㎤', '㎥', '㎦', '㎧', '㎨', '㎩',
-        '㎪', '㎫', '㎬', '㎭', '㎮', '㎯', '㎰', '㎱', '㎲', '㎳', '㎴', '㎵', '㎶', '㎷', '㎸',
-        '㎹', '㎺', '㎻', '㎼', '㎽', '㎾', '㎿', '㏀', '㏁', '㏃', '㏄', '㏅', '㏆', '㏈', '㏉',
-        '㏊', '㏌', '㏏', '㏐', '㏓', '㏔', '㏕', '㏖', '㏗', '㏙', '㏛', '㏜', '㏝',
+        '&', '_', '⅛', '¼', '⅜', '½', '⅝', '¾', '⅞', '⅙', '⅓', '⅔', '⅚', '⅕', '⅖', '⅗', '⅘', '°',
+        '$', '℃', '℉', '℧', '℈', '℥', '℔', '¢', '£', '¥', '€', '₩', '₪', '₤', '₨', '฿', '₡', '₣',
+        '₦', '₧', '₫', '₭', '₮', '₯', '₱', '﷼', '﹩', '￠', '￡', '￥', '￦', '㍱', '㍲', '㍳',
+        '㍴', '㍶', '㎀', '㎁', '㎂', '㎃', '㎄', '㎅', '㎆', '㎇', '㎈', '㎉', '㎊', '㎋', '㎌',
+        '㎍', '㎎', '㎏', '㎐', '㎑', '㎒', '㎓', '㎔', '㎕', '㎖', '㎗', '㎘', '㎙', '㎚', '㎛',
+        '㎜', '㎝', '㎞', '㎟', '㎠', '㎡', '㎢', '㎣', '㎤', '㎥', '㎦', '㎧', '㎨', '㎩', '㎪',
+        '㎫', '㎬', '㎭', '㎮', '㎯', '㎰', '㎱', '㎲', '㎳', '㎴', '㎵', '㎶', '㎷', '㎸', '㎹',
+        '㎺', '㎻', '㎼', '㎽', '㎾', '㎿', '㏀', '㏁', '㏃', '㏄', '㏅', '㏆', '㏈', '㏉', '㏊',
+        '㏌', '㏏', '㏐', '㏓', '㏔', '㏕', '㏖', '㏗', '㏙', '㏛', '㏜', '㏝',
     ];
     let only_valid_by_themselves = ['%', '‰', '‱', '′', '″', '’', '”', 'π'];
-    let split_on_subsequent_digit = ['$', '£'];
+    let split_on_subsequent_digit = ['$', '£', '°'];
     let always_invalid = ['λ'];
     if always_invalid.contains(&ch) {
         false
@@ -406,7 +498,11 @@ fn is_valid_in_ident(ch: char, prev: Option<char>) -> bool {
     }
 }
 
-fn parse_ident(input: &str, allow_dots: bool) -> Result<(Token, &str), FendError> {
+fn parse_ident<'a>(
+    input: &'a str,
+    allow_dots: bool,
+    interner: &Interner,
+) -> Result<(Token, &'a str), FendError> {
     let (first_char, _) = parse_char(input)?;
     if !is_valid_in_ident(first_char, None) || first_char == '.' && !allow_dots {
         return Err(FendError::InvalidCharAtBeginningOfIdent(first_char));
@@ -418,6 +514,10 @@ fn parse_ident(input: &str, allow_dots: bool) -> Result<(Token, &str), FendError
         if !is_valid_in_ident(next_char, Some(prev_char)) || next_char == '.' && !allow_dots {
             break;
         }
+        if remaining.starts_with("..") {
+            // e.g. `n..10`
+            break;
+        }
         remaining = remaining_input;
         byte_idx += next_char.len_utf8();
         prev_char = next_char;
@@ -427,9 +527,11 @@ fn parse_ident(input: &str, allow_dots: bool) -> Result<(Token, &str), FendError
         match ident {
             "to" | "as" | "in" => Token::Symbol(Symbol::UnitConversion),
             "per" => Token::Symbol(Symbol::Div),
+            // e.g. `3 weeks from today`
+            "from" => Token::Symbol(Symbol::Add),
             "of" => Token::Symbol(Symbol::Of),
             "mod" => Token::Symbol(Symbol::Mod),
-            _ => Token::Ident(Ident::new_string(ident.to_string())),
+            _ => Token::Ident(interner.intern(ident).into()),
         },
         input,
     ))
@@ -449,7 +551,13 @@ fn parse_symbol(ch: char, input: &mut &str) -> Result<Token, FendError> {
         '(' => Symbol::OpenParens,
         ')' => Symbol::CloseParens,
         '+' => Symbol::Add,
-        '!' => Symbol::Factorial,
+        '!' => {
+            if test_next('=') {
+                Symbol::NotEquals
+            } else {
+                Symbol::Factorial
+            }
+        }
         // unicode minus sign
         '-' | '\u{2212}' => Symbol::Sub,
         '*' | '\u{d7}' | '\u{2715}' => {
@@ -461,10 +569,16 @@ fn parse_symbol(ch: char, input: &mut &str) -> Result<Token, FendError> {
         }
         '/' | '\u{f7}' | '\u{2215}' => Symbol::Div, // unicode division symbol and slash
         '^' => Symbol::Pow,
+        '\u{2220}' => Symbol::Angle, // angle symbol, used for polar form
+        '\u{b1}' => Symbol::PlusMinus, // plus-minus sign, used for uncertainties
+        '?' => Symbol::Question,     // template placeholders, e.g. `?a`
+        '\u{221a}' => Symbol::Sqrt,  // square root symbol, e.g. `2√2`
         ':' => Symbol::Fn,
         '=' => {
             if test_next('>') {
                 Symbol::Fn
+            } else if test_next('=') {
+                Symbol::DoubleEquals
             } else {
                 Symbol::Equals
             }
@@ -474,18 +588,25 @@ fn parse_symbol(ch: char, input: &mut &str) -> Result<Token, FendError> {
         '<' => {
             if test_next('<') {
                 Symbol::ShiftLeft
+            } else if test_next('=') {
+                Symbol::LessOrEqual
             } else {
-                return Err(FendError::UnexpectedChar(ch));
+                Symbol::Less
             }
         }
         '>' => {
             if test_next('>') {
                 Symbol::ShiftRight
+            } else if test_next('=') {
+                Symbol::GreaterOrEqual
             } else {
-                return Err(FendError::UnexpectedChar(ch));
+                Symbol::Greater
             }
         }
         ';' => Symbol::Semicolon,
+        ',' => Symbol::Comma,
+        '[' => Symbol::OpenBracket,
+        ']' => Symbol::CloseBracket,
         _ => return Err(FendError::UnexpectedChar(ch)),
     }))
 }
@@ -616,7 +737,7 @@ fn parse_string_literal(input: &str, terminator: char) -> Result<(Token, &str),
 }
 
 // parses a unit beginning with ' or "
-fn parse_quote_unit(input: &str) -> (Token, &str) {
+fn parse_quote_unit<'a>(input: &'a str, interner: &Interner) -> (Token, &'a str) {
     let mut split_idx = 1;
     if let Some(ch) = input.split_at(1).1.chars().next() {
         if ch.is_alphabetic() {
@@ -635,18 +756,72 @@ fn parse_quote_unit(input: &str) -> (Token, &str) {
         }
     }
     let (a, b) = input.split_at(split_idx);
-    (Token::Ident(Ident::new_string(a.to_string())), b)
+    (Token::Ident(interner.intern(a).into()), b)
+}
+
+// whether `input` starts with exactly three digits, like the `000` after
+// the comma in `1,000`
+fn starts_with_digit_group(input: &str) -> bool {
+    input.bytes().take_while(u8::is_ascii_digit).count() == 3
 }
 
 pub(crate) struct Lexer<'a, 'b, I: Interrupt> {
     input: &'a str,
     // normally 0; 1 after backslash; 2 after ident after backslash
+    // (or after a parameter list like `\(x, y=2)`)
     after_backslash_state: u8,
+    // how many parentheses are open within a parameter list after a backslash
+    param_list_depth: usize,
+    // how many parentheses or brackets are open; within them, commas
+    // separate arguments or list elements instead of digits
+    group_depth: usize,
     after_number_or_to: bool,
+    // within an angle like `48°51'24"`, where ' and " are arcminutes and
+    // arcseconds instead of feet and inches
+    in_dms_angle: bool,
+    // within a duration like `2h15m`, where m is minutes instead of meters
+    in_compound_duration: bool,
+    interner: &'b Interner,
     int: &'b I,
 }
 
 impl<'a, 'b, I: Interrupt> Lexer<'a, 'b, I> {
+    fn state(&self) -> LexerState {
+        LexerState {
+            after_backslash_state: self.after_backslash_state,
+            param_list_depth: self.param_list_depth,
+            group_depth: self.group_depth,
+            after_number_or_to: self.after_number_or_to,
+            in_dms_angle: self.in_dms_angle,
+            in_compound_duration: self.in_compound_duration,
+        }
+    }
+
+    // lexes a time of day like `3:30pm`, or otherwise a number
+    fn next_number_or_time(&mut self) -> Result<Token, FendError> {
+        if let Some((time, remaining)) = Time::parse_prefix(self.input) {
+            self.input = remaining;
+            return Ok(Token::Time(time));
+        }
+        // within parentheses or brackets, a comma separates arguments
+        // (e.g. `max(1,2)` or `[1,2,3]`) unless it is followed by a group
+        // of three digits, like in `(1,000) * 2`
+        let number_input = if self.group_depth > 0 {
+            let end = self
+                .input
+                .match_indices(',')
+                .map(|(idx, _)| idx)
+                .find(|&idx| !starts_with_digit_group(&self.input[idx + 1..]))
+                .unwrap_or(self.input.len());
+            &self.input[..end]
+        } else {
+            self.input
+        };
+        let (num, remaining) = parse_number(number_input, self.int)?;
+        self.input = &self.input[number_input.len() - remaining.len()..];
+        Ok(Token::Num(num))
+    }
+
     fn next_token(&mut self) -> Result<Option<Token>, FendError> {
         while let Some(ch) = self.input.chars().next() {
             if self.input.starts_with("# ") {
@@ -675,16 +850,29 @@ impl<'a, 'b, I: Interrupt> Lexer<'a, 'b, I> {
             Some(ch) => {
                 if ch.is_whitespace() {
                     Token::Whitespace
+                } else if self.input.starts_with("..") {
+                    let (_, remaining) = self.input.split_at(2);
+                    self.input = remaining;
+                    Token::Symbol(Symbol::DotDot)
                 } else if ch.is_ascii_digit()
                     || (ch == '.' && self.after_backslash_state == 0)
                     || (ch == 'd' && following.is_some() && following.unwrap().is_ascii_digit())
                 {
-                    let (num, remaining) = parse_number(self.input, self.int)?;
+                    self.next_number_or_time()?
+                } else if self.in_dms_angle
+                    && self.after_number_or_to
+                    && matches!(ch, '\'' | '"' | '′' | '″')
+                {
+                    let (_, remaining) = self.input.split_at(ch.len_utf8());
                     self.input = remaining;
-                    Token::Num(num)
+                    Token::Ident(Ident::new_str(if matches!(ch, '\'' | '′') {
+                        "arcmin"
+                    } else {
+                        "arcsec"
+                    }))
                 } else if ch == '\'' || ch == '"' {
                     if self.after_number_or_to {
-                        let (token, remaining) = parse_quote_unit(self.input);
+                        let (token, remaining) = parse_quote_unit(self.input, self.interner);
                         self.input = remaining;
                         token
                     } else {
@@ -705,10 +893,20 @@ impl<'a, 'b, I: Interrupt> Lexer<'a, 'b, I> {
                     let (_terminator, remaining) = remaining.split_at(2);
                     self.input = remaining;
                     Token::StringLiteral(literal.to_string().into())
+                } else if self.after_number_or_to
+                    && (matches!(ch, 'h' | 'm')
+                        && matches!(following, Some(c) if c.is_ascii_digit())
+                        || ch == 'm'
+                            && self.in_compound_duration
+                            && !matches!(following, Some(c) if is_valid_in_ident(c, Some(ch))))
+                {
+                    let (_, remaining) = self.input.split_at(1);
+                    self.input = remaining;
+                    Token::Ident(Ident::new_str(if ch == 'h' { "h" } else { "min" }))
                 } else if is_valid_in_ident(ch, None) {
                     // dots aren't allowed in idents after a backslash
                     let (ident, remaining) =
-                        parse_ident(self.input, self.after_backslash_state != 1)?;
+                        parse_ident(self.input, self.after_backslash_state != 1, self.interner)?;
                     self.input = remaining;
                     ident
                 } else {
@@ -726,6 +924,7 @@ impl<'a, I: Interrupt> Iterator for Lexer<'a, '_, I> {
     type Item = Result<Token, FendError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let after_number_or_to = self.after_number_or_to;
         let res = match self.next_token() {
             Err(e) => Some(Err(e)),
             Ok(None) => None,
@@ -739,13 +938,50 @@ impl<'a, I: Interrupt> Iterator for Lexer<'a, '_, I> {
         } else {
             self.after_number_or_to = false;
         }
+        self.in_dms_angle = match &res {
+            Some(Ok(Token::Ident(ident))) => {
+                ident.as_str() == "°" || self.in_dms_angle && ident.as_str() == "arcmin"
+            }
+            Some(Ok(Token::Num(_))) => self.in_dms_angle,
+            _ => false,
+        };
+        self.in_compound_duration = match &res {
+            Some(Ok(Token::Ident(ident))) => {
+                after_number_or_to && matches!(ident.as_str(), "h" | "hr" | "min")
+            }
+            Some(Ok(Token::Num(_))) => self.in_compound_duration,
+            _ => false,
+        };
+        match res {
+            Some(Ok(Token::Symbol(Symbol::OpenParens | Symbol::OpenBracket))) => {
+                self.group_depth += 1;
+            }
+            Some(Ok(Token::Symbol(Symbol::CloseParens | Symbol::CloseBracket))) => {
+                self.group_depth = self.group_depth.saturating_sub(1);
+            }
+            _ => (),
+        }
         if let Some(Ok(Token::Symbol(Symbol::Backslash))) = res {
             self.after_backslash_state = 1;
         } else if self.after_backslash_state == 1 {
-            if let Some(Ok(Token::Ident(_))) = res {
-                self.after_backslash_state = 2;
-            } else {
-                self.after_backslash_state = 0;
+            match res {
+                Some(Ok(Token::Ident(_))) => self.after_backslash_state = 2,
+                Some(Ok(Token::Symbol(Symbol::OpenParens))) => {
+                    self.after_backslash_state = 0;
+                    self.param_list_depth = 1;
+                }
+                _ => self.after_backslash_state = 0,
+            }
+        } else if self.param_list_depth > 0 {
+            match res {
+                Some(Ok(Token::Symbol(Symbol::OpenParens))) => self.param_list_depth += 1,
+                Some(Ok(Token::Symbol(Symbol::CloseParens))) => {
+                    self.param_list_depth -= 1;
+                    if self.param_list_depth == 0 {
+                        self.after_backslash_state = 2;
+                    }
+                }
+                _ => (),
             }
         } else {
             self.after_backslash_state = 0;
@@ -754,11 +990,225 @@ impl<'a, I: Interrupt> Iterator for Lexer<'a, '_, I> {
     }
 }
 
-pub(crate) fn lex<'a, 'b, I: Interrupt>(input: &'a str, int: &'b I) -> Lexer<'a, 'b, I> {
+/// The lexer state that affects how the next token is lexed
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct LexerState {
+    after_backslash_state: u8,
+    param_list_depth: usize,
+    group_depth: usize,
+    after_number_or_to: bool,
+    in_dms_angle: bool,
+    in_compound_duration: bool,
+}
+
+/// The tokens of an input, each with the byte offset where it ends and the
+/// lexer state following it. This makes it possible to re-lex only the part
+/// of the input that changed after an edit.
+#[derive(Debug, Default)]
+pub(crate) struct LexedTokens {
+    tokens: Vec<(Token, usize, LexerState)>,
+    error: Option<FendError>,
+}
+
+impl LexedTokens {
+    pub(crate) fn new<I: Interrupt>(input: &str, interner: &Interner, int: &I) -> Self {
+        Self::lex_after(input, vec![], interner, int)
+    }
+
+    /// Re-lexes `new_input`, given that `self` holds the tokens of
+    /// `old_input`. Tokens are reused up to the last whitespace before
+    /// the first change: no token looks ahead past whitespace, so
+    /// those tokens are guaranteed to be unaffected by the edit.
+    pub(crate) fn relex<I: Interrupt>(
+        &self,
+        old_input: &str,
+        new_input: &str,
+        interner: &Interner,
+        int: &I,
+    ) -> Self {
+        let common_prefix = old_input
+            .char_indices()
+            .zip(new_input.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(old_input.len().min(new_input.len()), |((idx, _), _)| idx);
+        let boundary = new_input[..common_prefix]
+            .rfind(char::is_whitespace)
+            .unwrap_or(0);
+        let kept = self
+            .tokens
+            .iter()
+            .take_while(|(_, end, _)| *end <= boundary)
+            .cloned()
+            .collect();
+        Self::lex_after(new_input, kept, interner, int)
+    }
+
+    /// Lexes the rest of `input`, following the already lexed `tokens`
+    fn lex_after<I: Interrupt>(
+        input: &str,
+        mut tokens: Vec<(Token, usize, LexerState)>,
+        interner: &Interner,
+        int: &I,
+    ) -> Self {
+        let (start, state) = tokens
+            .last()
+            .map_or((0, LexerState::default()), |(_, end, state)| (*end, *state));
+        let mut lexer = Lexer {
+            input: &input[start..],
+            after_backslash_state: state.after_backslash_state,
+            param_list_depth: state.param_list_depth,
+            group_depth: state.group_depth,
+            after_number_or_to: state.after_number_or_to,
+            in_dms_angle: state.in_dms_angle,
+            in_compound_duration: state.in_compound_duration,
+            interner,
+            int,
+        };
+        let mut error = None;
+        while let Some(token) = lexer.next() {
+            match token {
+                Ok(token) => {
+                    let end = input.len() - lexer.input.len();
+                    tokens.push((token, end, lexer.state()));
+                }
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+        Self { tokens, error }
+    }
+
+    /// Returns the tokens, or the first error encountered while lexing
+    pub(crate) fn tokens(&self) -> Result<Vec<Token>, &FendError> {
+        match &self.error {
+            Some(e) => Err(e),
+            None => Ok(self.tokens.iter().map(|(t, _, _)| t.clone()).collect()),
+        }
+    }
+
+    /// Returns the tokens that were lexed before any error
+    pub(crate) fn tokens_before_error(&self) -> impl Iterator<Item = &Token> {
+        self.tokens.iter().map(|(t, _, _)| t)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns the byte range of the token at `index` in `input`, without
+    /// any leading whitespace. If `index` is past the last token, this is
+    /// the range up to the next whitespace, e.g. where lexing failed.
+    pub(crate) fn token_range(&self, input: &str, index: usize) -> Range<usize> {
+        let start = match index.checked_sub(1) {
+            Some(prev) => self
+                .tokens
+                .get(prev)
+                .map_or(input.len(), |(_, end, _)| *end),
+            None => 0,
+        };
+        let start = input.len() - input[start..].trim_start().len();
+        let end = match self.tokens.get(index) {
+            Some((_, end, _)) => *end,
+            None => input[start..]
+                .find(char::is_whitespace)
+                .map_or(input.len(), |len| start + len),
+        };
+        start..end.max(start)
+    }
+}
+
+pub(crate) fn lex<'a, 'b, I: Interrupt>(
+    input: &'a str,
+    interner: &'b Interner,
+    int: &'b I,
+) -> Lexer<'a, 'b, I> {
     Lexer {
         input,
         after_backslash_state: 0,
+        param_list_depth: 0,
+        group_depth: 0,
         after_number_or_to: false,
+        in_dms_angle: false,
+        in_compound_duration: false,
+        interner,
         int,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interrupt::Never;
+
+    #[test]
+    fn significant_figures_of_literals() {
+        for (literal, expected) in [
+            ("2.50", Some(3)),
+            ("0.0012", Some(2)),
+            ("1200", Some(2)),
+            ("1,200.0", Some(5)),
+            ("6.02e23", Some(3)),
+            ("0", None),
+            ("0.(3)", None),
+            ("2d6", None),
+        ] {
+            assert_eq!(count_significant_figures(literal), expected, "{}", literal);
+        }
+    }
+
+    fn assert_relex_matches(old_input: &str, new_input: &str) {
+        let interner = Interner::default();
+        let int = Never::default();
+        let old = LexedTokens::new(old_input, &interner, &int);
+        let relexed = old.relex(old_input, new_input, &interner, &int);
+        let fresh = LexedTokens::new(new_input, &interner, &int);
+        assert_eq!(
+            format!("{:?}", relexed.tokens()),
+            format!("{:?}", fresh.tokens()),
+            "relexing {:?} as {:?}",
+            old_input,
+            new_input
+        );
+    }
+
+    #[test]
+    fn relex_matches_full_lex() {
+        let edits = [
+            ("1 + 2", "1 + 23"),
+            ("1 + 2", "1 + 2 kg"),
+            ("1 + 2e", "1 + 2e5"),
+            ("5 feet 1", "5 feet 1\""),
+            ("\"hello wor", "\"hello world\""),
+            ("1 + 1 # a comment", "1 + 1 # a longer comment"),
+            ("1 + 1 # comment", "1 + 1 # comment\n+ 2"),
+            ("\\x.x + 1", "\\x.x + 12"),
+            ("3 to kg", "2 to kg"),
+            ("sqrt 2 + 1", "sqrt 2"),
+            ("1 + 2..", "1 + 2..5"),
+            ("5 ∠ 3", "5 ∠ 30°"),
+            ("48° 51' 2", "48° 51' 24\""),
+            ("2h 15", "2h 15m"),
+            ("14:3", "14:30 + 2h15m"),
+            ("1 + ", "1 + \"a b"),
+            ("max(1", "max(1,2)"),
+            ("[1,2", "[1,2] + 1,000"),
+            ("(1,00", "(1,000) * 2"),
+            ("[1,000", "[1,0000]"),
+        ];
+        for (old, new) in edits {
+            assert_relex_matches(old, new);
+        }
+    }
+
+    #[test]
+    fn relex_reuses_tokens_before_edit() {
+        let interner = Interner::default();
+        let int = Never::default();
+        let old = LexedTokens::new("1 + 2 + 3", &interner, &int);
+        let relexed = old.relex("1 + 2 + 3", "1 + 2 + 34", &interner, &int);
+        assert_eq!(relexed.tokens.len(), 5);
+        assert_eq!(relexed.tokens[3].1, old.tokens[3].1);
+    }
+}
@@ -9,22 +9,34 @@
 #![doc(html_root_url = "https://docs.rs/fend-core/1.0.1")]
 
 mod ast;
+mod chemistry;
 mod date;
 mod error;
 mod eval;
+mod exchange_rate;
 mod format;
+mod highlight;
 mod ident;
+mod interner;
 mod interrupt;
 mod lexer;
+mod limits;
 mod num;
 mod parser;
 mod scope;
+mod session;
+mod suggestions;
 mod units;
 mod value;
+mod verbose;
 
 use std::collections::HashMap;
+use std::fmt;
 
+pub use exchange_rate::ExchangeRateSource;
 pub use interrupt::Interrupt;
+pub use limits::Limits;
+pub use suggestions::Correction;
 
 /// This contains the result of a computation.
 #[derive(PartialEq, Eq, Debug)]
@@ -45,7 +57,11 @@ pub enum SpanKind {
     Whitespace,
     Ident,
     Boolean,
+    Matrix,
     Other,
+    /// The same result in another unit, e.g. in the other measurement
+    /// system (see `Context::show_dual_units`)
+    Secondary,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -82,6 +98,18 @@ impl<'a> SpanRef<'a> {
 }
 
 impl FendResult {
+    fn from_spans(spans: Vec<Span>, is_unit: bool) -> Self {
+        let mut plain_result = String::new();
+        for s in &spans {
+            plain_result.push_str(&s.string);
+        }
+        Self {
+            plain_result,
+            span_result: spans,
+            is_unit,
+        }
+    }
+
     /// This retrieves the main result of the computation.
     #[must_use]
     pub fn get_main_result(&self) -> &str {
@@ -97,6 +125,14 @@ impl FendResult {
         })
     }
 
+    /// This retrieves the main result spelled out in words, e.g.
+    /// `three point one four meters squared` instead of `3.14 m^2`.
+    /// This is useful for screen readers.
+    #[must_use]
+    pub fn get_main_result_verbose(&self) -> String {
+        verbose::spell_out(&self.span_result)
+    }
+
     /// Returns whether or not the result is the `()` type. It can sometimes
     /// be useful to hide these values.
     #[must_use]
@@ -120,12 +156,87 @@ struct CurrentTimeInfo {
     timezone_offset_secs: i64,
 }
 
+impl CurrentTimeInfo {
+    /// Returns the number of milliseconds since 1970-01-01 00:00 in the
+    /// local time zone, which is negative if the local time is before 1970
+    fn local_ms_since_epoch(&self) -> Result<i64, error::FendError> {
+        i64::try_from(self.elapsed_unix_time_ms)
+            .ok()
+            .zip(self.timezone_offset_secs.checked_mul(1000))
+            .and_then(|(ms, offset_ms)| ms.checked_sub(offset_ms))
+            .ok_or(error::FendError::DateOutOfRange)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum FCMode {
     CelsiusFahrenheit,
     CoulombFarad,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AngleMode {
+    Radians,
+    Degrees,
+}
+
+/// The kind of error that the last calculation failed with, see
+/// [`Context::get_error_kind`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input couldn't be parsed, e.g. `2 * * 3`
+    Syntax,
+    /// The input is valid, but couldn't be evaluated, e.g. `1/0` or
+    /// `5 kg to m`
+    Evaluation,
+    /// The calculation was interrupted, or exceeded its time limit
+    Interrupted,
+}
+
+/// A part of the language that can be disabled with
+/// [`Context::disable_language_feature`], e.g. to restrict untrusted input
+/// to arithmetic and units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LanguageFeature {
+    /// Lambdas like `\x.x^2` or `x => x^2`, including templates like `?a * 2`
+    Lambdas,
+    /// Variable assignments like `a = 5` and unit definitions like
+    /// `unit furlong = 220 yards`
+    Assignments,
+    /// String literals like `"hello"`
+    Strings,
+    /// Dates like `today`, or conversions like `"2020-01-01" to date`
+    Dates,
+}
+
+impl fmt::Display for LanguageFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Lambdas => "lambdas",
+            Self::Assignments => "assignments",
+            Self::Strings => "strings",
+            Self::Dates => "dates",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How exponents of units are shown in results, set with
+/// [`Context::set_unit_exponent_style`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnitExponentStyle {
+    /// Units with a negative exponent are shown after a slash, e.g.
+    /// `m / s^2` (default)
+    Slash,
+    /// Negative exponents are always shown as such, e.g. `m s^-2`
+    NegativeExponents,
+    /// Like `Slash`, but with Unicode superscript exponents, e.g. `m / s²`
+    Superscript,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum OutputMode {
     SimpleText,
@@ -139,8 +250,41 @@ pub struct Context {
     current_time: Option<CurrentTimeInfo>,
     variables: HashMap<String, value::Value>,
     fc_mode: FCMode,
+    // unit of angles without an explicit unit, e.g. in `sin 30`
+    angle_mode: AngleMode,
+    unit_exponent_style: UnitExponentStyle,
     random_u32: Option<fn() -> u32>,
+    // state of the seeded random number generator (if any), which
+    // takes precedence over `random_u32`
+    random_seed: Option<u64>,
     output_mode: OutputMode,
+    // names of variables assigned during `evaluate_pure`
+    assigned_variables: Option<Vec<String>>,
+    interner: interner::Interner,
+    // set while evaluating the argument of `quat`, where `i`, `j` and `k`
+    // refer to the quaternion units
+    quaternion_units: bool,
+    dual_units: bool,
+    track_significant_figures: bool,
+    // number of decimal places that approximate results are computed and
+    // displayed to
+    precision: usize,
+    // units defined with statements like `unit furlong = 220 yards`
+    custom_units: Vec<units::CustomUnit>,
+    // rate set with e.g. `takes 45 min per item`, which only applies to
+    // the rest of the current calculation
+    rate: Option<value::Value>,
+    exchange_rate_source: Option<exchange_rate::SharedExchangeRateSource>,
+    disabled_language_features: Vec<LanguageFeature>,
+    limits: Limits,
+    // nesting depth of the expression that is currently being evaluated
+    recursion_depth: usize,
+    // suggested correction for the last input, if it failed to evaluate
+    correction: Option<Correction>,
+    // kind of error of the last input, if it failed to evaluate
+    error_kind: Option<ErrorKind>,
+    // holidays used by `is_holiday` and `add_workdays`
+    holidays: Vec<date::Holiday>,
 }
 
 impl Default for Context {
@@ -158,13 +302,29 @@ impl Context {
             current_time: None,
             variables: HashMap::new(),
             fc_mode: FCMode::CelsiusFahrenheit,
+            angle_mode: AngleMode::Radians,
+            unit_exponent_style: UnitExponentStyle::Slash,
             random_u32: None,
+            random_seed: None,
             output_mode: OutputMode::SimpleText,
+            assigned_variables: None,
+            interner: interner::Interner::default(),
+            quaternion_units: false,
+            dual_units: false,
+            track_significant_figures: false,
+            precision: num::DEFAULT_PRECISION,
+            custom_units: vec![],
+            rate: None,
+            exchange_rate_source: None,
+            disabled_language_features: vec![],
+            limits: Limits::default(),
+            recursion_depth: 0,
+            correction: None,
+            error_kind: None,
+            holidays: date::Holiday::defaults(),
         }
     }
 
-    /// This method currently has no effect!
-    ///
     /// Set the current time. This API will likely change in the future!
     ///
     /// The first argument (`ms_since_1970`) must be the number of elapsed milliseconds
@@ -172,13 +332,14 @@ impl Context {
     /// as unix time.
     ///
     /// The second argument (`tz_offset_secs`) is the current time zone
-    /// offset to UTC, in seconds.
-    pub fn set_current_time_v1(&mut self, _ms_since_1970: u64, _tz_offset_secs: i64) {
-        // self.current_time = Some(CurrentTimeInfo {
-        //     elapsed_unix_time_ms: ms_since_1970,
-        //     timezone_offset_secs: tz_offset_secs,
-        // });
-        self.current_time = None;
+    /// offset to UTC, in seconds. Like JavaScript's `getTimezoneOffset`, it
+    /// is the difference between UTC and local time, so it is negative
+    /// east of UTC (e.g. -43200 in New Zealand).
+    pub fn set_current_time_v1(&mut self, ms_since_1970: u64, tz_offset_secs: i64) {
+        self.current_time = Some(CurrentTimeInfo {
+            elapsed_unix_time_ms: ms_since_1970,
+            timezone_offset_secs: tz_offset_secs,
+        });
     }
 
     /// Define the units `C` and `F` as coulomb and farad instead of degrees
@@ -187,14 +348,198 @@ impl Context {
         self.fc_mode = FCMode::CoulombFarad;
     }
 
+    /// Interpret angles without a unit as degrees instead of radians, so
+    /// that e.g. `sin 30` is 0.5. Explicit units like `sin (pi/6 rad)` are
+    /// still respected, and inverse functions like `asin` return degrees.
+    pub fn use_degrees(&mut self) {
+        self.angle_mode = AngleMode::Degrees;
+    }
+
+    /// Choose how exponents of units are shown, e.g. `m / s^2`, `m s^-2`
+    /// or `m / s²`
+    pub fn set_unit_exponent_style(&mut self, style: UnitExponentStyle) {
+        self.unit_exponent_style = style;
+    }
+
+    /// Also show results in the other measurement system (metric or US
+    /// customary), e.g. `5 m (approx. 16.4 feet)`. The secondary result
+    /// uses `SpanKind::Secondary`, so it can be shown in a dimmer color.
+    pub fn show_dual_units(&mut self) {
+        self.dual_units = true;
+    }
+
+    /// Keep track of the number of significant figures in number literals
+    /// like `2.50`, and round results accordingly: products and quotients
+    /// have as many significant figures as the least precise operand, and
+    /// sums and differences are rounded to the least precise decimal place.
+    pub fn track_significant_figures(&mut self) {
+        self.track_significant_figures = true;
+    }
+
+    /// Set the number of decimal places that irrational results like `pi`,
+    /// `sqrt 2` or `sin 1` are computed and displayed to. The default is
+    /// 10, and the maximum is 1000. This can also be changed with the
+    /// statement `set precision 100`. Results like `π/3` or `2√2` are only
+    /// kept in their exact symbolic form at the default precision.
+    pub fn set_precision(&mut self, digits: usize) {
+        self.precision = digits.min(num::MAX_PRECISION);
+    }
+
+    /// Returns the current number of decimal places, which may have been
+    /// changed with [`Context::set_precision`] or `set precision 100`.
+    #[must_use]
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// Define custom units, e.g. from a file that is shared within a team.
+    /// Each line contains a unit definition like `unit furlong = 220 yards`
+    /// or a prefix definition like `prefix lakh = 1e5`, using the same
+    /// syntax as in calculations. Empty lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// # Errors
+    /// Returns an error (including the line number) if a line is not a
+    /// valid unit definition. Units defined on earlier lines are kept.
+    pub fn define_units(&mut self, definitions: &str) -> Result<(), String> {
+        units::define_units(definitions, self, &interrupt::Never::default())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Returns the variables and custom units defined in this context as
+    /// statements like `tax_rate = 0.19`, one per line, e.g. to save them
+    /// to a file when the application exits. Values that can't be written
+    /// as an expression, like dates, are skipped, and approximate numbers
+    /// are rounded to the current precision.
+    ///
+    /// # Errors
+    /// Returns an error if a value can't be formatted.
+    pub fn save_variables(&self) -> Result<String, String> {
+        session::save(self, &interrupt::Never::default()).map_err(|e| e.to_string())
+    }
+
+    /// Restores variables and custom units that were saved with
+    /// [`Context::save_variables`]. Empty lines and lines starting with
+    /// `#` are ignored.
+    ///
+    /// # Errors
+    /// Returns an error (including the line number) if a line can't be
+    /// evaluated. Variables on earlier lines are kept.
+    pub fn restore_variables(&mut self, saved: &str) -> Result<(), String> {
+        session::restore(saved, self, &interrupt::Never::default()).map_err(|e| e.to_string())
+    }
+
+    /// Defines variables from pairs of names and expressions, e.g. aliases
+    /// like `f2c` for `x => (x - 32) * 5/9` that are loaded from a
+    /// configuration file. The expressions are evaluated in order, so later
+    /// ones can use earlier variables.
+    ///
+    /// # Errors
+    /// Returns an error (including the name) if a name is not a valid
+    /// identifier or an expression can't be evaluated. Variables defined
+    /// before the invalid one are kept.
+    pub fn define_variables<N: AsRef<str>, E: AsRef<str>>(
+        &mut self,
+        definitions: &[(N, E)],
+    ) -> Result<(), String> {
+        session::define(definitions, self, &interrupt::Never::default()).map_err(|e| e.to_string())
+    }
+
+    /// Set the holidays used by `is_holiday` and `add_workdays`, replacing
+    /// the default holidays (New Year's Day and Christmas Day). Each holiday
+    /// is either a day of the year like `12-25`, a single date like
+    /// `2025-05-05`, or a number of days after Easter Sunday like `easter-2`
+    /// (Good Friday) or `easter+1` (Easter Monday).
+    ///
+    /// # Errors
+    /// Returns an error if a holiday is invalid, in which case the holidays
+    /// are left unchanged.
+    pub fn set_holidays<S: AsRef<str>>(&mut self, holidays: &[S]) -> Result<(), String> {
+        self.holidays = holidays
+            .iter()
+            .map(|holiday| date::Holiday::parse(holiday.as_ref()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Use exchange rates from the given source (e.g. live data supplied by
+    /// the application) for currency conversions like `100 USD to EUR`,
+    /// instead of fend's built-in rates.
+    pub fn set_exchange_rate_source(&mut self, source: impl ExchangeRateSource + 'static) {
+        self.exchange_rate_source = Some(exchange_rate::SharedExchangeRateSource::new(source));
+    }
+
+    /// Disable a part of the language, so that any input using it returns
+    /// an error. This is useful for e.g. servers that evaluate untrusted
+    /// input and only need arithmetic and units.
+    pub fn disable_language_feature(&mut self, feature: LanguageFeature) {
+        if !self.disabled_language_features.contains(&feature) {
+            self.disabled_language_features.push(feature);
+        }
+    }
+
+    /// Limit the time, memory, recursion depth and output length of each
+    /// calculation. Calculations that exceed a limit return an error.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Returns a corrected version of the last evaluated input if it failed
+    /// because of e.g. a misspelled unit, which is also suggested in the
+    /// error message ("did you mean ...?").
+    #[must_use]
+    pub fn get_correction(&self) -> Option<&Correction> {
+        self.correction.as_ref()
+    }
+
+    /// Returns the kind of error that the last evaluated input failed with,
+    /// e.g. to exit with a different code for syntax errors, or `None` if it
+    /// was evaluated successfully.
+    #[must_use]
+    pub fn get_error_kind(&self) -> Option<ErrorKind> {
+        self.error_kind
+    }
+
     /// Set a random number generator
     pub fn set_random_u32_fn(&mut self, random_u32: fn() -> u32) {
         self.random_u32 = Some(random_u32);
     }
 
     /// Clear the random number generator after setting it with via [`Self::set_random_u32_fn`]
+    /// or [`Self::set_random_seed`]
     pub fn disable_rng(&mut self) {
         self.random_u32 = None;
+        self.random_seed = None;
+    }
+
+    /// Seed the random number generator, so that functions like `random()`
+    /// and `randint` return reproducible results. This overrides any function
+    /// set via [`Self::set_random_u32_fn`].
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_seed = Some(seed);
+    }
+
+    fn next_random_u32(&mut self) -> Result<u32, error::FendError> {
+        if let Some(state) = &mut self.random_seed {
+            // splitmix64
+            *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            return Ok(u32::try_from(z >> 32).unwrap());
+        }
+        let random_u32 = self
+            .random_u32
+            .ok_or(error::FendError::RandomNumbersNotAvailable)?;
+        Ok(random_u32())
+    }
+
+    fn random_u64(&mut self) -> Result<u64, error::FendError> {
+        let high = u64::from(self.next_random_u32()?);
+        let low = u64::from(self.next_random_u32()?);
+        Ok(high << 32 | low)
     }
 
     /// Change the output mode fixed-width terminal style. This enables ASCII
@@ -202,6 +547,95 @@ impl Context {
     pub fn set_output_mode_terminal(&mut self) {
         self.output_mode = OutputMode::TerminalFixedWidth;
     }
+
+    fn set_variable(&mut self, name: String, value: value::Value) {
+        if let Some(assigned_variables) = &mut self.assigned_variables {
+            assigned_variables.push(name.clone());
+        }
+        self.variables.insert(name, value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct VariableChange {
+    name: String,
+    old_value: Option<value::Value>,
+    new_value: value::Value,
+}
+
+/// This contains the changes that an evaluation would make to a [`Context`],
+/// as returned by [`evaluate_pure`]: variable assignments, custom units,
+/// settings like `set precision 50`, and the state of the seeded random
+/// number generator.
+///
+/// Frontends can use this to preview results without committing them,
+/// and to implement undo by reverting a delta after applying it.
+#[derive(Clone, Debug, Default)]
+pub struct ContextDelta {
+    variables: Vec<VariableChange>,
+    // units defined with e.g. `unit furlong = 220 yards`, which are
+    // added after any existing custom units
+    custom_units: Vec<units::CustomUnit>,
+    // old and new precision
+    precision: Option<(usize, usize)>,
+    // old and new state of the seeded random number generator
+    random_seed: Option<(Option<u64>, Option<u64>)>,
+}
+
+impl ContextDelta {
+    /// Returns true if the evaluation would not change the context.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+            && self.custom_units.is_empty()
+            && self.precision.is_none()
+            && self.random_seed.is_none()
+    }
+
+    /// Apply these changes to the given context.
+    pub fn apply(&self, context: &mut Context) {
+        for change in &self.variables {
+            context
+                .variables
+                .insert(change.name.clone(), change.new_value.clone());
+        }
+        context
+            .custom_units
+            .extend(self.custom_units.iter().cloned());
+        if let Some((_, new_precision)) = self.precision {
+            context.precision = new_precision;
+        }
+        if let Some((_, new_seed)) = self.random_seed {
+            context.random_seed = new_seed;
+        }
+    }
+
+    /// Undo these changes, restoring the context to the state it was in
+    /// before the delta was applied.
+    pub fn revert(&self, context: &mut Context) {
+        for change in self.variables.iter().rev() {
+            match &change.old_value {
+                Some(old_value) => {
+                    context
+                        .variables
+                        .insert(change.name.clone(), old_value.clone());
+                }
+                None => {
+                    context.variables.remove(&change.name);
+                }
+            }
+        }
+        let custom_units = context.custom_units.len();
+        context
+            .custom_units
+            .truncate(custom_units.saturating_sub(self.custom_units.len()));
+        if let Some((old_precision, _)) = self.precision {
+            context.precision = old_precision;
+        }
+        if let Some((old_seed, _)) = self.random_seed {
+            context.random_seed = old_seed;
+        }
+    }
 }
 
 /// This function evaluates a string using the given context. Any evaluation using this
@@ -229,6 +663,8 @@ pub fn evaluate_with_interrupt(
     context: &mut Context,
     int: &impl Interrupt,
 ) -> Result<FendResult, String> {
+    context.correction = None;
+    context.error_kind = None;
     if input.is_empty() {
         // no or blank input: return no output
         return Ok(FendResult {
@@ -237,19 +673,221 @@ pub fn evaluate_with_interrupt(
             is_unit: true,
         });
     }
-    let (result, is_unit) = match eval::evaluate_to_spans(input, None, context, int) {
+    let (result, is_unit) = match limits::enforce(context, int, |context, int| {
+        eval::evaluate_to_spans(input, None, context, int)
+    }) {
+        Ok(value) => value,
+        Err(e) => return Err(report_error(&e, input, context)),
+    };
+    Ok(FendResult::from_spans(result, is_unit))
+}
+
+/// Returns the error message, suggesting a correction if possible
+fn report_error(error: &error::FendError, input: &str, context: &mut Context) -> String {
+    context.error_kind = Some(match error {
+        error::FendError::Interrupted | error::FendError::TimeLimitExceeded => {
+            ErrorKind::Interrupted
+        }
+        // the input may be too large to lex again
+        error::FendError::MemoryLimitExceeded
+        | error::FendError::RecursionLimitExceeded
+        | error::FendError::OutputTooLong => ErrorKind::Evaluation,
+        error::FendError::ParseError(_) => ErrorKind::Syntax,
+        _ if locate_syntax_error(input, context).is_some() => ErrorKind::Syntax,
+        _ => ErrorKind::Evaluation,
+    });
+    context.correction = suggestions::suggest_correction(error, input, context);
+    match &context.correction {
+        Some(correction) => format!("{}, did you mean '{}'?", error, correction.replacement()),
+        None => error.to_string(),
+    }
+}
+
+/// This function evaluates a string without modifying the given context.
+/// Any changes the evaluation would make (such as variable assignments,
+/// custom units or settings) are returned separately, and can be committed
+/// with [`ContextDelta::apply`].
+///
+/// # Errors
+/// The first element of the returned tuple is an error if the given string
+/// is invalid. This may be due to parser or runtime errors.
+pub fn evaluate_pure(input: &str, context: &Context) -> (Result<FendResult, String>, ContextDelta) {
+    evaluate_pure_with_interrupt(input, context, &interrupt::Never::default())
+}
+
+/// This function evaluates a string using the provided Interrupt object,
+/// without modifying the given context. See [`evaluate_pure`] for details.
+///
+/// # Errors
+/// The first element of the returned tuple is an error if the given string
+/// is invalid. This may be due to parser or runtime errors.
+pub fn evaluate_pure_with_interrupt(
+    input: &str,
+    context: &Context,
+    int: &impl Interrupt,
+) -> (Result<FendResult, String>, ContextDelta) {
+    let mut scratch_context = context.clone();
+    scratch_context.assigned_variables = Some(vec![]);
+    let result = evaluate_with_interrupt(input, &mut scratch_context, int);
+    let mut delta = ContextDelta::default();
+    for name in scratch_context
+        .assigned_variables
+        .take()
+        .unwrap_or_default()
+    {
+        if delta.variables.iter().any(|change| change.name == name) {
+            continue;
+        }
+        if let Some(new_value) = scratch_context.variables.get(&name) {
+            delta.variables.push(VariableChange {
+                old_value: context.variables.get(&name).cloned(),
+                new_value: new_value.clone(),
+                name,
+            });
+        }
+    }
+    if scratch_context.custom_units.len() > context.custom_units.len() {
+        delta.custom_units = scratch_context.custom_units[context.custom_units.len()..].to_vec();
+    }
+    if scratch_context.precision != context.precision {
+        delta.precision = Some((context.precision, scratch_context.precision));
+    }
+    if scratch_context.random_seed != context.random_seed {
+        delta.random_seed = Some((context.random_seed, scratch_context.random_seed));
+    }
+    (result, delta)
+}
+
+/// The tokens of an input string, as returned by [`lex`] and [`relex`].
+///
+/// Frontends that show a live preview while the user is typing can keep
+/// the most recent `LexedInput` around, so that after each edit only the
+/// changed part of the input needs to be lexed again.
+#[derive(Debug, Default)]
+pub struct LexedInput {
+    input: String,
+    tokens: lexer::LexedTokens,
+}
+
+impl LexedInput {
+    /// Returns the input string that was lexed.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+/// Lexes the given input, which can then be evaluated with [`evaluate_lexed`].
+///
+/// Lexing errors are not reported here, but when evaluating the result.
+#[must_use]
+pub fn lex(input: &str, context: &Context) -> LexedInput {
+    LexedInput {
+        input: input.to_string(),
+        tokens: limits::with_memory_limit(context.limits, || {
+            lexer::LexedTokens::new(input, &context.interner, &interrupt::Never::default())
+        }),
+    }
+}
+
+/// Lexes `new_input`, reusing the tokens from the `previous` input that are
+/// unaffected by the edit. Only the part of the input starting shortly
+/// before the first changed character is lexed again, which is much faster
+/// than calling [`lex`] when a long input is edited near its end.
+#[must_use]
+pub fn relex(previous: &LexedInput, new_input: &str, context: &Context) -> LexedInput {
+    LexedInput {
+        input: new_input.to_string(),
+        tokens: limits::with_memory_limit(context.limits, || {
+            previous.tokens.relex(
+                &previous.input,
+                new_input,
+                &context.interner,
+                &interrupt::Never::default(),
+            )
+        }),
+    }
+}
+
+/// This function evaluates an input that has already been lexed with
+/// [`lex`] or [`relex`], and returns the same result as [`evaluate`].
+///
+/// # Errors
+/// It returns an error if the given input is invalid.
+/// This may be due to lexer, parser or runtime errors.
+pub fn evaluate_lexed(input: &LexedInput, context: &mut Context) -> Result<FendResult, String> {
+    evaluate_lexed_with_interrupt(input, context, &interrupt::Never::default())
+}
+
+/// This function evaluates an input that has already been lexed, using the
+/// provided Interrupt object. See [`evaluate_lexed`] for details.
+///
+/// # Errors
+/// It returns an error if the given input is invalid.
+/// This may be due to lexer, parser or runtime errors.
+pub fn evaluate_lexed_with_interrupt(
+    input: &LexedInput,
+    context: &mut Context,
+    int: &impl Interrupt,
+) -> Result<FendResult, String> {
+    context.correction = None;
+    context.error_kind = None;
+    if input.input.is_empty() || input.input.starts_with("!debug ") {
+        return evaluate_with_interrupt(&input.input, context, int);
+    }
+    let tokens = input.tokens.tokens().map_err(|e| {
+        context.error_kind = Some(ErrorKind::Syntax);
+        e.to_string()
+    })?;
+    let (result, is_unit) = match limits::enforce(context, int, |context, int| {
+        eval::evaluate_lexed_to_spans(tokens, context, int)
+    }) {
         Ok(value) => value,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(report_error(&e, &input.input, context)),
     };
-    let mut plain_result = String::new();
-    for s in &result {
-        plain_result.push_str(&s.string);
-    }
-    Ok(FendResult {
-        plain_result,
-        span_result: result,
-        is_unit,
+    Ok(FendResult::from_spans(result, is_unit))
+}
+
+/// Returns the byte range of the part of `input` that causes a lexer or
+/// parser error, e.g. so that it can be underlined. Returns `None` if
+/// there is no such error, or if it can't be located.
+#[must_use]
+pub fn locate_syntax_error(input: &str, context: &Context) -> Option<std::ops::Range<usize>> {
+    let lexed = lex(input, context);
+    eval::locate_syntax_error(&lexed.input, &lexed.tokens)
+}
+
+/// Returns the tokens that `input` is split into and the expression tree
+/// that they are parsed into, one per line, e.g. to include in bug reports
+/// about operator precedence or implicit multiplication. If the input can't
+/// be parsed, the parser error is shown instead of the expression. The
+/// format is meant to be read by humans, and may change.
+///
+/// # Errors
+/// Returns an error if the input can't be split into tokens.
+pub fn debug_parse(input: &str, context: &Context) -> Result<String, String> {
+    limits::with_memory_limit(context.limits, || {
+        eval::debug_parse(input, context, &interrupt::Never::default())
     })
+    .map_err(|e| e.to_string())
+}
+
+/// Returns whether `input` is unfinished because it ends with an operator
+/// like `+` or `to`, or has unclosed parentheses or brackets. Interactive
+/// prompts can use this to keep reading on the next line, so that long
+/// expressions can be split across several lines.
+#[must_use]
+pub fn is_incomplete(input: &str, context: &Context) -> bool {
+    let lexed = lex(input, context);
+    eval::is_incomplete(&lexed.tokens)
+}
+
+/// Splits `input` into spans for syntax highlighting, e.g. to colour the
+/// input while it is being typed. The spans cover the whole input and use
+/// the same kinds as results, like [`SpanKind::Number`] for numbers.
+#[must_use]
+pub fn highlight<'a>(input: &'a str, context: &Context) -> Vec<SpanRef<'a>> {
+    highlight::highlight(input, context)
 }
 
 #[derive(Debug)]
@@ -270,22 +908,138 @@ impl Completion {
     }
 }
 
+/// Returns completions for the last word of `prefix` using only built-in
+/// names, together with the position at which they should be inserted. See
+/// [`get_completions`] to also complete variables and custom units.
 #[must_use]
-pub fn get_completions_for_prefix(mut prefix: &str) -> (usize, Vec<Completion>) {
-    let mut prepend = "";
+pub fn get_completions_for_prefix(prefix: &str) -> (usize, Vec<Completion>) {
+    get_completions(prefix, &Context::new())
+}
+
+/// Returns completions for the last word of `prefix`, e.g. `kilometer` for
+/// `5 kilome`, together with the position at which they should be inserted.
+/// This includes variables, function names and units.
+#[must_use]
+pub fn get_completions(prefix: &str, context: &Context) -> (usize, Vec<Completion>) {
     let position = prefix.len();
-    if let Some((a, b)) = prefix.rsplit_once(' ') {
-        prepend = a;
-        prefix = b;
-    }
-    if prefix.is_empty() {
+    let word_start = prefix
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !c.is_alphanumeric() && c != '_')
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let word = &prefix[word_start..];
+    if word.is_empty() {
         return (0, vec![]);
     }
-    let mut res = units::get_completions_for_prefix(prefix);
-    for c in &mut res {
-        c.display.insert_str(0, prepend);
+    let completions = suggestions::get_completions(word, context)
+        .into_iter()
+        .map(|name| Completion {
+            insert: name[word.len()..].to_string(),
+            display: name,
+        })
+        .collect();
+    (position, completions)
+}
+
+/// A group of units that measure the same kind of quantity, e.g. length.
+/// Any two units in the same category can be converted between each other.
+#[derive(Debug)]
+pub struct UnitCategory {
+    name: &'static str,
+    units: &'static [&'static str],
+}
+
+impl UnitCategory {
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the units in this category, most common first. Each
+    /// of these can be used as-is in an expression (e.g. `km/h`).
+    #[must_use]
+    pub fn units(&self) -> &'static [&'static str] {
+        self.units
+    }
+}
+
+/// Returns the built-in unit categories, for example to
+/// let users pick units from a list.
+#[must_use]
+pub fn get_unit_categories() -> Vec<UnitCategory> {
+    units::get_unit_categories()
+}
+
+/// Returns the names of all units with the given dimension, which can be
+/// a name like `power` or `length` or a unit expression like `km/h`. This
+/// includes custom units defined in the context, and is the same list
+/// that `units of power` returns.
+///
+/// # Errors
+/// Returns an error if the dimension is invalid, or if there are no
+/// units with that dimension.
+pub fn get_units_of_dimension(
+    dimension: &str,
+    context: &mut Context,
+) -> Result<Vec<String>, String> {
+    let int = interrupt::Never::default();
+    let dimension = match value::dimension::Dimension::from_any_name(dimension) {
+        Some(dimension) => dimension,
+        None => eval::evaluate_to_value(dimension, None, context, &int)
+            .and_then(|value| value::dimension::Dimension::of_value(&value, &int))
+            .map_err(|e| e.to_string())?,
+    };
+    units::units_of_dimension(&dimension, context, &int).map_err(|e| e.to_string())
+}
+
+/// Information about a currency supported by fend, e.g. for showing
+/// a currency picker or formatting amounts.
+#[derive(Debug)]
+pub struct CurrencyInfo {
+    code: &'static str,
+    symbol: &'static str,
+    name: &'static str,
+    decimal_places: u8,
+}
+
+impl CurrencyInfo {
+    /// Returns the ISO 4217 currency code (e.g. `JPY`), which can be
+    /// used as a unit in expressions.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Returns the usual symbol for the currency (e.g. `¥`).
+    #[must_use]
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    /// Returns the full name of the currency (e.g. `Japanese yen`).
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns how many decimal places amounts in this currency are
+    /// usually shown with (e.g. 0 for JPY, 2 for USD).
+    #[must_use]
+    pub fn decimal_places(&self) -> u8 {
+        self.decimal_places
     }
-    (position, res)
+}
+
+/// Returns all currencies that fend has exchange rates for.
+#[must_use]
+pub fn get_currencies() -> Vec<CurrencyInfo> {
+    units::get_currencies()
+}
+
+/// Looks up a currency by its ISO 4217 code (case-insensitive).
+#[must_use]
+pub fn get_currency_info(code: &str) -> Option<CurrencyInfo> {
+    units::get_currency_info(code)
 }
 
 const fn get_version_as_str() -> &'static str {
@@ -0,0 +1,112 @@
+use crate::error::{FendError, Interrupt};
+use crate::num::Number;
+use crate::value::Value;
+use std::{cell::Cell, mem, time::Duration, time::Instant};
+
+/// Limits on the resources that a single calculation may use, which make
+/// it safer to evaluate untrusted input (e.g. in bots or on servers). Set
+/// them with [`Context::set_limits`](crate::Context::set_limits). Every
+/// limit is disabled by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum time that a calculation may take. Note that this uses
+    /// [`Instant`], which is not available on some platforms like
+    /// `wasm32-unknown-unknown`.
+    pub max_time: Option<Duration>,
+    /// The approximate maximum size (in bytes) of any single number during
+    /// a calculation (e.g. `10^10^10` needs more than a gigabyte), and of
+    /// all lists created during a calculation combined (e.g. `1..10^9`)
+    pub max_memory: Option<usize>,
+    /// The maximum nesting depth of the input (e.g. parentheses), and of
+    /// the evaluation (e.g. recursive function calls)
+    pub max_recursion: Option<usize>,
+    /// The maximum length (in characters) of the result
+    pub max_output_len: Option<usize>,
+}
+
+thread_local! {
+    // maximum number of 64-bit words in a number during the
+    // current calculation, see `Limits::max_memory`
+    static MAX_NUMBER_WORDS: Cell<Option<usize>> = Cell::new(None);
+    // remaining number of bytes that lists may use during the current
+    // calculation, see `Limits::max_memory`
+    static LIST_BYTES_LEFT: Cell<Option<usize>> = Cell::new(None);
+}
+
+/// Returns an error if a number with the given number of 64-bit words
+/// exceeds the memory limit of the current calculation
+pub(crate) fn check_number_size(words: usize) -> Result<(), FendError> {
+    match MAX_NUMBER_WORDS.with(Cell::get) {
+        Some(max) if words > max => Err(FendError::MemoryLimitExceeded),
+        _ => Ok(()),
+    }
+}
+
+/// Counts `len` more list elements (e.g. from a range like `1..100`)
+/// against the memory limit of the current calculation
+pub(crate) fn allocate_list_elements(len: usize) -> Result<(), FendError> {
+    let size = len.saturating_mul(mem::size_of::<Value>() + mem::size_of::<Number>());
+    LIST_BYTES_LEFT.with(|left| match left.get() {
+        Some(bytes) if size > bytes => Err(FendError::MemoryLimitExceeded),
+        Some(bytes) => {
+            left.set(Some(bytes - size));
+            Ok(())
+        }
+        None => Ok(()),
+    })
+}
+
+/// Interrupts the calculation once the time limit is reached
+pub(crate) struct LimitedInterrupt<'a, I: Interrupt> {
+    int: &'a I,
+    deadline: Option<Instant>,
+}
+
+impl<I: Interrupt> LimitedInterrupt<'_, I> {
+    fn is_past_deadline(&self) -> bool {
+        self.deadline
+            .map_or(false, |deadline| Instant::now() >= deadline)
+    }
+}
+
+impl<I: Interrupt> Interrupt for LimitedInterrupt<'_, I> {
+    fn should_interrupt(&self) -> bool {
+        self.int.should_interrupt() || self.is_past_deadline()
+    }
+}
+
+/// Runs `f` with the memory limit from `limits`, e.g. while lexing input
+/// outside of a calculation, since number literals like `1e1000000` are
+/// computed by the lexer
+pub(crate) fn with_memory_limit<T>(limits: Limits, f: impl FnOnce() -> T) -> T {
+    let previous = MAX_NUMBER_WORDS.with(|max| max.replace(limits.max_memory.map(|m| m / 8)));
+    let previous_list_bytes = LIST_BYTES_LEFT.with(|left| left.replace(limits.max_memory));
+    let result = f();
+    MAX_NUMBER_WORDS.with(|max| max.set(previous));
+    LIST_BYTES_LEFT.with(|left| left.set(previous_list_bytes));
+    result
+}
+
+/// Runs a calculation, enforcing the time and memory limits
+pub(crate) fn enforce<I: Interrupt, T>(
+    context: &mut crate::Context,
+    int: &I,
+    calculation: impl FnOnce(&mut crate::Context, &LimitedInterrupt<'_, I>) -> Result<T, FendError>,
+) -> Result<T, FendError> {
+    let limits = context.limits;
+    let int = LimitedInterrupt {
+        int,
+        // a time limit that is too large to represent is the same as none
+        deadline: limits
+            .max_time
+            .and_then(|max_time| Instant::now().checked_add(max_time)),
+    };
+    context.recursion_depth = 0;
+    let result = with_memory_limit(limits, || calculation(context, &int));
+    match result {
+        Err(FendError::Interrupted) if !int.int.should_interrupt() && int.is_past_deadline() => {
+            Err(FendError::TimeLimitExceeded)
+        }
+        result => result,
+    }
+}
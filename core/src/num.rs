@@ -7,8 +7,13 @@ mod complex;
 mod dist;
 mod exact;
 mod formatting_style;
+pub(crate) mod number_theory;
+pub(crate) mod polynomial;
 mod real;
+pub(crate) mod statistics;
+pub(crate) mod uncertain;
 mod unit;
+pub(crate) mod vector;
 
 pub(crate) use formatting_style::FormattingStyle;
 
@@ -18,6 +23,23 @@ pub(crate) type Number = unit::Value;
 pub(crate) type Base = base::Base;
 pub(crate) type Exact<T> = exact::Exact<T>;
 
+/// The default number of decimal places that approximate results are
+/// computed and displayed to. At this precision, functions like `sin` are
+/// evaluated using `f64`.
+pub(crate) const DEFAULT_PRECISION: usize = 10;
+
+/// The largest precision that can be set with `set precision`
+pub(crate) const MAX_PRECISION: usize = 1000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum RoundingMode {
+    /// round to the nearest integer, with halves rounded away from zero
+    Nearest,
+    Floor,
+    Ceil,
+    Trunc,
+}
+
 #[derive(Debug)]
 pub(crate) enum RangeBound<T> {
     None,
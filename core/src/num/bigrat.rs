@@ -2,9 +2,14 @@ use crate::error::{FendError, Interrupt};
 use crate::format::Format;
 use crate::interrupt::test_int;
 use crate::num::biguint::BigUint;
-use crate::num::{Base, Exact, FormattingStyle, Range, RangeBound};
+use crate::num::number_theory;
+use crate::num::{
+    Base, Exact, FormattingStyle, Range, RangeBound, RoundingMode, DEFAULT_PRECISION,
+};
 use std::{cmp, fmt, hash, ops};
 
+mod series;
+
 mod sign {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub(crate) enum Sign {
@@ -146,55 +151,113 @@ impl BigRat {
         })
     }
 
+    /// Approximates an irrational function, using `f64` at the default
+    /// precision and an arbitrary-precision series otherwise
+    fn approximate_fn<I: Interrupt>(
+        self,
+        precision: usize,
+        f64_fn: fn(f64) -> f64,
+        series_fn: fn(Self, usize, &I) -> Result<Self, FendError>,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if precision > DEFAULT_PRECISION {
+            series::round_result(series_fn(self, precision, int)?, precision, int)
+        } else {
+            Self::from_f64(f64_fn(self.into_f64(int)?), int)
+        }
+    }
+
+    /// Returns an approximation of pi, accurate to the given number of
+    /// decimal places
+    pub(crate) fn pi<I: Interrupt>(precision: usize, int: &I) -> Result<Self, FendError> {
+        if precision > DEFAULT_PRECISION {
+            series::pi(precision, int)
+        } else {
+            let num = Self::from(3_141_592_653_589_793_238);
+            let den = Self::from(1_000_000_000_000_000_000);
+            num.div(&den, int)
+        }
+    }
+
+    /// Returns an approximation of `e^self`, accurate to the given number of
+    /// decimal places
+    pub(crate) fn exp<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::exp, series::exp, int)
+    }
+
     // sin works for all real numbers
-    pub(crate) fn sin<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn sin<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         Ok(if self == 0.into() {
             Exact::new(Self::from(0), true)
         } else {
-            Exact::new(Self::from_f64(f64::sin(self.into_f64(int)?), int)?, false)
+            Exact::new(
+                self.approximate_fn(precision, f64::sin, series::sin, int)?,
+                false,
+            )
+        })
+    }
+
+    // cos is usually computed as sin(pi/2 - x), unless a high precision
+    // is required
+    pub(crate) fn cos<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(if self == 0.into() {
+            Exact::new(Self::from(1), true)
+        } else {
+            Exact::new(
+                self.approximate_fn(precision, f64::cos, series::cos, int)?,
+                false,
+            )
         })
     }
 
     // asin, acos and atan only work for values between -1 and 1
-    pub(crate) fn asin<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn asin<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         let one = Self::from(1);
         if self > one || self < -one {
             return Err(out_of_range(self.fm(int)?, Range::open(-1, 1)));
         }
-        Self::from_f64(f64::asin(self.into_f64(int)?), int)
+        self.approximate_fn(precision, f64::asin, series::asin, int)
     }
 
-    pub(crate) fn acos<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn acos<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         let one = Self::from(1);
         if self > one || self < -one {
             return Err(out_of_range(self.fm(int)?, Range::open(-1, 1)));
         }
-        Self::from_f64(f64::acos(self.into_f64(int)?), int)
+        self.approximate_fn(precision, f64::acos, series::acos, int)
     }
 
     // note that this works for any real number, unlike asin and acos
-    pub(crate) fn atan<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Self::from_f64(f64::atan(self.into_f64(int)?), int)
+    pub(crate) fn atan<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::atan, series::atan, int)
     }
 
-    pub(crate) fn sinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Self::from_f64(f64::sinh(self.into_f64(int)?), int)
+    pub(crate) fn sinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::sinh, series::sinh, int)
     }
 
-    pub(crate) fn cosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Self::from_f64(f64::cosh(self.into_f64(int)?), int)
+    pub(crate) fn cosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::cosh, series::cosh, int)
     }
 
-    pub(crate) fn tanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Self::from_f64(f64::tanh(self.into_f64(int)?), int)
+    pub(crate) fn tanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::tanh, series::tanh, int)
     }
 
-    pub(crate) fn asinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Self::from_f64(f64::asinh(self.into_f64(int)?), int)
+    pub(crate) fn asinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.approximate_fn(precision, f64::asinh, series::asinh, int)
     }
 
     // value must not be less than 1
-    pub(crate) fn acosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn acosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         if self < 1.into() {
             return Err(out_of_range(
                 self.fm(int)?,
@@ -204,20 +267,20 @@ impl BigRat {
                 },
             ));
         }
-        Self::from_f64(f64::acosh(self.into_f64(int)?), int)
+        self.approximate_fn(precision, f64::acosh, series::acosh, int)
     }
 
     // value must be between -1 and 1.
-    pub(crate) fn atanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn atanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         let one: Self = 1.into();
         if self >= one || self <= -one {
             return Err(out_of_range(self.fm(int)?, Range::open(-1, 1)));
         }
-        Self::from_f64(f64::atanh(self.into_f64(int)?), int)
+        self.approximate_fn(precision, f64::atanh, series::atanh, int)
     }
 
     // For all logs: value must be greater than 0
-    pub(crate) fn ln<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn ln<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         if self <= 0.into() {
             return Err(out_of_range(
                 self.fm(int)?,
@@ -227,10 +290,10 @@ impl BigRat {
                 },
             ));
         }
-        Self::from_f64(f64::ln(self.into_f64(int)?), int)
+        self.approximate_fn(precision, f64::ln, series::ln, int)
     }
 
-    pub(crate) fn log2<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn log2<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         if self <= 0.into() {
             return Err(out_of_range(
                 self.fm(int)?,
@@ -240,10 +303,15 @@ impl BigRat {
                 },
             ));
         }
-        Self::from_f64(f64::log2(self.into_f64(int)?), int)
+        self.approximate_fn(
+            precision,
+            f64::log2,
+            |x, p, int| series::log(x, 2, p, int),
+            int,
+        )
     }
 
-    pub(crate) fn log10<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+    pub(crate) fn log10<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
         if self <= 0.into() {
             return Err(out_of_range(
                 self.fm(int)?,
@@ -253,7 +321,12 @@ impl BigRat {
                 },
             ));
         }
-        Self::from_f64(f64::log10(self.into_f64(int)?), int)
+        self.approximate_fn(
+            precision,
+            f64::log10,
+            |x, p, int| series::log(x, 10, p, int),
+            int,
+        )
     }
 
     pub(crate) fn factorial<I: Interrupt>(mut self, int: &I) -> Result<Self, FendError> {
@@ -550,7 +623,7 @@ impl BigRat {
                 MaxDigitsToPrint::DecimalPlaces(dp)
             }
         } else {
-            MaxDigitsToPrint::DecimalPlaces(10)
+            MaxDigitsToPrint::DecimalPlaces(DEFAULT_PRECISION)
         };
         let print_integer_part = |ignore_minus_if_zero: bool| {
             let sign =
@@ -805,6 +878,7 @@ impl BigRat {
     pub(crate) fn pow<I: Interrupt>(
         mut self,
         mut rhs: Self,
+        precision: usize,
         int: &I,
     ) -> Result<Exact<Self>, FendError> {
         self = self.simplify(int)?;
@@ -815,7 +889,7 @@ impl BigRat {
         if rhs.sign == Sign::Negative {
             // a^-b => 1/a^b
             rhs.sign = Sign::Positive;
-            let inverse_res = self.pow(rhs, int)?;
+            let inverse_res = self.pow(rhs, precision, int)?;
             return Ok(Exact::new(
                 Self::from(1).div(&inverse_res.value, int)?,
                 inverse_res.exact,
@@ -840,6 +914,7 @@ impl BigRat {
                     num: rhs.den,
                     den: 1.into(),
                 },
+                precision,
                 int,
             )?)
         }
@@ -858,7 +933,7 @@ impl BigRat {
                 .clone()
                 .add(high_bound.clone(), int)?
                 .div(&2.into(), int)?;
-            if &guess.clone().pow(n.clone(), int)?.value < val {
+            if &guess.clone().pow(n.clone(), DEFAULT_PRECISION, int)?.value < val {
                 low_bound = guess;
             } else {
                 high_bound = guess;
@@ -869,7 +944,12 @@ impl BigRat {
 
     // the boolean indicates whether or not the result is exact
     // n must be an integer
-    pub(crate) fn root_n<I: Interrupt>(self, n: &Self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn root_n<I: Interrupt>(
+        self,
+        n: &Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         if self.num != 0.into() && self.sign == Sign::Negative {
             return Err(FendError::RootsOfNegativeNumbers);
         }
@@ -893,6 +973,11 @@ impl BigRat {
                 true,
             ));
         }
+        if precision > DEFAULT_PRECISION {
+            let root = series::root_n(self, n, precision, int)?;
+            let root = series::round_result(root, precision, int)?;
+            return Ok(Exact::new(root, false));
+        }
         // TODO check in which cases this might still be exact
         let num_rat = if num.exact {
             Self::from(num.value)
@@ -917,6 +1002,61 @@ impl BigRat {
         Ok(Exact::new(num_rat.div(&den_rat, int)?, false))
     }
 
+    /// Writes the square root of this number as `a√b`, where `a` is rational
+    /// and `b` is a square-free integer. Returns `None` if the number is
+    /// negative, or too large to factorise quickly.
+    pub(crate) fn sqrt_as_radical<I: Interrupt>(
+        self,
+        int: &I,
+    ) -> Result<Option<(Self, u64)>, FendError> {
+        const MAX_RADICAND: u64 = 1_000_000_000_000;
+        let x = self.simplify(int)?;
+        if x.num == 0.into() {
+            return Ok(Some((x, 1)));
+        }
+        if x.sign == Sign::Negative {
+            return Ok(None);
+        }
+        // sqrt(a/b) == sqrt(ab)/b
+        let product = match x.num.mul(&x.den, int)?.try_as_usize(int) {
+            Ok(product) => match u64::try_from(product) {
+                Ok(product) if product <= MAX_RADICAND => product,
+                _ => return Ok(None),
+            },
+            Err(_) => return Ok(None),
+        };
+        let (square_root, square_free) = number_theory::square_free_decomposition(product, int)?;
+        Ok(Some((
+            Self::from(square_root).div(&Self::from(x.den), int)?,
+            square_free,
+        )))
+    }
+
+    /// Computes `self^rhs` as `a√b` (see `sqrt_as_radical`), if `rhs` is
+    /// an integer or half an integer
+    pub(crate) fn pow_as_radical<I: Interrupt>(
+        self,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Option<(Self, u64)>, FendError> {
+        let rhs = rhs.simplify(int)?;
+        if rhs.den == 1.into() {
+            return Ok(Some((self.pow(rhs, DEFAULT_PRECISION, int)?.value, 1)));
+        }
+        if rhs.den != 2.into() {
+            return Ok(None);
+        }
+        // x^(n/2) == sqrt(x^n)
+        let exponent = Self {
+            sign: rhs.sign,
+            num: rhs.num,
+            den: 1.into(),
+        };
+        self.pow(exponent, DEFAULT_PRECISION, int)?
+            .value
+            .sqrt_as_radical(int)
+    }
+
     pub(crate) fn mul<I: Interrupt>(self, rhs: &Self, int: &I) -> Result<Self, FendError> {
         Ok(Self {
             sign: Sign::sign_of_product(self.sign, rhs.sign),
@@ -929,6 +1069,35 @@ impl BigRat {
         self.add_internal(rhs, int)
     }
 
+    pub(crate) fn round<I: Interrupt>(
+        self,
+        mode: RoundingMode,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let (quotient, remainder) = self.num.divmod(&self.den, int)?;
+        let round_away_from_zero = remainder != 0.into()
+            && match mode {
+                RoundingMode::Nearest => remainder.clone().add(&remainder) >= self.den,
+                RoundingMode::Floor => self.sign == Sign::Negative,
+                RoundingMode::Ceil => self.sign == Sign::Positive,
+                RoundingMode::Trunc => false,
+            };
+        let num = if round_away_from_zero {
+            quotient.add(&1.into())
+        } else {
+            quotient
+        };
+        Ok(Self {
+            sign: if num == 0.into() {
+                Sign::Positive
+            } else {
+                self.sign
+            },
+            num,
+            den: 1.into(),
+        })
+    }
+
     pub(crate) fn is_definitely_zero(&self) -> bool {
         self.num.is_definitely_zero()
     }
@@ -989,12 +1158,25 @@ impl From<BigUint> for BigRat {
     }
 }
 
-#[derive(Default)]
 pub(crate) struct FormatOptions {
     pub(crate) base: Base,
     pub(crate) style: FormattingStyle,
     pub(crate) term: &'static str,
     pub(crate) use_parens_if_fraction: bool,
+    // number of decimal places for approximate numbers
+    pub(crate) precision: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            base: Base::default(),
+            style: FormattingStyle::default(),
+            term: "",
+            use_parens_if_fraction: false,
+            precision: DEFAULT_PRECISION,
+        }
+    }
 }
 
 impl Format for BigRat {
@@ -1057,7 +1239,13 @@ impl Format for BigRat {
             return x.format_as_fraction(base, sign, term, mixed, use_parens_if_fraction, int);
         }
 
-        // not a fraction, will be printed as a decimal
+        // not a fraction, will be printed as a decimal, with non-terminating
+        // numbers shown to the working precision
+        let style = if style == FormattingStyle::Auto && !terminating()? {
+            FormattingStyle::DecimalPlaces(params.precision)
+        } else {
+            style
+        };
         x.format_as_decimal(style, base, sign, term, terminating, int)
     }
 }
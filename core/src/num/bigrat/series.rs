@@ -0,0 +1,433 @@
+//! Arbitrary-precision approximations of irrational functions, used when
+//! the working precision is higher than `f64` can provide. Intermediate
+//! results are fixed-point numbers with a denominator of `10^digits`.
+
+use super::sign::Sign;
+use super::BigRat;
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::biguint::BigUint;
+use crate::num::RoundingMode;
+
+// extra digits used for intermediate results, so that rounding errors
+// don't affect the requested number of decimal places
+const GUARD_DIGITS: usize = 10;
+
+fn power_of_ten<I: Interrupt>(exponent: usize, int: &I) -> Result<BigUint, FendError> {
+    let mut result = BigUint::from(1);
+    for _ in 0..exponent {
+        result = result.mul(&10.into(), int)?;
+    }
+    Ok(result)
+}
+
+struct FixedPoint {
+    scale: BigUint,
+}
+
+impl FixedPoint {
+    fn new<I: Interrupt>(precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self {
+            scale: power_of_ten(precision + GUARD_DIGITS, int)?,
+        })
+    }
+
+    /// Rounds `x` towards zero to a multiple of `1 / scale`
+    fn fix<I: Interrupt>(&self, x: BigRat, int: &I) -> Result<BigRat, FendError> {
+        if x.den == self.scale {
+            return Ok(x);
+        }
+        let (num, _) = x.num.mul(&self.scale, int)?.divmod(&x.den, int)?;
+        Ok(BigRat {
+            sign: x.sign,
+            num,
+            den: self.scale.clone(),
+        })
+    }
+
+    fn int<I: Interrupt>(&self, n: u64, int: &I) -> Result<BigRat, FendError> {
+        self.fix(n.into(), int)
+    }
+
+    fn mul<I: Interrupt>(&self, a: &BigRat, b: &BigRat, int: &I) -> Result<BigRat, FendError> {
+        self.fix(a.clone().mul(b, int)?, int)
+    }
+
+    fn div<I: Interrupt>(&self, a: BigRat, b: &BigRat, int: &I) -> Result<BigRat, FendError> {
+        self.fix(a.div(b, int)?, int)
+    }
+
+    /// Divides a fixed-point number by a small integer
+    fn div_int<I: Interrupt>(&self, a: BigRat, n: u64, int: &I) -> Result<BigRat, FendError> {
+        let a = self.fix(a, int)?;
+        let (num, _) = a.num.divmod(&n.into(), int)?;
+        Ok(BigRat {
+            sign: a.sign,
+            num,
+            den: a.den,
+        })
+    }
+}
+
+/// Rounds a result to fewer digits than were computed, so that small errors
+/// don't show up in the displayed digits, e.g. `cos(pi/3)` is `0.5` rather
+/// than `0.4999...`
+pub(super) fn round_result<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let scale = BigRat::from(power_of_ten(precision + GUARD_DIGITS / 2, int)?);
+    x.mul(&scale, int)?
+        .round(RoundingMode::Nearest, int)?
+        .div(&scale, int)
+}
+
+/// Returns the number of decimal digits before the decimal point of `x`
+fn integer_digits<I: Interrupt>(x: &BigRat, int: &I) -> Result<usize, FendError> {
+    let integer_part = x.clone().round(RoundingMode::Trunc, int)?.num;
+    let mut digits = 1;
+    let mut power = BigUint::from(10);
+    while power <= integer_part {
+        test_int(int)?;
+        power = power.mul(&10.into(), int)?;
+        digits += 1;
+    }
+    Ok(digits)
+}
+
+/// Evaluates `sum(x^(2n+1) / (2n+1))`, alternating the sign of each term
+/// if `alternating` is true. This is `atan(x)` or `atanh(x)` respectively,
+/// and converges quickly for small `x`.
+fn odd_power_series<I: Interrupt>(
+    x: &BigRat,
+    alternating: bool,
+    fp: &FixedPoint,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let x = fp.fix(x.clone(), int)?;
+    let x_squared = fp.mul(&x, &x, int)?;
+    let mut power = x.clone();
+    let mut sum = x;
+    let mut n = 1;
+    loop {
+        test_int(int)?;
+        power = fp.mul(&power, &x_squared, int)?;
+        if power.is_definitely_zero() {
+            return Ok(sum);
+        }
+        let term = fp.div_int(power.clone(), 2 * n + 1, int)?;
+        sum = if alternating && n % 2 == 1 {
+            sum.add(-term, int)?
+        } else {
+            sum.add(term, int)?
+        };
+        n += 1;
+    }
+}
+
+pub(super) fn pi<I: Interrupt>(precision: usize, int: &I) -> Result<BigRat, FendError> {
+    // Machin's formula: pi = 16 atan(1/5) - 4 atan(1/239)
+    let fp = FixedPoint::new(precision, int)?;
+    let a = odd_power_series(&BigRat::from(1).div(&5.into(), int)?, true, &fp, int)?;
+    let b = odd_power_series(&BigRat::from(1).div(&239.into(), int)?, true, &fp, int)?;
+    a.mul(&16.into(), int)?.add(-b.mul(&4.into(), int)?, int)
+}
+
+fn ln_2<I: Interrupt>(fp: &FixedPoint, int: &I) -> Result<BigRat, FendError> {
+    // ln 2 = 2 atanh(1/3)
+    let third = BigRat::from(1).div(&3.into(), int)?;
+    odd_power_series(&third, false, fp, int)?.mul(&2.into(), int)
+}
+
+pub(super) fn exp<I: Interrupt>(x: BigRat, precision: usize, int: &I) -> Result<BigRat, FendError> {
+    if x.sign == Sign::Negative && !x.is_definitely_zero() {
+        let fp = FixedPoint::new(precision, int)?;
+        let inverse = exp(-x, precision + GUARD_DIGITS, int)?;
+        return fp.div(1.into(), &inverse, int);
+    }
+    // the result has about `x / 2` digits before the decimal point, which
+    // need to be computed in addition to the requested decimal places
+    let extra_digits = integer_digits(&x.clone().div(&2.into(), int)?, int)?;
+    // reduce the argument to at most 1/2, then square the result as many
+    // times as the argument was halved
+    let half = BigRat::from(1).div(&2.into(), int)?;
+    let mut x = x;
+    let mut halvings = 0;
+    while x > half {
+        test_int(int)?;
+        x = x.div(&2.into(), int)?;
+        halvings += 1;
+    }
+    let fp = FixedPoint::new(precision + extra_digits + halvings, int)?;
+    let x = fp.fix(x, int)?;
+    let mut term = fp.int(1, int)?;
+    let mut sum = term.clone();
+    let mut n = 1;
+    loop {
+        test_int(int)?;
+        term = fp.div_int(fp.mul(&term, &x, int)?, n, int)?;
+        if term.is_definitely_zero() {
+            break;
+        }
+        sum = sum.add(term.clone(), int)?;
+        n += 1;
+    }
+    for _ in 0..halvings {
+        test_int(int)?;
+        sum = fp.mul(&sum, &sum, int)?;
+    }
+    Ok(sum)
+}
+
+/// `x` must be positive
+pub(super) fn ln<I: Interrupt>(x: BigRat, precision: usize, int: &I) -> Result<BigRat, FendError> {
+    // write x as m * 2^k with 1 <= m < 2, so that ln x = ln m + k ln 2
+    let one = BigRat::from(1);
+    let two = BigRat::from(2);
+    let mut m = x;
+    let mut k: i64 = 0;
+    while m >= two {
+        test_int(int)?;
+        m = m.div(&two, int)?;
+        k += 1;
+    }
+    while m < one {
+        test_int(int)?;
+        m = m.mul(&two, int)?;
+        k -= 1;
+    }
+    let fp = FixedPoint::new(precision, int)?;
+    // ln m = 2 atanh((m - 1) / (m + 1))
+    let ratio = m
+        .clone()
+        .add(-one.clone(), int)?
+        .div(&m.add(one, int)?, int)?;
+    let ln_m = odd_power_series(&ratio, false, &fp, int)?.mul(&2.into(), int)?;
+    let k_ln_2 = ln_2(&fp, int)?.mul(&k.unsigned_abs().into(), int)?;
+    if k < 0 {
+        ln_m.add(-k_ln_2, int)
+    } else {
+        ln_m.add(k_ln_2, int)
+    }
+}
+
+/// `x` must not be negative
+pub(super) fn root_n<I: Interrupt>(
+    x: BigRat,
+    n: &BigUint,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    if x.is_definitely_zero() {
+        return Ok(x);
+    }
+    let fp = FixedPoint::new(precision, int)?;
+    let ln_x = ln(x, precision + GUARD_DIGITS, int)?;
+    let ln_root = ln_x.div(&n.clone().into(), int)?;
+    let result = exp(ln_root, precision + GUARD_DIGITS, int)?;
+    fp.fix(result, int)
+}
+
+/// Evaluates `sin(x)` if `offset` is 1, or `cos(x)` if `offset` is 0
+fn sin_or_cos<I: Interrupt>(
+    x: BigRat,
+    offset: u64,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    // reduce the argument to the range [-pi, pi], which requires pi
+    // to be accurate to as many digits as the integer part of x
+    let extra_digits = integer_digits(&x, int)?;
+    let fp = FixedPoint::new(precision + extra_digits, int)?;
+    let two_pi = pi(precision + extra_digits, int)?.mul(&2.into(), int)?;
+    let turns = x
+        .clone()
+        .div(&two_pi, int)?
+        .round(RoundingMode::Nearest, int)?;
+    let x = fp.fix(x.add(-turns.mul(&two_pi, int)?, int)?, int)?;
+    let x_squared = fp.mul(&x, &x, int)?;
+    let mut term = if offset == 1 { x } else { fp.int(1, int)? };
+    let mut sum = term.clone();
+    let mut n = 1;
+    loop {
+        test_int(int)?;
+        term = -fp.mul(&term, &x_squared, int)?;
+        term = fp.div_int(term, (2 * n - 1 + offset) * (2 * n + offset), int)?;
+        if term.is_definitely_zero() {
+            return Ok(sum);
+        }
+        sum = sum.add(term.clone(), int)?;
+        n += 1;
+    }
+}
+
+pub(super) fn sin<I: Interrupt>(x: BigRat, precision: usize, int: &I) -> Result<BigRat, FendError> {
+    sin_or_cos(x, 1, precision, int)
+}
+
+pub(super) fn cos<I: Interrupt>(x: BigRat, precision: usize, int: &I) -> Result<BigRat, FendError> {
+    sin_or_cos(x, 0, precision, int)
+}
+
+pub(super) fn atan<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let one = BigRat::from(1);
+    if x > one || x < -one.clone() {
+        // atan(x) = ±pi/2 - atan(1/x)
+        let half_pi = pi(precision, int)?.div(&2.into(), int)?;
+        let inverse = atan(one.div(&x, int)?, precision, int)?;
+        return if x.sign == Sign::Negative {
+            (-half_pi).add(-inverse, int)
+        } else {
+            half_pi.add(-inverse, int)
+        };
+    }
+    // halve the argument twice using atan(x) = 2 atan(x / (1 + sqrt(1 + x^2))),
+    // so that the series converges quickly
+    let fp = FixedPoint::new(precision, int)?;
+    let mut x = x;
+    for _ in 0..2 {
+        let x_squared = x.clone().mul(&x, int)?;
+        let root = root_n(one.clone().add(x_squared, int)?, &2.into(), precision, int)?;
+        x = fp.div(x, &one.clone().add(root, int)?, int)?;
+    }
+    odd_power_series(&x, true, &fp, int)?.mul(&4.into(), int)
+}
+
+/// `x` must be between -1 and 1
+pub(super) fn asin<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let one = BigRat::from(1);
+    if x == one || x == -one.clone() {
+        let half_pi = pi(precision, int)?.div(&2.into(), int)?;
+        return Ok(if x.sign == Sign::Negative {
+            -half_pi
+        } else {
+            half_pi
+        });
+    }
+    // asin(x) = atan(x / sqrt(1 - x^2))
+    let x_squared = x.clone().mul(&x, int)?;
+    let root = root_n(
+        one.add(-x_squared, int)?,
+        &2.into(),
+        precision + GUARD_DIGITS,
+        int,
+    )?;
+    atan(x.div(&root, int)?, precision, int)
+}
+
+/// `x` must be between -1 and 1
+pub(super) fn acos<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let half_pi = pi(precision, int)?.div(&2.into(), int)?;
+    half_pi.add(-asin(x, precision, int)?, int)
+}
+
+pub(super) fn sinh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let fp = FixedPoint::new(precision, int)?;
+    let e_x = exp(x, precision + GUARD_DIGITS, int)?;
+    let inverse = fp.div(1.into(), &e_x, int)?;
+    fp.div_int(e_x.add(-inverse, int)?, 2, int)
+}
+
+pub(super) fn cosh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let fp = FixedPoint::new(precision, int)?;
+    let e_x = exp(x, precision + GUARD_DIGITS, int)?;
+    let inverse = fp.div(1.into(), &e_x, int)?;
+    fp.div_int(e_x.add(inverse, int)?, 2, int)
+}
+
+pub(super) fn tanh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    // tanh(x) = (e^2x - 1) / (e^2x + 1)
+    let fp = FixedPoint::new(precision, int)?;
+    let e_2x = exp(x.mul(&2.into(), int)?, precision + GUARD_DIGITS, int)?;
+    let num = e_2x.clone().add(-BigRat::from(1), int)?;
+    fp.div(num, &e_2x.add(1.into(), int)?, int)
+}
+
+pub(super) fn asinh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    // asinh(x) = ln(x + sqrt(x^2 + 1)), and asinh(-x) = -asinh(x)
+    if x.sign == Sign::Negative {
+        return Ok(-asinh(-x, precision, int)?);
+    }
+    let x_squared = x.clone().mul(&x, int)?;
+    let root = root_n(
+        x_squared.add(1.into(), int)?,
+        &2.into(),
+        precision + GUARD_DIGITS,
+        int,
+    )?;
+    ln(x.add(root, int)?, precision, int)
+}
+
+/// `x` must be at least 1
+pub(super) fn acosh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    // acosh(x) = ln(x + sqrt(x^2 - 1))
+    let x_squared = x.clone().mul(&x, int)?;
+    let root = root_n(
+        x_squared.add(-BigRat::from(1), int)?,
+        &2.into(),
+        precision + GUARD_DIGITS,
+        int,
+    )?;
+    ln(x.add(root, int)?, precision, int)
+}
+
+/// `x` must be between -1 and 1 (exclusive)
+pub(super) fn atanh<I: Interrupt>(
+    x: BigRat,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    // atanh(x) = ln((1 + x) / (1 - x)) / 2
+    let one = BigRat::from(1);
+    let ratio = one
+        .clone()
+        .add(x.clone(), int)?
+        .div(&one.add(-x, int)?, int)?;
+    ln(ratio, precision + GUARD_DIGITS, int)?.div(&2.into(), int)
+}
+
+/// Computes `ln(x) / ln(base)`. `x` must be positive.
+pub(super) fn log<I: Interrupt>(
+    x: BigRat,
+    base: u64,
+    precision: usize,
+    int: &I,
+) -> Result<BigRat, FendError> {
+    let fp = FixedPoint::new(precision, int)?;
+    let ln_x = ln(x, precision + GUARD_DIGITS, int)?;
+    let ln_base = ln(base.into(), precision + GUARD_DIGITS, int)?;
+    fp.div(ln_x, &ln_base, int)
+}
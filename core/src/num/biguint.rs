@@ -221,7 +221,10 @@ impl BigUint {
                 result = result.mul(&base, int)?;
             }
             exponent >>= 1;
-            base = base.clone().mul(&base, int)?;
+            // skip the final squaring, since its result would be unused
+            if exponent > 0 {
+                base = base.clone().mul(&base, int)?;
+            }
         }
         Ok(result)
     }
@@ -326,6 +329,7 @@ impl BigUint {
             *self = Self::from(0);
             return Ok(());
         }
+        crate::limits::check_number_size(self.value_len() + other.value_len())?;
         let self_clone = self.clone();
         self.make_large();
         match self {
@@ -358,14 +362,32 @@ impl BigUint {
     }
 
     // Note: 0! = 1, 1! = 1
-    pub(crate) fn factorial<I: Interrupt>(mut self, int: &I) -> Result<Self, FendError> {
-        let mut res = Self::from(1);
-        while self > 1.into() {
-            test_int(int)?;
-            res = res.mul(&self, int)?;
-            self = self.sub(&1.into());
+    pub(crate) fn factorial<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        let n = u64::try_from(self.try_as_usize(int)?).unwrap_or(u64::MAX);
+        Self::product_range(2, n, int)
+    }
+
+    /// Computes the product of all integers from `low` to `high` (inclusive).
+    /// The range is split in half recursively, so that most multiplications
+    /// are between numbers of similar size.
+    pub(crate) fn product_range<I: Interrupt>(
+        low: u64,
+        high: u64,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        test_int(int)?;
+        if high < low {
+            return Ok(Self::from(1));
+        }
+        if high - low < 16 {
+            let mut res = Self::from(1);
+            for factor in low..=high {
+                res = res.mul(&Self::from(factor), int)?;
+            }
+            return Ok(res);
         }
-        Ok(res)
+        let mid = low + (high - low) / 2;
+        Self::product_range(low, mid, int)?.mul(&Self::product_range(mid + 1, high, int)?, int)
     }
 
     pub(crate) fn mul<I: Interrupt>(mut self, other: &Self, int: &I) -> Result<Self, FendError> {
@@ -705,6 +727,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_product_range() -> Res {
+        let int = &crate::interrupt::Never::default();
+        assert_eq!(BigUint::product_range(5, 4, int)?, BigUint::from(1));
+        assert_eq!(BigUint::product_range(3, 3, int)?, BigUint::from(3));
+        assert_eq!(BigUint::product_range(99, 100, int)?, BigUint::from(9900));
+        // large enough to be split in half
+        assert_eq!(
+            BigUint::product_range(1, 20, int)?,
+            BigUint::from(2_432_902_008_176_640_000)
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_cmp() {
         assert_eq!(BigUint::from(0), BigUint::from(0));
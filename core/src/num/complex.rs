@@ -1,7 +1,7 @@
 use crate::error::{FendError, Interrupt};
 use crate::num::real::{self, Real};
 use crate::num::Exact;
-use crate::num::{Base, FormattingStyle};
+use crate::num::{Base, FormattingStyle, RoundingMode, DEFAULT_PRECISION};
 use std::cmp::Ordering;
 use std::fmt;
 use std::ops::Neg;
@@ -54,11 +54,19 @@ impl Complex {
         })
     }
 
-    pub(crate) fn pow<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Exact<Self>, FendError> {
-        if self.imag != 0.into() || rhs.imag != 0.into() {
+    pub(crate) fn pow<I: Interrupt>(
+        self,
+        rhs: Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        if self.imag != 0.into() {
             return Err(FendError::ExpComplex);
         }
-        let real = self.real.pow(rhs.real, int)?;
+        if rhs.imag != 0.into() {
+            return self.pow_complex_exponent(rhs, precision, int);
+        }
+        let real = self.real.pow(rhs.real, precision, int)?;
         Ok(Exact::new(
             Self {
                 real: real.value,
@@ -68,6 +76,38 @@ impl Complex {
         ))
     }
 
+    /// Raises a positive real number to a complex power, using
+    /// `a^(x + iy) = a^x * (cos(y ln a) + i sin(y ln a))`
+    fn pow_complex_exponent<I: Interrupt>(
+        self,
+        rhs: Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        if self.real <= 0.into() {
+            return Err(FendError::ExpComplex);
+        }
+        let magnitude = self
+            .real
+            .clone()
+            .pow(rhs.real, precision, int)?
+            .apply(Self::from);
+        let angle = Exact::new(self.real.ln(precision, int)?, false)
+            .mul(Exact::new(&rhs.imag, true), int)?;
+        let angle = Self::from(angle.value);
+        let rotation = angle.clone().cos(precision, int)?.add(
+            angle
+                .sin(precision, int)?
+                .mul(&Exact::new(Self::i(), true), int)?,
+            int,
+        )?;
+        Ok(magnitude.mul(&rotation, int)?.combine(false))
+    }
+
+    pub(crate) fn is_real(&self) -> bool {
+        self.imag == 0.into()
+    }
+
     pub(crate) fn i() -> Self {
         Self {
             real: 0.into(),
@@ -82,6 +122,22 @@ impl Complex {
         }
     }
 
+    pub(crate) fn is_symbolic(&self) -> bool {
+        self.real.is_symbolic() || self.imag.is_symbolic()
+    }
+
+    /// Replaces multiples of pi with a rational approximation
+    pub(crate) fn approximate_pi<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Ok(Self {
+            real: self.real.approximate_pi(precision, int)?,
+            imag: self.imag.approximate_pi(precision, int)?,
+        })
+    }
+
     pub(crate) fn abs<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
         Ok(if self.imag.is_zero() {
             if self.real < 0.into() {
@@ -114,28 +170,60 @@ impl Complex {
                 )
             }
         } else {
-            let power = self.real.pow(2.into(), int)?;
-            let power2 = self.imag.pow(2.into(), int)?;
+            let power = self.real.pow(2.into(), DEFAULT_PRECISION, int)?;
+            let power2 = self.imag.pow(2.into(), DEFAULT_PRECISION, int)?;
             let real = power.add(power2, int)?;
             let res_squared = Self {
                 real: real.value,
                 imag: 0.into(),
             };
-            let result = res_squared.root_n(&Self::from(2), int)?;
+            let result = res_squared.root_n(&Self::from(2), DEFAULT_PRECISION, int)?;
             result.combine(real.exact)
         })
     }
 
+    /// Returns the argument (or phase) of the number in radians, between
+    /// -pi (exclusive) and pi (inclusive)
+    pub(crate) fn arg<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
+        let zero = Real::from(0);
+        let pi = Exact::new(Real::pi(), true);
+        let half_pi = pi.clone().div(&Exact::new(2.into(), true), int)?;
+        let real_sign = self.real.cmp(&zero);
+        let imag_sign = self.imag.cmp(&zero);
+        let arg = match (real_sign, imag_sign) {
+            (Ordering::Equal, Ordering::Equal) => return Err(FendError::ArgumentOfZero),
+            (Ordering::Greater, Ordering::Equal) => Exact::new(zero, true),
+            (Ordering::Less, Ordering::Equal) => pi,
+            (Ordering::Equal, Ordering::Greater) => half_pi,
+            (Ordering::Equal, Ordering::Less) => -half_pi,
+            _ => {
+                let ratio = Exact::new(self.imag, true).div(&Exact::new(self.real, true), int)?;
+                // e.g. atan(±1) == ±pi/4 is kept exact
+                let atan = ratio
+                    .value
+                    .atan(DEFAULT_PRECISION, int)?
+                    .combine(ratio.exact);
+                match (real_sign, imag_sign) {
+                    (Ordering::Greater, _) => atan,
+                    (_, Ordering::Greater) => atan.add(pi, int)?,
+                    _ => atan.add(-pi, int)?,
+                }
+            }
+        };
+        Ok(arg.apply(Self::from))
+    }
+
     pub(crate) fn format<I: Interrupt>(
         &self,
         exact: bool,
         style: FormattingStyle,
         base: Base,
         use_parentheses: UseParentheses,
+        precision: usize,
         int: &I,
     ) -> Result<Exact<Formatted>, FendError> {
         let style = if !exact && style == FormattingStyle::Auto {
-            FormattingStyle::DecimalPlaces(10)
+            FormattingStyle::DecimalPlaces(precision)
         } else if self.imag != 0.into() && style == FormattingStyle::Auto {
             FormattingStyle::Exact
         } else {
@@ -144,7 +232,9 @@ impl Complex {
 
         if self.imag.is_zero() {
             let use_parens = use_parentheses == UseParentheses::IfComplexOrFraction;
-            let x = self.real.format(base, style, false, use_parens, int)?;
+            let x = self
+                .real
+                .format(base, style, false, use_parens, precision, int)?;
             return Ok(Exact::new(
                 Formatted {
                     first_component: x.value,
@@ -158,7 +248,9 @@ impl Complex {
 
         Ok(if self.real.is_zero() {
             let use_parens = use_parentheses == UseParentheses::IfComplexOrFraction;
-            let x = self.imag.format(base, style, true, use_parens, int)?;
+            let x = self
+                .imag
+                .format(base, style, true, use_parens, precision, int)?;
             Exact::new(
                 Formatted {
                     first_component: x.value,
@@ -170,14 +262,19 @@ impl Complex {
             )
         } else {
             let mut exact = exact;
-            let real_part = self.real.format(base, style, false, false, int)?;
+            let real_part = self
+                .real
+                .format(base, style, false, false, precision, int)?;
             exact = exact && real_part.exact;
             let (positive, imag_part) = if self.imag > 0.into() {
-                (true, self.imag.format(base, style, true, false, int)?)
+                (
+                    true,
+                    self.imag.format(base, style, true, false, precision, int)?,
+                )
             } else {
                 (
                     false,
-                    (-self.imag.clone()).format(base, style, true, false, int)?,
+                    (-self.imag.clone()).format(base, style, true, false, precision, int)?,
                 )
             };
             exact = exact && imag_part.exact;
@@ -195,11 +292,16 @@ impl Complex {
         })
     }
 
-    pub(crate) fn root_n<I: Interrupt>(self, n: &Self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn root_n<I: Interrupt>(
+        self,
+        n: &Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         if self.imag != 0.into() || n.imag != 0.into() {
             return Err(FendError::RootsComplex);
         }
-        let real_root = self.real.root_n(&n.real, int)?;
+        let real_root = self.real.root_n(&n.real, precision, int)?;
         Ok(Exact::new(
             Self {
                 real: real_root.value,
@@ -217,81 +319,111 @@ impl Complex {
         }
     }
 
-    pub(crate) fn sin<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
-        Ok(self.expect_real()?.sin(int)?.apply(Self::from))
+    pub(crate) fn compare(&self, other: &Self) -> Result<Ordering, FendError> {
+        if !self.imag.is_zero() || !other.imag.is_zero() {
+            return Err(FendError::ExpectedARealNumber);
+        }
+        Ok(self.real.cmp(&other.real))
     }
 
-    pub(crate) fn cos<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
-        // cos(self) == sin(pi/2 - self)
-        let pi = Exact::new(Self::pi(), true);
-        let half_pi = pi.div(Exact::new(2.into(), true), int)?;
-        let sin_arg = half_pi.add(-Exact::new(self, true), int)?;
-        Ok(sin_arg
-            .value
-            .expect_real()?
-            .sin(int)?
-            .combine(sin_arg.exact)
-            .apply(Self::from))
+    pub(crate) fn sin<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(self.expect_real()?.sin(precision, int)?.apply(Self::from))
     }
 
-    pub(crate) fn tan<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
-        let num = self.clone().sin(int)?;
-        let den = self.cos(int)?;
+    pub(crate) fn cos<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(self.expect_real()?.cos(precision, int)?.apply(Self::from))
+    }
+
+    pub(crate) fn tan<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        let num = self.clone().sin(precision, int)?;
+        let den = self.cos(precision, int)?;
         num.div(den, int)
     }
 
-    pub(crate) fn asin<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.asin(int)?))
+    pub(crate) fn asin<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(self.expect_real()?.asin(precision, int)?.apply(Self::from))
     }
 
-    pub(crate) fn acos<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.acos(int)?))
+    pub(crate) fn acos<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(self.expect_real()?.acos(precision, int)?.apply(Self::from))
     }
 
-    pub(crate) fn atan<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.atan(int)?))
+    pub(crate) fn atan<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        Ok(self.expect_real()?.atan(precision, int)?.apply(Self::from))
     }
 
-    pub(crate) fn sinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.sinh(int)?))
+    pub(crate) fn sinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.sinh(precision, int)?))
     }
 
-    pub(crate) fn cosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.cosh(int)?))
+    pub(crate) fn cosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.cosh(precision, int)?))
     }
 
-    pub(crate) fn tanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.tanh(int)?))
+    pub(crate) fn tanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.tanh(precision, int)?))
     }
 
-    pub(crate) fn asinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.asinh(int)?))
+    pub(crate) fn asinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.asinh(precision, int)?))
     }
 
-    pub(crate) fn acosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.acosh(int)?))
+    pub(crate) fn acosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.acosh(precision, int)?))
     }
 
-    pub(crate) fn atanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.atanh(int)?))
+    pub(crate) fn atanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.atanh(precision, int)?))
     }
 
-    pub(crate) fn ln<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.ln(int)?))
+    pub(crate) fn ln<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.ln(precision, int)?))
     }
 
-    pub(crate) fn log2<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.log2(int)?))
+    pub(crate) fn log2<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.log2(precision, int)?))
     }
 
-    pub(crate) fn log10<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.expect_real()?.log10(int)?))
+    pub(crate) fn log10<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.log10(precision, int)?))
     }
 
     pub(crate) fn is_definitely_one(&self) -> bool {
         self.real.is_definitely_one() && self.imag.is_definitely_zero()
     }
 
+    pub(crate) fn round<I: Interrupt>(
+        self,
+        mode: RoundingMode,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Ok(Self::from(self.expect_real()?.round(mode, int)?))
+    }
+
     pub(crate) fn modulo<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
         Ok(Self::from(
             self.expect_real()?.modulo(rhs.expect_real()?, int)?,
@@ -66,13 +66,13 @@ impl Dist {
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     pub(crate) fn sample<I: Interrupt>(
         self,
-        ctx: &crate::Context,
+        ctx: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
         if self.parts.len() == 1 {
             return Ok(self);
         }
-        let mut random = ctx.random_u32.ok_or(FendError::RandomNumbersNotAvailable)?();
+        let mut random = ctx.next_random_u32()?;
         let mut res = None;
         for (k, v) in self.parts {
             random = random.saturating_sub((v.into_f64(int)? * f64::from(u32::MAX)) as u32);
@@ -105,6 +105,7 @@ impl Dist {
                 style,
                 base,
                 use_parentheses,
+                ctx.precision,
                 int,
             )?;
             write!(out, "{}", res.value)?;
@@ -128,7 +129,7 @@ impl Dist {
             let mut first = true;
             for (num, _prob, prob_f64) in ordered_kvs {
                 let num = num
-                    .format(exact, style, base, use_parentheses, int)?
+                    .format(exact, style, base, use_parentheses, ctx.precision, int)?
                     .value
                     .to_string();
                 if ctx.output_mode == crate::OutputMode::TerminalFixedWidth {
@@ -15,9 +15,9 @@ pub(crate) enum FormattingStyle {
     /// Print with the given number of significant figures (not including any leading zeroes)
     SignificantFigures(usize),
     /// If exact and no recurring digits: ExactFloat, if complex/imag: MixedFraction,
-    /// otherwise: DecimalPlaces(10)
+    /// otherwise: DecimalPlaces(precision), 10 by default
     Auto,
-    /// If not exact: DecimalPlaces(10). If no recurring digits: ExactFloat.
+    /// If not exact: DecimalPlaces(precision). If no recurring digits: ExactFloat.
     /// Other numbers: MixedFraction, albeit possibly including fractions of pi
     Exact,
 }
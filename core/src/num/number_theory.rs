@@ -0,0 +1,170 @@
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::Number;
+
+/// Returns the prime factorisation of `n` as (prime, exponent) pairs
+fn factorize<I: Interrupt>(mut n: u64, int: &I) -> Result<Vec<(u64, u32)>, FendError> {
+    let mut factors = vec![];
+    let mut p = 2;
+    while p <= n / p {
+        test_int(int)?;
+        let mut exponent = 0;
+        while let (quotient, 0) = (n / p, n % p) {
+            n = quotient;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    Ok(factors)
+}
+
+fn expect_positive(n: u64) -> Result<u64, FendError> {
+    if n == 0 {
+        return Err(FendError::ExpectedAPositiveInteger);
+    }
+    Ok(n)
+}
+
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+/// Writes `n` as `s^2 * f`, where `f` is square-free, returning `(s, f)`
+pub(crate) fn square_free_decomposition<I: Interrupt>(
+    n: u64,
+    int: &I,
+) -> Result<(u64, u64), FendError> {
+    let mut square_root = 1;
+    let mut square_free = 1;
+    for (prime, exponent) in factorize(expect_positive(n)?, int)? {
+        square_root *= prime.pow(exponent / 2);
+        if exponent % 2 == 1 {
+            square_free *= prime;
+        }
+    }
+    Ok((square_root, square_free))
+}
+
+/// Euler's totient function
+pub(crate) fn totient<I: Interrupt>(n: u64, int: &I) -> Result<u64, FendError> {
+    let mut result = expect_positive(n)?;
+    for (p, _) in factorize(n, int)? {
+        result = result / p * (p - 1);
+    }
+    Ok(result)
+}
+
+/// Returns all positive divisors of `n`, in ascending order
+pub(crate) fn divisors<I: Interrupt>(n: u64, int: &I) -> Result<Vec<u64>, FendError> {
+    let mut result = vec![1];
+    for (prime, exponent) in factorize(expect_positive(n)?, int)? {
+        let mut new_divisors = vec![];
+        for &divisor in &result {
+            let mut power = 1;
+            for i in 0..=exponent {
+                test_int(int)?;
+                new_divisors.push(divisor * power);
+                // the next power would overflow for e.g. 2^63
+                if i < exponent {
+                    power *= prime;
+                }
+            }
+        }
+        result = new_divisors;
+    }
+    result.sort_unstable();
+    Ok(result)
+}
+
+pub(crate) fn num_divisors<I: Interrupt>(n: u64, int: &I) -> Result<u64, FendError> {
+    Ok(factorize(expect_positive(n)?, int)?
+        .into_iter()
+        .map(|(_, exponent)| u64::from(exponent) + 1)
+        .product())
+}
+
+/// Computes the `n`th Fibonacci number using the fast doubling method:
+/// F(2k) = F(k) * (2 * F(k+1) - F(k)) and F(2k+1) = F(k)^2 + F(k+1)^2
+pub(crate) fn fib<I: Interrupt>(n: u64, int: &I) -> Result<Number, FendError> {
+    // invariant: `current` = F(k) and `next` = F(k+1)
+    let mut current = Number::from(0);
+    let mut next = Number::from(1);
+    for bit in (0..(u64::BITS - n.leading_zeros())).rev() {
+        test_int(int)?;
+        let twice_next = next.clone().mul(2.into(), int)?;
+        let doubled = current
+            .clone()
+            .mul(twice_next.sub(current.clone(), int)?, int)?;
+        let doubled_next = current
+            .clone()
+            .mul(current, int)?
+            .add(next.clone().mul(next, int)?, int)?;
+        if (n >> bit) & 1 == 1 {
+            current = doubled_next.clone();
+            next = doubled.add(doubled_next, int)?;
+        } else {
+            current = doubled;
+            next = doubled_next;
+        }
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interrupt::Never;
+
+    #[test]
+    fn factorize_small_numbers() {
+        let int = &Never::default();
+        assert_eq!(factorize(1, int).unwrap(), vec![]);
+        assert_eq!(factorize(12, int).unwrap(), vec![(2, 2), (3, 1)]);
+        assert_eq!(factorize(97, int).unwrap(), vec![(97, 1)]);
+    }
+
+    #[test]
+    fn factorize_large_prime_factor() {
+        let int = &Never::default();
+        assert_eq!(
+            factorize(2 * 4_294_967_291, int).unwrap(),
+            vec![(2, 1), (4_294_967_291, 1)]
+        );
+    }
+
+    #[test]
+    fn square_free_decomposition_of_72() {
+        let int = &Never::default();
+        assert_eq!(square_free_decomposition(72, int).unwrap(), (6, 2));
+        assert_eq!(square_free_decomposition(49, int).unwrap(), (7, 1));
+    }
+
+    #[test]
+    fn divisors_of_36() {
+        let int = &Never::default();
+        assert_eq!(
+            divisors(36, int).unwrap(),
+            vec![1, 2, 3, 4, 6, 9, 12, 18, 36]
+        );
+    }
+
+    #[test]
+    fn divisors_at_u64_boundary() {
+        let int = &Never::default();
+        let divisors_of_2_63 = divisors(1 << 63, int).unwrap();
+        assert_eq!(divisors_of_2_63.len(), 64);
+        assert_eq!(divisors_of_2_63.last(), Some(&(1 << 63)));
+        assert_eq!(divisors(u64::MAX, int).unwrap().last(), Some(&u64::MAX));
+    }
+}
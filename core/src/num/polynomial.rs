@@ -0,0 +1,251 @@
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::number_theory;
+use crate::num::{Exact, Number, RoundingMode};
+use std::cmp::Ordering;
+
+// Polynomials are represented by their coefficients, starting
+// with the constant term, e.g. `[-6, 11, -6, 1]` is
+// x^3 - 6x^2 + 11x - 6. The last coefficient is never zero.
+
+/// Coefficients larger than this are not factorised when
+/// searching for rational roots
+const MAX_RATIONAL_ROOT_COEFFICIENT: u64 = 1_000_000_000_000;
+/// Limits how many candidates are checked when searching for rational roots
+const MAX_RATIONAL_ROOT_CANDIDATES: u64 = 1000;
+/// Irrational roots are approximated to a multiple of 2^-GRID_BITS
+const GRID_BITS: u64 = 48;
+
+/// Evaluates the polynomial at `x`, using Horner's method
+pub(crate) fn evaluate<I: Interrupt>(
+    coefficients: &[Number],
+    x: &Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let mut iter = coefficients.iter().rev();
+    let mut result = match iter.next() {
+        Some(leading) => leading.clone(),
+        None => return Ok(0.into()),
+    };
+    for c in iter {
+        test_int(int)?;
+        result = result.mul(x.clone(), int)?.add(c.clone(), int)?;
+    }
+    Ok(result)
+}
+
+fn sign<I: Interrupt>(n: &Number, int: &I) -> Result<Ordering, FendError> {
+    n.compare(&0.into(), int)
+}
+
+fn derivative<I: Interrupt>(coefficients: &[Number], int: &I) -> Result<Vec<Number>, FendError> {
+    let mut result = vec![];
+    for (k, c) in (1u64..).zip(coefficients.iter().skip(1)) {
+        result.push(c.clone().mul(k.into(), int)?);
+    }
+    Ok(result)
+}
+
+/// Divides the polynomial by `x - root`, where `root` is known to be
+/// an exact root
+fn deflate<I: Interrupt>(
+    coefficients: &[Number],
+    root: &Number,
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    let mut result = vec![];
+    let mut carry: Option<Number> = None;
+    for c in coefficients.iter().skip(1).rev() {
+        let next = match carry {
+            Some(carry) => c.clone().add(carry.mul(root.clone(), int)?, int)?,
+            None => c.clone(),
+        };
+        result.push(next.clone());
+        carry = Some(next);
+    }
+    result.reverse();
+    Ok(result)
+}
+
+fn integer_coefficients<I: Interrupt>(coefficients: &[Number], int: &I) -> Option<Vec<i64>> {
+    coefficients
+        .iter()
+        .map(|c| c.clone().try_as_i64(int).ok())
+        .collect()
+}
+
+/// Lists the possible rational roots `±p/q` of a polynomial with integer
+/// coefficients, where `p` divides the constant term and `q` divides the
+/// leading coefficient
+fn rational_root_candidates<I: Interrupt>(
+    coefficients: &[Number],
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    let mut candidates = vec![];
+    if let Some([constant, .., leading]) = integer_coefficients(coefficients, int).as_deref() {
+        let (constant, leading) = (constant.unsigned_abs(), leading.unsigned_abs());
+        if constant > MAX_RATIONAL_ROOT_COEFFICIENT
+            || leading > MAX_RATIONAL_ROOT_COEFFICIENT
+            || number_theory::num_divisors(constant, int)?
+                * number_theory::num_divisors(leading, int)?
+                > MAX_RATIONAL_ROOT_CANDIDATES
+        {
+            return Ok(candidates);
+        }
+        for p in number_theory::divisors(constant, int)? {
+            for q in number_theory::divisors(leading, int)? {
+                test_int(int)?;
+                let candidate = Number::from(p).div(q.into(), int)?;
+                candidates.push(-candidate.clone());
+                candidates.push(candidate);
+            }
+        }
+    }
+    Ok(candidates)
+}
+
+/// Finds all rational roots of a polynomial with integer coefficients,
+/// dividing them out of `coefficients`
+fn remove_rational_roots<I: Interrupt>(
+    coefficients: &mut Vec<Number>,
+    roots: &mut Vec<Number>,
+    int: &I,
+) -> Result<(), FendError> {
+    for candidate in rational_root_candidates(coefficients, int)? {
+        let mut is_root = false;
+        while coefficients.len() > 1 && evaluate(coefficients, &candidate, int)?.is_zero() {
+            *coefficients = deflate(coefficients, &candidate, int)?;
+            is_root = true;
+        }
+        if is_root {
+            roots.push(candidate);
+        }
+    }
+    Ok(())
+}
+
+/// Every real root lies strictly within `(-bound, bound)`
+fn cauchy_bound<I: Interrupt>(coefficients: &[Number], int: &I) -> Result<Number, FendError> {
+    let mut max: Number = 0.into();
+    if let Some((leading, rest)) = coefficients.split_last() {
+        for c in rest {
+            let ratio = c.clone().div(leading.clone(), int)?.abs(int)?;
+            if ratio.compare(&max, int)? == Ordering::Greater {
+                max = ratio;
+            }
+        }
+    }
+    max.add(1.into(), int)
+}
+
+/// Bisects between `low` and `high`, which are integers on the grid used by
+/// `numeric_roots`. `scaled` must evaluate to a value with sign `low_sign`
+/// at `low` and the opposite sign at `high`.
+fn bisect<I: Interrupt>(
+    scaled: &[Number],
+    mut low: Number,
+    mut high: Number,
+    low_sign: Ordering,
+    int: &I,
+) -> Result<Exact<Number>, FendError> {
+    let one = Number::from(1);
+    while high.clone().sub(low.clone(), int)?.compare(&one, int)? == Ordering::Greater {
+        test_int(int)?;
+        let mid = low
+            .clone()
+            .add(high.clone(), int)?
+            .div(2.into(), int)?
+            .round(None, RoundingMode::Floor, int)?;
+        match sign(&evaluate(scaled, &mid, int)?, int)? {
+            Ordering::Equal => return Ok(Exact::new(mid, true)),
+            s if s == low_sign => low = mid,
+            _ => high = mid,
+        }
+    }
+    Ok(Exact::new(low, false))
+}
+
+/// Finds the real roots of any polynomial by bisecting each interval
+/// between consecutive roots of its derivative
+fn numeric_roots<I: Interrupt>(coefficients: &[Number], int: &I) -> Result<Vec<Number>, FendError> {
+    match coefficients {
+        [] | [_] => return Ok(vec![]),
+        [c, b] => return Ok(vec![-c.clone().div(b.clone(), int)?]),
+        _ => (),
+    }
+    // Bisection only looks at points `m / scale` for integers `m`. Evaluating
+    // `scale^n * p(m / scale)` instead of `p(m / scale)` then only involves
+    // integer arithmetic (for integer coefficients), which is much faster
+    // than working with fractions whose denominators keep growing.
+    let scale = Number::from(2).pow(GRID_BITS.into(), int)?;
+    let mut scaled = vec![];
+    let mut power = Number::from(1);
+    for c in coefficients.iter().rev() {
+        scaled.push(c.clone().mul(power.clone(), int)?);
+        power = power.mul(scale.clone(), int)?;
+    }
+    scaled.reverse();
+    let to_grid = |x: Number, mode| x.mul(scale.clone(), int)?.round(None, mode, int);
+    let bound = to_grid(cauchy_bound(coefficients, int)?, RoundingMode::Ceil)?;
+    let mut points = vec![-bound.clone()];
+    for point in numeric_roots(&derivative(coefficients, int)?, int)? {
+        points.push(to_grid(point, RoundingMode::Nearest)?);
+    }
+    points.push(bound);
+    let mut roots = vec![];
+    for window in points.windows(2) {
+        let (low, high) = (&window[0], &window[1]);
+        let low_sign = sign(&evaluate(&scaled, low, int)?, int)?;
+        let high_sign = sign(&evaluate(&scaled, high, int)?, int)?;
+        let root = if low_sign == Ordering::Equal {
+            Exact::new(low.clone(), true)
+        } else if high_sign != Ordering::Equal && high_sign != low_sign {
+            bisect(&scaled, low.clone(), high.clone(), low_sign, int)?
+        } else {
+            continue;
+        };
+        let value = root.value.div(scale.clone(), int)?;
+        roots.push(if root.exact {
+            value
+        } else {
+            value.make_approximate()
+        });
+    }
+    Ok(roots)
+}
+
+/// Returns the distinct real roots of a polynomial in ascending order.
+/// Rational roots are found exactly, and any other roots are
+/// approximated.
+pub(crate) fn real_roots<I: Interrupt>(
+    mut coefficients: Vec<Number>,
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    if coefficients.is_empty() {
+        return Err(FendError::ZeroPolynomialRoots);
+    }
+    let mut roots = vec![];
+    if coefficients.len() > 1 && coefficients[0].is_zero() {
+        roots.push(0.into());
+        while coefficients[0].is_zero() {
+            coefficients.remove(0);
+        }
+    }
+    remove_rational_roots(&mut coefficients, &mut roots, int)?;
+    roots.extend(numeric_roots(&coefficients, int)?);
+    let mut sorted: Vec<Number> = vec![];
+    'roots: for root in roots {
+        for (idx, x) in sorted.iter().enumerate() {
+            match root.compare(x, int)? {
+                Ordering::Equal => continue 'roots,
+                Ordering::Less => {
+                    sorted.insert(idx, root);
+                    continue 'roots;
+                }
+                Ordering::Greater => (),
+            }
+        }
+        sorted.push(root);
+    }
+    Ok(sorted)
+}
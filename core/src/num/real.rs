@@ -1,8 +1,9 @@
 use crate::error::{FendError, Interrupt};
 use crate::format::Format;
 use crate::num::bigrat::{BigRat, FormattedBigRat};
+use crate::num::number_theory::gcd;
 use crate::num::Exact;
-use crate::num::{Base, FormattingStyle};
+use crate::num::{Base, FormattingStyle, RoundingMode, DEFAULT_PRECISION};
 use std::cmp::Ordering;
 use std::ops::Neg;
 use std::{fmt, hash};
@@ -25,6 +26,7 @@ impl fmt::Debug for Real {
                     write!(f, "{:?} * pi", x)
                 }
             }
+            Pattern::Radical(x, r) => write!(f, "{:?} * sqrt({})", x, r),
         }
     }
 }
@@ -35,16 +37,19 @@ pub(crate) enum Pattern {
     Simple(BigRat),
     // n * pi
     Pi(BigRat),
+    // n * sqrt(r), where r is a square-free integer greater than 1
+    Radical(BigRat, u64),
 }
 
 impl Ord for Real {
     fn cmp(&self, other: &Self) -> Ordering {
         match (&self.pattern, &other.pattern) {
             (Pattern::Simple(a), Pattern::Simple(b)) | (Pattern::Pi(a), Pattern::Pi(b)) => a.cmp(b),
+            (Pattern::Radical(a, r), Pattern::Radical(b, s)) if r == s => a.cmp(b),
             _ => {
                 let int = &crate::interrupt::Never::default();
-                let a = self.clone().approximate(int).unwrap();
-                let b = other.clone().approximate(int).unwrap();
+                let a = self.clone().approximate(DEFAULT_PRECISION, int).unwrap();
+                let b = other.clone().approximate(DEFAULT_PRECISION, int).unwrap();
                 a.cmp(&b)
             }
         }
@@ -69,23 +74,86 @@ impl hash::Hash for Real {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         match &self.pattern {
             Pattern::Simple(r) | Pattern::Pi(r) => r.hash(state),
+            Pattern::Radical(r, radicand) => {
+                r.hash(state);
+                radicand.hash(state);
+            }
         }
     }
 }
 
 impl Real {
-    fn approximate<I: Interrupt>(self, int: &I) -> Result<BigRat, FendError> {
+    fn approximate<I: Interrupt>(self, precision: usize, int: &I) -> Result<BigRat, FendError> {
         match self.pattern {
             Pattern::Simple(s) => Ok(s),
-            Pattern::Pi(n) => {
-                let num = BigRat::from(3_141_592_653_589_793_238);
-                let den = BigRat::from(1_000_000_000_000_000_000);
-                let pi = num.div(&den, int)?;
-                Ok(n.mul(&pi, int)?)
+            Pattern::Pi(n) => n.mul(&BigRat::pi(precision, int)?, int),
+            // n√r == ±√(n^2 * r), which is more accurate than multiplying
+            // by an approximation of √r
+            Pattern::Radical(n, r) => {
+                let root = n
+                    .clone()
+                    .mul(&n, int)?
+                    .mul(&r.into(), int)?
+                    .root_n(&2.into(), precision, int)?
+                    .value;
+                Ok(if n < 0.into() { -root } else { root })
+            }
+        }
+    }
+
+    /// Returns `coefficient * sqrt(radicand)`, where the radicand must be
+    /// square-free
+    fn radical(coefficient: BigRat, radicand: u64) -> Self {
+        if radicand == 1 || coefficient == 0.into() {
+            Self::from(coefficient)
+        } else {
+            Self {
+                pattern: Pattern::Radical(coefficient, radicand),
             }
         }
     }
 
+    /// Computes `self^rhs` exactly if the result can be written as a
+    /// rational multiple of a square root, e.g. `8^(1/2) == 2√2`
+    fn pow_as_radical<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Option<Self>, FendError> {
+        let n = match &rhs.pattern {
+            Pattern::Simple(n) => n.clone(),
+            Pattern::Pi(_) | Pattern::Radical(_, _) => return Ok(None),
+        };
+        let (coefficient, radicand) = match &self.pattern {
+            Pattern::Simple(x) => (BigRat::from(1), x.clone()),
+            // (a√r)^n == a^n * r^(n/2), where n must be an integer
+            Pattern::Radical(a, r) => match a.clone().pow_as_radical(n.clone(), int)? {
+                Some((a, 1)) => (a, BigRat::from(*r)),
+                _ => return Ok(None),
+            },
+            Pattern::Pi(_) => return Ok(None),
+        };
+        let n = match &self.pattern {
+            Pattern::Radical(_, _) => n.div(&2.into(), int)?,
+            _ => n,
+        };
+        Ok(match radicand.pow_as_radical(n, int)? {
+            Some((c, r)) => Some(Self::radical(coefficient.mul(&c, int)?, r)),
+            None => None,
+        })
+    }
+
+    /// Replaces multiples of pi and square roots with a rational
+    /// approximation, accurate to the given number of decimal places
+    pub(crate) fn approximate_pi<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Ok(Self::from(self.approximate(precision, int)?))
+    }
+
+    /// Whether this number is shown as a multiple of pi or a square root
+    pub(crate) fn is_symbolic(&self) -> bool {
+        !self.is_zero() && !matches!(self.pattern, Pattern::Simple(_))
+    }
+
     pub(crate) fn try_as_usize<I: Interrupt>(self, int: &I) -> Result<usize, FendError> {
         match self.pattern {
             Pattern::Simple(s) => s.try_as_usize(int),
@@ -96,96 +164,230 @@ impl Real {
                     Err(FendError::CannotConvertToInteger)
                 }
             }
+            Pattern::Radical(_, _) => Err(FendError::CannotConvertToInteger),
         }
     }
 
     // sin works for all real numbers
-    pub(crate) fn sin<I: Interrupt>(self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn sin<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         Ok(match self.pattern {
-            Pattern::Simple(s) => s.sin(int)?.apply(Self::from),
+            Pattern::Simple(s) => s.sin(precision, int)?.apply(Self::from),
             Pattern::Pi(n) => {
                 if n < 0.into() {
                     let s = Self {
                         pattern: Pattern::Pi(n),
                     };
                     // sin(-x) == -sin(x)
-                    return Ok(-Self::sin(-s, int)?);
+                    return Ok(-Self::sin(-s, precision, int)?);
                 }
-                if let Ok(integer) = n.clone().mul(&6.into(), int)?.try_as_usize(int) {
+                // multiples of 15 degrees
+                if let Ok(integer) = n.clone().mul(&12.into(), int)?.try_as_usize(int) {
                     // values from https://en.wikipedia.org/wiki/Trigonometric_constants_expressed_in_real_radicals#Table_of_some_common_angles
-                    if integer % 6 == 0 {
-                        return Ok(Exact::new(Self::from(0), true));
-                    } else if integer % 12 == 3 {
-                        return Ok(Exact::new(Self::from(1), true));
-                    } else if integer % 12 == 9 {
-                        return Ok(Exact::new(-Self::from(1), true));
-                    } else if integer % 12 == 1 || integer % 12 == 5 {
-                        return Exact::new(Self::from(1), true)
-                            .div(&Exact::new(2.into(), true), int);
-                    } else if integer % 12 == 7 || integer % 12 == 11 {
-                        return Exact::new(-Self::from(1), true)
-                            .div(&Exact::new(2.into(), true), int);
+                    let half = |x: Self| Exact::new(x, true).div(&Exact::new(2.into(), true), int);
+                    match integer % 24 {
+                        0 | 12 => return Ok(Exact::new(Self::from(0), true)),
+                        6 => return Ok(Exact::new(Self::from(1), true)),
+                        18 => return Ok(Exact::new(-Self::from(1), true)),
+                        2 | 10 => return half(Self::from(1)),
+                        14 | 22 => return half(-Self::from(1)),
+                        3 | 9 => return half(Self::radical(1.into(), 2)),
+                        15 | 21 => return half(-Self::radical(1.into(), 2)),
+                        4 | 8 => return half(Self::radical(1.into(), 3)),
+                        16 | 20 => return half(-Self::radical(1.into(), 3)),
+                        _ => (),
                     }
                 }
                 let s = Self {
                     pattern: Pattern::Pi(n),
                 };
-                s.approximate(int)?.sin(int)?.apply(Self::from)
+                s.approximate(precision, int)?
+                    .sin(precision, int)?
+                    .apply(Self::from)
             }
+            Pattern::Radical(n, r) => Self::radical(n, r)
+                .approximate(precision, int)?
+                .sin(precision, int)?
+                .apply(Self::from),
         })
     }
 
-    pub(crate) fn asin<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.asin(int)?))
+    pub(crate) fn cos<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        if let Pattern::Simple(s) = &self.pattern {
+            if precision > DEFAULT_PRECISION {
+                return Ok(s.clone().cos(precision, int)?.apply(Self::from));
+            }
+        }
+        // cos(x) == sin(pi/2 - x)
+        let pi = Exact::new(Self::pi(), true);
+        let half_pi = pi.div(&Exact::new(2.into(), true), int)?;
+        let sin_arg = half_pi.add(-Exact::new(self, true), int)?;
+        Ok(sin_arg.value.sin(precision, int)?.combine(sin_arg.exact))
+    }
+
+    /// Returns true if `self` and `other` have the same exact representation,
+    /// without comparing approximations
+    fn is_exactly(&self, other: &Self) -> bool {
+        match (&self.pattern, &other.pattern) {
+            (Pattern::Simple(a), Pattern::Simple(b)) | (Pattern::Pi(a), Pattern::Pi(b)) => a == b,
+            (Pattern::Radical(a, r), Pattern::Radical(b, s)) => r == s && a == b,
+            _ => false,
+        }
+    }
+
+    /// Finds a multiple of 15 degrees between 0 and `max_twelfths * pi/12`
+    /// whose sine or tangent is exactly `self`, e.g. `asin(√2/2) == π/4`
+    fn special_angle<I: Interrupt>(
+        &self,
+        max_twelfths: u64,
+        tangent: bool,
+        int: &I,
+    ) -> Result<Option<Self>, FendError> {
+        for twelfths in 0..=max_twelfths {
+            let angle = Self {
+                pattern: Pattern::Pi(BigRat::from(twelfths).div(&12.into(), int)?),
+            };
+            let mut value = angle.clone().sin(DEFAULT_PRECISION, int)?;
+            if tangent {
+                let cos = angle.clone().cos(DEFAULT_PRECISION, int)?;
+                value = value.div(&cos, int)?;
+            }
+            if value.exact && value.value.is_exactly(self) {
+                return Ok(Some(angle));
+            }
+        }
+        Ok(None)
+    }
+
+    // asin(x) for special values like 1/2 or √2/2 is an exact multiple of pi
+    fn exact_asin<I: Interrupt>(
+        &self,
+        precision: usize,
+        tangent: bool,
+        int: &I,
+    ) -> Result<Option<Self>, FendError> {
+        if precision != DEFAULT_PRECISION {
+            return Ok(None);
+        }
+        // asin(-x) == -asin(x), and likewise for atan
+        if *self < 0.into() {
+            return Ok((-self.clone())
+                .special_angle(if tangent { 5 } else { 6 }, tangent, int)?
+                .map(Neg::neg));
+        }
+        self.special_angle(if tangent { 5 } else { 6 }, tangent, int)
+    }
+
+    pub(crate) fn asin<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        if let Some(angle) = self.exact_asin(precision, false, int)? {
+            return Ok(Exact::new(angle, true));
+        }
+        Ok(Exact::new(
+            Self::from(self.approximate(precision, int)?.asin(precision, int)?),
+            false,
+        ))
     }
 
-    pub(crate) fn acos<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.acos(int)?))
+    pub(crate) fn acos<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        // acos(x) == pi/2 - asin(x)
+        if let Some(angle) = self.exact_asin(precision, false, int)? {
+            let half_pi = Exact::new(Self::pi(), true).div(&Exact::new(2.into(), true), int)?;
+            return half_pi.add(-Exact::new(angle, true), int);
+        }
+        Ok(Exact::new(
+            Self::from(self.approximate(precision, int)?.acos(precision, int)?),
+            false,
+        ))
     }
 
-    pub(crate) fn atan<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.atan(int)?))
+    pub(crate) fn atan<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
+        if let Some(angle) = self.exact_asin(precision, true, int)? {
+            return Ok(Exact::new(angle, true));
+        }
+        Ok(Exact::new(
+            Self::from(self.approximate(precision, int)?.atan(precision, int)?),
+            false,
+        ))
     }
 
-    pub(crate) fn sinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.sinh(int)?))
+    pub(crate) fn sinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.sinh(precision, int)?,
+        ))
     }
 
-    pub(crate) fn cosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.cosh(int)?))
+    pub(crate) fn cosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.cosh(precision, int)?,
+        ))
     }
 
-    pub(crate) fn tanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.tanh(int)?))
+    pub(crate) fn tanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.tanh(precision, int)?,
+        ))
     }
 
-    pub(crate) fn asinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.asinh(int)?))
+    pub(crate) fn asinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.asinh(precision, int)?,
+        ))
     }
 
-    pub(crate) fn acosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.acosh(int)?))
+    pub(crate) fn acosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.acosh(precision, int)?,
+        ))
     }
 
-    pub(crate) fn atanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.atanh(int)?))
+    pub(crate) fn atanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.atanh(precision, int)?,
+        ))
     }
 
     // For all logs: value must be greater than 0
-    pub(crate) fn ln<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.ln(int)?))
+    pub(crate) fn ln<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.ln(precision, int)?,
+        ))
     }
 
-    pub(crate) fn log2<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.log2(int)?))
+    pub(crate) fn log2<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.log2(precision, int)?,
+        ))
     }
 
-    pub(crate) fn log10<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.log10(int)?))
+    pub(crate) fn log10<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(precision, int)?.log10(precision, int)?,
+        ))
     }
 
     pub(crate) fn factorial<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        Ok(Self::from(self.approximate(int)?.factorial(int)?))
+        Ok(Self::from(
+            self.approximate(DEFAULT_PRECISION, int)?.factorial(int)?,
+        ))
     }
 
     pub(crate) fn format<I: Interrupt>(
@@ -194,36 +396,54 @@ impl Real {
         mut style: FormattingStyle,
         imag: bool,
         use_parens_if_fraction: bool,
+        precision: usize,
         int: &I,
     ) -> Result<Exact<Formatted>, FendError> {
-        let mut pi = false;
-        if style == FormattingStyle::Exact && !self.is_zero() {
-            if let Pattern::Pi(_) = self.pattern {
-                pi = true;
-            }
-        }
-
-        let term = match (imag, pi) {
-            (false, false) => "",
-            (false, true) => "\u{3c0}", // pi symbol
-            (true, false) => "i",
-            (true, true) => "\u{3c0}i",
+        // multiples of pi and square roots are shown symbolically, e.g. `π/3`
+        // or `2√2`, unless a decimal representation was requested
+        let symbolic =
+            matches!(style, FormattingStyle::Auto | FormattingStyle::Exact) && !self.is_zero();
+
+        let term = match (&self.pattern, imag) {
+            (Pattern::Pi(_), false) if symbolic => "\u{3c0}", // pi symbol
+            (Pattern::Pi(_), true) if symbolic => "\u{3c0}i",
+            (Pattern::Radical(_, _), false) if symbolic => "\u{221a}", // square root symbol
+            // e.g. `i√3/2` rather than the ambiguous `√3i/2`
+            (Pattern::Radical(_, _), true) if symbolic => "i\u{221a}",
+            (_, false) => "",
+            (_, true) => "i",
         };
 
         let mut override_exact = true;
+        let mut radicand = None;
 
         let rat = match &self.pattern {
             Pattern::Simple(f) => f.clone(),
-            Pattern::Pi(f) => {
-                if pi {
-                    f.clone()
-                } else {
-                    override_exact = false;
-                    if style == FormattingStyle::Auto {
-                        style = FormattingStyle::DecimalPlaces(10);
-                    }
-                    self.clone().approximate(int)?
+            // e.g. `acos 1 == pi/2 - pi/2` is exactly zero
+            Pattern::Pi(f) | Pattern::Radical(f, _) if self.is_zero() => f.clone(),
+            Pattern::Pi(f) if symbolic => {
+                // e.g. `7π/3` rather than `2 1/3 π`
+                if style == FormattingStyle::Auto {
+                    style = FormattingStyle::ImproperFraction;
+                }
+                f.clone()
+            }
+            Pattern::Radical(f, r) if symbolic => {
+                style = FormattingStyle::ImproperFraction;
+                let options = bigrat::FormatOptions {
+                    base,
+                    style: FormattingStyle::Exact,
+                    ..bigrat::FormatOptions::default()
+                };
+                radicand = Some(BigRat::from(*r).format(&options, int)?.value.to_string());
+                f.clone()
+            }
+            Pattern::Pi(_) | Pattern::Radical(_, _) => {
+                override_exact = false;
+                if style == FormattingStyle::Auto {
+                    style = FormattingStyle::DecimalPlaces(precision);
                 }
+                self.clone().approximate(precision, int)?
             }
         };
 
@@ -233,6 +453,7 @@ impl Real {
                 style,
                 term,
                 use_parens_if_fraction,
+                precision,
             },
             int,
         )?;
@@ -240,12 +461,18 @@ impl Real {
         Ok(Exact::new(
             Formatted {
                 num: formatted.value,
+                radicand,
             },
             exact,
         ))
     }
 
-    pub(crate) fn pow<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn pow<I: Interrupt>(
+        self,
+        rhs: Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         // x^1 == x
         if let Pattern::Simple(n) = &rhs.pattern {
             if n == &1.into() {
@@ -260,35 +487,57 @@ impl Real {
             }
         }
 
+        if precision == DEFAULT_PRECISION {
+            if let Some(result) = self.pow_as_radical(&rhs, int)? {
+                return Ok(Exact::new(result, true));
+            }
+        }
+
         if let (Pattern::Simple(a), Pattern::Simple(b)) =
             (self.clone().pattern, rhs.clone().pattern)
         {
-            Ok(a.pow(b, int)?.apply(Self::from))
+            Ok(a.pow(b, precision, int)?.apply(Self::from))
         } else {
             Ok(self
-                .approximate(int)?
-                .pow(rhs.approximate(int)?, int)?
+                .approximate(precision, int)?
+                .pow(rhs.approximate(precision, int)?, precision, int)?
                 .combine(false)
                 .apply(Self::from))
         }
     }
 
-    pub(crate) fn root_n<I: Interrupt>(self, n: &Self, int: &I) -> Result<Exact<Self>, FendError> {
+    pub(crate) fn root_n<I: Interrupt>(
+        self,
+        n: &Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Exact<Self>, FendError> {
         // TODO: Combining these match blocks is not currently possible because
         // 'binding by-move and by-ref in the same pattern is unstable'
         // https://github.com/rust-lang/rust/pull/76119
+        if precision == DEFAULT_PRECISION && n == &2.into() {
+            if let Some(root) =
+                self.pow_as_radical(&Self::from(BigRat::from(1).div(&2.into(), int)?), int)?
+            {
+                return Ok(Exact::new(root, true));
+            }
+        }
         Ok(match self.pattern {
             Pattern::Simple(a) => match &n.pattern {
-                Pattern::Simple(b) => a.root_n(b, int)?.apply(Self::from),
-                Pattern::Pi(_) => {
-                    let b = n.clone().approximate(int)?;
-                    a.root_n(&b, int)?.apply(Self::from).combine(false)
+                Pattern::Simple(b) => a.root_n(b, precision, int)?.apply(Self::from),
+                Pattern::Pi(_) | Pattern::Radical(_, _) => {
+                    let b = n.clone().approximate(precision, int)?;
+                    a.root_n(&b, precision, int)?
+                        .apply(Self::from)
+                        .combine(false)
                 }
             },
-            Pattern::Pi(_) => {
-                let a = self.clone().approximate(int)?;
-                let b = n.clone().approximate(int)?;
-                a.root_n(&b, int)?.apply(Self::from).combine(false)
+            Pattern::Pi(_) | Pattern::Radical(_, _) => {
+                let a = self.clone().approximate(precision, int)?;
+                let b = n.clone().approximate(precision, int)?;
+                a.root_n(&b, precision, int)?
+                    .apply(Self::from)
+                    .combine(false)
             }
         })
     }
@@ -301,30 +550,42 @@ impl Real {
 
     pub(crate) fn is_zero(&self) -> bool {
         match &self.pattern {
-            Pattern::Simple(a) | Pattern::Pi(a) => a.is_definitely_zero() || a == &0.into(),
+            Pattern::Simple(a) | Pattern::Pi(a) | Pattern::Radical(a, _) => {
+                a.is_definitely_zero() || a == &0.into()
+            }
         }
     }
 
     pub(crate) fn is_definitely_zero(&self) -> bool {
         match &self.pattern {
-            Pattern::Simple(a) | Pattern::Pi(a) => a.is_definitely_zero(),
+            Pattern::Simple(a) | Pattern::Pi(a) | Pattern::Radical(a, _) => a.is_definitely_zero(),
         }
     }
 
     pub(crate) fn is_definitely_one(&self) -> bool {
         match &self.pattern {
             Pattern::Simple(a) => a.is_definitely_one(),
-            Pattern::Pi(_) => false,
+            Pattern::Pi(_) | Pattern::Radical(_, _) => false,
         }
     }
 
     pub(crate) fn expect_rational(self) -> Result<BigRat, FendError> {
         match self.pattern {
             Pattern::Simple(a) => Ok(a),
-            Pattern::Pi(_) => Err(FendError::ExpectedARationalNumber),
+            Pattern::Pi(_) | Pattern::Radical(_, _) => Err(FendError::ExpectedARationalNumber),
         }
     }
 
+    pub(crate) fn round<I: Interrupt>(
+        self,
+        mode: RoundingMode,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Ok(Self::from(
+            self.approximate(DEFAULT_PRECISION, int)?.round(mode, int)?,
+        ))
+    }
+
     pub(crate) fn modulo<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
         Ok(Self::from(
             self.expect_rational()?
@@ -353,9 +614,12 @@ impl Exact<Real> {
                     },
                     args_exact,
                 ),
+                (Pattern::Radical(a, r), Pattern::Radical(b, s)) if r == s => {
+                    Self::new(Real::radical(a.add(b, int)?, r), args_exact)
+                }
                 _ => {
-                    let a = self.value.approximate(int)?;
-                    let b = rhs.value.approximate(int)?;
+                    let a = self.value.approximate(DEFAULT_PRECISION, int)?;
+                    let b = rhs.value.approximate(DEFAULT_PRECISION, int)?;
                     Self::new(a.add(b, int)?.into(), false)
                 }
             },
@@ -369,30 +633,46 @@ impl Exact<Real> {
             return Ok(Self::new(rhs.value.clone(), rhs.exact));
         }
         let args_exact = self.exact && rhs.exact;
-        Ok(match self.value.pattern {
-            Pattern::Simple(a) => match &rhs.value.pattern {
-                Pattern::Simple(b) => Self::new(a.mul(b, int)?.into(), args_exact),
-                Pattern::Pi(b) => Self::new(
-                    Real {
-                        pattern: Pattern::Pi(a.mul(b, int)?),
-                    },
-                    args_exact,
-                ),
-            },
-            Pattern::Pi(a) => match &rhs.value.pattern {
-                Pattern::Simple(b) => Self::new(
+        Ok(match (self.value.pattern, &rhs.value.pattern) {
+            (Pattern::Simple(a), Pattern::Simple(b)) => {
+                Self::new(a.mul(b, int)?.into(), args_exact)
+            }
+            (Pattern::Simple(a), Pattern::Pi(b)) | (Pattern::Pi(a), Pattern::Simple(b)) => {
+                Self::new(
                     Real {
                         pattern: Pattern::Pi(a.mul(b, int)?),
                     },
                     args_exact,
-                ),
-                Pattern::Pi(_) => Self::new(
-                    Real {
-                        pattern: Pattern::Pi(a.mul(&rhs.value.clone().approximate(int)?, int)?),
-                    },
-                    false,
-                ),
-            },
+                )
+            }
+            (Pattern::Simple(a), Pattern::Radical(b, r)) => {
+                Self::new(Real::radical(a.mul(b, int)?, *r), args_exact)
+            }
+            (Pattern::Radical(a, r), Pattern::Simple(b)) => {
+                Self::new(Real::radical(a.mul(b, int)?, r), args_exact)
+            }
+            // a√r * b√s == ab * g√(rs/g^2), where g = gcd(r, s)
+            (Pattern::Radical(a, r), Pattern::Radical(b, s))
+                if (r / gcd(r, *s)).checked_mul(s / gcd(r, *s)).is_some() =>
+            {
+                let divisor = gcd(r, *s);
+                let coefficient = a.mul(b, int)?.mul(&divisor.into(), int)?;
+                let radicand = (r / divisor) * (s / divisor);
+                Self::new(Real::radical(coefficient, radicand), args_exact)
+            }
+            (Pattern::Pi(a), Pattern::Pi(_)) => Self::new(
+                Real {
+                    pattern: Pattern::Pi(
+                        a.mul(&rhs.value.clone().approximate(DEFAULT_PRECISION, int)?, int)?,
+                    ),
+                },
+                false,
+            ),
+            (a, _) => {
+                let a = Real { pattern: a }.approximate(DEFAULT_PRECISION, int)?;
+                let b = rhs.value.clone().approximate(DEFAULT_PRECISION, int)?;
+                Self::new(a.mul(&b, int)?.into(), false)
+            }
         })
     }
 
@@ -403,23 +683,33 @@ impl Exact<Real> {
         if self.exact && self.value.is_zero() {
             return Ok(self);
         }
-        Ok(match self.value.pattern {
-            Pattern::Simple(a) => match &rhs.value.pattern {
-                Pattern::Simple(b) => Self::new(a.div(b, int)?.into(), self.exact && rhs.exact),
-                Pattern::Pi(_) => Self::new(
-                    a.div(&rhs.value.clone().approximate(int)?, int)?.into(),
-                    false,
-                ),
-            },
-            Pattern::Pi(a) => match &rhs.value.pattern {
-                Pattern::Simple(b) => Self::new(
-                    Real {
-                        pattern: Pattern::Pi(a.div(b, int)?),
-                    },
-                    self.exact && rhs.exact,
-                ),
-                Pattern::Pi(b) => Self::new(a.div(b, int)?.into(), self.exact && rhs.exact),
-            },
+        if let Pattern::Radical(b, s) = &rhs.value.pattern {
+            // 1/(b√s) == √s/(bs)
+            let inverse = Real::radical(
+                BigRat::from(1).div(&b.clone().mul(&(*s).into(), int)?, int)?,
+                *s,
+            );
+            return self.mul(Exact::new(&inverse, rhs.exact), int);
+        }
+        let args_exact = self.exact && rhs.exact;
+        Ok(match (self.value.pattern, &rhs.value.pattern) {
+            (Pattern::Simple(a), Pattern::Simple(b)) | (Pattern::Pi(a), Pattern::Pi(b)) => {
+                Self::new(a.div(b, int)?.into(), args_exact)
+            }
+            (Pattern::Pi(a), Pattern::Simple(b)) => Self::new(
+                Real {
+                    pattern: Pattern::Pi(a.div(b, int)?),
+                },
+                args_exact,
+            ),
+            (Pattern::Radical(a, r), Pattern::Simple(b)) => {
+                Self::new(Real::radical(a.div(b, int)?, r), args_exact)
+            }
+            (a, _) => {
+                let a = Real { pattern: a }.approximate(DEFAULT_PRECISION, int)?;
+                let b = rhs.value.clone().approximate(DEFAULT_PRECISION, int)?;
+                Self::new(a.div(&b, int)?.into(), false)
+            }
         })
     }
 }
@@ -433,6 +723,9 @@ impl Neg for Real {
             Pattern::Pi(n) => Self {
                 pattern: Pattern::Pi(-n),
             },
+            Pattern::Radical(n, r) => Self {
+                pattern: Pattern::Radical(-n, r),
+            },
         }
     }
 }
@@ -456,10 +749,19 @@ impl From<BigRat> for Real {
 #[derive(Debug)]
 pub(crate) struct Formatted {
     num: FormattedBigRat,
+    // the radicand of a square root, which is shown after the `√` in `num`
+    radicand: Option<String>,
 }
 
 impl fmt::Display for Formatted {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.num)
+        match &self.radicand {
+            Some(radicand) => {
+                let num = self.num.to_string();
+                let root = format!("\u{221a}{}", radicand);
+                write!(f, "{}", num.replacen('\u{221a}', &root, 1))
+            }
+            None => write!(f, "{}", self.num),
+        }
     }
 }
@@ -0,0 +1,167 @@
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::{Number, RoundingMode};
+use std::cmp::Ordering;
+
+fn expect_non_empty(values: &[Number]) -> Result<(), FendError> {
+    if values.is_empty() {
+        return Err(FendError::ExpectedAtLeastOneArgument);
+    }
+    Ok(())
+}
+
+fn count(values: &[Number]) -> Number {
+    Number::from(u64::try_from(values.len()).unwrap())
+}
+
+/// Sorts the values in ascending order, converting them
+/// all to the unit of the first value
+fn sorted<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Vec<Number>, FendError> {
+    let mut result: Vec<Number> = Vec::with_capacity(values.len());
+    for value in values {
+        test_int(int)?;
+        let value = match result.first() {
+            Some(first) => value.convert_unit_of(first.clone(), int)?,
+            None => value,
+        };
+        result.push(value);
+    }
+    let mut error = None;
+    result.sort_by(|a, b| {
+        a.compare(b, int).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+
+/// Returns the sum of the (non-empty) values, in the unit of the first value
+fn sum<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    let mut values = values.into_iter();
+    let mut sum = values.next().unwrap();
+    for value in values {
+        test_int(int)?;
+        sum = sum.add(value, int)?;
+    }
+    Ok(sum)
+}
+
+/// Returns the arithmetic mean, in the unit of the first value
+pub(crate) fn mean<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    expect_non_empty(&values)?;
+    let n = count(&values);
+    sum(values, int)?.div(n, int)
+}
+
+/// Divides each value by the sum of all values, so that the results add up to 1
+pub(crate) fn normalize<I: Interrupt>(
+    values: Vec<Number>,
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    expect_non_empty(&values)?;
+    let total = sum(values.clone(), int)?;
+    if total.is_zero() {
+        return Err(FendError::DivideByZero);
+    }
+    values
+        .into_iter()
+        .map(|value| value.div(total.clone(), int))
+        .collect()
+}
+
+pub(crate) fn median<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    expect_non_empty(&values)?;
+    let mut values = sorted(values, int)?;
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        return Ok(values.swap_remove(mid));
+    }
+    let upper = values.swap_remove(mid);
+    let lower = values.swap_remove(mid - 1);
+    mean(vec![lower, upper], int)
+}
+
+/// Returns the most common value. If there are several, the smallest is returned.
+pub(crate) fn mode<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    expect_non_empty(&values)?;
+    let values = sorted(values, int)?;
+    let mut best = (0, 0);
+    let mut run_start = 0;
+    for i in 1..=values.len() {
+        test_int(int)?;
+        if i == values.len() || values[i].compare(&values[run_start], int)? != Ordering::Equal {
+            if i - run_start > best.1 {
+                best = (run_start, i - run_start);
+            }
+            run_start = i;
+        }
+    }
+    Ok(values[best.0].clone())
+}
+
+/// Returns the sample variance, i.e. the sum of squared
+/// deviations from the mean divided by `n - 1`
+pub(crate) fn variance<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    if values.len() < 2 {
+        return Err(FendError::WrongNumberOfArguments {
+            function: "variance",
+            expected: "at least 2",
+            found: values.len(),
+        });
+    }
+    let n = count(&values);
+    let mean = mean(values.clone(), int)?;
+    let mut sum = None;
+    for value in values {
+        test_int(int)?;
+        let deviation = value.sub(mean.clone(), int)?;
+        let square = deviation.clone().mul(deviation, int)?;
+        sum = Some(match sum {
+            Some(sum) => square.add(sum, int)?,
+            None => square,
+        });
+    }
+    sum.unwrap().div(n.sub(1.into(), int)?, int)
+}
+
+/// Returns the sample standard deviation
+pub(crate) fn stdev<I: Interrupt>(values: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    let half = Number::from(1).div(2.into(), int)?;
+    variance(values, int)?.pow(half, int)
+}
+
+/// Returns the `p`th percentile (with `p` between 0 and 100, or given as a
+/// percentage), linearly interpolating between the closest values
+pub(crate) fn percentile<I: Interrupt>(
+    values: Vec<Number>,
+    p: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    expect_non_empty(&values)?;
+    let fraction = if p.unit_equal_to("%") || p.unit_equal_to("percent") {
+        p.convert_to(1.into(), int)?
+    } else {
+        p.div(100.into(), int)?
+    };
+    if fraction.compare(&0.into(), int)? == Ordering::Less
+        || fraction.compare(&1.into(), int)? == Ordering::Greater
+    {
+        return Err(FendError::InvalidPercentile);
+    }
+    let mut values = sorted(values, int)?;
+    let rank = fraction.mul(count(&values).sub(1.into(), int)?, int)?;
+    let lower_rank = rank.clone().round(None, RoundingMode::Floor, int)?;
+    let weight = rank.sub(lower_rank.clone(), int)?;
+    let idx = lower_rank.try_as_usize(int)?;
+    if idx + 1 >= values.len() {
+        return Ok(values.swap_remove(idx));
+    }
+    let upper = values.swap_remove(idx + 1);
+    let lower = values.swap_remove(idx);
+    let difference = upper.sub(lower.clone(), int)?;
+    lower.add(difference.mul(weight, int)?, int)
+}
@@ -0,0 +1,135 @@
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::{FormattingStyle, Number};
+use crate::{Span, SpanKind};
+use std::cmp::Ordering;
+
+/// A number with a standard uncertainty, e.g. `5.0 ± 0.1`. Uncertainties
+/// are propagated to first order, treating all inputs as independent.
+#[derive(Clone, Debug)]
+pub(crate) struct Uncertain {
+    value: Number,
+    // always non-negative, and in the same unit as `value`
+    uncertainty: Number,
+}
+
+impl Uncertain {
+    pub(crate) fn new<I: Interrupt>(
+        value: Number,
+        uncertainty: Number,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let uncertainty = in_unit_of(uncertainty.abs(int)?, &value, int)?;
+        Ok(Self { value, uncertainty })
+    }
+
+    pub(crate) fn exact(value: Number) -> Self {
+        Self {
+            uncertainty: Number::from(0),
+            value,
+        }
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        Ok(self.value.compare(&rhs.value, int)? == Ordering::Equal
+            && self.uncertainty.compare(&rhs.uncertainty, int)? == Ordering::Equal)
+    }
+
+    /// Computes `f(args)`. The uncertainty of the result is estimated by
+    /// varying each argument by its uncertainty: if `f(x ± σ)` differs
+    /// by `±d`, then the contribution to the result's uncertainty is `d`.
+    /// Contributions of different arguments are added in quadrature.
+    pub(crate) fn propagate<I: Interrupt>(
+        args: &[Self],
+        mut f: impl FnMut(Vec<Number>) -> Result<Number, FendError>,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let values: Vec<Number> = args.iter().map(|a| a.value.clone()).collect();
+        let value = f(values.clone())?;
+        let mut variance: Option<Number> = None;
+        for (idx, arg) in args.iter().enumerate() {
+            test_int(int)?;
+            if arg.uncertainty.is_zero() {
+                continue;
+            }
+            let mut high = values.clone();
+            high[idx] = arg.value.clone().add(arg.uncertainty.clone(), int)?;
+            let mut low = values.clone();
+            low[idx] = arg.value.clone().sub(arg.uncertainty.clone(), int)?;
+            let difference = f(high)?.sub(f(low)?, int)?.div(2.into(), int)?;
+            let square = difference.clone().mul(difference, int)?;
+            variance = Some(match variance {
+                Some(v) => v.add(square, int)?,
+                None => square,
+            });
+        }
+        let uncertainty = match variance {
+            Some(v) => v.pow(Number::from(1).div(2.into(), int)?, int)?,
+            None => Number::from(0),
+        };
+        Self::new(value, uncertainty, int)
+    }
+
+    pub(crate) fn convert_to<I: Interrupt>(self, unit: Number, int: &I) -> Result<Self, FendError> {
+        let value = self.value.convert_to(unit.clone(), int)?;
+        let uncertainty = self.uncertainty.convert_to(unit, int)?;
+        Self::new(value, uncertainty, int)
+    }
+
+    /// Formats the value as e.g. `5.0 ± 0.1`. The uncertainty is rounded
+    /// to one significant figure, and the value is rounded to the same
+    /// decimal place.
+    pub(crate) fn format<I: Interrupt>(
+        &self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Vec<Span>, FendError> {
+        let (value, uncertainty, decimal_places) =
+            match self.uncertainty.leading_digit_power(int)? {
+                Some(power) => {
+                    let uncertainty = self.uncertainty.clone().round_to_power(power, int)?;
+                    // rounding may carry into the next digit, e.g. 0.096 -> 0.1
+                    let power = uncertainty.leading_digit_power(int)?.unwrap_or(power);
+                    let value = self.value.clone().round_to_power(power, int)?;
+                    let decimal_places = usize::try_from(-power).unwrap_or(0);
+                    (
+                        value.with_format(FormattingStyle::DecimalPlaces(decimal_places)),
+                        uncertainty,
+                        decimal_places,
+                    )
+                }
+                None => (self.value.clone(), self.uncertainty.clone(), 0),
+            };
+        let mut value = value.format(ctx, int)?;
+        value.pad_decimal_places(decimal_places);
+        let uncertainty = uncertainty
+            .with_format(FormattingStyle::SignificantFigures(1))
+            .format(ctx, int)?;
+        let mut spans = vec![];
+        push_number(&mut spans, value.number(), value.unit_str());
+        spans.push(Span {
+            string: " \u{b1} ".to_string(),
+            kind: SpanKind::Other,
+        });
+        push_number(&mut spans, uncertainty.number(), uncertainty.unit_str());
+        Ok(spans)
+    }
+}
+
+/// Returns `x` in the same unit as `target`
+fn in_unit_of<I: Interrupt>(x: Number, target: &Number, int: &I) -> Result<Number, FendError> {
+    target.clone().mul(0.into(), int)?.add(x, int)
+}
+
+fn push_number(spans: &mut Vec<Span>, number: &str, unit: &str) {
+    spans.push(Span {
+        string: number.to_string(),
+        kind: SpanKind::Number,
+    });
+    if !unit.is_empty() {
+        spans.push(Span {
+            string: unit.to_string(),
+            kind: SpanKind::Ident,
+        });
+    }
+}
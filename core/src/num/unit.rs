@@ -1,12 +1,17 @@
 use crate::ast::Bop;
 use crate::error::{FendError, Interrupt};
+use crate::interner::SharedStr;
+use crate::num::bigrat::BigRat;
+use crate::num::biguint::BigUint;
 use crate::num::complex::{Complex, UseParentheses};
 use crate::num::dist::Dist;
-use crate::num::{Base, FormattingStyle};
+use crate::num::real::Real;
+use crate::num::{Base, FormattingStyle, RoundingMode, DEFAULT_PRECISION};
 use crate::scope::Scope;
+use crate::value::dimension::Dimension;
 use crate::{ast, ident::Ident};
-use crate::{Span, SpanKind};
-use std::borrow::Cow;
+use crate::{Span, SpanKind, UnitExponentStyle};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 use std::ops::Neg;
@@ -30,6 +35,9 @@ pub(crate) struct Value {
     base: Base,
     format: FormattingStyle,
     simplifiable: bool,
+    // number of significant figures, for literals like `2.50` (see
+    // `Context::track_significant_figures`)
+    significant_figures: Option<usize>,
 }
 
 impl Value {
@@ -40,6 +48,24 @@ impl Value {
         self.try_as_usize_unit(int)
     }
 
+    pub(crate) fn try_as_u64<I: Interrupt>(self, int: &I) -> Result<u64, FendError> {
+        u64::try_from(self.try_as_usize(int)?).map_err(|_| FendError::CannotConvertToInteger)
+    }
+
+    pub(crate) fn try_as_i64<I: Interrupt>(self, int: &I) -> Result<i64, FendError> {
+        if !self.is_unitless() {
+            return Err(FendError::NumberWithUnitToInt);
+        }
+        if self.compare(&0.into(), int)? == Ordering::Less {
+            let magnitude = (-self).try_as_u64(int)?;
+            i64::try_from(magnitude)
+                .map(|m| -m)
+                .map_err(|_| FendError::CannotConvertToInteger)
+        } else {
+            i64::try_from(self.try_as_u64(int)?).map_err(|_| FendError::CannotConvertToInteger)
+        }
+    }
+
     pub(crate) fn try_as_usize_unit<I: Interrupt>(self, int: &I) -> Result<usize, FendError> {
         if !self.exact {
             return Err(FendError::InexactNumberToInt);
@@ -49,9 +75,9 @@ impl Value {
 
     pub(crate) fn create_unit_value_from_value<I: Interrupt>(
         value: &Self,
-        prefix: Cow<'static, str>,
-        singular_name: Cow<'static, str>,
-        plural_name: Cow<'static, str>,
+        prefix: SharedStr,
+        singular_name: SharedStr,
+        plural_name: SharedStr,
         int: &I,
     ) -> Result<Self, FendError> {
         let (hashmap, scale) = value.unit.to_hashmap_and_scale(int)?;
@@ -63,14 +89,17 @@ impl Value {
         Ok(result)
     }
 
-    pub(crate) fn new_base_unit(
-        singular_name: Cow<'static, str>,
-        plural_name: Cow<'static, str>,
-    ) -> Self {
+    pub(crate) fn new_base_unit(singular_name: SharedStr, plural_name: SharedStr) -> Self {
         let base_unit = BaseUnit::new(singular_name.clone());
         let mut hashmap = HashMap::new();
         hashmap.insert(base_unit, 1.into());
-        let unit = NamedUnit::new(Cow::Borrowed(""), singular_name, plural_name, hashmap, 1);
+        let unit = NamedUnit::new(
+            SharedStr::Static(""),
+            singular_name,
+            plural_name,
+            hashmap,
+            1,
+        );
         Self::new(1, vec![UnitExponent::new(unit, 1)])
     }
 
@@ -81,6 +110,7 @@ impl Value {
             exact: self.exact,
             base: self.base,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
             format,
         }
     }
@@ -92,10 +122,103 @@ impl Value {
             exact: self.exact,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
             base,
         }
     }
 
+    pub(crate) fn with_significant_figures(self, significant_figures: Option<usize>) -> Self {
+        Self {
+            significant_figures,
+            ..self
+        }
+    }
+
+    /// Returns the power of ten of the leading digit, e.g. -2 for 0.05 or
+    /// 1 for 60. Returns `None` for zero or for distributions.
+    pub(crate) fn leading_digit_power<I: Interrupt>(
+        &self,
+        int: &I,
+    ) -> Result<Option<i64>, FendError> {
+        if self.is_zero() || self.value.one_point_ref().is_err() {
+            return Ok(None);
+        }
+        let x = Self::new(self.value.clone(), vec![]).abs(int)?;
+        let mut power = 0;
+        let mut power_of_ten = Self::from(1);
+        loop {
+            let next = power_of_ten.clone().mul(10.into(), int)?;
+            if x.compare(&next, int)? == Ordering::Less {
+                break;
+            }
+            power_of_ten = next;
+            power += 1;
+        }
+        while x.compare(&power_of_ten, int)? == Ordering::Less {
+            power_of_ten = power_of_ten.div(10.into(), int)?;
+            power -= 1;
+        }
+        Ok(Some(power))
+    }
+
+    /// Rounds to the nearest multiple of `10^power` (in the unit of `self`)
+    pub(crate) fn round_to_power<I: Interrupt>(
+        self,
+        power: i64,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut granularity = Self::from(10).pow(Self::from(power.unsigned_abs()), int)?;
+        if power < 0 {
+            granularity = Self::from(1).div(granularity, int)?;
+        }
+        self.div(granularity.clone(), int)?
+            .round(None, RoundingMode::Nearest, int)?
+            .mul(granularity, int)
+    }
+
+    /// Returns the power of ten of the last significant digit
+    fn last_significant_digit<I: Interrupt>(&self, int: &I) -> Result<Option<i64>, FendError> {
+        Ok(match self.significant_figures {
+            Some(significant_figures) => self
+                .leading_digit_power(int)?
+                .map(|power| power - i64::try_from(significant_figures).unwrap_or(i64::MAX) + 1),
+            None => None,
+        })
+    }
+
+    /// When adding or subtracting, the result is only as precise as the
+    /// least precise operand, e.g. `12.3 + 1.234` is precise to 0.1
+    fn least_precise_digit<I: Interrupt>(
+        &self,
+        rhs: &Self,
+        int: &I,
+    ) -> Result<Option<i64>, FendError> {
+        Ok(
+            match (
+                self.last_significant_digit(int)?,
+                rhs.last_significant_digit(int)?,
+            ) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, None) => a,
+                (None, b) => b,
+            },
+        )
+    }
+
+    fn with_last_significant_digit<I: Interrupt>(
+        self,
+        last_digit: Option<i64>,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let significant_figures = match last_digit {
+            Some(last_digit) => self
+                .leading_digit_power(int)?
+                .map(|power| usize::try_from(power - last_digit + 1).unwrap_or(0)),
+            None => None,
+        };
+        Ok(self.with_significant_figures(significant_figures))
+    }
+
     pub(crate) fn factorial<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
         if !self.is_unitless() {
             return Err(FendError::FactorialUnitless);
@@ -107,9 +230,47 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
+    /// Computes `self! / rhs!` by only multiplying the factors that don't
+    /// cancel out, e.g. `100! / 98!` is computed as `100 * 99`
+    pub(crate) fn factorial_quotient<I: Interrupt>(
+        self,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let integers = if self.is_unitless() && rhs.is_unitless() {
+            self.clone()
+                .try_as_u64(int)
+                .ok()
+                .zip(rhs.clone().try_as_u64(int).ok())
+        } else {
+            None
+        };
+        match integers {
+            Some((a, b)) => {
+                let product = BigUint::product_range(a.min(b) + 1, a.max(b), int)?;
+                let product = Self {
+                    value: Dist::from(Complex::from(Real::from(BigRat::from(product)))),
+                    unit: self.unit,
+                    exact: self.exact && rhs.exact,
+                    base: self.base,
+                    format: self.format,
+                    simplifiable: self.simplifiable,
+                    significant_figures: self.significant_figures,
+                };
+                if a >= b {
+                    Ok(product)
+                } else {
+                    Self::from(1).div(product, int)
+                }
+            }
+            None => self.factorial(int)?.div(rhs.factorial(int)?, int),
+        }
+    }
+
     fn new(value: impl Into<Dist>, unit_components: Vec<UnitExponent>) -> Self {
         Self {
             value: value.into(),
@@ -120,24 +281,28 @@ impl Value {
             base: Base::default(),
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
         }
     }
 
     pub(crate) fn add<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        let last_digit = self.least_precise_digit(&rhs, int)?;
         let scale_factor = Unit::compute_scale_factor(&rhs.unit, &self.unit, int)?;
         let scaled = Exact::new(rhs.value, rhs.exact)
             .mul(&scale_factor.scale_1.apply(Dist::from), int)?
             .div(&scale_factor.scale_2.apply(Dist::from), int)?;
         let value =
             Exact::new(self.value, self.exact).add(&Exact::new(scaled.value, scaled.exact), int)?;
-        Ok(Self {
+        Self {
             value: value.value,
             unit: self.unit,
             exact: self.exact && rhs.exact && value.exact,
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
-        })
+            significant_figures: None,
+        }
+        .with_last_significant_digit(last_digit, int)
     }
 
     /// Called for implicit addition to modify the second operand.
@@ -173,23 +338,153 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: false,
+            significant_figures: self.significant_figures,
+        })
+    }
+
+    /// Like `convert_to`, but also converts between units with reciprocal
+    /// dimensions, e.g. from fuel efficiency to fuel consumption
+    /// (`30 mpg to L/100km`)
+    pub(crate) fn convert_to_or_reciprocal<I: Interrupt>(
+        self,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        match self.clone().convert_to(rhs.clone(), int) {
+            Err(e @ FendError::IncompatibleConversion { .. }) => {
+                let reciprocal_unit =
+                    Self::from(1).div(Self::new(1, self.unit.components.clone()), int)?;
+                match Unit::compute_scale_factor(&reciprocal_unit.unit, &rhs.unit, int) {
+                    Ok(_) => Self::from(1).div(self, int)?.convert_to(rhs, int),
+                    Err(FendError::Interrupted) => Err(FendError::Interrupted),
+                    Err(_) => Err(e),
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Converts `self` to the unit of `rhs`, keeping the numerical value of `rhs`
+    /// (e.g. converting `1 m` to the unit of `3 ft` gives `3.28... ft`)
+    pub(crate) fn convert_unit_of<I: Interrupt>(
+        self,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        self.convert_to(Self::new(1, rhs.unit.components), int)
+    }
+
+    /// Compares two numbers, converting `rhs` to the unit of `self` first
+    pub(crate) fn compare<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Ordering, FendError> {
+        let rhs = rhs.clone().convert_unit_of(self.clone(), int)?;
+        self.value
+            .one_point_ref()?
+            .compare(rhs.value.one_point_ref()?)
+    }
+
+    /// Returns the integer exponent of each base unit in this number's unit,
+    /// e.g. `[("meter", 2), ("second", -1)]` for `3 m^2/s`
+    pub(crate) fn base_unit_exponents<I: Interrupt>(
+        &self,
+        int: &I,
+    ) -> Result<Vec<(String, i64)>, FendError> {
+        let (hashmap, _) = self.unit.to_hashmap_and_scale(int)?;
+        let (hashmap, _, _) = Unit::reduce_hashmap(hashmap, int)?;
+        Unit::integer_exponents(&hashmap, int)
+    }
+
+    /// Rounds to a multiple of `granularity` (by default 1 in the unit of `self`).
+    /// A unitless integer granularity is instead interpreted as a number
+    /// of decimal places, so `round(x, 2)` rounds to the nearest 0.01 and
+    /// `round(x, -2)` to the nearest 100.
+    pub(crate) fn round<I: Interrupt>(
+        self,
+        granularity: Option<Self>,
+        mode: RoundingMode,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let unit_one = Self::new(1, self.unit.components.clone());
+        let granularity = match granularity {
+            None => unit_one.clone(),
+            Some(g) if g.is_unitless() => {
+                if let Ok(decimal_places) = g.clone().try_as_i64(int) {
+                    let scale = Self::from(10).pow(decimal_places.unsigned_abs().into(), int)?;
+                    if decimal_places < 0 {
+                        unit_one.clone().mul(scale, int)?
+                    } else {
+                        unit_one.clone().div(scale, int)?
+                    }
+                } else {
+                    unit_one.clone().mul(g, int)?
+                }
+            }
+            Some(g) => g,
+        };
+        let (base, format, simplifiable) = (self.base, self.format, self.simplifiable);
+        let (exact, significant_figures) = (self.exact, self.significant_figures);
+        let scaled = self.convert_unit_of(granularity.clone(), int)?;
+        let ratio = Exact::new(scaled.value, scaled.exact).div(
+            &Exact::new(granularity.value.clone(), granularity.exact),
+            int,
+        )?;
+        let rounded = Dist::from(ratio.value.one_point()?.round(mode, int)?);
+        let value = Exact::new(rounded, ratio.exact)
+            .mul(&Exact::new(granularity.value, granularity.exact), int)?;
+        let res = Self {
+            value: value.value,
+            unit: granularity.unit,
+            exact: exact && value.exact,
+            base,
+            format,
+            simplifiable,
+            significant_figures,
+        }
+        .convert_unit_of(unit_one, int)?;
+        Ok(Self {
+            simplifiable,
+            ..res
         })
     }
 
     pub(crate) fn sub<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        let last_digit = self.least_precise_digit(&rhs, int)?;
+        if let (Some(difference_unit), Some(_)) = (
+            self.unit.temperature_difference_unit(int)?,
+            rhs.unit.temperature_difference_unit(int)?,
+        ) {
+            // the difference between two absolute temperatures like
+            // `20 \u{b0}C - 68 \u{b0}F` is a temperature difference
+            let rhs = rhs.convert_unit_of(self.clone(), int)?;
+            let value =
+                Exact::new(self.value, self.exact).add(&-Exact::new(rhs.value, rhs.exact), int)?;
+            return Self {
+                value: value.value,
+                unit: Unit {
+                    components: vec![UnitExponent::new(difference_unit, 1)],
+                },
+                exact: value.exact,
+                base: self.base,
+                format: self.format,
+                simplifiable: self.simplifiable,
+                significant_figures: None,
+            }
+            .with_last_significant_digit(last_digit, int);
+        }
         let scale_factor = Unit::compute_scale_factor(&rhs.unit, &self.unit, int)?;
         let scaled = Exact::new(rhs.value, rhs.exact)
             .mul(&scale_factor.scale_1.apply(Dist::from), int)?
             .div(&scale_factor.scale_2.apply(Dist::from), int)?;
         let value = Exact::new(self.value, self.exact).add(&-scaled, int)?;
-        Ok(Self {
+        Self {
             value: value.value,
             unit: self.unit,
             exact: self.exact && rhs.exact && value.exact,
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
-        })
+            significant_figures: None,
+        }
+        .with_last_significant_digit(last_digit, int)
     }
 
     pub(crate) fn div<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
@@ -209,6 +504,10 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: min_significant_figures(
+                self.significant_figures,
+                rhs.significant_figures,
+            ),
         })
     }
 
@@ -227,6 +526,7 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
@@ -247,11 +547,30 @@ impl Value {
             Bop::Mul => self.mul(rhs, int),
             Bop::Div => self.div(rhs, int),
             Bop::Mod => self.modulo(rhs, int),
-            Bop::Pow => self.pow(rhs, int),
+            Bop::Pow => self.pow_with_precision(rhs, context.precision, int),
+            Bop::Polar => self.polar(rhs, None, context, int),
+            // `a ± b` is not a single number, see `UncertainValue`
+            Bop::PlusMinus => Err(FendError::UnsupportedUncertaintyOperation),
         }
     }
 
-    fn is_unitless(&self) -> bool {
+    /// Returns the (singular) names of this number's units, without prefixes
+    pub(crate) fn unit_names(&self) -> Vec<&str> {
+        self.unit
+            .components
+            .iter()
+            .map(|c| c.unit.prefix_and_name(false).1)
+            .collect()
+    }
+
+    /// Removes the unit while keeping the numerical value, e.g. `20 dBm`
+    /// becomes `20`
+    pub(crate) fn without_unit(mut self) -> Self {
+        self.unit = Unit { components: vec![] };
+        self
+    }
+
+    pub(crate) fn is_unitless(&self) -> bool {
         // todo this is broken for unitless components
         self.unit.components.is_empty()
     }
@@ -261,6 +580,17 @@ impl Value {
     }
 
     pub(crate) fn pow<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
+        self.pow_with_precision(rhs, DEFAULT_PRECISION, int)
+    }
+
+    /// Like `pow`, but irrational results (e.g. `2^(1/2)`) are accurate to
+    /// the given number of decimal places
+    pub(crate) fn pow_with_precision<I: Interrupt>(
+        self,
+        rhs: Self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Self, FendError> {
         if !rhs.is_unitless() {
             return Err(FendError::ExpUnitless);
         }
@@ -278,15 +608,26 @@ impl Value {
         let new_unit = Unit {
             components: new_components,
         };
-        let value = self.value.one_point()?.pow(rhs.value.one_point()?, int)?;
-        Ok(Self {
+        let value = self
+            .value
+            .one_point()?
+            .pow(rhs.value.one_point()?, precision, int)?;
+        let result = Self {
             value: value.value.into(),
             unit: new_unit,
             exact: self.exact && rhs.exact && exact_res && value.exact,
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
-        })
+            significant_figures: self.significant_figures,
+        };
+        if !result.exact && precision > DEFAULT_PRECISION {
+            // powers of approximate numbers can have many more digits than
+            // needed, which would make further calculations slow
+            let power = i64::try_from(precision).unwrap_or(i64::MAX) + 10;
+            return result.round_to_power(-power, int);
+        }
+        Ok(result)
     }
 
     pub(crate) fn i() -> Self {
@@ -297,6 +638,45 @@ impl Value {
             base: Base::default(),
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
+        }
+    }
+
+    /// Returns an approximation of `e`, accurate to the given number of
+    /// decimal places
+    pub(crate) fn e<I: Interrupt>(precision: usize, int: &I) -> Result<Self, FendError> {
+        let e = BigRat::from(1).exp(precision, int)?;
+        Ok(Self::new(Complex::from(Real::from(e)), vec![]).make_approximate())
+    }
+
+    /// Replaces multiples of pi with a rational approximation, accurate to
+    /// the given number of decimal places
+    pub(crate) fn approximate_pi<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        Ok(Self {
+            value: self
+                .value
+                .one_point()?
+                .approximate_pi(precision, int)?
+                .into(),
+            exact: false,
+            ..self
+        })
+    }
+
+    /// Like `approximate_pi`, but leaves numbers without multiples of pi or
+    /// square roots (including exact ones) unchanged
+    pub(crate) fn approximate_symbolic<I: Interrupt>(
+        self,
+        precision: usize,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        match self.value.one_point_ref() {
+            Ok(value) if value.is_symbolic() => self.approximate_pi(precision, int),
+            _ => Ok(self),
         }
     }
 
@@ -308,6 +688,7 @@ impl Value {
             base: Base::default(),
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
         }
     }
 
@@ -320,9 +701,47 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
+    /// Returns true if this number has an imaginary part
+    pub(crate) fn is_complex(&self) -> bool {
+        self.value
+            .one_point_ref()
+            .map_or(false, |value| !value.is_real())
+    }
+
+    /// Returns the argument of a complex number in radians. Any units are
+    /// ignored, so e.g. the phase of a current in amperes can be computed.
+    pub(crate) fn arg<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
+        let value = self.value.one_point()?.arg(int)?;
+        Ok(Self {
+            value: value.value.into(),
+            unit: Unit::unitless(),
+            exact: self.exact && value.exact,
+            base: self.base,
+            format: self.format,
+            simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
+        })
+    }
+
+    /// Creates the complex number with magnitude `self` and angle `angle`,
+    /// i.e. `self * (cos(angle) + i sin(angle))`
+    pub(crate) fn polar<I: Interrupt>(
+        self,
+        angle: Self,
+        scope: Option<Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let cos = angle.clone().cos(scope.clone(), context, int)?;
+        let sin = angle.sin(scope, context, int)?;
+        let rotation = cos.add(sin.mul(Self::i(), int)?, int)?;
+        self.mul(rotation, int)
+    }
+
     pub(crate) fn make_approximate(self) -> Self {
         Self {
             value: self.value,
@@ -331,6 +750,7 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         }
     }
 
@@ -342,6 +762,7 @@ impl Value {
             base,
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
         }
     }
 
@@ -374,6 +795,7 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
@@ -393,12 +815,13 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
     pub(crate) fn sample<I: Interrupt>(
         self,
-        ctx: &crate::Context,
+        ctx: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
         Ok(Self {
@@ -413,9 +836,35 @@ impl Value {
         context: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
+        let angle = if self.is_unitless() && context.angle_mode == crate::AngleMode::Degrees {
+            let degrees =
+                ast::resolve_identifier(&Ident::new_str("degrees"), scope.clone(), context, int)?
+                    .expect_num()?;
+            self.mul(degrees, int)?
+        } else {
+            self
+        };
         let radians = ast::resolve_identifier(&Ident::new_str("radians"), scope, context, int)?
             .expect_num()?;
-        self.convert_to(radians, int)
+        angle.convert_to(radians, int)
+    }
+
+    /// Converts the result of an inverse trigonometric function (in
+    /// radians) to degrees if the context uses degrees by default
+    pub(crate) fn convert_rad_to_default_angle_unit<I: Interrupt>(
+        self,
+        scope: Option<Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        match context.angle_mode {
+            crate::AngleMode::Radians => Ok(self),
+            crate::AngleMode::Degrees => {
+                let degrees = ast::resolve_identifier(&Ident::new_str("°"), scope, context, int)?
+                    .expect_num()?;
+                self.convert_to(degrees, int)
+            }
+        }
     }
 
     fn unitless() -> Self {
@@ -426,6 +875,7 @@ impl Value {
             base: Base::default(),
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
         }
     }
 
@@ -442,12 +892,13 @@ impl Value {
         context: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
+        let precision = context.precision;
         if let Ok(rad) = self.clone().convert_angle_to_rad(scope, context, int) {
             Ok(rad
-                .apply_fn_exact(Complex::sin, false, int)?
+                .apply_fn_exact(|x, int| x.sin(precision, int), false, int)?
                 .convert_to(Self::unitless(), int)?)
         } else {
-            self.apply_fn_exact(Complex::sin, false, int)
+            self.apply_fn_exact(|x, int| x.sin(precision, int), false, int)
         }
     }
 
@@ -457,11 +908,12 @@ impl Value {
         context: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
+        let precision = context.precision;
         if let Ok(rad) = self.clone().convert_angle_to_rad(scope, context, int) {
-            rad.apply_fn_exact(Complex::cos, false, int)?
+            rad.apply_fn_exact(|x, int| x.cos(precision, int), false, int)?
                 .convert_to(Self::unitless(), int)
         } else {
-            self.apply_fn_exact(Complex::cos, false, int)
+            self.apply_fn_exact(|x, int| x.cos(precision, int), false, int)
         }
     }
 
@@ -471,60 +923,61 @@ impl Value {
         context: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
+        let precision = context.precision;
         if let Ok(rad) = self.clone().convert_angle_to_rad(scope, context, int) {
-            rad.apply_fn_exact(Complex::tan, false, int)?
+            rad.apply_fn_exact(|x, int| x.tan(precision, int), false, int)?
                 .convert_to(Self::unitless(), int)
         } else {
-            self.apply_fn_exact(Complex::tan, false, int)
+            self.apply_fn_exact(|x, int| x.tan(precision, int), false, int)
         }
     }
 
-    pub(crate) fn asin<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::asin, false, int)
+    pub(crate) fn asin<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn_exact(|x, int| x.asin(precision, int), false, int)
     }
 
-    pub(crate) fn acos<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::acos, false, int)
+    pub(crate) fn acos<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn_exact(|x, int| x.acos(precision, int), false, int)
     }
 
-    pub(crate) fn atan<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::atan, false, int)
+    pub(crate) fn atan<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn_exact(|x, int| x.atan(precision, int), false, int)
     }
 
-    pub(crate) fn sinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::sinh, false, int)
+    pub(crate) fn sinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.sinh(precision, int), false, int)
     }
 
-    pub(crate) fn cosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::cosh, false, int)
+    pub(crate) fn cosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.cosh(precision, int), false, int)
     }
 
-    pub(crate) fn tanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::tanh, false, int)
+    pub(crate) fn tanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.tanh(precision, int), false, int)
     }
 
-    pub(crate) fn asinh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::asinh, false, int)
+    pub(crate) fn asinh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.asinh(precision, int), false, int)
     }
 
-    pub(crate) fn acosh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::acosh, false, int)
+    pub(crate) fn acosh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.acosh(precision, int), false, int)
     }
 
-    pub(crate) fn atanh<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::atanh, false, int)
+    pub(crate) fn atanh<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.atanh(precision, int), false, int)
     }
 
-    pub(crate) fn ln<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::ln, true, int)
+    pub(crate) fn ln<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.ln(precision, int), true, int)
     }
 
-    pub(crate) fn log2<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::log2, true, int)
+    pub(crate) fn log2<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.log2(precision, int), true, int)
     }
 
-    pub(crate) fn log10<I: Interrupt>(self, int: &I) -> Result<Self, FendError> {
-        self.apply_fn(Complex::log10, true, int)
+    pub(crate) fn log10<I: Interrupt>(self, precision: usize, int: &I) -> Result<Self, FendError> {
+        self.apply_fn(|x, int| x.log10(precision, int), true, int)
     }
 
     pub(crate) fn format<I: Interrupt>(
@@ -532,6 +985,11 @@ impl Value {
         ctx: &crate::Context,
         int: &I,
     ) -> Result<FormattedValue, FendError> {
+        if ctx.track_significant_figures && self.format == FormattingStyle::default() {
+            if let Some(formatted) = self.format_significant_figures(ctx, int)? {
+                return Ok(formatted);
+            }
+        }
         let use_parentheses = if self.unit.components.is_empty() {
             UseParentheses::No
         } else {
@@ -556,6 +1014,7 @@ impl Value {
             self.base,
             self.format,
             true,
+            ctx.unit_exponent_style,
             int,
         )?;
         exact = exact && unit_string.exact;
@@ -566,6 +1025,36 @@ impl Value {
         })
     }
 
+    /// Rounds to the tracked number of significant figures, keeping trailing
+    /// zeroes, so e.g. `2.50 * 2` is shown as `5` and `2.50 * 2.00` as `5.00`
+    fn format_significant_figures<I: Interrupt>(
+        &self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Option<FormattedValue>, FendError> {
+        if self.significant_figures.is_none() {
+            return Ok(None);
+        }
+        let (significant_figures, power) =
+            match (self.significant_figures, self.leading_digit_power(int)?) {
+                (Some(sf), Some(power)) => (i64::try_from(sf).unwrap_or(i64::MAX), power),
+                _ => return Ok(None),
+            };
+        let rounded = self
+            .clone()
+            .with_significant_figures(None)
+            .approximate_symbolic(ctx.precision, int)?
+            .round_to_power(power - significant_figures + 1, int)?;
+        // rounding may carry into the next digit, e.g. 9.96 -> 10.0
+        let power = rounded.leading_digit_power(int)?.unwrap_or(power);
+        let decimal_places = usize::try_from(significant_figures - power - 1).unwrap_or(0);
+        let mut formatted = rounded
+            .with_format(FormattingStyle::DecimalPlaces(decimal_places))
+            .format(ctx, int)?;
+        formatted.pad_decimal_places(decimal_places);
+        Ok(Some(formatted))
+    }
+
     pub(crate) fn mul<I: Interrupt>(self, rhs: Self, int: &I) -> Result<Self, FendError> {
         let components = [self.unit.components, rhs.unit.components].concat();
         let value =
@@ -577,6 +1066,10 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: min_significant_figures(
+                self.significant_figures,
+                rhs.significant_figures,
+            ),
         })
     }
 
@@ -631,7 +1124,7 @@ impl Value {
                         res_comp.exponent = sum.value;
                         res_exact = res_exact && sum.exact && scale.exact;
 
-                        let scale = scale.value.pow(comp.exponent, int)?;
+                        let scale = scale.value.pow(comp.exponent, DEFAULT_PRECISION, int)?;
                         let adjusted_value = Exact {
                             value: res_value.one_point()?,
                             exact: res_exact,
@@ -664,6 +1157,7 @@ impl Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         })
     }
 
@@ -672,6 +1166,14 @@ impl Value {
     }
 }
 
+fn min_significant_figures(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
 impl Neg for Value {
     type Output = Self;
     fn neg(self) -> Self {
@@ -682,6 +1184,7 @@ impl Neg for Value {
             base: self.base,
             format: self.format,
             simplifiable: self.simplifiable,
+            significant_figures: self.significant_figures,
         }
     }
 }
@@ -695,6 +1198,7 @@ impl From<u64> for Value {
             base: Base::default(),
             format: FormattingStyle::default(),
             simplifiable: true,
+            significant_figures: None,
         }
     }
 }
@@ -722,6 +1226,36 @@ pub(crate) struct FormattedValue {
 }
 
 impl FormattedValue {
+    pub(crate) fn number(&self) -> &str {
+        &self.number
+    }
+
+    pub(crate) fn unit_str(&self) -> &str {
+        &self.unit_str
+    }
+
+    /// Adds trailing zeroes so that e.g. `5` is shown as `5.00`
+    pub(crate) fn pad_decimal_places(&mut self, decimal_places: usize) {
+        if decimal_places == 0
+            || !self
+                .number
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+        {
+            return;
+        }
+        let current = self
+            .number
+            .split_once('.')
+            .map(|(_, fraction)| fraction.len());
+        if current.is_none() {
+            self.number.push('.');
+        }
+        for _ in current.unwrap_or(0)..decimal_places {
+            self.number.push('0');
+        }
+    }
+
     pub(crate) fn spans(self, spans: &mut Vec<Span>) {
         if !self.exact {
             spans.push(Span {
@@ -788,6 +1322,40 @@ impl Unit {
         prefix.is_empty() && name == rhs
     }
 
+    /// Returns `deltaC` or `deltaF` if this unit is an absolute temperature
+    /// scale like `\u{b0}C` or `\u{b0}F`
+    fn temperature_difference_unit<I: Interrupt>(
+        &self,
+        int: &I,
+    ) -> Result<Option<NamedUnit>, FendError> {
+        if self.components.len() != 1 {
+            return Ok(None);
+        }
+        let (hashmap, scale) = self.to_hashmap_and_scale(int)?;
+        if hashmap.len() != 1 || scale.value != 1.into() {
+            return Ok(None);
+        }
+        let (name, scale) = if hashmap.get(&BaseUnit::new_static("celsius")) == Some(&1.into()) {
+            ("deltaC", Complex::from(1))
+        } else if hashmap.get(&BaseUnit::new_static("fahrenheit")) == Some(&1.into()) {
+            let scale = Exact::new(Complex::from(5), true)
+                .div(Exact::new(Complex::from(9), true), int)?
+                .value;
+            ("deltaF", scale)
+        } else {
+            return Ok(None);
+        };
+        let mut base_units = HashMap::new();
+        base_units.insert(BaseUnit::new_static("kelvin"), 1.into());
+        Ok(Some(NamedUnit::new(
+            SharedStr::Static(""),
+            SharedStr::Static(name),
+            SharedStr::Static(name),
+            base_units,
+            scale,
+        )))
+    }
+
     fn to_hashmap_and_scale<I: Interrupt>(&self, int: &I) -> Result<HashmapScale, FendError> {
         let mut hashmap = HashMap::<BaseUnit, Complex>::new();
         let mut scale = Complex::from(1);
@@ -802,11 +1370,9 @@ impl Unit {
         hashmap: HashMap<BaseUnit, Complex>,
         int: &I,
     ) -> Result<HashmapScaleOffset, FendError> {
-        if hashmap.len() == 1
-            && hashmap.get(&BaseUnit::new(Cow::Borrowed("celsius"))) == Some(&1.into())
-        {
+        if hashmap.len() == 1 && hashmap.get(&BaseUnit::new_static("celsius")) == Some(&1.into()) {
             let mut result_hashmap = HashMap::new();
-            result_hashmap.insert(BaseUnit::new(Cow::Borrowed("kelvin")), 1.into());
+            result_hashmap.insert(BaseUnit::new_static("kelvin"), 1.into());
             return Ok((
                 result_hashmap,
                 Exact::new(1.into(), true),
@@ -814,11 +1380,10 @@ impl Unit {
                     .div(Exact::new(Complex::from(100), true), int)?,
             ));
         }
-        if hashmap.len() == 1
-            && hashmap.get(&BaseUnit::new(Cow::Borrowed("fahrenheit"))) == Some(&1.into())
+        if hashmap.len() == 1 && hashmap.get(&BaseUnit::new_static("fahrenheit")) == Some(&1.into())
         {
             let mut result_hashmap = HashMap::new();
-            result_hashmap.insert(BaseUnit::new(Cow::Borrowed("kelvin")), 1.into());
+            result_hashmap.insert(BaseUnit::new_static("kelvin"), 1.into());
             return Ok((
                 result_hashmap,
                 Exact::new(Complex::from(5), true).div(Exact::new(Complex::from(9), true), int)?,
@@ -837,7 +1402,7 @@ impl Unit {
                     &Exact::new(Complex::from(5), true)
                         .div(Exact::new(Complex::from(9), true), int)?
                         .value
-                        .pow(exponent.clone(), int)?,
+                        .pow(exponent.clone(), DEFAULT_PRECISION, int)?,
                     int,
                 )?;
             }
@@ -846,11 +1411,66 @@ impl Unit {
         Ok((result_hashmap, scale_adjustment, Exact::new(0.into(), true)))
     }
 
+    fn integer_exponents<I: Interrupt>(
+        hashmap: &HashMap<BaseUnit, Complex>,
+        int: &I,
+    ) -> Result<Vec<(String, i64)>, FendError> {
+        let mut result = vec![];
+        for (base_unit, exponent) in hashmap {
+            let exponent = if exponent.compare(&0.into())? == Ordering::Less {
+                -i64::try_from((-exponent.clone()).try_as_usize(int)?)
+                    .map_err(|_| FendError::CannotConvertToInteger)?
+            } else {
+                i64::try_from(exponent.clone().try_as_usize(int)?)
+                    .map_err(|_| FendError::CannotConvertToInteger)?
+            };
+            result.push((base_unit.name().to_string(), exponent));
+        }
+        Ok(result)
+    }
+
+    /// Returns e.g. `energy` or `length^2 / time`, or `None` if the
+    /// dimension has non-integer exponents
+    fn describe_dimension<I: Interrupt>(
+        hashmap: &HashMap<BaseUnit, Complex>,
+        int: &I,
+    ) -> Result<Option<String>, FendError> {
+        match Self::integer_exponents(hashmap, int) {
+            Ok(exponents) => Ok(Some(Dimension::new(exponents).describe())),
+            Err(FendError::Interrupted) => Err(FendError::Interrupted),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Returns the cryptocurrency that is missing an exchange rate if
+    /// the conversion fails because it can't be converted to other
+    /// currencies (e.g. `BTC to USD` without an exchange rate source)
+    fn missing_exchange_rate(
+        hash_a: &HashMap<BaseUnit, Complex>,
+        hash_b: &HashMap<BaseUnit, Complex>,
+    ) -> Option<String> {
+        let is_crypto = |base_unit: &BaseUnit| {
+            crate::units::builtin::is_cryptocurrency(base_unit.name())
+                && hash_a.get(base_unit) != hash_b.get(base_unit)
+        };
+        let eur = BaseUnit::new_static("_EUR");
+        let mut cryptos = hash_a.keys().chain(hash_b.keys()).filter(|u| is_crypto(u));
+        let crypto = cryptos.next()?;
+        if hash_a.get(&eur) != hash_b.get(&eur) || cryptos.any(|u| u != crypto) {
+            Some(crypto.name().to_string())
+        } else {
+            None
+        }
+    }
+
     fn print_base_units<I: Interrupt>(
         hash: HashMap<BaseUnit, Complex>,
         int: &I,
     ) -> Result<String, FendError> {
-        let from_base_units: Vec<_> = hash
+        let mut base_units: Vec<_> = hash.into_iter().collect();
+        // sort for deterministic error messages
+        base_units.sort_by(|(a, _), (b, _)| a.name().cmp(b.name()));
+        let from_base_units: Vec<_> = base_units
             .into_iter()
             .map(|(base_unit, exponent)| {
                 UnitExponent::new(NamedUnit::new_from_base(base_unit), exponent)
@@ -865,6 +1485,7 @@ impl Unit {
             Base::default(),
             FormattingStyle::Auto,
             false,
+            UnitExponentStyle::Slash,
             int,
         )?
         .value)
@@ -887,6 +1508,9 @@ impl Unit {
                 scale_2: scale_b.mul(&adj_b, int)?,
             })
         } else {
+            if let Some(code) = Self::missing_exchange_rate(&hash_a, &hash_b) {
+                return Err(FendError::NoExchangeRate(code));
+            }
             let from_formatted = from
                 .format(
                     "unitless",
@@ -894,6 +1518,7 @@ impl Unit {
                     Base::default(),
                     FormattingStyle::Auto,
                     false,
+                    UnitExponentStyle::Slash,
                     int,
                 )?
                 .value;
@@ -904,12 +1529,20 @@ impl Unit {
                     Base::default(),
                     FormattingStyle::Auto,
                     false,
+                    UnitExponentStyle::Slash,
                     int,
                 )?
                 .value;
             Err(FendError::IncompatibleConversion {
                 from: from_formatted,
                 to: into_formatted,
+                dimensions: match (
+                    Self::describe_dimension(&hash_a, int)?,
+                    Self::describe_dimension(&hash_b, int)?,
+                ) {
+                    (Some(a), Some(b)) => Some(Box::new((a, b))),
+                    _ => None,
+                },
                 from_base: Self::print_base_units(hash_a, int)?,
                 to_base: Self::print_base_units(hash_b, int)?,
             })
@@ -920,6 +1553,7 @@ impl Unit {
         Self { components: vec![] }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn format<I: Interrupt>(
         &self,
         unitless: &str,
@@ -927,6 +1561,7 @@ impl Unit {
         base: Base,
         format: FormattingStyle,
         consider_printing_space: bool,
+        style: UnitExponentStyle,
         int: &I,
     ) -> Result<Exact<String>, FendError> {
         let mut unit_string = String::new();
@@ -948,8 +1583,9 @@ impl Unit {
                 positive_components.push(unit_exponent);
             }
         }
-        let invert_negative_component =
-            !positive_components.is_empty() && negative_components.len() == 1;
+        let invert_negative_component = style != UnitExponentStyle::NegativeExponents
+            && !positive_components.is_empty()
+            && negative_components.len() == 1;
         let mut merged_components = vec![];
         let pluralised_idx = if positive_components.is_empty() {
             usize::MAX
@@ -978,7 +1614,14 @@ impl Unit {
             } else {
                 format
             };
-            let formatted_exp = unit_exponent.format(base, exp_format, plural, invert, int)?;
+            let formatted_exp = unit_exponent.format(
+                base,
+                exp_format,
+                plural,
+                invert,
+                style == UnitExponentStyle::Superscript,
+                int,
+            )?;
             unit_string.push_str(formatted_exp.value.to_string().as_str());
             exact = exact && formatted_exp.exact;
         }
@@ -1033,9 +1676,9 @@ mod tests {
         hashmap.insert(base_kg, 1.into());
         let kg = NamedUnit::new("k".into(), "g".into(), "g".into(), hashmap.clone(), 1);
         let g = NamedUnit::new(
-            Cow::Borrowed(""),
-            Cow::Borrowed("g"),
-            Cow::Borrowed("g"),
+            SharedStr::Static(""),
+            SharedStr::Static("g"),
+            SharedStr::Static("g"),
             hashmap,
             Exact::new(Complex::from(1), true)
                 .div(Exact::new(1000.into(), true), int)
@@ -1,9 +1,10 @@
-use std::{borrow::Cow, fmt};
+use crate::interner::SharedStr;
+use std::fmt;
 
 /// Represents a base unit, identified solely by its name. The name is not exposed to the user.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub(crate) struct BaseUnit {
-    name: Cow<'static, str>,
+    pub(super) name: SharedStr,
 }
 
 impl fmt::Debug for BaseUnit {
@@ -13,13 +14,13 @@ impl fmt::Debug for BaseUnit {
 }
 
 impl BaseUnit {
-    pub(crate) const fn new(name: Cow<'static, str>) -> Self {
+    pub(crate) const fn new(name: SharedStr) -> Self {
         Self { name }
     }
 
     pub(crate) const fn new_static(name: &'static str) -> Self {
         Self {
-            name: Cow::Borrowed(name),
+            name: SharedStr::Static(name),
         }
     }
 
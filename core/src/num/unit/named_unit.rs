@@ -1,23 +1,24 @@
-use std::{borrow::Cow, collections::HashMap, fmt};
+use std::{collections::HashMap, fmt};
 
 use super::base_unit::BaseUnit;
+use crate::interner::SharedStr;
 use crate::num::complex::Complex;
 
 /// A named unit, like kilogram, megabyte or percent.
 #[derive(Clone, Eq, PartialEq)]
 pub(crate) struct NamedUnit {
-    prefix: Cow<'static, str>,
-    singular_name: Cow<'static, str>,
-    plural_name: Cow<'static, str>,
+    prefix: SharedStr,
+    singular_name: SharedStr,
+    plural_name: SharedStr,
     pub(super) base_units: HashMap<BaseUnit, Complex>,
     pub(super) scale: Complex,
 }
 
 impl NamedUnit {
     pub(crate) fn new(
-        prefix: Cow<'static, str>,
-        singular_name: Cow<'static, str>,
-        plural_name: Cow<'static, str>,
+        prefix: SharedStr,
+        singular_name: SharedStr,
+        plural_name: SharedStr,
         base_units: HashMap<BaseUnit, Complex>,
         scale: impl Into<Complex>,
     ) -> Self {
@@ -33,8 +34,8 @@ impl NamedUnit {
     pub(crate) fn new_from_base(base_unit: BaseUnit) -> Self {
         Self {
             prefix: "".into(),
-            singular_name: base_unit.name().to_string().into(),
-            plural_name: base_unit.name().to_string().into(),
+            singular_name: base_unit.name.clone(),
+            plural_name: base_unit.name.clone(),
             base_units: {
                 let mut base_units = HashMap::new();
                 base_units.insert(base_unit, 1.into());
@@ -5,7 +5,7 @@ use crate::{
     interrupt::test_int,
     num::{
         complex::{self, Complex, UseParentheses},
-        Base, Exact, FormattingStyle,
+        Base, Exact, FormattingStyle, DEFAULT_PRECISION,
     },
     Interrupt,
 };
@@ -56,11 +56,11 @@ impl UnitExponent {
                 }
             }
         }
-        let pow_result = self
-            .unit
-            .scale
-            .clone()
-            .pow(overall_exp.value.clone(), int)?;
+        let pow_result =
+            self.unit
+                .scale
+                .clone()
+                .pow(overall_exp.value.clone(), DEFAULT_PRECISION, int)?;
         *scale = Exact::new(scale.clone(), true).mul(&pow_result, int)?.value;
         *exact = *exact && pow_result.exact;
         Ok(())
@@ -72,6 +72,7 @@ impl UnitExponent {
         format: FormattingStyle,
         plural: bool,
         invert_exp: bool,
+        superscript: bool,
         int: &I,
     ) -> Result<Exact<FormattedExponent<'_>>, FendError> {
         let (prefix, name) = self.unit.prefix_and_name(plural);
@@ -83,8 +84,14 @@ impl UnitExponent {
         let (exact, exponent) = if exp == 1.into() {
             (true, None)
         } else {
-            let formatted =
-                exp.format(true, format, base, UseParentheses::IfComplexOrFraction, int)?;
+            let formatted = exp.format(
+                true,
+                format,
+                base,
+                UseParentheses::IfComplexOrFraction,
+                DEFAULT_PRECISION,
+                int,
+            )?;
             (formatted.exact, Some(formatted.value))
         };
         Ok(Exact::new(
@@ -92,6 +99,7 @@ impl UnitExponent {
                 prefix,
                 name,
                 number: exponent,
+                superscript,
             },
             exact,
         ))
@@ -113,14 +121,41 @@ pub(crate) struct FormattedExponent<'a> {
     prefix: &'a str,
     name: &'a str,
     number: Option<complex::Formatted>,
+    // e.g. `s²` instead of `s^2`, for integer exponents
+    superscript: bool,
 }
 
 impl<'a> fmt::Display for FormattedExponent<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}{}", self.prefix, self.name.replace('_', " "))?;
         if let Some(number) = &self.number {
-            write!(f, "^{}", number)?;
+            let number = number.to_string();
+            match to_superscript(&number) {
+                Some(superscript) if self.superscript => write!(f, "{}", superscript)?,
+                _ => write!(f, "^{}", number)?,
+            }
         }
         Ok(())
     }
 }
+
+/// Converts an integer exponent like `-2` to superscript characters (`⁻²`)
+fn to_superscript(exponent: &str) -> Option<String> {
+    exponent
+        .chars()
+        .map(|ch| match ch {
+            '-' => Some('⁻'),
+            '0' => Some('⁰'),
+            '1' => Some('¹'),
+            '2' => Some('²'),
+            '3' => Some('³'),
+            '4' => Some('⁴'),
+            '5' => Some('⁵'),
+            '6' => Some('⁶'),
+            '7' => Some('⁷'),
+            '8' => Some('⁸'),
+            '9' => Some('⁹'),
+            _ => None,
+        })
+        .collect()
+}
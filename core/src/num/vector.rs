@@ -0,0 +1,52 @@
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::Number;
+
+fn expect_same_length(a: &[Number], b: &[Number]) -> Result<(), FendError> {
+    if a.len() != b.len() {
+        return Err(FendError::VectorLengthMismatch);
+    }
+    Ok(())
+}
+
+/// Computes the dot product, e.g. `dot([1, 2], [3 m, 4 m])` is 11 m
+pub(crate) fn dot<I: Interrupt>(
+    a: Vec<Number>,
+    b: Vec<Number>,
+    int: &I,
+) -> Result<Number, FendError> {
+    expect_same_length(&a, &b)?;
+    let mut sum: Option<Number> = None;
+    for (x, y) in a.into_iter().zip(b) {
+        test_int(int)?;
+        let product = x.mul(y, int)?;
+        sum = Some(match sum {
+            Some(sum) => sum.add(product, int)?,
+            None => product,
+        });
+    }
+    sum.ok_or(FendError::ExpectedAtLeastOneArgument)
+}
+
+pub(crate) fn cross<I: Interrupt>(
+    a: &[Number],
+    b: &[Number],
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    expect_same_length(a, b)?;
+    if a.len() != 3 {
+        return Err(FendError::ExpectedA3dVector);
+    }
+    let component = |i: usize, j: usize| -> Result<Number, FendError> {
+        a[i].clone()
+            .mul(b[j].clone(), int)?
+            .sub(a[j].clone().mul(b[i].clone(), int)?, int)
+    };
+    Ok(vec![component(1, 2)?, component(2, 0)?, component(0, 1)?])
+}
+
+/// Computes the Euclidean length of a vector
+pub(crate) fn norm<I: Interrupt>(v: Vec<Number>, int: &I) -> Result<Number, FendError> {
+    let half = Number::from(1).div(2.into(), int)?;
+    dot(v.clone(), v, int)?.pow(half, int)
+}
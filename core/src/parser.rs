@@ -1,6 +1,9 @@
-use crate::ast::{Bop, Expr};
+use crate::ast::{Bop, Expr, Param};
+use crate::ident::Ident;
 use crate::lexer::{Symbol, Token};
+use crate::units::PrefixRule;
 use crate::value::Value;
+use std::cmp::Ordering;
 use std::fmt;
 
 #[derive(Debug)]
@@ -110,17 +113,88 @@ fn parse_parens(input: &[Token]) -> ParseResult<'_> {
     if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::CloseParens) {
         return Ok((Expr::Literal(Value::from(())), remaining));
     }
-    let (inner, mut input) = parse_expression(input)?;
+    let (inner, input) = parse_expression(input)?;
+    let (mut elements, mut input) = parse_list_cont(input, inner)?;
     // allow omitting closing parentheses at end of input
     if !input.is_empty() {
         let (_, remaining) = parse_fixed_symbol(input, Symbol::CloseParens)?;
         input = remaining;
     }
-    Ok((Expr::Parens(Box::new(inner)), input))
+    if elements.len() == 1 {
+        let inner = elements.pop().unwrap();
+        Ok((Expr::Parens(Box::new(inner)), input))
+    } else {
+        // e.g. `max(1, 2, 3)`
+        Ok((Expr::List(elements), input))
+    }
+}
+
+// parses any further comma-separated elements following `first`
+fn parse_list_cont(mut input: &[Token], first: Expr) -> ParseResult<'_, Vec<Expr>> {
+    let mut elements = vec![first];
+    while let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Comma) {
+        let (element, remaining) = parse_expression(remaining)?;
+        elements.push(element);
+        input = remaining;
+    }
+    Ok((elements, input))
+}
+
+fn parse_list(input: &[Token]) -> ParseResult<'_> {
+    let (_, input) = parse_fixed_symbol(input, Symbol::OpenBracket)?;
+    if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::CloseBracket) {
+        return Ok((Expr::List(vec![]), remaining));
+    }
+    let (first, input) = parse_expression(input)?;
+    let (elements, mut input) = parse_list_cont(input, first)?;
+    // allow omitting the closing bracket at end of input
+    if !input.is_empty() {
+        let (_, remaining) = parse_fixed_symbol(input, Symbol::CloseBracket)?;
+        input = remaining;
+    }
+    if elements
+        .iter()
+        .all(|element| matches!(element, Expr::List(_)))
+    {
+        return Ok((Expr::Matrix(elements), input));
+    }
+    Ok((Expr::List(elements), input))
+}
+
+// a parameter list like `(x, y=2)`, where each parameter may have a default
+fn parse_params(input: &[Token]) -> ParseResult<'_, Vec<Param>> {
+    let (list, input) = parse_parens(input)?;
+    let elements = match list {
+        Expr::Parens(inner) => vec![*inner],
+        Expr::List(elements) => elements,
+        _ => return Err(ParseError::ExpectedIdentifierAsArgument),
+    };
+    let mut params = vec![];
+    for element in elements {
+        params.push(match element {
+            Expr::Ident(ident) => (ident, None),
+            Expr::Assign(ident, default) => (ident, Some(*default)),
+            _ => return Err(ParseError::ExpectedIdentifierAsArgument),
+        });
+    }
+    Ok((params, input))
 }
 
 fn parse_backslash_lambda(input: &[Token]) -> ParseResult<'_> {
     let (_, input) = parse_fixed_symbol(input, Symbol::Backslash)?;
+    if let Ok((params, input)) = parse_params(input) {
+        let (_, input) = parse_fixed_symbol(input, Symbol::Dot)
+            .map_err(|e| ParseError::ExpectedDotInLambda(Box::new(e)))?;
+        let (body, input) = parse_function(input)?;
+        // e.g. `\(x, y=2).body` becomes `\x.\y.body`
+        let names: Vec<Ident> = params.iter().map(|(name, _)| name.clone()).collect();
+        let body = Expr::Params(params, Box::new(body));
+        let lambda = names
+            .into_iter()
+            .rev()
+            .fold(body, |body, name| Expr::Fn(name, Box::new(body)));
+        return Ok((lambda, input));
+    }
     let (ident, input) = if let (Expr::Ident(ident), input) = parse_ident(input)? {
         (ident, input)
     } else {
@@ -132,6 +206,29 @@ fn parse_backslash_lambda(input: &[Token]) -> ParseResult<'_> {
     Ok((Expr::Fn(ident, Box::new(rhs)), input))
 }
 
+// a template placeholder like `?a`
+fn parse_placeholder(input: &[Token]) -> ParseResult<'_> {
+    let (_, input) = parse_fixed_symbol(input, Symbol::Question)?;
+    match parse_token(input, false)? {
+        (Token::Ident(ident), remaining) => Ok((Expr::Placeholder(ident), remaining)),
+        _ => Err(ParseError::ExpectedIdentifier),
+    }
+}
+
+// a square root like `√2`, which binds more tightly than exponentiation,
+// so that results like `2√2` or `√3/2` can be parsed again
+fn parse_sqrt(input: &[Token]) -> ParseResult<'_> {
+    let (_, input) = parse_fixed_symbol(input, Symbol::Sqrt)?;
+    let (operand, input) = parse_factorial(input)?;
+    Ok((
+        Expr::ApplyFunctionCall(
+            Box::new(Expr::Ident(Ident::new_str("sqrt"))),
+            Box::new(operand),
+        ),
+        input,
+    ))
+}
+
 fn parse_parens_or_literal(input: &[Token]) -> ParseResult<'_> {
     let (token, remaining) = parse_token(input, true)?;
 
@@ -139,8 +236,12 @@ fn parse_parens_or_literal(input: &[Token]) -> ParseResult<'_> {
         Token::Num(_) => parse_number(input),
         Token::Ident(_) => parse_ident(input),
         Token::StringLiteral(s) => Ok((Expr::Literal(Value::String(s)), remaining)),
+        Token::Time(time) => Ok((Expr::Literal(time.into()), remaining)),
         Token::Symbol(Symbol::OpenParens) => parse_parens(input),
+        Token::Symbol(Symbol::OpenBracket) => parse_list(input),
         Token::Symbol(Symbol::Backslash) => parse_backslash_lambda(input),
+        Token::Symbol(Symbol::Question) => parse_placeholder(input),
+        Token::Symbol(Symbol::Sqrt) => parse_sqrt(input),
         Token::Symbol(s) => Err(ParseError::UnexpectedSymbol(s)),
         Token::Whitespace => Err(ParseError::UnexpectedWhitespace),
     }
@@ -286,6 +387,18 @@ fn parse_modulo_cont(input: &[Token]) -> ParseResult<'_> {
     Ok((b, input))
 }
 
+// polar form, e.g. `5 ∠ 30°`: the angle may itself have a unit, so
+// `5 ∠ 30° * 2` is parsed as `(5 ∠ 30°) * 2`
+fn parse_polar_cont(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_symbol(input, Symbol::Angle)?;
+    let (mut angle, mut input) = parse_power(input, true)?;
+    while let Ok((new_angle, remaining)) = parse_apply_cont(input, &angle) {
+        angle = new_angle;
+        input = remaining;
+    }
+    Ok((angle, input))
+}
+
 fn parse_multiplicative(input: &[Token]) -> ParseResult<'_> {
     let (mut res, mut input) = parse_power(input, true)?;
     loop {
@@ -298,6 +411,9 @@ fn parse_multiplicative(input: &[Token]) -> ParseResult<'_> {
         } else if let Ok((term, remaining)) = parse_modulo_cont(input) {
             res = Expr::Bop(Bop::Mod, Box::new(res.clone()), Box::new(term));
             input = remaining;
+        } else if let Ok((angle, remaining)) = parse_polar_cont(input) {
+            res = Expr::Bop(Bop::Polar, Box::new(res.clone()), Box::new(angle));
+            input = remaining;
         } else if let Ok((new_res, remaining)) = parse_mixed_fraction(input, &res) {
             res = new_res;
             input = remaining;
@@ -341,12 +457,37 @@ fn parse_subtraction_cont(input: &[Token]) -> ParseResult<'_> {
     Ok((b, input))
 }
 
+fn parse_plus_minus_cont(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_symbol(input, Symbol::PlusMinus)?;
+    let (b, input) = parse_implicit_addition(input)?;
+    Ok((b, input))
+}
+
 fn parse_to_cont(input: &[Token]) -> ParseResult<'_> {
     let (_, input) = parse_fixed_symbol(input, Symbol::UnitConversion)?;
-    let (b, input) = parse_implicit_addition(input)?;
+    let (mut b, mut input) = parse_implicit_addition(input)?;
+    if matches!(b, Expr::Ident(_)) {
+        // mixed units, e.g. `to ft+in` or `to hr+min+s`
+        while let Ok((unit, remaining)) = parse_mixed_unit_cont(input) {
+            b = Expr::Bop(Bop::Plus, Box::new(b), Box::new(unit));
+            input = remaining;
+        }
+    }
     Ok((b, input))
 }
 
+fn parse_mixed_unit_cont(input: &[Token]) -> ParseResult<'_> {
+    let ((), input) = parse_fixed_symbol(input, Symbol::Add)?;
+    // `in` is lexed as a unit conversion, but here it can only mean inches
+    if let Ok(((), remaining)) = parse_fixed_symbol(input, Symbol::UnitConversion) {
+        return Ok((Expr::Ident(Ident::new_str("inch")), remaining));
+    }
+    match parse_implicit_addition(input)? {
+        (unit @ Expr::Ident(_), remaining) => Ok((unit, remaining)),
+        _ => Err(ParseError::ExpectedIdentifier),
+    }
+}
+
 fn parse_additive(input: &[Token]) -> ParseResult<'_> {
     let (mut res, mut input) = parse_implicit_addition(input)?;
     loop {
@@ -356,6 +497,9 @@ fn parse_additive(input: &[Token]) -> ParseResult<'_> {
         } else if let Ok((term, remaining)) = parse_subtraction_cont(input) {
             res = Expr::Bop(Bop::Minus, Box::new(res), Box::new(term));
             input = remaining;
+        } else if let Ok((term, remaining)) = parse_plus_minus_cont(input) {
+            res = Expr::Bop(Bop::PlusMinus, Box::new(res), Box::new(term));
+            input = remaining;
         } else if let Ok((term, remaining)) = parse_to_cont(input) {
             res = Expr::As(Box::new(res), Box::new(term));
             input = remaining;
@@ -366,8 +510,45 @@ fn parse_additive(input: &[Token]) -> ParseResult<'_> {
     Ok((res, input))
 }
 
-fn parse_function(input: &[Token]) -> ParseResult<'_> {
+fn parse_range(input: &[Token]) -> ParseResult<'_> {
     let (lhs, input) = parse_additive(input)?;
+    if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::DotDot) {
+        let (rhs, remaining) = parse_additive(remaining)?;
+        return Ok((Expr::Range(Box::new(lhs), Box::new(rhs)), remaining));
+    }
+    Ok((lhs, input))
+}
+
+fn parse_equality(input: &[Token]) -> ParseResult<'_> {
+    let (lhs, input) = parse_range(input)?;
+    for (symbol, equal) in [(Symbol::DoubleEquals, true), (Symbol::NotEquals, false)] {
+        if let Ok((_, remaining)) = parse_fixed_symbol(input, symbol) {
+            let (rhs, remaining) = parse_range(remaining)?;
+            return Ok((
+                Expr::Equality(equal, Box::new(lhs), Box::new(rhs)),
+                remaining,
+            ));
+        }
+    }
+    for (symbol, ordering, or_equal) in [
+        (Symbol::Less, Ordering::Less, false),
+        (Symbol::LessOrEqual, Ordering::Less, true),
+        (Symbol::Greater, Ordering::Greater, false),
+        (Symbol::GreaterOrEqual, Ordering::Greater, true),
+    ] {
+        if let Ok((_, remaining)) = parse_fixed_symbol(input, symbol) {
+            let (rhs, remaining) = parse_range(remaining)?;
+            return Ok((
+                Expr::Comparison(ordering, or_equal, Box::new(lhs), Box::new(rhs)),
+                remaining,
+            ));
+        }
+    }
+    Ok((lhs, input))
+}
+
+fn parse_function(input: &[Token]) -> ParseResult<'_> {
+    let (lhs, input) = parse_equality(input)?;
     if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Fn) {
         if let Expr::Ident(s) = lhs {
             let (rhs, remaining) = parse_function(remaining)?;
@@ -390,6 +571,98 @@ fn parse_assignment(input: &[Token]) -> ParseResult<'_> {
     Ok((lhs, input))
 }
 
+/// Parses the rest of a statement like `unit furlong = 220 yards` or
+/// `unit long flop flops`, after the `unit` keyword
+fn parse_unit_definition(input: &[Token]) -> ParseResult<'_> {
+    let mut names = vec![];
+    let mut input = input;
+    while let Ok((Token::Ident(name), remaining)) = parse_token(input, true) {
+        names.push(name);
+        input = remaining;
+    }
+    let mut rule = PrefixRule::NoPrefixesAllowed;
+    if names.len() >= 2 {
+        if let Some(r) = PrefixRule::from_keyword(names[0].as_str()) {
+            rule = r;
+            names.remove(0);
+        }
+    }
+    let (singular, plural) = match names.as_slice() {
+        [singular] => (singular.clone(), singular.clone()),
+        [singular, plural] => (singular.clone(), plural.clone()),
+        _ => return Err(ParseError::ExpectedIdentifier),
+    };
+    if let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Equals) {
+        let (definition, remaining) = parse_assignment(remaining)?;
+        return Ok((
+            Expr::DefineUnit(rule, singular, plural, Some(Box::new(definition))),
+            remaining,
+        ));
+    }
+    match parse_token(input, true) {
+        Err(_) | Ok((Token::Symbol(Symbol::Semicolon), _)) => {
+            Ok((Expr::DefineUnit(rule, singular, plural, None), input))
+        }
+        Ok(_) => Err(ParseError::UnexpectedInput),
+    }
+}
+
+/// Parses the rest of a statement like `prefix lakh = 1e5` or
+/// `prefix short L = 1e5`, after the `prefix` keyword. Long prefixes can
+/// be used with units defined as `long`, and short prefixes with units
+/// defined as `short`.
+fn parse_prefix_definition(input: &[Token]) -> ParseResult<'_> {
+    let mut names = vec![];
+    let mut input = input;
+    while let Ok((Token::Ident(name), remaining)) = parse_token(input, true) {
+        names.push(name);
+        input = remaining;
+    }
+    let (rule, name) = match names.as_slice() {
+        [name] => (PrefixRule::LongPrefix, name.clone()),
+        [keyword, name] if keyword.as_str() == "long" => (PrefixRule::LongPrefix, name.clone()),
+        [keyword, name] if keyword.as_str() == "short" => (PrefixRule::ShortPrefix, name.clone()),
+        _ => return Err(ParseError::ExpectedIdentifier),
+    };
+    let ((), remaining) = parse_fixed_symbol(input, Symbol::Equals)?;
+    let (definition, remaining) = parse_assignment(remaining)?;
+    Ok((
+        Expr::DefineUnit(rule, name.clone(), name, Some(Box::new(definition))),
+        remaining,
+    ))
+}
+
+/// Parses a statement like `set precision 100`, which changes a setting,
+/// a unit or prefix definition like `unit furlong = 220 yards`, or a rate like
+/// `takes 45 min per item`
+fn parse_set(input: &[Token]) -> ParseResult<'_> {
+    if let Ok((Token::Ident(keyword), remaining)) = parse_token(input, true) {
+        if keyword.as_str() == "takes" {
+            if let Ok((rate, remaining)) = parse_assignment(remaining) {
+                return Ok((Expr::Takes(Box::new(rate)), remaining));
+            }
+        }
+        if keyword.as_str() == "unit" {
+            if let Ok(res) = parse_unit_definition(remaining) {
+                return Ok(res);
+            }
+        }
+        if keyword.as_str() == "prefix" {
+            if let Ok(res) = parse_prefix_definition(remaining) {
+                return Ok(res);
+            }
+        }
+        if keyword.as_str() == "set" {
+            if let Ok((Token::Ident(setting), remaining)) = parse_token(remaining, true) {
+                if let Ok((value, remaining)) = parse_assignment(remaining) {
+                    return Ok((Expr::Set(setting, Box::new(value)), remaining));
+                }
+            }
+        }
+    }
+    parse_assignment(input)
+}
+
 fn parse_statements(mut input: &[Token]) -> ParseResult<'_> {
     while let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Semicolon) {
         input = remaining;
@@ -397,13 +670,13 @@ fn parse_statements(mut input: &[Token]) -> ParseResult<'_> {
     if input.is_empty() {
         return Ok((Expr::Literal(Value::from(())), &[]));
     }
-    let (mut result, mut input) = parse_assignment(input)?;
+    let (mut result, mut input) = parse_set(input)?;
     while let Ok((_, remaining)) = parse_fixed_symbol(input, Symbol::Semicolon) {
         if remaining.is_empty() || matches!(remaining[0], Token::Symbol(Symbol::Semicolon)) {
             input = remaining;
             continue;
         }
-        let (rhs, remaining) = parse_assignment(remaining)?;
+        let (rhs, remaining) = parse_set(remaining)?;
         result = Expr::Statements(Box::new(result), Box::new(rhs));
         input = remaining;
     }
@@ -414,10 +687,34 @@ pub(crate) fn parse_expression(input: &[Token]) -> ParseResult<'_> {
     parse_statements(input)
 }
 
+// each top-level statement (or right-hand side of an assignment) that
+// contains placeholders becomes a template, e.g. `tpl = ?a * ?b / 100`
+fn into_templates(expr: Expr) -> Expr {
+    match expr {
+        // unmatched closing parentheses add opening parentheses at the start
+        Expr::Parens(inner) => Expr::Parens(Box::new(into_templates(*inner))),
+        Expr::Statements(a, b) => {
+            Expr::Statements(Box::new(into_templates(*a)), Box::new(into_templates(*b)))
+        }
+        Expr::Assign(name, value) => Expr::Assign(name, Box::new(value.into_template())),
+        expr => expr.into_template(),
+    }
+}
+
+/// Returns the index of the token where parsing fails, if it can be
+/// determined. This is `input.len()` if the input ends too early.
+pub(crate) fn find_error_position(input: &[Token]) -> Option<usize> {
+    match parse_expression(input) {
+        Ok((_, remaining)) if !remaining.is_empty() => Some(input.len() - remaining.len()),
+        Err(ParseError::ExpectedAToken) => Some(input.len()),
+        _ => None,
+    }
+}
+
 pub(crate) fn parse_tokens(input: &[Token]) -> Result<Expr, ParseError> {
     let (res, remaining) = parse_expression(input)?;
     if !remaining.is_empty() {
         return Err(ParseError::UnexpectedInput);
     }
-    Ok(res)
+    Ok(into_templates(res))
 }
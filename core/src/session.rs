@@ -0,0 +1,174 @@
+use crate::error::{FendError, Interrupt};
+use crate::eval::evaluate_to_value;
+use crate::interrupt::test_int;
+use crate::lexer::{self, Token};
+
+/// Writes the custom units and variables of `context` as statements, one
+/// per line, so that they can be restored by evaluating each line. Units
+/// come first because variables can refer to them. The results of previous
+/// calculations (`_` and `ans`) are left out, as are values that can't be
+/// written as an expression.
+pub(crate) fn save<I: Interrupt>(context: &crate::Context, int: &I) -> Result<String, FendError> {
+    let mut res = String::new();
+    for (i, unit) in context.custom_units.iter().enumerate() {
+        test_int(int)?;
+        // only the latest definition of a unit is used
+        let redefined = context.custom_units[i + 1..]
+            .iter()
+            .any(|later| later.singular() == unit.singular());
+        if redefined {
+            continue;
+        }
+        if let Some(statement) = unit.to_source(context, int)? {
+            res.push_str(&statement);
+            res.push('\n');
+        }
+    }
+    let mut names = context
+        .variables
+        .keys()
+        .filter(|name| !matches!(name.as_str(), "_" | "ans"))
+        .collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        test_int(int)?;
+        if let Some(value) = context.variables[name].to_source(context, int)? {
+            res.push_str(name);
+            res.push_str(" = ");
+            res.push_str(&value);
+            res.push('\n');
+        }
+    }
+    Ok(res)
+}
+
+/// Evaluates each line of `saved`, ignoring empty lines and lines starting
+/// with `#`
+pub(crate) fn restore<I: Interrupt>(
+    saved: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    for (i, line) in saved.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        evaluate_to_value(line, None, context, int)
+            .map_err(|e| FendError::InvalidSavedVariables(i + 1, Box::new(e)))?;
+    }
+    Ok(())
+}
+
+/// Assigns the result of each expression to the variable with the given
+/// name, in order, so that later expressions can use earlier variables
+pub(crate) fn define<I: Interrupt, N: AsRef<str>, E: AsRef<str>>(
+    definitions: &[(N, E)],
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    for (name, expr) in definitions {
+        let name = name.as_ref();
+        let value = check_variable_name(name, context, int)
+            .and_then(|()| evaluate_to_value(expr.as_ref(), None, context, int))
+            .map_err(|e| FendError::InvalidVariableDefinition(name.to_string(), Box::new(e)))?;
+        context.set_variable(name.to_string(), value);
+    }
+    Ok(())
+}
+
+/// Returns an error unless `name` is a single identifier, e.g. not a
+/// number or a keyword like `to`
+fn check_variable_name<I: Interrupt>(
+    name: &str,
+    context: &crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    let tokens = lexer::lex(name, &context.interner, int).collect::<Result<Vec<_>, _>>()?;
+    match tokens.as_slice() {
+        [Token::Ident(ident)] if ident.as_str() == name => Ok(()),
+        _ => Err(FendError::InvalidVariableName),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interrupt::Never;
+    use crate::Context;
+
+    fn round_trip(input: &str) -> (String, Context) {
+        let mut context = Context::new();
+        for line in input.lines() {
+            crate::evaluate(line, &mut context).unwrap();
+        }
+        let saved = save(&context, &Never::default()).unwrap();
+        let mut restored = Context::new();
+        restore(&saved, &mut restored, &Never::default()).unwrap();
+        (saved, restored)
+    }
+
+    fn eval(input: &str, context: &mut Context) -> String {
+        crate::evaluate(input, context)
+            .unwrap()
+            .get_main_result()
+            .to_string()
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let (saved, mut context) = round_trip(
+            "tax_rate = 0.19
+            unit furlong furlongs = 220 yards
+            unit furlong furlongs = 201 m
+            prefix lakh = 1e5
+            distance = 3 furlongs
+            third = 1/3
+            root = sqrt(2)
+            name = \"a \\\"quoted\\\" name\"
+            square = \\x.x^2
+            list = [1, 2 km]
+            flag = true
+            start_date = date(2024, 5, 1)
+            5 + 5",
+        );
+        assert_eq!(
+            saved,
+            "unit furlong furlongs = 201 m
+prefix lakh = 100000
+distance = 3 furlongs
+flag = true
+list = [1, 2 km]
+name = \"a \\\"quoted\\\" name\"
+root = √2
+square = \\x.(x^2)
+tax_rate = 0.19
+third = 1/3
+"
+        );
+        assert!(crate::evaluate("ans", &mut context).is_err());
+        assert_eq!(eval("distance to m", &mut context), "603 m");
+        assert_eq!(eval("third * 3", &mut context), "1");
+        assert_eq!(eval("square 4", &mut context), "16");
+        assert_eq!(eval("root^2", &mut context), "2");
+        assert_eq!(eval("2 lakh", &mut context), "200000");
+        assert_eq!(eval("tax_rate * 100", &mut context), "19");
+        assert!(crate::evaluate("start_date", &mut context).is_err());
+    }
+
+    #[test]
+    fn restore_errors() {
+        let mut context = Context::new();
+        let err = restore(
+            "a = 1\n\n# comment\nb = 2 +",
+            &mut context,
+            &Never::default(),
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 4: unexpected input found"
+        );
+        assert_eq!(eval("a", &mut context), "1");
+        assert!(crate::evaluate("later", &mut context).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::error::FendError;
+use crate::lexer::{LexedTokens, Token};
+use crate::{interrupt, limits, units, Context};
+
+/// A corrected version of an input that couldn't be evaluated, e.g.
+/// because of a misspelled unit or function name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Correction {
+    original: String,
+    replacement: String,
+    input: String,
+}
+
+impl Correction {
+    /// Returns the part of the input that was replaced, like `metr`.
+    #[must_use]
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns what it was replaced with, like `meter`.
+    #[must_use]
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// Returns the whole corrected input, which can be evaluated instead.
+    #[must_use]
+    pub fn corrected_input(&self) -> &str {
+        &self.input
+    }
+}
+
+// names of built-in functions and constants that are suggested
+// when the user misspells them
+const BUILTIN_NAMES: &[&str] = &[
+    "pi",
+    "tau",
+    "phi",
+    "true",
+    "false",
+    "sample",
+    "sqrt",
+    "cbrt",
+    "conjugate",
+    "abs",
+    "max",
+    "min",
+    "clamp",
+    "round",
+    "floor",
+    "ceil",
+    "truncate",
+    "random",
+    "randint",
+    "totient",
+    "divisors",
+    "fibonacci",
+    "mean",
+    "average",
+    "median",
+    "mode",
+    "stdev",
+    "variance",
+    "percentile",
+    "normalize",
+    "determinant",
+    "transpose",
+    "inverse",
+    "polynomial",
+    "quaternion",
+    "rotate",
+    "roots",
+    "download_time",
+    "molar_mass",
+    "format",
+    "date",
+    "between",
+    "easter",
+    "is_holiday",
+    "add_workdays",
+    "moon_phase",
+    "julian_day",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "sinh",
+    "cosh",
+    "tanh",
+    "ln",
+    "log",
+    "log2",
+    "log10",
+    "exp",
+    "approximately",
+    "exact",
+    "fraction",
+    "mixed_fraction",
+    "float",
+    "decimal",
+    "hexadecimal",
+    "binary",
+    "octal",
+    "version",
+    "today",
+    "tomorrow",
+    "yesterday",
+    "now",
+];
+
+pub(crate) fn is_builtin_name(name: &str) -> bool {
+    BUILTIN_NAMES.contains(&name)
+}
+
+/// Suggests a correction for the input if evaluating it failed
+/// because of an unknown identifier
+pub(crate) fn suggest_correction(
+    error: &FendError,
+    input: &str,
+    context: &Context,
+) -> Option<Correction> {
+    let name = match error {
+        FendError::IdentifierNotFound(name) => name.as_str(),
+        _ => return None,
+    };
+    let replacement = closest_name(name, context)?;
+    let tokens = limits::with_memory_limit(context.limits, || {
+        LexedTokens::new(input, &context.interner, &interrupt::Never::default())
+    });
+    let mut corrected = String::new();
+    let mut end = 0;
+    for (i, token) in tokens.tokens().ok()?.iter().enumerate() {
+        if matches!(token, Token::Ident(ident) if ident.as_str() == name) {
+            let range = tokens.token_range(input, i);
+            corrected.push_str(&input[end..range.start]);
+            corrected.push_str(replacement);
+            end = range.end;
+        }
+    }
+    if end == 0 {
+        // the identifier isn't part of the input, e.g. if it
+        // was used by a function
+        return None;
+    }
+    corrected.push_str(&input[end..]);
+    Some(Correction {
+        original: name.to_string(),
+        replacement: replacement.to_string(),
+        input: corrected,
+    })
+}
+
+/// Returns the most similar variable, function or unit name, if any
+/// is similar enough
+fn closest_name<'a>(name: &str, context: &'a Context) -> Option<&'a str> {
+    let len = name.chars().count();
+    if len < 3 {
+        return None;
+    }
+    let max_distance = if len <= 5 { 1 } else { 2 };
+    let mut best: Option<(usize, &str)> = None;
+    let candidates = context
+        .variables
+        .keys()
+        .map(String::as_str)
+        .chain(BUILTIN_NAMES.iter().copied())
+        .chain(units::all_unit_names(context));
+    for candidate in candidates {
+        let distance = if candidate.eq_ignore_ascii_case(name) {
+            0
+        } else {
+            edit_distance(name, candidate)
+        };
+        let is_closer = match best {
+            Some((best_distance, _)) => distance < best_distance,
+            None => true,
+        };
+        if distance <= max_distance && is_closer {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Returns the variable, function and unit names that start with `prefix`
+/// (but aren't equal to it), sorted and without duplicates
+pub(crate) fn get_completions(prefix: &str, context: &Context) -> Vec<String> {
+    let mut names: Vec<String> = context
+        .variables
+        .keys()
+        .map(String::as_str)
+        .chain(BUILTIN_NAMES.iter().copied())
+        .filter(|name| name.starts_with(prefix))
+        .map(ToString::to_string)
+        .chain(units::get_completions_for_prefix(prefix, context))
+        .filter(|name| name != prefix)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Number of inserted, deleted, substituted or swapped adjacent characters
+/// needed to turn `a` into `b` (optimal string alignment distance)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i];
+        for j in 1..=b.len() {
+            let prev = &rows[i - 1];
+            let mut distance = (prev[j - 1] + usize::from(a[i - 1] != b[j - 1]))
+                .min(prev[j] + 1)
+                .min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            row.push(distance);
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("meter", "meter"), 0);
+        assert_eq!(edit_distance("metr", "meter"), 1);
+        assert_eq!(edit_distance("sqtr", "sqrt"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn completions() {
+        let mut context = Context::new();
+        crate::evaluate("distance = 5 km", &mut context).unwrap();
+        assert_eq!(
+            get_completions("kilome", &context),
+            ["kilometer", "kilometre"]
+        );
+        assert_eq!(get_completions("dista", &context), ["distance"]);
+        assert_eq!(get_completions("sqr", &context), ["sqrt"]);
+        assert!(get_completions("mete", &context).contains(&"meter".to_string()));
+        assert!(get_completions("meter", &context).is_empty());
+    }
+
+    #[test]
+    fn builtin_names_are_defined() {
+        let mut context = Context::new();
+        for name in BUILTIN_NAMES {
+            if let Err(msg) = crate::evaluate(name, &mut context) {
+                assert!(!msg.starts_with("unknown identifier"), "{}", msg);
+            }
+        }
+    }
+}
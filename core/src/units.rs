@@ -1,11 +1,13 @@
-use std::borrow::Cow;
-
 use crate::error::{FendError, Interrupt};
 use crate::eval::evaluate_to_value;
-use crate::num::Number;
+use crate::interner::SharedStr;
+use crate::interrupt::test_int;
+use crate::num::{FormattingStyle, Number};
+use crate::value::dimension::Dimension;
 use crate::value::Value;
+use std::cmp::Ordering;
 
-mod builtin;
+pub(crate) mod builtin;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) enum PrefixRule {
@@ -16,14 +18,138 @@ pub(crate) enum PrefixRule {
     ShortPrefix,
 }
 
+impl PrefixRule {
+    /// The keyword in a statement like `unit long flop flops = ...` that
+    /// allows a custom unit to be used with prefixes
+    pub(crate) fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "long" => Some(Self::LongPrefixAllowed),
+            "short" => Some(Self::ShortPrefixAllowed),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn keyword(self) -> Option<&'static str> {
+        match self {
+            Self::LongPrefixAllowed => Some("long"),
+            Self::ShortPrefixAllowed => Some("short"),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct UnitDef {
-    singular: &'static str,
-    plural: &'static str,
+    singular: SharedStr,
+    plural: SharedStr,
     prefix_rule: PrefixRule,
     value: Value,
 }
 
+/// A unit defined at runtime with a statement like `unit furlong = 220 yards`
+#[derive(Clone, Debug)]
+pub(crate) struct CustomUnit {
+    singular: SharedStr,
+    plural: SharedStr,
+    prefix_rule: PrefixRule,
+    value: Value,
+    // the value that the unit was defined as, e.g. `220 yards`
+    definition: Option<Value>,
+}
+
+impl CustomUnit {
+    /// Returns the statement that defines this unit, like
+    /// `unit furlong furlongs = 220 yards`, or `None` if its
+    /// definition can't be written as an expression
+    pub(crate) fn to_source<I: Interrupt>(
+        &self,
+        context: &crate::Context,
+        int: &I,
+    ) -> Result<Option<String>, FendError> {
+        let mut res = match self.prefix_rule {
+            PrefixRule::LongPrefix => "prefix ",
+            PrefixRule::ShortPrefix => "prefix short ",
+            _ => "unit ",
+        }
+        .to_string();
+        if let Some(keyword) = self.prefix_rule.keyword() {
+            res.push_str(keyword);
+            res.push(' ');
+        }
+        res.push_str(self.singular.as_str());
+        if self.plural.as_str() != self.singular.as_str() {
+            res.push(' ');
+            res.push_str(self.plural.as_str());
+        }
+        if let Some(definition) = &self.definition {
+            match definition.to_source(context, int)? {
+                Some(definition) => {
+                    res.push_str(" = ");
+                    res.push_str(&definition);
+                }
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(res))
+    }
+
+    pub(crate) fn singular(&self) -> &str {
+        self.singular.as_str()
+    }
+
+    fn matches(&self, ident: &str, case_sensitive: bool) -> bool {
+        if case_sensitive {
+            self.singular == ident || self.plural == ident
+        } else {
+            self.singular.eq_ignore_ascii_case(ident) || self.plural.eq_ignore_ascii_case(ident)
+        }
+    }
+}
+
+/// Registers a new unit in the given context. Without a definition, a new
+/// base unit is created, e.g. `unit widget widgets`. Prefixes like
+/// `prefix lakh = 1e5` are also stored as units, with a unitless value.
+pub(crate) fn define_custom_unit<I: Interrupt>(
+    singular: &str,
+    plural: &str,
+    prefix_rule: PrefixRule,
+    definition: Option<Value>,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    let singular = context.interner.intern(singular);
+    let plural = context.interner.intern(plural);
+    let is_prefix = matches!(
+        prefix_rule,
+        PrefixRule::LongPrefix | PrefixRule::ShortPrefix
+    );
+    let num = match definition.clone() {
+        Some(definition) if is_prefix => {
+            let num = definition.expect_num()?;
+            if !num.is_unitless() {
+                return Err(FendError::ExpectedAUnitlessNumber);
+            }
+            num
+        }
+        Some(definition) => Number::create_unit_value_from_value(
+            &definition.expect_num()?,
+            SharedStr::Static(""),
+            singular.clone(),
+            plural.clone(),
+            int,
+        )?,
+        None => Number::new_base_unit(singular.clone(), plural.clone()),
+    };
+    context.custom_units.push(CustomUnit {
+        singular,
+        plural,
+        prefix_rule,
+        value: Value::Num(Box::new(num)),
+        definition,
+    });
+    Ok(())
+}
+
 fn expr_unit<I: Interrupt>(
     singular: &'static str,
     plural: &'static str,
@@ -52,12 +178,12 @@ fn expr_unit<I: Interrupt>(
     if definition == "!" {
         return Ok(UnitDef {
             value: Value::Num(Box::new(Number::new_base_unit(
-                Cow::Borrowed(singular),
-                Cow::Borrowed(plural),
+                SharedStr::Static(singular),
+                SharedStr::Static(plural),
             ))),
             prefix_rule: rule,
-            singular,
-            plural,
+            singular: SharedStr::Static(singular),
+            plural: SharedStr::Static(plural),
         });
     }
     let (alias, definition) = definition
@@ -67,20 +193,39 @@ fn expr_unit<I: Interrupt>(
     if !alias && rule != PrefixRule::LongPrefix {
         num = Number::create_unit_value_from_value(
             &num,
-            Cow::Borrowed(""),
-            Cow::Borrowed(singular),
-            Cow::Borrowed(plural),
+            SharedStr::Static(""),
+            SharedStr::Static(singular),
+            SharedStr::Static(plural),
             int,
         )?;
     }
     Ok(UnitDef {
         value: Value::Num(Box::new(num)),
         prefix_rule: rule,
-        singular,
-        plural,
+        singular: SharedStr::Static(singular),
+        plural: SharedStr::Static(plural),
     })
 }
 
+/// Registers every unit in `definitions`, which contains one statement
+/// like `unit furlong = 220 yards` per line. Empty lines and lines starting
+/// with `#` are ignored.
+pub(crate) fn define_units<I: Interrupt>(
+    definitions: &str,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<(), FendError> {
+    for (i, line) in definitions.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        crate::eval::evaluate_unit_definition(line, context, int)
+            .map_err(|e| FendError::InvalidUnitDefinition(i + 1, Box::new(e)))?;
+    }
+    Ok(())
+}
+
 fn construct_prefixed_unit<I: Interrupt>(
     a: UnitDef,
     b: UnitDef,
@@ -88,13 +233,8 @@ fn construct_prefixed_unit<I: Interrupt>(
 ) -> Result<Value, FendError> {
     let product = a.value.expect_num()?.mul(b.value.expect_num()?, int)?;
     assert_eq!(a.singular, a.plural);
-    let unit = Number::create_unit_value_from_value(
-        &product,
-        Cow::Borrowed(a.singular),
-        Cow::Borrowed(b.singular),
-        Cow::Borrowed(b.plural),
-        int,
-    )?;
+    let unit =
+        Number::create_unit_value_from_value(&product, a.singular, b.singular, b.plural, int)?;
     Ok(Value::Num(Box::new(unit)))
 }
 
@@ -106,9 +246,10 @@ pub(crate) fn query_unit<'a, I: Interrupt>(
     if ident.starts_with('\'') && ident.ends_with('\'') && ident.len() >= 3 {
         let ident = ident.split_at(1).1;
         let ident = ident.split_at(ident.len() - 1).0;
+        let name = context.interner.intern(ident);
         return Ok(Value::Num(Box::new(Number::new_base_unit(
-            ident.to_string().into(),
-            ident.to_string().into(),
+            name.clone(),
+            name,
         ))));
     }
     query_unit_static(ident, context, int)
@@ -174,6 +315,52 @@ fn query_unit_case_sensitive<I: Interrupt>(
     Err(FendError::IdentifierNotFound(ident.to_string().into()))
 }
 
+/// Looks up a currency like `USD` in the exchange rate source supplied
+/// by the application (if any)
+fn query_exchange_rate<I: Interrupt>(
+    ident: &str,
+    case_sensitive: bool,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<UnitDef>, FendError> {
+    let source = match &context.exchange_rate_source {
+        Some(source) => source.clone(),
+        None => return Ok(None),
+    };
+    let code = ident.to_ascii_uppercase();
+    let is_code = (ident.len() == 3 || builtin::is_cryptocurrency(&code))
+        && ident
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || (!case_sensitive && ch.is_ascii_lowercase()));
+    // don't shadow other built-in units that look like currency codes
+    if !is_code
+        || code == "EUR"
+        || (builtin::query_unit(ident, false, case_sensitive).is_some()
+            && !builtin::is_exchange_rate(&code)
+            && !builtin::is_cryptocurrency(&code))
+    {
+        return Ok(None);
+    }
+    if let Some(rate) = source.units_per_euro(&code) {
+        let value = evaluate_to_value(&format!("(1/{}) _EUR", rate), None, context, int)?;
+        let name = context.interner.intern(&code);
+        let num = Number::create_unit_value_from_value(
+            &value.expect_num()?,
+            SharedStr::Static(""),
+            name.clone(),
+            name.clone(),
+            int,
+        )?;
+        return Ok(Some(UnitDef {
+            singular: name.clone(),
+            plural: name,
+            prefix_rule: PrefixRule::NoPrefixesAllowed,
+            value: Value::Num(Box::new(num)),
+        }));
+    }
+    Ok(None)
+}
+
 fn query_unit_internal<'a, I: Interrupt>(
     ident: &'a str,
     short_prefixes: bool,
@@ -181,6 +368,25 @@ fn query_unit_internal<'a, I: Interrupt>(
     context: &mut crate::Context,
     int: &I,
 ) -> Result<UnitDef, FendError> {
+    // units defined later take precedence over earlier and built-in units
+    if let Some(unit) = context
+        .custom_units
+        .iter()
+        .rev()
+        .find(|unit| unit.matches(ident, case_sensitive))
+    {
+        return Ok(UnitDef {
+            singular: unit.singular.clone(),
+            plural: unit.plural.clone(),
+            prefix_rule: unit.prefix_rule,
+            value: unit.value.clone(),
+        });
+    }
+    if !short_prefixes {
+        if let Some(unit) = query_exchange_rate(ident, case_sensitive, context, int)? {
+            return Ok(unit);
+        }
+    }
     if ident == "C" {
         return if context.fc_mode == crate::FCMode::CelsiusFahrenheit {
             expr_unit("C", "C", "=\u{b0}C", context, int)
@@ -201,29 +407,427 @@ fn query_unit_internal<'a, I: Interrupt>(
     }
 }
 
-pub(crate) fn get_completions_for_prefix(prefix: &str) -> Vec<crate::Completion> {
-    use crate::Completion;
+pub(crate) fn get_unit_categories() -> Vec<crate::UnitCategory> {
+    builtin::UNIT_CATEGORIES
+        .iter()
+        .map(|&(name, units)| crate::UnitCategory { name, units })
+        .collect()
+}
 
-    let mut result = vec![];
+pub(crate) fn get_currencies() -> Vec<crate::CurrencyInfo> {
+    builtin::CURRENCY_INFO
+        .iter()
+        .map(
+            |&(code, symbol, name, decimal_places)| crate::CurrencyInfo {
+                code,
+                symbol,
+                name,
+                decimal_places,
+            },
+        )
+        .collect()
+}
+
+pub(crate) fn get_currency_info(code: &str) -> Option<crate::CurrencyInfo> {
+    get_currencies()
+        .into_iter()
+        .find(|c| c.code.eq_ignore_ascii_case(code))
+}
 
-    let mut add = |name: &str| {
-        if name.starts_with(prefix) && name != prefix {
-            result.push(Completion {
-                display: name.to_string(),
-                insert: name.split_at(prefix.len()).1.to_string(),
-            });
+/// Converts `num` to the other measurement system (metric or US customary),
+/// picking the largest unit in which the result is at least 1. Returns `None`
+/// if `num` isn't a length, mass, volume, temperature or speed.
+pub(crate) fn convert_to_other_system<I: Interrupt>(
+    num: &Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<Number>, FendError> {
+    let us_customary = num
+        .unit_names()
+        .iter()
+        .any(|name| builtin::US_CUSTOMARY_UNITS.contains(name));
+    for (metric, us) in builtin::DUAL_UNITS {
+        let mut result = None;
+        for target in if us_customary { *metric } else { *us } {
+            let unit = evaluate_to_value(target, None, context, int)?.expect_num()?;
+            let converted = match num.clone().convert_to(unit.clone(), int) {
+                Ok(converted) => converted,
+                Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+                // a different kind of quantity
+                Err(_) => break,
+            };
+            let magnitude = converted.clone().div(unit, int)?.abs(int)?;
+            if result.is_some() && magnitude.compare(&1.into(), int)? == Ordering::Less {
+                break;
+            }
+            result = Some(converted);
+        }
+        if result.is_some() {
+            return Ok(result);
         }
+    }
+    Ok(None)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum UnitSystem {
+    Si,
+    Imperial,
+}
+
+/// Converts `num` to the canonical units of a measurement system by
+/// replacing each of its base units, e.g. `60 mph` to `26.8224 m / s`.
+/// Temperatures are converted to kelvin or degrees Fahrenheit.
+pub(crate) fn convert_to_system<I: Interrupt>(
+    num: Number,
+    system: UnitSystem,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let exponents = num.base_unit_exponents(int)?;
+    if exponents.is_empty() {
+        return Ok(num);
+    }
+    let target = if exponents == [("kelvin".to_string(), 1)] {
+        match system {
+            UnitSystem::Si => "K".to_string(),
+            UnitSystem::Imperial => "\u{b0}F".to_string(),
+        }
+    } else {
+        let position = |base_unit: &str| {
+            builtin::SYSTEM_BASE_UNITS
+                .iter()
+                .position(|(name, _, _)| *name == base_unit)
+        };
+        let mut exponents = exponents;
+        // positive exponents come first so that the unit is shown as a
+        // fraction, and other base units like currencies come before the
+        // SI base units, e.g. `EUR / lb`
+        exponents.sort_by_key(|(base_unit, exponent)| (*exponent < 0, position(base_unit)));
+        let mut terms = vec![];
+        for (base_unit, exponent) in exponents {
+            let unit = match position(&base_unit) {
+                Some(i) => match system {
+                    UnitSystem::Si => builtin::SYSTEM_BASE_UNITS[i].1,
+                    UnitSystem::Imperial => builtin::SYSTEM_BASE_UNITS[i].2,
+                },
+                None => base_unit.as_str(),
+            };
+            terms.push(format!("{}^{}", unit, exponent));
+        }
+        terms.join(" ")
     };
+    let unit = evaluate_to_value(&target, None, context, int)?.expect_num()?;
+    num.convert_to(unit, int)
+}
 
+/// Returns the unit and reference power of a level like `20 dBm`
+fn power_level(num: &Number) -> Option<(&'static str, &'static str)> {
+    match num.unit_names().as_slice() {
+        [name] => builtin::POWER_LEVEL_UNITS
+            .iter()
+            .find(|(level, _)| level == name)
+            .copied(),
+        _ => None,
+    }
+}
+
+/// Returns the reference power of a level like `20 dBm`
+fn power_level_reference(num: &Number) -> Option<&'static str> {
+    power_level(num).map(|(_, reference)| reference)
+}
+
+/// Converts a power (or power ratio) to decibels, e.g. `100 mW` to `20`
+/// if the reference is `1 mW`
+fn to_decibels<I: Interrupt>(
+    num: Number,
+    reference: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    let ratio = num
+        .convert_to(reference.clone(), int)?
+        .without_unit()
+        .div(reference.without_unit(), int)?;
+    Number::from(10).mul(ratio.log10(context.precision, int)?, int)
+}
+
+/// Converts decibels to a power (or power ratio), e.g. `20` to `100 mW`
+/// if the reference is `1 mW`
+fn from_decibels<I: Interrupt>(
+    decibels: Number,
+    reference: Number,
+    int: &I,
+) -> Result<Number, FendError> {
+    let ratio = Number::from(10).pow(decibels.div(10.into(), int)?, int)?;
+    reference.mul(ratio, int)
+}
+
+/// Conversions involving logarithmic units, which aren't proportional to
+/// the quantities they measure: power levels like `20 dBm to mW`, and ratios
+/// like `100 to dB` (using the power ratio convention of `10 log10`).
+/// Returns `None` if neither side is logarithmic.
+pub(crate) fn convert_logarithmic<I: Interrupt>(
+    num: &Number,
+    target: &Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<Number>, FendError> {
+    let decibel = evaluate_to_value("dB", None, context, int)?.expect_num()?;
+    let is_level = |n: &Number| -> Result<bool, FendError> {
+        Ok(n.base_unit_exponents(int)? == [("neper".to_string(), 1)])
+    };
+    let from_level = power_level_reference(num);
+    let to_level = power_level_reference(target);
+    if from_level.is_some() && from_level == to_level {
+        return Ok(None);
+    }
+    if from_level.is_none() && to_level.is_none() {
+        // plain ratios
+        return Ok(if num.is_unitless() && is_level(target)? {
+            let decibels = to_decibels(num.clone(), 1.into(), context, int)?;
+            Some(
+                decibels
+                    .mul(decibel, int)?
+                    .convert_to(target.clone(), int)?,
+            )
+        } else if is_level(num)? && target.is_unitless() {
+            let decibels = num.clone().convert_to(decibel, int)?.without_unit();
+            Some(from_decibels(decibels, target.clone(), int)?)
+        } else {
+            None
+        });
+    }
+    let power = match from_level {
+        Some(reference) => {
+            let reference = evaluate_to_value(reference, None, context, int)?.expect_num()?;
+            from_decibels(num.clone().without_unit(), reference, int)?
+        }
+        None => num.clone(),
+    };
+    Ok(Some(match to_level {
+        Some(reference) => {
+            let reference = evaluate_to_value(reference, None, context, int)?.expect_num()?;
+            to_decibels(power, reference, context, int)?.mul(target.clone(), int)?
+        }
+        None => power.convert_to(target.clone(), int)?,
+    }))
+}
+
+/// Adds or subtracts two power levels like `20 dBm + 20 dBm`. The sum of
+/// two levels is the level of their combined power (about `23 dBm`), while
+/// the difference between two levels is a ratio in `dB`. Returns `None`
+/// unless both operands are power levels.
+pub(crate) fn combine_power_levels<I: Interrupt>(
+    a: &Number,
+    b: &Number,
+    subtract: bool,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Option<Number>, FendError> {
+    if let (Some((level, reference)), Some(b_reference)) =
+        (power_level(a), power_level_reference(b))
+    {
+        let reference = evaluate_to_value(reference, None, context, int)?.expect_num()?;
+        let b_reference = evaluate_to_value(b_reference, None, context, int)?.expect_num()?;
+        if subtract {
+            // e.g. `0 dBW` is `30 dBm`
+            let offset = to_decibels(b_reference, reference, context, int)?;
+            let b_decibels = b.clone().without_unit().add(offset, int)?;
+            let decibel = evaluate_to_value("dB", None, context, int)?.expect_num()?;
+            let difference = a.clone().without_unit().sub(b_decibels, int)?;
+            return Ok(Some(difference.mul(decibel, int)?));
+        }
+        let a_power = from_decibels(a.clone().without_unit(), reference.clone(), int)?;
+        let b_power = from_decibels(b.clone().without_unit(), b_reference, int)?;
+        let level = evaluate_to_value(level, None, context, int)?.expect_num()?;
+        let total = a_power.add(b_power, int)?;
+        return Ok(Some(
+            to_decibels(total, reference, context, int)?.mul(level, int)?,
+        ));
+    }
+    Ok(None)
+}
+
+/// Converts `num` to the most readable unit for its magnitude, e.g.
+/// `123456789 bytes` to `117.7 MiB`. Numbers that aren't in any of the
+/// supported quantities are returned unchanged.
+pub(crate) fn convert_to_human_units<I: Interrupt>(
+    num: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    convert_to_readable_unit(num, builtin::HUMAN_UNITS, context, int)
+}
+
+/// Like `convert_to_human_units`, but for totals of durations like
+/// `takes 45 min per item; 32 items`, e.g. `24 hours`
+pub(crate) fn convert_total_to_human_units<I: Interrupt>(
+    num: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    convert_to_readable_unit(num, builtin::HUMAN_TOTAL_UNITS, context, int)
+}
+
+/// Converts `num` to the largest unit in which it is at least 1, from the
+/// first group of `unit_groups` (each from smallest to largest) that
+/// it is compatible with
+fn convert_to_readable_unit<I: Interrupt>(
+    num: Number,
+    unit_groups: &[&[&str]],
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    if num.is_zero() {
+        return Ok(num);
+    }
+    for units in unit_groups {
+        let mut result = None;
+        for target in *units {
+            let unit = evaluate_to_value(target, None, context, int)?.expect_num()?;
+            let converted = match num.clone().convert_to(unit.clone(), int) {
+                Ok(converted) => converted,
+                Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+                // a different kind of quantity
+                Err(_) => break,
+            };
+            let magnitude = converted.clone().div(unit, int)?.abs(int)?;
+            if result.is_some() && magnitude.compare(&1.into(), int)? == Ordering::Less {
+                break;
+            }
+            result = Some(converted);
+        }
+        if let Some(result) = result {
+            return Ok(result.with_format(FormattingStyle::SignificantFigures(4)));
+        }
+    }
+    Ok(num)
+}
+
+/// Returns the singular and plural names of all custom and built-in
+/// units, without prefixes
+pub(crate) fn all_unit_names(context: &crate::Context) -> Vec<&str> {
+    let mut names = vec![];
+    for unit in context.custom_units.iter().rev() {
+        names.push(unit.singular.as_str());
+        names.push(unit.plural.as_str());
+    }
     for group in builtin::ALL_UNIT_DEFS {
-        for (s, _, _, _) in *group {
-            // only add singular name, since plurals
-            // unnecessarily clutter autocompletions
-            add(s);
+        for (singular, plural, _, _) in *group {
+            names.push(singular);
+            names.push(plural);
+        }
+    }
+    names.retain(|name| !name.is_empty());
+    names
+}
+
+/// Returns the names of all custom and built-in units with the given
+/// dimension, e.g. `watt`, `W` and `horsepower` for power. These can all be
+/// used as targets of a conversion like `to hp`.
+pub(crate) fn units_of_dimension<I: Interrupt>(
+    dimension: &Dimension,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Vec<String>, FendError> {
+    let mut names: Vec<String> = context
+        .custom_units
+        .iter()
+        .map(|unit| unit.singular.to_string())
+        .collect();
+    for group in builtin::MEASUREMENT_UNIT_DEFS {
+        for (singular, _, _, _) in *group {
+            names.push((*singular).to_string());
+        }
+    }
+    let mut result = vec![];
+    for name in names {
+        test_int(int)?;
+        let unit = match query_unit_static(&name, context, int) {
+            Ok(Value::Num(unit)) => unit,
+            Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+            _ => continue,
+        };
+        if Dimension::new(unit.base_unit_exponents(int)?) == *dimension && !result.contains(&name) {
+            result.push(name);
+        }
+    }
+    if result.is_empty() {
+        return Err(FendError::NoUnitsOfDimension(dimension.describe()));
+    }
+    Ok(result)
+}
+
+/// Converts `num` to a named derived unit with the same dimensions, e.g.
+/// `15 kg m / s^2` to `15 N`. Numbers with a single unit, or that don't
+/// match any named unit, are returned unchanged.
+pub(crate) fn convert_to_simplest_unit<I: Interrupt>(
+    num: Number,
+    context: &mut crate::Context,
+    int: &I,
+) -> Result<Number, FendError> {
+    if num.unit_names().len() <= 1 {
+        return Ok(num);
+    }
+    for target in builtin::NAMED_DERIVED_UNITS {
+        let unit = evaluate_to_value(target, None, context, int)?.expect_num()?;
+        match num.clone().convert_to(unit, int) {
+            Ok(converted) => return Ok(converted),
+            Err(FendError::Interrupted) => return Err(FendError::Interrupted),
+            // different dimensions
+            Err(_) => (),
         }
     }
+    Ok(num)
+}
 
-    result.sort_by(|a, b| a.display().cmp(b.display()));
+/// Returns the names of custom and built-in units that start with `prefix`,
+/// for tab completion. Plurals are left out since they would clutter the
+/// list. Units that allow long prefixes are also completed after one, so
+/// that e.g. `kilome` is completed to `kilometer`.
+pub(crate) fn get_completions_for_prefix(prefix: &str, context: &crate::Context) -> Vec<String> {
+    let mut units: Vec<(&str, PrefixRule)> = context
+        .custom_units
+        .iter()
+        .rev()
+        .map(|unit| (unit.singular.as_str(), unit.prefix_rule))
+        .collect();
+    for group in builtin::ALL_UNIT_DEFS {
+        for (singular, _, definition, _) in *group {
+            let rule = if definition.starts_with("l@") {
+                PrefixRule::LongPrefixAllowed
+            } else if definition.starts_with("lp@") {
+                PrefixRule::LongPrefix
+            } else {
+                PrefixRule::NoPrefixesAllowed
+            };
+            units.push((singular, rule));
+        }
+    }
+    units.retain(|(name, _)| !name.is_empty());
 
+    let mut result: Vec<String> = units
+        .iter()
+        .filter(|(name, _)| name.starts_with(prefix))
+        .map(|(name, _)| (*name).to_string())
+        .collect();
+    for &(long_prefix, rule) in &units {
+        if rule != PrefixRule::LongPrefix {
+            continue;
+        }
+        let rest = match prefix.strip_prefix(long_prefix) {
+            Some(rest) => rest,
+            None => continue,
+        };
+        if rest.is_empty() {
+            continue;
+        }
+        for &(name, rule) in &units {
+            if rule == PrefixRule::LongPrefixAllowed && name.starts_with(rest) {
+                result.push(format!("{}{}", long_prefix, name));
+            }
+        }
+    }
     result
 }
@@ -45,6 +45,9 @@ const TEMPERATURE_SCALES: &[UnitTuple] = &[
     ("fahrenheit", "", "l@!", ""),
     ("\u{b0}F", "", "fahrenheit", ""),
     ("oF", "", "=\u{b0}F", ""),
+    // temperature differences, e.g. `20 \u{b0}C + 5 deltaF`
+    ("deltaC", "", "kelvin", ""),
+    ("deltaF", "", "5/9 kelvin", ""),
 ];
 
 const BITS_AND_BYTES: &[UnitTuple] = &[
@@ -53,6 +56,11 @@ const BITS_AND_BYTES: &[UnitTuple] = &[
     ("byte", "bytes", "l@8 bits", ""),
     ("b", "", "s@bit", ""),
     ("B", "", "s@byte", ""),
+    // e.g. `Mbit/s`, as used for network speeds
+    ("kbit", "", "kilobit", ""),
+    ("Mbit", "", "megabit", ""),
+    ("Gbit", "", "gigabit", ""),
+    ("Tbit", "", "terabit", ""),
     ("octet", "octets", "l@8 bits", ""),
 ];
 
@@ -428,6 +436,9 @@ const RATIOS: &[UnitTuple] = &[
     ("bel", "bels", "0.5 * ln(10) neper", ""),
     ("decibel", "decibels", "1/10 bel", ""),
     ("dB", "", "decibel", ""),
+    // power levels relative to 1 mW and 1 W, see `POWER_LEVEL_UNITS`
+    ("dBm", "", "dB", ""),
+    ("dBW", "", "dB", ""),
     ("mill", "mills", "0.001", ""),
     ("ppm", "", "1e-6", ""),
     ("parts_per_million", "", "ppm", ""),
@@ -439,6 +450,14 @@ const RATIOS: &[UnitTuple] = &[
     ("basispoint", "", "0.01 %", ""),
 ];
 
+// dimensionless units for counting things, e.g. `3 times per week` or
+// `45 min per item`
+const COUNTS: &[UnitTuple] = &[
+    ("time", "times", "1", ""),
+    ("occurrence", "occurrences", "1", ""),
+    ("item", "items", "1", ""),
+];
+
 const COMMON_PHYSICAL_UNITS: &[UnitTuple] = &[
     ("electron_volt", "electron_volts", "l@electron_charge V", ""),
     ("eV", "", "s@electron_volt", ""),
@@ -651,6 +670,63 @@ const EXCHANGE_RATES: &[UnitTuple] = &[
     ("ZAR", "ZAR", "(1/16.3923) _EUR", ""),
 ];
 
+// cryptocurrencies don't have built-in exchange rates, so they can only be
+// converted to other currencies using rates from an `ExchangeRateSource`
+const CRYPTOCURRENCIES: &[UnitTuple] = &[
+    ("BTC", "BTC", "!", "Bitcoin"),
+    ("bitcoin", "bitcoins", "BTC", ""),
+    ("satoshi", "satoshis", "1e-8 BTC", ""),
+    ("sat", "sats", "satoshi", ""),
+    ("ETH", "ETH", "!", "Ether"),
+    ("ether", "", "ETH", ""),
+    ("gwei", "", "1e-9 ETH", ""),
+    ("wei", "", "1e-18 ETH", ""),
+    ("LTC", "LTC", "!", "Litecoin"),
+    ("litecoin", "litecoins", "LTC", ""),
+    ("XRP", "XRP", "!", "XRP"),
+    ("SOL", "SOL", "!", "Solana"),
+    ("DOGE", "DOGE", "!", "Dogecoin"),
+    ("dogecoin", "dogecoins", "DOGE", ""),
+];
+
+// ISO 4217 code, symbol, full name and number of decimal places (minor units)
+// for every currency with an exchange rate
+pub(crate) const CURRENCY_INFO: &[(&str, &str, &str, u8)] = &[
+    ("EUR", "\u{20ac}", "Euro", 2),
+    ("USD", "$", "United States dollar", 2),
+    ("JPY", "\u{a5}", "Japanese yen", 0),
+    ("BGN", "\u{43b}\u{432}", "Bulgarian lev", 2),
+    ("CZK", "K\u{10d}", "Czech koruna", 2),
+    ("DKK", "kr", "Danish krone", 2),
+    ("GBP", "\u{a3}", "Pound sterling", 2),
+    ("HUF", "Ft", "Hungarian forint", 2),
+    ("PLN", "z\u{142}", "Polish z\u{142}oty", 2),
+    ("RON", "lei", "Romanian leu", 2),
+    ("SEK", "kr", "Swedish krona", 2),
+    ("CHF", "CHF", "Swiss franc", 2),
+    ("ISK", "kr", "Icelandic kr\u{f3}na", 0),
+    ("NOK", "kr", "Norwegian krone", 2),
+    ("HRK", "kn", "Croatian kuna", 2),
+    ("RUB", "\u{20bd}", "Russian ruble", 2),
+    ("TRY", "\u{20ba}", "Turkish lira", 2),
+    ("AUD", "AU$", "Australian dollar", 2),
+    ("BRL", "R$", "Brazilian real", 2),
+    ("CAD", "CA$", "Canadian dollar", 2),
+    ("CNY", "\u{a5}", "Chinese yuan", 2),
+    ("HKD", "HK$", "Hong Kong dollar", 2),
+    ("IDR", "Rp", "Indonesian rupiah", 2),
+    ("ILS", "\u{20aa}", "Israeli new shekel", 2),
+    ("INR", "\u{20b9}", "Indian rupee", 2),
+    ("KRW", "\u{20a9}", "South Korean won", 0),
+    ("MXN", "MX$", "Mexican peso", 2),
+    ("MYR", "RM", "Malaysian ringgit", 2),
+    ("NZD", "NZ$", "New Zealand dollar", 2),
+    ("PHP", "\u{20b1}", "Philippine peso", 2),
+    ("SGD", "S$", "Singapore dollar", 2),
+    ("THB", "\u{e3f}", "Thai baht", 2),
+    ("ZAR", "R", "South African rand", 2),
+];
+
 pub(crate) const ALL_UNIT_DEFS: &[&[UnitTuple]] = &[
     BASE_UNITS,
     BASE_UNIT_ABBREVIATIONS,
@@ -666,6 +742,7 @@ pub(crate) const ALL_UNIT_DEFS: &[&[UnitTuple]] = &[
     COMMON_SI_DERIVED_UNITS,
     TIME_UNITS,
     RATIOS,
+    COUNTS,
     COMMON_PHYSICAL_UNITS,
     IMPERIAL_UNITS,
     LIQUID_UNITS,
@@ -676,6 +753,207 @@ pub(crate) const ALL_UNIT_DEFS: &[&[UnitTuple]] = &[
     NAUTICAL_UNITS,
     CURRENCIES,
     EXCHANGE_RATES,
+    CRYPTOCURRENCIES,
+];
+
+// units of measurement listed by queries like `units of power`,
+// i.e. without prefixes, number words, constants or currencies
+pub(crate) const MEASUREMENT_UNIT_DEFS: &[&[UnitTuple]] = &[
+    BASE_UNITS,
+    BASE_UNIT_ABBREVIATIONS,
+    TEMPERATURE_SCALES,
+    BITS_AND_BYTES,
+    ANGLES,
+    SOLID_ANGLES,
+    COMMON_SI_DERIVED_UNITS,
+    TIME_UNITS,
+    COMMON_PHYSICAL_UNITS,
+    IMPERIAL_UNITS,
+    LIQUID_UNITS,
+    AVOIRDUPOIS_WEIGHT,
+    TROY_WEIGHT,
+    OTHER_WEIGHTS,
+    IMPERIAL_ABBREVIATIONS,
+    NAUTICAL_UNITS,
+];
+
+// units used to show results in the other measurement system:
+// (metric units, US customary units), each from smallest to largest
+pub(crate) const DUAL_UNITS: &[(&[&str], &[&str])] = &[
+    (&["mm", "cm", "m", "km"], &["inch", "foot", "mile"]),
+    (&["g", "kg"], &["oz", "lb"]),
+    (&["mL", "L"], &["floz", "gallon"]),
+    (&["\u{b0}C"], &["\u{b0}F"]),
+    (&["km/h"], &["mph"]),
+];
+
+// base unit, SI unit and imperial unit used by `to si` and `to imperial`,
+// in the order they are written in compound units
+pub(crate) const SYSTEM_BASE_UNITS: &[(&str, &str, &str)] = &[
+    ("kilogram", "kg", "lb"),
+    ("meter", "m", "ft"),
+    ("second", "s", "s"),
+    ("kelvin", "K", "\u{b0}R"),
+    ("ampere", "A", "A"),
+    ("mole", "mol", "mol"),
+    ("candela", "cd", "cd"),
+    ("neper", "Np", "Np"),
+];
+
+// logarithmic power levels, and the power that a level of 0 refers to
+pub(crate) const POWER_LEVEL_UNITS: &[(&str, &str)] = &[("dBm", "mW"), ("dBW", "W")];
+
+// units used by `to human`, each from smallest to largest
+pub(crate) const HUMAN_UNITS: &[&[&str]] = &[
+    &["ns", "\u{b5}s", "ms", "s", "min", "h", "day", "year"],
+    &["nm", "\u{b5}m", "mm", "m", "km"],
+    &["mg", "g", "kg", "t"],
+    &["mL", "L"],
+    &["B", "KiB", "MiB", "GiB", "TiB", "PiB"],
+    &["Hz", "kHz", "MHz", "GHz", "THz"],
+    &["mW", "W", "kW", "MW", "GW"],
+    &["J", "kJ", "MJ", "GJ"],
+    &["Pa", "kPa", "MPa", "GPa"],
+    &["mA", "A", "kA"],
+    &["mV", "V", "kV"],
+];
+
+// units for totals like `takes 45 min per item; 32 items`, which stop at
+// hours because it is unclear whether a day of work has 8 or 24 hours
+pub(crate) const HUMAN_TOTAL_UNITS: &[&[&str]] = &[&["seconds", "minutes", "hours"]];
+
+// named derived units used by `to simplest`, e.g. `15 kg m / s^2` is
+// shown as `15 N`; if several have the same dimensions the first is used
+pub(crate) const NAMED_DERIVED_UNITS: &[&str] = &[
+    "N", "J", "W", "Pa", "coulomb", "V", "ohm", "farad", "S", "Wb", "tesla", "H", "Hz",
+];
+
+// (singular) names of US customary units, which are shown in metric
+// units when dual units are enabled
+pub(crate) const US_CUSTOMARY_UNITS: &[&str] = &[
+    "inch",
+    "foot",
+    "ft",
+    "yard",
+    "yd",
+    "mile",
+    "mi",
+    "ounce",
+    "oz",
+    "pound",
+    "lb",
+    "floz",
+    "fluid_ounce",
+    "pint",
+    "quart",
+    "gallon",
+    "gal",
+    "mph",
+    "fahrenheit",
+    "\u{b0}F",
+];
+
+// category name, units (or unit expressions) that can be converted between each other
+pub(crate) const UNIT_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "length",
+        &[
+            "meter",
+            "kilometer",
+            "centimeter",
+            "millimeter",
+            "micrometer",
+            "nanometer",
+            "mile",
+            "yard",
+            "foot",
+            "inch",
+            "nautical_mile",
+            "astronomical_unit",
+            "light_year",
+        ],
+    ),
+    (
+        "mass",
+        &[
+            "kilogram",
+            "gram",
+            "milligram",
+            "tonne",
+            "pound",
+            "ounce",
+            "stone",
+        ],
+    ),
+    (
+        "time",
+        &[
+            "second",
+            "millisecond",
+            "minute",
+            "hour",
+            "day",
+            "week",
+            "year",
+        ],
+    ),
+    (
+        "temperature",
+        &["celsius", "fahrenheit", "kelvin", "rankine"],
+    ),
+    (
+        "area",
+        &[
+            "m^2", "km^2", "cm^2", "hectare", "acre", "mi^2", "ft^2", "inch^2",
+        ],
+    ),
+    (
+        "volume",
+        &[
+            "liter",
+            "milliliter",
+            "m^3",
+            "cm^3",
+            "gallon",
+            "quart",
+            "pint",
+            "fluid_ounce",
+        ],
+    ),
+    ("speed", &["m/s", "km/h", "mph", "ft/s", "knot"]),
+    (
+        "information",
+        &[
+            "bit", "byte", "kilobyte", "megabyte", "gigabyte", "terabyte", "kibibyte", "mebibyte",
+            "gibibyte",
+        ],
+    ),
+    (
+        "energy",
+        &[
+            "joule",
+            "kilojoule",
+            "calorie",
+            "kilocalorie",
+            "kWh",
+            "BTU",
+            "eV",
+        ],
+    ),
+    ("power", &["watt", "kilowatt", "horsepower"]),
+    ("pressure", &["pascal", "bar", "atmosphere", "psi", "mmHg"]),
+    (
+        "angle",
+        &[
+            "degree",
+            "radian",
+            "gradian",
+            "turn",
+            "arcminute",
+            "arcsecond",
+        ],
+    ),
+    ("frequency", &["hertz", "kilohertz", "megahertz", "rpm"]),
 ];
 
 const SHORT_PREFIXES: &[(&str, &str)] = &[
@@ -711,6 +989,18 @@ const SHORT_PREFIXES: &[(&str, &str)] = &[
     ("y", "sp@yocto"),
 ];
 
+/// Returns true if the given currency code has a built-in exchange rate
+pub(crate) fn is_exchange_rate(code: &str) -> bool {
+    EXCHANGE_RATES.iter().any(|(c, _, _, _)| *c == code)
+}
+
+/// Returns true if the given code is a cryptocurrency like `BTC`
+pub(crate) fn is_cryptocurrency(code: &str) -> bool {
+    CRYPTOCURRENCIES
+        .iter()
+        .any(|(c, _, def, _)| *c == code && *def == "!")
+}
+
 #[allow(clippy::too_many_lines)]
 pub(crate) fn query_unit<'a>(
     ident: &'a str,
@@ -775,4 +1065,85 @@ mod tests {
             test_group(group);
         }
     }
+
+    #[test]
+    fn test_currency_info() {
+        let mut ctx = crate::Context::new();
+        for (code, _, _, _) in CURRENCY_INFO {
+            test_str(&format!("1 {} to EUR", code), &mut ctx);
+        }
+        for (code, _, _, _) in EXCHANGE_RATES {
+            assert!(
+                CURRENCY_INFO.iter().any(|(c, _, _, _)| c == code),
+                "missing currency info for {}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_dual_units() {
+        let mut ctx = crate::Context::new();
+        for (metric, us) in DUAL_UNITS {
+            for unit in metric.iter().chain(*us) {
+                test_str(&format!("1 {} to {}", unit, metric[0]), &mut ctx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_system_base_units() {
+        let mut ctx = crate::Context::new();
+        for (base_unit, si, imperial) in SYSTEM_BASE_UNITS {
+            test_str(&format!("1 {} to {}", base_unit, si), &mut ctx);
+            test_str(&format!("1 {} to {}", base_unit, imperial), &mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_power_level_units() {
+        let mut ctx = crate::Context::new();
+        for (level, reference) in POWER_LEVEL_UNITS {
+            test_str(&format!("1 {} to {}", level, level), &mut ctx);
+            test_str(&format!("1 {} to W", reference), &mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_human_units() {
+        let mut ctx = crate::Context::new();
+        for units in HUMAN_UNITS {
+            for unit in *units {
+                test_str(&format!("1 {} to {}", unit, units[0]), &mut ctx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_human_total_units() {
+        let mut ctx = crate::Context::new();
+        for units in HUMAN_TOTAL_UNITS {
+            for unit in *units {
+                test_str(&format!("1 {} to {}", unit, units[0]), &mut ctx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_named_derived_units() {
+        let mut ctx = crate::Context::new();
+        for unit in NAMED_DERIVED_UNITS {
+            test_str(&format!("1 {} to simplest", unit), &mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_unit_categories() {
+        let mut ctx = crate::Context::new();
+        for (_, units) in UNIT_CATEGORIES {
+            for unit in *units {
+                test_str(&format!("1 {} to {}", unit, units[0]), &mut ctx);
+            }
+        }
+    }
 }
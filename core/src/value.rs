@@ -1,14 +1,30 @@
-use crate::ast::Bop;
+use crate::ast::{Bop, Param};
 use crate::error::{FendError, Interrupt};
-use crate::num::{Base, FormattingStyle, Number};
+use crate::num::{number_theory, statistics, vector, Base, FormattingStyle, Number, RoundingMode};
 use crate::scope::Scope;
 use crate::{ast::Expr, ident::Ident};
 use crate::{Span, SpanKind};
+use duration::Duration;
+use matrix::Matrix;
+use polynomial::Polynomial;
+use quaternion::Quaternion;
+use std::any::Any;
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::{fmt, sync::Arc};
+use uncertain::UncertainValue;
 
 mod boolean;
+pub(crate) mod dimension;
+pub(crate) mod duration;
 pub(crate) mod func;
+pub(crate) mod matrix;
+pub(crate) mod mixed_units;
+pub(crate) mod polar;
+pub(crate) mod polynomial;
+pub(crate) mod quaternion;
+mod table;
+pub(crate) mod uncertain;
 mod unit;
 
 pub(crate) trait BoxClone {
@@ -21,7 +37,17 @@ impl<T: Clone + ValueTrait> BoxClone for T {
     }
 }
 
-pub(crate) trait ValueTrait: fmt::Debug + BoxClone + 'static {
+pub(crate) trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: ValueTrait> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub(crate) trait ValueTrait: fmt::Debug + BoxClone + AsAny + 'static {
     fn type_name(&self) -> &'static str;
 
     fn format(&self, indent: usize, spans: &mut Vec<Span>);
@@ -41,6 +67,32 @@ pub(crate) trait ValueTrait: fmt::Debug + BoxClone + 'static {
         Err(FendError::ExpectedANumber)
     }
 
+    fn sub(&self, _rhs: Value, _context: &mut crate::Context) -> Result<Value, FendError> {
+        Err(FendError::InvalidOperandsForSubtraction)
+    }
+
+    fn mul(&self, _rhs: Value) -> Result<Value, FendError> {
+        Err(FendError::ExpectedANumber)
+    }
+
+    fn div(&self, _rhs: Value) -> Result<Value, FendError> {
+        Err(FendError::ExpectedANumber)
+    }
+
+    fn pow(&self, _exponent: i64) -> Result<Value, FendError> {
+        Err(FendError::ExpectedANumber)
+    }
+
+    fn equals(&self, _rhs: &Value) -> bool {
+        false
+    }
+
+    /// Orders this value relative to `rhs` for `<`, `<=`, `>` and `>=`,
+    /// or returns `None` if they can't be compared
+    fn compare(&self, _rhs: &Value) -> Option<Ordering> {
+        None
+    }
+
     fn is_unit(&self) -> bool {
         false
     }
@@ -69,6 +121,7 @@ pub(crate) enum Value {
     // user-defined function with a named parameter
     Fn(Ident, Box<Expr>, Option<Arc<Scope>>),
     Object(Vec<(Cow<'static, str>, Box<Value>)>),
+    List(Vec<Value>),
     String(Cow<'static, str>),
     Dynamic(Box<dyn ValueTrait>),
 }
@@ -77,6 +130,7 @@ pub(crate) enum Value {
 pub(crate) enum BuiltInFunction {
     Approximately,
     Abs,
+    Arg,
     Sin,
     Cos,
     Tan,
@@ -94,6 +148,53 @@ pub(crate) enum BuiltInFunction {
     Log10,
     Base,
     Sample,
+    Min,
+    Max,
+    Clamp,
+    Round,
+    Floor,
+    Ceil,
+    Trunc,
+    Table,
+    Random,
+    RandInt,
+    RandomChoice,
+    RandomSeed,
+    Totient,
+    Divisors,
+    NumDivisors,
+    Sigma,
+    Fib,
+    Mean,
+    Median,
+    Mode,
+    Stdev,
+    Variance,
+    Percentile,
+    Normalize,
+    PercentOfTotal,
+    Dot,
+    Cross,
+    Norm,
+    Transpose,
+    Determinant,
+    Inverse,
+    Polynomial,
+    Roots,
+    Conjugate,
+    Quaternion,
+    Rotate,
+    CurrencyInfo,
+    DownloadTime,
+    MolarMass,
+    FormatDate,
+    Date,
+    Between,
+    Easter,
+    IsHoliday,
+    AddWorkdays,
+    MoonPhase,
+    JulianDay,
 }
 
 impl BuiltInFunction {
@@ -134,6 +235,7 @@ impl BuiltInFunction {
         match self {
             Self::Approximately => "approximately",
             Self::Abs => "abs",
+            Self::Arg => "arg",
             Self::Sin => "sin",
             Self::Cos => "cos",
             Self::Tan => "tan",
@@ -151,6 +253,53 @@ impl BuiltInFunction {
             Self::Log10 => "log10",
             Self::Base => "base",
             Self::Sample => "sample",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Clamp => "clamp",
+            Self::Round => "round",
+            Self::Floor => "floor",
+            Self::Ceil => "ceil",
+            Self::Trunc => "trunc",
+            Self::Table => "table",
+            Self::Random => "random",
+            Self::RandInt => "randint",
+            Self::RandomChoice => "random_choice",
+            Self::RandomSeed => "random_seed",
+            Self::Totient => "totient",
+            Self::Divisors => "divisors",
+            Self::NumDivisors => "num_divisors",
+            Self::Sigma => "sigma",
+            Self::Fib => "fib",
+            Self::Mean => "mean",
+            Self::Median => "median",
+            Self::Mode => "mode",
+            Self::Stdev => "stdev",
+            Self::Variance => "variance",
+            Self::Percentile => "percentile",
+            Self::Normalize => "normalize",
+            Self::PercentOfTotal => "percentoftotal",
+            Self::Dot => "dot",
+            Self::Cross => "cross",
+            Self::Norm => "norm",
+            Self::Transpose => "transpose",
+            Self::Determinant => "det",
+            Self::Inverse => "inverse",
+            Self::Polynomial => "poly",
+            Self::Roots => "roots",
+            Self::Conjugate => "conjugate",
+            Self::Quaternion => "quat",
+            Self::Rotate => "rotate",
+            Self::CurrencyInfo => "currencyinfo",
+            Self::DownloadTime => "download_time",
+            Self::MolarMass => "molar_mass",
+            Self::FormatDate => "format",
+            Self::Date => "date",
+            Self::Between => "between",
+            Self::Easter => "easter",
+            Self::IsHoliday => "is_holiday",
+            Self::AddWorkdays => "add_workdays",
+            Self::MoonPhase => "moon_phase",
+            Self::JulianDay => "julian_day",
         }
     }
 }
@@ -175,6 +324,77 @@ impl Value {
         }
     }
 
+    /// Returns the numbers in a list (or a single number)
+    fn expect_nums(self) -> Result<Vec<Number>, FendError> {
+        self.into_list().into_iter().map(Self::expect_num).collect()
+    }
+
+    /// Returns the two vectors passed to e.g. `dot([1, 2], [3, 4])`
+    fn expect_two_vectors(
+        function: &'static str,
+        arg: Self,
+    ) -> Result<(Vec<Number>, Vec<Number>), FendError> {
+        let args = arg.into_list();
+        if args.len() != 2 {
+            return Err(FendError::WrongNumberOfArguments {
+                function,
+                expected: "2",
+                found: args.len(),
+            });
+        }
+        let mut vectors = vec![];
+        for arg in args {
+            match arg {
+                Self::List(_) => vectors.push(arg.expect_nums()?),
+                _ => return Err(FendError::ExpectedAVector),
+            }
+        }
+        let b = vectors.pop().unwrap();
+        let a = vectors.pop().unwrap();
+        Ok((a, b))
+    }
+
+    /// Multiplies or divides each element of a list by a number,
+    /// e.g. `2 * [1 m, 2 m]` or `[3, 6] / 3`
+    pub(crate) fn scale_list<I: Interrupt>(
+        self,
+        bop: Bop,
+        rhs: Self,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut result = vec![];
+        match (self, rhs) {
+            (Self::Num(scalar), Self::List(elements)) => {
+                for x in elements {
+                    result.push(scalar.clone().mul(x.expect_num()?, int)?);
+                }
+            }
+            (Self::List(elements), Self::Num(scalar)) => {
+                for x in elements {
+                    let x = x.expect_num()?;
+                    result.push(match bop {
+                        Bop::Div => x.div((*scalar).clone(), int)?,
+                        _ => x.mul((*scalar).clone(), int)?,
+                    });
+                }
+            }
+            _ => return Err(FendError::ExpectedANumber),
+        }
+        Ok(Self::List(
+            result.into_iter().map(|x| Self::Num(Box::new(x))).collect(),
+        ))
+    }
+
+    /// Returns the elements of a list, or the value itself if it isn't a list
+    pub(crate) fn into_list(self) -> Vec<Self> {
+        match self {
+            Self::List(elements) => elements,
+            // e.g. `max()`
+            value if value.is_unit() => vec![],
+            value => vec![value],
+        }
+    }
+
     pub(crate) fn expect_dyn(self) -> Result<Box<dyn ValueTrait>, FendError> {
         match self {
             Self::Dynamic(d) => Ok(d),
@@ -182,6 +402,14 @@ impl Value {
         }
     }
 
+    /// Returns the underlying dynamic value if it has type `T`
+    pub(crate) fn as_dyn<T: ValueTrait>(&self) -> Option<&T> {
+        match self {
+            Self::Dynamic(d) => d.as_any().downcast_ref(),
+            _ => None,
+        }
+    }
+
     pub(crate) fn is_unit(&self) -> bool {
         match self {
             Self::Dynamic(d) => d.is_unit(),
@@ -196,6 +424,74 @@ impl Value {
         }
     }
 
+    pub(crate) fn sub_dyn(
+        self,
+        rhs: Self,
+        context: &mut crate::Context,
+    ) -> Result<Self, FendError> {
+        match self {
+            Self::Dynamic(d) => d.sub(rhs, context),
+            _ => Err(FendError::InvalidOperandsForSubtraction),
+        }
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        Ok(match (self, rhs) {
+            (Self::Num(a), Self::Num(b)) => a.compare(b, int)? == Ordering::Equal,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::List(a), Self::List(b)) => {
+                if a.len() != b.len() {
+                    return Ok(false);
+                }
+                for (a, b) in a.iter().zip(b) {
+                    if !a.equals(b, int)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            (Self::Dynamic(a), b) => {
+                if let (Some(a), Some(b)) = (self.as_dyn::<Matrix>(), b.as_dyn::<Matrix>()) {
+                    a.equals(b, int)?
+                } else if let (Some(a), Some(b)) =
+                    (self.as_dyn::<Polynomial>(), b.as_dyn::<Polynomial>())
+                {
+                    a.equals(b, int)?
+                } else if let (Some(a), Some(b)) =
+                    (self.as_dyn::<Quaternion>(), b.as_dyn::<Quaternion>())
+                {
+                    a.equals(b, int)?
+                } else if let (Some(a), Some(b)) = (
+                    self.as_dyn::<UncertainValue>(),
+                    b.as_dyn::<UncertainValue>(),
+                ) {
+                    a.equals(b, int)?
+                } else if let (Some(a), Some(b)) =
+                    (self.as_dyn::<Duration>(), b.as_dyn::<Duration>())
+                {
+                    a.equals(b, int)?
+                } else {
+                    a.equals(b)
+                }
+            }
+            _ => false,
+        })
+    }
+
+    pub(crate) fn compare<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Ordering, FendError> {
+        match (self, rhs) {
+            (Self::Num(a), Self::Num(b)) => a.compare(b, int),
+            (Self::Dynamic(a), b) => {
+                if let (Some(a), Some(b)) = (self.as_dyn::<Duration>(), b.as_dyn::<Duration>()) {
+                    a.compare(b, int)
+                } else {
+                    a.compare(b).ok_or(FendError::InvalidOperandsForComparison)
+                }
+            }
+            _ => Err(FendError::InvalidOperandsForComparison),
+        }
+    }
+
     pub(crate) fn handle_num(
         self,
         eval_fn: impl FnOnce(Number) -> Result<Number, FendError>,
@@ -262,6 +558,18 @@ impl Value {
                         self_.format_to_plain_string(0, context, int)?,
                     ));
                 }
+                if other.as_dyn::<Polynomial>().is_some() {
+                    return Polynomial::bop(Bop::Mul, Self::Num(n), other, context, int);
+                }
+                if other.as_dyn::<Quaternion>().is_some() {
+                    return Quaternion::bop(Bop::Mul, Self::Num(n), other, context, int);
+                }
+                if other.as_dyn::<UncertainValue>().is_some() {
+                    return UncertainValue::bop(Bop::Mul, Self::Num(n), other, context, int);
+                }
+                if other.as_dyn::<Duration>().is_some() {
+                    return Duration::bop(Bop::Mul, Self::Num(n), other, context, int);
+                }
                 let n2 = n.clone();
                 other.handle_num(
                     |x| n.mul(x, int),
@@ -273,11 +581,55 @@ impl Value {
                 Self::apply_built_in_function(func, other, scope, context, int)?
             }
             Self::Fn(param, expr, custom_scope) => {
+                if let Some((params, _)) = expr.params() {
+                    let args = into_arguments(other, params)?;
+                    return Self::apply_named_arguments(
+                        param,
+                        *expr,
+                        custom_scope,
+                        args,
+                        scope.as_ref(),
+                        context,
+                        int,
+                    );
+                }
+                let other = match into_named_arguments(other) {
+                    Ok(args) => {
+                        return Self::apply_named_arguments(
+                            param,
+                            *expr,
+                            custom_scope,
+                            args,
+                            scope.as_ref(),
+                            context,
+                            int,
+                        );
+                    }
+                    Err(other) => other,
+                };
                 let new_scope = Scope::with_variable(param, other, scope, custom_scope);
                 return crate::ast::evaluate(*expr, Some(Arc::new(new_scope)), context, int);
             }
             Self::Dynamic(d) => {
-                let other = crate::ast::evaluate(other, scope, context, int)?;
+                let other = crate::ast::evaluate(other, scope.clone(), context, int)?;
+                if let Some(p) = d.as_any().downcast_ref::<Polynomial>() {
+                    return p.apply(other, context, int);
+                }
+                // e.g. `(5 ± 0.1) m`
+                if d.as_any().downcast_ref::<UncertainValue>().is_some() {
+                    return UncertainValue::bop(Bop::Mul, Self::Dynamic(d), other, context, int);
+                }
+                // e.g. `fridays between(...)`, which is parsed as
+                // `(fridays between)(...)`
+                if let Self::BuiltInFunction(f) = other {
+                    if d.as_any()
+                        .downcast_ref::<crate::date::DayOfWeek>()
+                        .is_some()
+                    {
+                        let lhs = Box::new(Expr::Literal(Self::Dynamic(d)));
+                        return Ok(f.wrap_with_expr(|x| Expr::Apply(lhs, x), scope));
+                    }
+                }
                 match d.apply(other) {
                     None => return Err(FendError::IsNotAFunctionOrNumber(stringified_self)),
                     Some(Err(msg)) => return Err(msg),
@@ -288,6 +640,79 @@ impl Value {
         })
     }
 
+    /// Calls a lambda with named arguments, e.g. `(\\a.\\b.a - b)(b=1, a=3)`.
+    /// Each argument binds the parameter with the same name, searching
+    /// through nested lambdas.
+    fn apply_named_arguments<I: Interrupt>(
+        mut param: Ident,
+        mut body: Expr,
+        fn_scope: Option<Arc<Scope>>,
+        mut args: Vec<(Ident, Expr)>,
+        scope: Option<&Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut params = vec![param.as_str().to_string()];
+        let mut inner = &body;
+        while let Expr::Fn(p, next) = inner {
+            params.push(p.as_str().to_string());
+            inner = next;
+        }
+        if let Some((name, _)) = args
+            .iter()
+            .find(|(name, _)| !params.iter().any(|p| p == name.as_str()))
+        {
+            return Err(FendError::UnknownArgument(name.to_string()));
+        }
+        for (i, (name, _)) in args.iter().enumerate() {
+            if args[..i].iter().any(|(n, _)| n.as_str() == name.as_str()) {
+                return Err(FendError::DuplicateArgument(name.to_string()));
+            }
+        }
+        // lambdas with a parameter list like `\(x, y=2).x^y` can't be
+        // partially applied, so every parameter needs an argument or default
+        let defaults = body.params().map(|(params, _)| params.to_vec());
+        let mut new_scope = fn_scope;
+        loop {
+            let position = args
+                .iter()
+                .position(|(name, _)| name.as_str() == param.as_str());
+            let (value, value_scope) = match position {
+                Some(idx) => (args.remove(idx).1, scope.cloned()),
+                // defaults are evaluated in the scope of the lambda, so they
+                // can refer to earlier parameters
+                None => match defaults
+                    .iter()
+                    .flatten()
+                    .find(|(name, _)| name.as_str() == param.as_str())
+                {
+                    Some((_, Some(default))) => (default.clone(), new_scope.clone()),
+                    _ => return Err(FendError::MissingArgument(param.to_string())),
+                },
+            };
+            new_scope = Some(Arc::new(Scope::with_variable(
+                param,
+                value,
+                value_scope,
+                new_scope,
+            )));
+            if args.is_empty() && defaults.is_none() {
+                break;
+            }
+            match body {
+                Expr::Fn(next_param, next_body) => {
+                    param = next_param;
+                    body = *next_body;
+                }
+                _ if args.is_empty() => break,
+                // all remaining arguments name one of the nested parameters
+                _ => return Err(FendError::UnknownArgument(args[0].0.to_string())),
+            }
+        }
+        crate::ast::evaluate(body, new_scope, context, int)
+    }
+
+    #[allow(clippy::too_many_lines)]
     fn apply_built_in_function<I: Interrupt>(
         func: BuiltInFunction,
         arg: Expr,
@@ -295,25 +720,67 @@ impl Value {
         context: &mut crate::Context,
         int: &I,
     ) -> Result<Self, FendError> {
+        // polynomials are built from expressions with an undefined variable,
+        // so their argument can't be evaluated normally
+        match func {
+            BuiltInFunction::Polynomial => {
+                return Polynomial::poly_value(arg, scope, context, int);
+            }
+            BuiltInFunction::Roots => return Polynomial::roots_value(arg, scope, context, int),
+            BuiltInFunction::Quaternion => {
+                return Quaternion::quat_value(arg, scope, context, int);
+            }
+            _ => (),
+        }
         let arg = crate::ast::evaluate(arg, scope.clone(), context, int)?;
+        if let Some(q) = arg.as_dyn::<Quaternion>() {
+            match func {
+                BuiltInFunction::Conjugate => return q.conjugate_value(context, int),
+                BuiltInFunction::Abs | BuiltInFunction::Norm => {
+                    return Ok(Self::Num(Box::new(q.norm(int)?)));
+                }
+                _ => (),
+            }
+        }
+        if let Some(u) = arg.as_dyn::<UncertainValue>() {
+            return u.apply_fn(
+                context,
+                |x, context| {
+                    let x = Expr::Literal(Self::Num(Box::new(x)));
+                    Self::apply_built_in_function(func, x, scope.clone(), context, int)?
+                        .expect_num()
+                },
+                int,
+            );
+        }
         Ok(Self::Num(Box::new(match func {
             BuiltInFunction::Approximately => arg.expect_num()?.make_approximate(),
             BuiltInFunction::Abs => arg.expect_num()?.abs(int)?,
+            BuiltInFunction::Arg => arg.expect_num()?.arg(int)?,
             BuiltInFunction::Sin => arg.expect_num()?.sin(scope, context, int)?,
             BuiltInFunction::Cos => arg.expect_num()?.cos(scope, context, int)?,
             BuiltInFunction::Tan => arg.expect_num()?.tan(scope, context, int)?,
-            BuiltInFunction::Asin => arg.expect_num()?.asin(int)?,
-            BuiltInFunction::Acos => arg.expect_num()?.acos(int)?,
-            BuiltInFunction::Atan => arg.expect_num()?.atan(int)?,
-            BuiltInFunction::Sinh => arg.expect_num()?.sinh(int)?,
-            BuiltInFunction::Cosh => arg.expect_num()?.cosh(int)?,
-            BuiltInFunction::Tanh => arg.expect_num()?.tanh(int)?,
-            BuiltInFunction::Asinh => arg.expect_num()?.asinh(int)?,
-            BuiltInFunction::Acosh => arg.expect_num()?.acosh(int)?,
-            BuiltInFunction::Atanh => arg.expect_num()?.atanh(int)?,
-            BuiltInFunction::Ln => arg.expect_num()?.ln(int)?,
-            BuiltInFunction::Log2 => arg.expect_num()?.log2(int)?,
-            BuiltInFunction::Log10 => arg.expect_num()?.log10(int)?,
+            BuiltInFunction::Asin => arg
+                .expect_num()?
+                .asin(context.precision, int)?
+                .convert_rad_to_default_angle_unit(scope, context, int)?,
+            BuiltInFunction::Acos => arg
+                .expect_num()?
+                .acos(context.precision, int)?
+                .convert_rad_to_default_angle_unit(scope, context, int)?,
+            BuiltInFunction::Atan => arg
+                .expect_num()?
+                .atan(context.precision, int)?
+                .convert_rad_to_default_angle_unit(scope, context, int)?,
+            BuiltInFunction::Sinh => arg.expect_num()?.sinh(context.precision, int)?,
+            BuiltInFunction::Cosh => arg.expect_num()?.cosh(context.precision, int)?,
+            BuiltInFunction::Tanh => arg.expect_num()?.tanh(context.precision, int)?,
+            BuiltInFunction::Asinh => arg.expect_num()?.asinh(context.precision, int)?,
+            BuiltInFunction::Acosh => arg.expect_num()?.acosh(context.precision, int)?,
+            BuiltInFunction::Atanh => arg.expect_num()?.atanh(context.precision, int)?,
+            BuiltInFunction::Ln => arg.expect_num()?.ln(context.precision, int)?,
+            BuiltInFunction::Log2 => arg.expect_num()?.log2(context.precision, int)?,
+            BuiltInFunction::Log10 => arg.expect_num()?.log10(context.precision, int)?,
             BuiltInFunction::Base => {
                 let n: u8 = arg
                     .expect_num()?
@@ -323,9 +790,379 @@ impl Value {
                 return Ok(Self::Base(Base::from_plain_base(n)?));
             }
             BuiltInFunction::Sample => arg.expect_num()?.sample(context, int)?,
+            BuiltInFunction::Min => Self::min_or_max(arg, Ordering::Less, int)?,
+            BuiltInFunction::Max => Self::min_or_max(arg, Ordering::Greater, int)?,
+            BuiltInFunction::Clamp => {
+                let args = arg.into_list();
+                if args.len() != 3 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "clamp",
+                        expected: "3",
+                        found: args.len(),
+                    });
+                }
+                let mut args = args.into_iter();
+                let x = args.next().unwrap().expect_num()?;
+                let lo = args.next().unwrap().expect_num()?;
+                let hi = args.next().unwrap().expect_num()?;
+                if lo.compare(&hi, int)? == Ordering::Greater {
+                    return Err(FendError::ClampBoundsOutOfOrder);
+                }
+                if x.compare(&lo, int)? == Ordering::Less {
+                    lo.convert_unit_of(x, int)?
+                } else if x.compare(&hi, int)? == Ordering::Greater {
+                    hi.convert_unit_of(x, int)?
+                } else {
+                    x
+                }
+            }
+            BuiltInFunction::Round => Self::round(func, arg, RoundingMode::Nearest, int)?,
+            BuiltInFunction::Floor => Self::round(func, arg, RoundingMode::Floor, int)?,
+            BuiltInFunction::Ceil => Self::round(func, arg, RoundingMode::Ceil, int)?,
+            BuiltInFunction::Trunc => Self::round(func, arg, RoundingMode::Trunc, int)?,
+            BuiltInFunction::Table => return Self::table(arg, scope.as_ref(), context, int),
+            BuiltInFunction::Random => {
+                if !arg.is_unit() {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "random",
+                        expected: "0",
+                        found: arg.into_list().len(),
+                    });
+                }
+                let random = Number::from(u64::from(context.next_random_u32()?));
+                random.div(Number::from(1 << 32), int)?.make_approximate()
+            }
+            BuiltInFunction::RandInt => {
+                let args = arg.into_list();
+                if args.len() != 2 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "randint",
+                        expected: "2",
+                        found: args.len(),
+                    });
+                }
+                let mut args = args.into_iter();
+                let low = args.next().unwrap().expect_num()?;
+                let high = args.next().unwrap().expect_num()?;
+                // make sure the lower bound is an integer
+                low.clone().abs(int)?.try_as_usize(int)?;
+                if low.compare(&high, int)? == Ordering::Greater {
+                    return Err(FendError::ClampBoundsOutOfOrder);
+                }
+                let difference = high.sub(low.clone(), int)?.try_as_usize(int)?;
+                let count = u64::try_from(difference).unwrap().saturating_add(1);
+                let random = context.random_u64()? % count;
+                low.add(random.into(), int)?
+            }
+            BuiltInFunction::RandomChoice => {
+                let mut choices = arg.into_list();
+                if choices.is_empty() {
+                    return Err(FendError::ExpectedAtLeastOneArgument);
+                }
+                let idx = context.random_u64()? % u64::try_from(choices.len()).unwrap();
+                return Ok(choices.swap_remove(usize::try_from(idx).unwrap()));
+            }
+            BuiltInFunction::RandomSeed => {
+                let seed = u64::try_from(arg.expect_num()?.try_as_usize(int)?).unwrap();
+                context.set_random_seed(seed);
+                return Ok(Self::from(()));
+            }
+            BuiltInFunction::Totient => {
+                let n = number_theory::totient(arg.expect_num()?.try_as_u64(int)?, int)?;
+                Number::from(n)
+            }
+            BuiltInFunction::Divisors => {
+                let n = arg.expect_num()?.try_as_u64(int)?;
+                let divisors = number_theory::divisors(n, int)?;
+                return Ok(Self::List(
+                    divisors
+                        .into_iter()
+                        .map(|d| Self::Num(Box::new(Number::from(d))))
+                        .collect(),
+                ));
+            }
+            BuiltInFunction::NumDivisors => {
+                let n = arg.expect_num()?.try_as_u64(int)?;
+                Number::from(number_theory::num_divisors(n, int)?)
+            }
+            BuiltInFunction::Sigma => {
+                let n = arg.expect_num()?.try_as_u64(int)?;
+                let mut sum = Number::from(0);
+                for d in number_theory::divisors(n, int)? {
+                    sum = sum.add(d.into(), int)?;
+                }
+                sum
+            }
+            BuiltInFunction::Fib => number_theory::fib(arg.expect_num()?.try_as_u64(int)?, int)?,
+            BuiltInFunction::Mean => statistics::mean(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Median => statistics::median(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Mode => statistics::mode(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Stdev => statistics::stdev(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Variance => statistics::variance(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Percentile => {
+                let args = arg.into_list();
+                if args.len() != 2 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "percentile",
+                        expected: "2",
+                        found: args.len(),
+                    });
+                }
+                let mut args = args.into_iter();
+                let values = Self::expect_nums(args.next().unwrap())?;
+                let p = args.next().unwrap().expect_num()?;
+                statistics::percentile(values, p, int)?
+            }
+            BuiltInFunction::Normalize => {
+                let values = statistics::normalize(Self::expect_nums(arg)?, int)?;
+                return Ok(Self::List(
+                    values.into_iter().map(|x| Self::Num(Box::new(x))).collect(),
+                ));
+            }
+            BuiltInFunction::PercentOfTotal => {
+                let percent = crate::units::query_unit("%", context, int)?.expect_num()?;
+                let mut values = vec![];
+                for value in statistics::normalize(Self::expect_nums(arg)?, int)? {
+                    let value = value.convert_to(percent.clone(), int)?;
+                    values.push(Self::Num(Box::new(value)));
+                }
+                return Ok(Self::List(values));
+            }
+            BuiltInFunction::Dot => {
+                let (a, b) = Self::expect_two_vectors("dot", arg)?;
+                vector::dot(a, b, int)?
+            }
+            BuiltInFunction::Cross => {
+                let (a, b) = Self::expect_two_vectors("cross", arg)?;
+                return Ok(Self::List(
+                    vector::cross(&a, &b, int)?
+                        .into_iter()
+                        .map(|x| Self::Num(Box::new(x)))
+                        .collect(),
+                ));
+            }
+            BuiltInFunction::Norm => vector::norm(Self::expect_nums(arg)?, int)?,
+            BuiltInFunction::Transpose => return Matrix::transpose_value(&arg, context, int),
+            BuiltInFunction::Determinant => Matrix::determinant_value(&arg, int)?,
+            BuiltInFunction::Inverse => return Matrix::inverse_value(&arg, context, int),
+            BuiltInFunction::Conjugate => arg.expect_num()?.conjugate()?,
+            BuiltInFunction::Rotate => return Quaternion::rotate_value(arg, int),
+            BuiltInFunction::CurrencyInfo => return Self::currency_info(arg),
+            BuiltInFunction::DownloadTime => Self::download_time(arg, scope, context, int)?,
+            BuiltInFunction::MolarMass => match arg {
+                Self::String(formula) => {
+                    let expr = crate::chemistry::molar_mass_expression(formula.as_ref())?;
+                    crate::eval::evaluate_to_value(&expr, None, context, int)?.expect_num()?
+                }
+                _ => return Err(FendError::ExpectedAString),
+            },
+            // e.g. `date(2024, 5, 1)` or `date("2024-05-01")`
+            BuiltInFunction::Date => {
+                if let Self::String(s) = arg {
+                    return Ok(crate::date::Date::parse(s.as_ref())?.into());
+                }
+                let args = arg.into_list();
+                if args.len() != 3 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "date",
+                        expected: "3",
+                        found: args.len(),
+                    });
+                }
+                let mut args = args.into_iter();
+                let mut next_int = || args.next().unwrap().expect_num()?.try_as_i64(int);
+                let (year, month, day) = (next_int()?, next_int()?, next_int()?);
+                return Ok(crate::date::Date::from_ymd(year, month, day)?.into());
+            }
+            BuiltInFunction::Between => {
+                let args = arg.into_list();
+                if args.len() != 2 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "between",
+                        expected: "2",
+                        found: args.len(),
+                    });
+                }
+                return Ok(crate::date::DateInterval::between(&args[0], &args[1])?.into());
+            }
+            // e.g. `easter(2025)`
+            BuiltInFunction::Easter => {
+                let year = arg.expect_num()?.try_as_i64(int)?;
+                return Ok(crate::date::easter(year)?.into());
+            }
+            BuiltInFunction::IsHoliday => {
+                let date = crate::date::expect_date(&arg)?;
+                return Ok(crate::date::is_holiday(date, &context.holidays).into());
+            }
+            // e.g. `add_workdays('2025-04-17', 3)`, which skips weekends
+            // and holidays
+            BuiltInFunction::AddWorkdays => {
+                let args = arg.into_list();
+                if args.len() != 2 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "add_workdays",
+                        expected: "2",
+                        found: args.len(),
+                    });
+                }
+                let date = crate::date::expect_date(&args[0])?;
+                let workdays = args[1].clone().expect_num()?.try_as_i64(int)?;
+                return Ok(
+                    crate::date::add_workdays(date, workdays, &context.holidays, int)?.into(),
+                );
+            }
+            // e.g. `moon_phase(today)`
+            BuiltInFunction::MoonPhase => {
+                return Ok(crate::date::MoonPhase::new(&arg, context, int)?.into());
+            }
+            BuiltInFunction::JulianDay => crate::date::julian_day(&arg, int)?,
+            BuiltInFunction::FormatDate => {
+                let args = arg.into_list();
+                if args.len() != 2 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "format",
+                        expected: "2",
+                        found: args.len(),
+                    });
+                }
+                let mut args = args.into_iter();
+                let value = args.next().unwrap();
+                return match args.next().unwrap() {
+                    Self::String(pattern) => Ok(Self::String(
+                        crate::date::format(&value, pattern.as_ref())?.into(),
+                    )),
+                    _ => Err(FendError::ExpectedAString),
+                };
+            }
+            BuiltInFunction::Polynomial | BuiltInFunction::Roots | BuiltInFunction::Quaternion => {
+                unreachable!("polynomial and quaternion arguments are evaluated above")
+            }
         })))
     }
 
+    /// Returns the time it takes to transfer a file of the given size at
+    /// the given rate, e.g. `download_time(700 MB, 20 Mbit/s)`
+    fn download_time<I: Interrupt>(
+        arg: Self,
+        scope: Option<Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        let args = arg.into_list();
+        if args.len() != 2 {
+            return Err(FendError::WrongNumberOfArguments {
+                function: "download_time",
+                expected: "2",
+                found: args.len(),
+            });
+        }
+        let mut args = args.into_iter();
+        let size = args.next().unwrap().expect_num()?;
+        let rate = args.next().unwrap().expect_num()?;
+        let seconds = crate::ast::resolve_identifier(&Ident::new_str("s"), scope, context, int)?
+            .expect_num()?;
+        let time = size.div(rate, int)?.convert_to(seconds, int)?;
+        crate::units::convert_to_human_units(time, context, int)
+    }
+
+    /// Returns an object describing a currency, e.g. `currencyinfo("JPY")`
+    fn currency_info(arg: Self) -> Result<Self, FendError> {
+        let info = match arg {
+            Self::String(code) => crate::units::get_currency_info(code.as_ref())
+                .ok_or_else(|| FendError::UnknownCurrency(code.to_string()))?,
+            _ => return Err(FendError::ExpectedAString),
+        };
+        let decimal_places = Number::from(u64::from(info.decimal_places));
+        Ok(Self::Object(vec![
+            ("code".into(), Box::new(Self::String(info.code.into()))),
+            ("symbol".into(), Box::new(Self::String(info.symbol.into()))),
+            ("name".into(), Box::new(Self::String(info.name.into()))),
+            (
+                "decimal_places".into(),
+                Box::new(Self::Num(Box::new(decimal_places))),
+            ),
+        ]))
+    }
+
+    /// Applies a function to each element of a list,
+    /// e.g. `table(1..10, \n. n miles to km)`
+    fn table<I: Interrupt>(
+        arg: Self,
+        scope: Option<&Arc<Scope>>,
+        context: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let args = arg.into_list();
+        if args.len() != 2 {
+            return Err(FendError::WrongNumberOfArguments {
+                function: "table",
+                expected: "2",
+                found: args.len(),
+            });
+        }
+        let mut args = args.into_iter();
+        let inputs = args.next().unwrap().into_list();
+        let func = args.next().unwrap();
+        let mut rows = vec![];
+        for input in inputs {
+            let output = func.clone().apply(
+                Expr::Literal(input.clone()),
+                ApplyMulHandling::OnlyApply,
+                scope.cloned(),
+                context,
+                int,
+            )?;
+            let mut left = vec![];
+            input.format(0, &mut left, context, int)?;
+            let mut right = vec![];
+            output.format(0, &mut right, context, int)?;
+            rows.push((left, right));
+        }
+        Ok(table::Table::new(rows).into())
+    }
+
+    /// Handles e.g. `round(x)`, `round(x, 2)` or `floor(x, 0.5 m)`
+    fn round<I: Interrupt>(
+        func: BuiltInFunction,
+        arg: Self,
+        mode: RoundingMode,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        let args = arg.into_list();
+        if args.len() > 2 {
+            return Err(FendError::WrongNumberOfArguments {
+                function: func.as_str(),
+                expected: "1 or 2",
+                found: args.len(),
+            });
+        }
+        let mut args = args.into_iter();
+        let x = args.next().ok_or(FendError::ExpectedANumber)?;
+        let granularity = args.next().map(Self::expect_num).transpose()?;
+        x.expect_num()?.round(granularity, mode, int)
+    }
+
+    /// Finds the smallest (`Ordering::Less`) or largest (`Ordering::Greater`)
+    /// number in the argument list, taking units into account
+    fn min_or_max<I: Interrupt>(
+        arg: Self,
+        ordering: Ordering,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        let mut args = arg.into_list().into_iter();
+        let mut res = args
+            .next()
+            .ok_or(FendError::ExpectedAtLeastOneArgument)?
+            .expect_num()?;
+        for arg in args {
+            let arg = arg.expect_num()?;
+            if arg.compare(&res, int)? == ordering {
+                res = arg;
+            }
+        }
+        Ok(res)
+    }
+
     pub(crate) fn format_to_plain_string<I: Interrupt>(
         &self,
         indent: usize,
@@ -341,6 +1178,53 @@ impl Value {
         Ok(res)
     }
 
+    /// Returns an expression that evaluates to this value, e.g. to save
+    /// variables between sessions. Approximate numbers lose any digits
+    /// past the current precision, and captured variables of functions
+    /// aren't included. Values that can't be written as an expression
+    /// (like dates) return `None`.
+    pub(crate) fn to_source<I: Interrupt>(
+        &self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Option<String>, FendError> {
+        Ok(Some(match self {
+            Self::Num(n) => {
+                let n = Self::Num(Box::new(n.clone().with_format(FormattingStyle::Exact)));
+                let s = n.format_to_plain_string(0, ctx, int)?;
+                match s.strip_prefix("approx. ") {
+                    Some(s) => s.to_string(),
+                    None => s,
+                }
+            }
+            Self::BuiltInFunction(_) | Self::Fn(_, _, _) => {
+                self.format_to_plain_string(0, ctx, int)?
+            }
+            Self::List(elements) => {
+                let mut res = "[".to_string();
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        res.push_str(", ");
+                    }
+                    match element.to_source(ctx, int)? {
+                        Some(element) => res.push_str(&element),
+                        None => return Ok(None),
+                    }
+                }
+                res.push(']');
+                res
+            }
+            Self::String(s) => format!("\"{}\"", s.escape_default()),
+            Self::Dynamic(d) => match d.as_any().downcast_ref::<bool>() {
+                Some(b) => b.to_string(),
+                None => return Ok(None),
+            },
+            Self::Format(_) | Self::Dp | Self::Sf | Self::Base(_) | Self::Object(_) => {
+                return Ok(None)
+            }
+        }))
+    }
+
     pub(crate) fn format<I: Interrupt>(
         &self,
         indent: usize,
@@ -388,7 +1272,9 @@ impl Value {
             }
             Self::Fn(name, expr, _scope) => {
                 let expr_str = (&**expr).format(ctx, int)?;
-                let res = if name.as_str().contains('.') {
+                let res = if let Some((params, body)) = expr.params() {
+                    Expr::format_params(params, body, ctx, int)?
+                } else if name.as_str().contains('.') {
                     format!("{}:{}", name, expr_str)
                 } else {
                     format!("\\{}.{}", name, expr_str)
@@ -413,6 +1299,16 @@ impl Value {
                 }
                 spans.push(Span::from_string("\n}".to_string()));
             }
+            Self::List(elements) => {
+                spans.push(Span::from_string("[".to_string()));
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        spans.push(Span::from_string(", ".to_string()));
+                    }
+                    element.format(indent, spans, ctx, int)?;
+                }
+                spans.push(Span::from_string("]".to_string()));
+            }
             Self::String(s) => {
                 spans.push(Span {
                     string: s.to_string(),
@@ -468,8 +1364,56 @@ impl fmt::Debug for Value {
                 s.push('}');
                 write!(f, "{}", s)
             }
+            Self::List(elements) => write!(f, "{:?}", elements),
             Self::String(s) => write!(f, r#""{}""#, s.as_ref()),
             Self::Dynamic(d) => write!(f, "{:?}", d),
         }
     }
 }
+
+/// Matches the arguments of a call to a lambda with a parameter list, like
+/// `f(1, y=2)` or `f(1, 2)`, to the parameter names
+fn into_arguments(arg: Expr, params: &[Param]) -> Result<Vec<(Ident, Expr)>, FendError> {
+    let elements = match arg {
+        Expr::Parens(inner) => vec![*inner],
+        Expr::List(elements) if params.len() > 1 => elements,
+        arg => vec![arg],
+    };
+    if elements.len() > params.len() {
+        return Err(FendError::TooManyArguments(params.len()));
+    }
+    let mut args = vec![];
+    let mut seen_named = false;
+    for (element, (param, _)) in elements.into_iter().zip(params) {
+        args.push(match element {
+            Expr::Assign(name, value) => {
+                seen_named = true;
+                (name, *value)
+            }
+            _ if seen_named => return Err(FendError::PositionalAfterNamedArgument),
+            value => (param.clone(), value),
+        });
+    }
+    Ok(args)
+}
+
+/// Returns the arguments of a call like `f(a=1, b=2)` if they are all
+/// named, or otherwise the unchanged argument
+fn into_named_arguments(arg: Expr) -> Result<Vec<(Ident, Expr)>, Expr> {
+    let elements = match arg {
+        Expr::Parens(inner) if matches!(*inner, Expr::Assign(_, _)) => vec![*inner],
+        Expr::List(elements)
+            if !elements.is_empty() && elements.iter().all(|e| matches!(e, Expr::Assign(_, _))) =>
+        {
+            elements
+        }
+        arg => return Err(arg),
+    };
+    Ok(elements
+        .into_iter()
+        .filter_map(|element| match element {
+            Expr::Assign(name, value) => Some((name, *value)),
+            _ => None,
+        })
+        .collect())
+}
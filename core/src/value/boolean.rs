@@ -1,4 +1,4 @@
-use super::ValueTrait;
+use super::{Value, ValueTrait};
 use crate::error::FendError;
 
 impl ValueTrait for bool {
@@ -16,4 +16,8 @@ impl ValueTrait for bool {
     fn as_bool(&self) -> Result<bool, FendError> {
         Ok(*self)
     }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
 }
@@ -0,0 +1,230 @@
+use super::{Value, ValueTrait};
+use crate::error::{FendError, Interrupt};
+use crate::{Span, SpanKind};
+use std::collections::BTreeMap;
+
+/// Dimension names for each base unit, in the order they are printed
+const DIMENSIONS: &[(&str, &str)] = &[
+    ("length", "meter"),
+    ("mass", "kilogram"),
+    ("time", "second"),
+    ("current", "ampere"),
+    ("temperature", "kelvin"),
+    ("amount", "mole"),
+    ("luminous_intensity", "candela"),
+    ("information", "bit"),
+    ("currency", "_EUR"),
+];
+
+/// Names of common derived dimensions, with the exponent of each base unit
+const NAMED_DIMENSIONS: &[(&str, &[(&str, i64)])] = &[
+    ("area", &[("meter", 2)]),
+    ("volume", &[("meter", 3)]),
+    ("velocity", &[("meter", 1), ("second", -1)]),
+    ("speed", &[("meter", 1), ("second", -1)]),
+    ("acceleration", &[("meter", 1), ("second", -2)]),
+    ("frequency", &[("second", -1)]),
+    ("density", &[("kilogram", 1), ("meter", -3)]),
+    ("momentum", &[("kilogram", 1), ("meter", 1), ("second", -1)]),
+    ("force", &[("kilogram", 1), ("meter", 1), ("second", -2)]),
+    ("energy", &[("kilogram", 1), ("meter", 2), ("second", -2)]),
+    ("power", &[("kilogram", 1), ("meter", 2), ("second", -3)]),
+    (
+        "pressure",
+        &[("kilogram", 1), ("meter", -1), ("second", -2)],
+    ),
+    ("charge", &[("ampere", 1), ("second", 1)]),
+    (
+        "voltage",
+        &[
+            ("kilogram", 1),
+            ("meter", 2),
+            ("second", -3),
+            ("ampere", -1),
+        ],
+    ),
+    (
+        "resistance",
+        &[
+            ("kilogram", 1),
+            ("meter", 2),
+            ("second", -3),
+            ("ampere", -2),
+        ],
+    ),
+];
+
+/// A physical dimension like `length^2 / time`, stored as
+/// the exponent of each base unit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Dimension {
+    exponents: BTreeMap<String, i64>,
+}
+
+impl Dimension {
+    /// Returns the dimension with the given name (e.g. `length`), if it exists
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        DIMENSIONS
+            .iter()
+            .find(|(dimension, _)| *dimension == name)
+            .map(|(_, base_unit)| Self::new(vec![((*base_unit).to_string(), 1)]))
+    }
+
+    /// Like `from_name`, but also accepts derived dimensions like `power`
+    pub(crate) fn from_any_name(name: &str) -> Option<Self> {
+        Self::from_name(name).or_else(|| {
+            NAMED_DIMENSIONS
+                .iter()
+                .find(|(dimension, _)| *dimension == name)
+                .map(|(_, exponents)| {
+                    Self::new(
+                        exponents
+                            .iter()
+                            .map(|(base_unit, e)| ((*base_unit).to_string(), *e))
+                            .collect(),
+                    )
+                })
+        })
+    }
+
+    /// Returns the dimension of a number (e.g. `length` for `5 km`), or
+    /// the value itself if it's already a dimension
+    pub(crate) fn of_value<I: Interrupt>(value: &Value, int: &I) -> Result<Self, FendError> {
+        if let Some(dimension) = value.as_dyn::<Self>() {
+            return Ok(dimension.clone());
+        }
+        match value {
+            Value::Num(num) => Ok(Self::new(num.base_unit_exponents(int)?)),
+            _ => Err(FendError::ExpectedADimension),
+        }
+    }
+
+    pub(crate) fn new(exponents: Vec<(String, i64)>) -> Self {
+        Self {
+            exponents: exponents
+                .into_iter()
+                .filter(|(_, exponent)| *exponent != 0)
+                .collect(),
+        }
+    }
+
+    fn combine(&self, rhs: &Self, sign: i64) -> Self {
+        let mut exponents = self.exponents.clone();
+        for (base_unit, exponent) in &rhs.exponents {
+            *exponents.entry(base_unit.clone()).or_insert(0) += sign * exponent;
+        }
+        Self::new(exponents.into_iter().collect())
+    }
+
+    fn sort_key(base_unit: &str) -> (usize, &str) {
+        let idx = DIMENSIONS
+            .iter()
+            .position(|(_, b)| *b == base_unit)
+            .unwrap_or(DIMENSIONS.len());
+        (idx, base_unit)
+    }
+
+    fn display_name(base_unit: &str) -> &str {
+        DIMENSIONS
+            .iter()
+            .find(|(_, b)| *b == base_unit)
+            .map_or(base_unit, |(dimension, _)| dimension)
+    }
+
+    /// Returns the name of this dimension like `energy`, or otherwise
+    /// the dimensions it consists of like `length^2 / time`
+    pub(crate) fn describe(&self) -> String {
+        for (name, exponents) in NAMED_DIMENSIONS {
+            if exponents.len() == self.exponents.len()
+                && exponents
+                    .iter()
+                    .all(|(base_unit, e)| self.exponents.get(*base_unit) == Some(e))
+            {
+                return (*name).to_string();
+            }
+        }
+        self.format_expression()
+    }
+
+    fn format_expression(&self) -> String {
+        let mut terms: Vec<_> = self.exponents.iter().map(|(b, e)| (b, *e)).collect();
+        terms.sort_by_key(|(base_unit, _)| Self::sort_key(base_unit));
+        let numerator: Vec<_> = terms.iter().filter(|(_, e)| *e > 0).copied().collect();
+        let denominator: Vec<_> = terms
+            .iter()
+            .filter(|(_, e)| *e < 0)
+            .map(|(b, e)| (*b, -e))
+            .collect();
+        if terms.is_empty() {
+            "dimensionless".to_string()
+        } else if denominator.is_empty() {
+            Self::format_terms(&numerator)
+        } else {
+            let numerator = if numerator.is_empty() {
+                "1".to_string()
+            } else {
+                Self::format_terms(&numerator)
+            };
+            if denominator.len() == 1 {
+                format!("{} / {}", numerator, Self::format_terms(&denominator))
+            } else {
+                format!("{} / ({})", numerator, Self::format_terms(&denominator))
+            }
+        }
+    }
+
+    fn format_terms(terms: &[(&String, i64)]) -> String {
+        let mut res = String::new();
+        for (i, (base_unit, exponent)) in terms.iter().enumerate() {
+            if i != 0 {
+                res.push_str(" * ");
+            }
+            res.push_str(Self::display_name(base_unit));
+            if *exponent != 1 {
+                res.push('^');
+                res.push_str(&exponent.to_string());
+            }
+        }
+        res
+    }
+}
+
+impl ValueTrait for Dimension {
+    fn type_name(&self) -> &'static str {
+        "dimension"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.push(Span {
+            string: self.format_expression(),
+            kind: SpanKind::Ident,
+        });
+    }
+
+    fn mul(&self, rhs: Value) -> Result<Value, FendError> {
+        match rhs.as_dyn::<Self>() {
+            Some(rhs) => Ok(self.combine(rhs, 1).into()),
+            None => Err(FendError::ExpectedADimension),
+        }
+    }
+
+    fn div(&self, rhs: Value) -> Result<Value, FendError> {
+        match rhs.as_dyn::<Self>() {
+            Some(rhs) => Ok(self.combine(rhs, -1).into()),
+            None => Err(FendError::ExpectedADimension),
+        }
+    }
+
+    fn pow(&self, exponent: i64) -> Result<Value, FendError> {
+        let mut exponents = vec![];
+        for (base_unit, e) in &self.exponents {
+            let e = e.checked_mul(exponent).ok_or(FendError::ExponentTooLarge)?;
+            exponents.push((base_unit.clone(), e));
+        }
+        Ok(Self::new(exponents).into())
+    }
+
+    fn equals(&self, rhs: &Value) -> bool {
+        rhs.as_dyn::<Self>() == Some(self)
+    }
+}
@@ -0,0 +1,125 @@
+use super::mixed_units::MixedUnits;
+use super::{Value, ValueTrait};
+use crate::ast::Bop;
+use crate::error::{FendError, Interrupt};
+use crate::num::Number;
+use crate::Span;
+use std::cmp::Ordering;
+
+/// A span of time like `2 weeks 3 days 4 hours`, which is shown in
+/// several units from weeks down to seconds. Like uncertain values, the
+/// duration is formatted when it is created.
+#[derive(Clone, Debug)]
+pub(crate) struct Duration {
+    seconds: Number,
+    spans: Vec<Span>,
+}
+
+impl Duration {
+    /// Creates a duration from a number with a unit of time
+    pub(crate) fn new<I: Interrupt>(
+        time: Number,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let seconds = time.convert_to(Self::unit("seconds", ctx, int)?, int)?;
+        let mut larger_units = vec![];
+        for unit in ["weeks", "days", "hours", "minutes"] {
+            larger_units.push(Self::unit(unit, ctx, int)?);
+        }
+        let smallest_unit = Self::unit("seconds", ctx, int)?;
+        let mut spans = vec![];
+        MixedUnits::new(&seconds, &larger_units, &smallest_unit, ctx, int)?.format(0, &mut spans);
+        Ok(Self { seconds, spans })
+    }
+
+    fn unit<I: Interrupt>(
+        name: &str,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        crate::units::query_unit(name, ctx, int)?.expect_num()
+    }
+
+    /// Returns the length of this duration in seconds
+    pub(crate) fn to_number(&self) -> Number {
+        self.seconds.clone()
+    }
+
+    /// Returns true if evaluating `lhs <bop> rhs` should be handled by
+    /// `Duration::bop`
+    pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+        lhs.as_dyn::<Self>().is_some() || rhs.as_dyn::<Self>().is_some()
+    }
+
+    fn operand(value: Value) -> Result<Number, FendError> {
+        match value {
+            Value::Num(n) => Ok(*n),
+            value => match value.as_dyn::<Self>() {
+                Some(duration) => Ok(duration.to_number()),
+                None => Err(FendError::UnsupportedDurationOperation),
+            },
+        }
+    }
+
+    /// Evaluates `lhs <bop> rhs` where at least one operand is a duration.
+    /// Durations can be added to other durations and scaled by plain
+    /// numbers, while adding them to dates is handled by `date::add`.
+    pub(crate) fn bop<I: Interrupt>(
+        bop: Bop,
+        lhs: Value,
+        rhs: Value,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let rhs_is_duration = rhs.as_dyn::<Self>().is_some();
+        let (a, b) = (Self::operand(lhs)?, Self::operand(rhs)?);
+        let result = match bop {
+            Bop::Plus => a.add(b, int)?,
+            Bop::Minus => a.sub(b, int)?,
+            Bop::Mul if a.is_unitless() || b.is_unitless() => a.mul(b, int)?,
+            // dividing by a duration gives a plain number
+            Bop::Div if rhs_is_duration => {
+                let seconds = Self::unit("seconds", ctx, int)?;
+                let (a, b) = (
+                    a.convert_to(seconds.clone(), int)?,
+                    b.convert_to(seconds, int)?,
+                );
+                return Ok(Value::Num(Box::new(
+                    a.without_unit().div(b.without_unit(), int)?,
+                )));
+            }
+            Bop::Div if b.is_unitless() => a.div(b, int)?,
+            _ => return Err(FendError::UnsupportedDurationOperation),
+        };
+        Ok(Self::new(result, ctx, int)?.into())
+    }
+
+    pub(crate) fn convert_to<I: Interrupt>(
+        &self,
+        unit: Number,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        Ok(Value::Num(Box::new(
+            self.seconds.clone().convert_to(unit, int)?,
+        )))
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        Ok(self.seconds.compare(&rhs.seconds, int)? == Ordering::Equal)
+    }
+
+    pub(crate) fn compare<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<Ordering, FendError> {
+        self.seconds.compare(&rhs.seconds, int)
+    }
+}
+
+impl ValueTrait for Duration {
+    fn type_name(&self) -> &'static str {
+        "duration"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
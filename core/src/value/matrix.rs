@@ -0,0 +1,414 @@
+use super::{Value, ValueTrait};
+use crate::ast::Bop;
+use crate::error::{FendError, Interrupt};
+use crate::interrupt::test_int;
+use crate::num::Number;
+use crate::{Span, SpanKind};
+
+/// A matrix of numbers, e.g. `[[1, 2], [3, 4]]`. Elements are
+/// formatted when the matrix is created, so that columns
+/// can be aligned.
+#[derive(Clone, Debug)]
+pub(crate) struct Matrix {
+    rows: Vec<Vec<Number>>,
+    cells: Vec<Vec<Vec<Span>>>,
+}
+
+impl Matrix {
+    fn new<I: Interrupt>(
+        rows: Vec<Vec<Number>>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut cells = vec![];
+        for row in &rows {
+            let mut formatted_row = vec![];
+            for element in row {
+                let mut spans = vec![];
+                Value::Num(Box::new(element.clone())).format(0, &mut spans, ctx, int)?;
+                formatted_row.push(spans);
+            }
+            cells.push(formatted_row);
+        }
+        Ok(Self { rows, cells })
+    }
+
+    /// Turns a list of equally-sized lists of numbers into a matrix,
+    /// and leaves any other list unchanged
+    pub(crate) fn from_list<I: Interrupt>(
+        elements: Vec<Value>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let is_matrix = elements.first().map_or(false, |first| match first {
+            Value::List(first) if !first.is_empty() => elements.iter().all(|row| match row {
+                Value::List(row) => {
+                    row.len() == first.len() && row.iter().all(|x| matches!(x, Value::Num(_)))
+                }
+                _ => false,
+            }),
+            _ => false,
+        });
+        if !is_matrix {
+            return Ok(Value::List(elements));
+        }
+        let mut rows = vec![];
+        for row in elements {
+            rows.push(
+                row.into_list()
+                    .into_iter()
+                    .map(Value::expect_num)
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+        Ok(Self::new(rows, ctx, int)?.into())
+    }
+
+    /// Returns true if evaluating `lhs <bop> rhs` should be handled by
+    /// `Matrix::bop`
+    pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+        lhs.as_dyn::<Self>().is_some() || rhs.as_dyn::<Self>().is_some()
+    }
+
+    fn expect_matrix(value: &Value) -> Result<Self, FendError> {
+        match value.as_dyn::<Self>() {
+            Some(matrix) => Ok(matrix.clone()),
+            None => Err(FendError::ExpectedAMatrix),
+        }
+    }
+
+    fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn num_cols(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    fn expect_square(&self) -> Result<usize, FendError> {
+        if self.num_rows() != self.num_cols() {
+            return Err(FendError::ExpectedASquareMatrix);
+        }
+        Ok(self.num_rows())
+    }
+
+    fn identity(size: usize) -> Vec<Vec<Number>> {
+        (0..size)
+            .map(|i| (0..size).map(|j| Number::from(u64::from(i == j))).collect())
+            .collect()
+    }
+
+    fn map<I: Interrupt>(
+        &self,
+        mut f: impl FnMut(Number) -> Result<Number, FendError>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let mut rows = vec![];
+        for row in &self.rows {
+            test_int(int)?;
+            rows.push(row.iter().cloned().map(&mut f).collect::<Result<_, _>>()?);
+        }
+        Self::new(rows, ctx, int)
+    }
+
+    fn zip_with<I: Interrupt>(
+        &self,
+        rhs: &Self,
+        mut f: impl FnMut(Number, Number) -> Result<Number, FendError>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if self.num_rows() != rhs.num_rows() || self.num_cols() != rhs.num_cols() {
+            return Err(FendError::MatrixDimensionMismatch);
+        }
+        let mut rows = vec![];
+        for (a, b) in self.rows.iter().zip(&rhs.rows) {
+            test_int(int)?;
+            let mut row = vec![];
+            for (x, y) in a.iter().zip(b) {
+                row.push(f(x.clone(), y.clone())?);
+            }
+            rows.push(row);
+        }
+        Self::new(rows, ctx, int)
+    }
+
+    fn matrix_mul<I: Interrupt>(
+        &self,
+        rhs: &Self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        if self.num_cols() != rhs.num_rows() {
+            return Err(FendError::MatrixDimensionMismatch);
+        }
+        let rhs_cols = rhs.transpose(ctx, int)?.rows;
+        let mut rows = vec![];
+        for row in &self.rows {
+            let mut result_row = vec![];
+            for col in &rhs_cols {
+                test_int(int)?;
+                let mut sum: Option<Number> = None;
+                for (x, y) in row.iter().zip(col) {
+                    let product = x.clone().mul(y.clone(), int)?;
+                    sum = Some(match sum {
+                        Some(sum) => sum.add(product, int)?,
+                        None => product,
+                    });
+                }
+                result_row.push(sum.unwrap());
+            }
+            rows.push(result_row);
+        }
+        Self::new(rows, ctx, int)
+    }
+
+    fn transpose<I: Interrupt>(&self, ctx: &crate::Context, int: &I) -> Result<Self, FendError> {
+        let rows = (0..self.num_cols())
+            .map(|c| self.rows.iter().map(|row| row[c].clone()).collect())
+            .collect();
+        Self::new(rows, ctx, int)
+    }
+
+    fn determinant<I: Interrupt>(&self, int: &I) -> Result<Number, FendError> {
+        let size = self.expect_square()?;
+        let mut rows = self.rows.clone();
+        let mut det = Number::from(1);
+        for col in 0..size {
+            match find_pivot(&rows, col) {
+                Some(pivot) if pivot != col => {
+                    rows.swap(pivot, col);
+                    det = -det;
+                }
+                Some(_) => (),
+                None => return Ok(Number::from(0)),
+            }
+            let (upper, lower) = rows.split_at_mut(col + 1);
+            let pivot_row = &upper[col];
+            det = det.mul(pivot_row[col].clone(), int)?;
+            for row in lower {
+                let factor = row[col].clone().div(pivot_row[col].clone(), int)?;
+                subtract_row(row, pivot_row, &factor, int)?;
+            }
+        }
+        Ok(det)
+    }
+
+    /// Computes the inverse using Gauss-Jordan elimination
+    fn inverse<I: Interrupt>(&self, ctx: &crate::Context, int: &I) -> Result<Self, FendError> {
+        let size = self.expect_square()?;
+        let mut rows: Vec<Vec<Number>> = self
+            .rows
+            .iter()
+            .zip(Self::identity(size))
+            .map(|(row, identity_row)| [row.clone(), identity_row].concat())
+            .collect();
+        for col in 0..size {
+            let pivot = find_pivot(&rows, col).ok_or(FendError::SingularMatrix)?;
+            rows.swap(pivot, col);
+            let pivot_value = rows[col][col].clone();
+            let mut pivot_row = vec![];
+            for x in &rows[col] {
+                pivot_row.push(x.clone().div(pivot_value.clone(), int)?);
+            }
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != col {
+                    let factor = row[col].clone();
+                    subtract_row(row, &pivot_row, &factor, int)?;
+                }
+            }
+            rows[col] = pivot_row;
+        }
+        let rows = rows.into_iter().map(|row| row[size..].to_vec()).collect();
+        Self::new(rows, ctx, int)
+    }
+
+    fn pow<I: Interrupt>(
+        &self,
+        exponent: i64,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let size = self.expect_square()?;
+        let mut base = if exponent < 0 {
+            self.inverse(ctx, int)?
+        } else {
+            self.clone()
+        };
+        let mut exponent = exponent.unsigned_abs();
+        let mut result = Self::new(Self::identity(size), ctx, int)?;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.matrix_mul(&base, ctx, int)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.matrix_mul(&base, ctx, int)?;
+            }
+        }
+        Ok(result)
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        if self.num_rows() != rhs.num_rows() || self.num_cols() != rhs.num_cols() {
+            return Ok(false);
+        }
+        for (a, b) in self.rows.iter().flatten().zip(rhs.rows.iter().flatten()) {
+            if a.compare(b, int)? != std::cmp::Ordering::Equal {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Evaluates `lhs <bop> rhs` where at least one operand is a matrix
+    pub(crate) fn bop<I: Interrupt>(
+        bop: Bop,
+        lhs: Value,
+        rhs: Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let result = match (bop, lhs, rhs) {
+            (Bop::Plus, a, b) => Self::expect_matrix(&a)?.zip_with(
+                &Self::expect_matrix(&b)?,
+                |x, y| x.add(y, int),
+                ctx,
+                int,
+            )?,
+            (Bop::Minus, a, b) => Self::expect_matrix(&a)?.zip_with(
+                &Self::expect_matrix(&b)?,
+                |x, y| x.sub(y, int),
+                ctx,
+                int,
+            )?,
+            (Bop::Mul, Value::Num(n), m) => {
+                Self::expect_matrix(&m)?.map(|x| n.clone().mul(x, int), ctx, int)?
+            }
+            (Bop::Mul, m, Value::Num(n)) => {
+                Self::expect_matrix(&m)?.map(|x| x.mul((*n).clone(), int), ctx, int)?
+            }
+            (Bop::Mul, a, b) => {
+                Self::expect_matrix(&a)?.matrix_mul(&Self::expect_matrix(&b)?, ctx, int)?
+            }
+            (Bop::Div, m, Value::Num(n)) => {
+                Self::expect_matrix(&m)?.map(|x| x.div((*n).clone(), int), ctx, int)?
+            }
+            (Bop::Div, Value::Num(n), m) => Self::expect_matrix(&m)?.inverse(ctx, int)?.map(
+                |x| n.clone().mul(x, int),
+                ctx,
+                int,
+            )?,
+            (Bop::Div, a, b) => {
+                let inverse = Self::expect_matrix(&b)?.inverse(ctx, int)?;
+                Self::expect_matrix(&a)?.matrix_mul(&inverse, ctx, int)?
+            }
+            (Bop::Pow, m, exponent) => {
+                let exponent = exponent.expect_num()?.try_as_i64(int)?;
+                Self::expect_matrix(&m)?.pow(exponent, ctx, int)?
+            }
+            _ => return Err(FendError::UnsupportedMatrixOperation),
+        };
+        Ok(result.into())
+    }
+
+    pub(crate) fn transpose_value<I: Interrupt>(
+        value: &Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        Ok(Self::expect_matrix(value)?.transpose(ctx, int)?.into())
+    }
+
+    pub(crate) fn determinant_value<I: Interrupt>(
+        value: &Value,
+        int: &I,
+    ) -> Result<Number, FendError> {
+        Self::expect_matrix(value)?.determinant(int)
+    }
+
+    pub(crate) fn inverse_value<I: Interrupt>(
+        value: &Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        Ok(Self::expect_matrix(value)?.inverse(ctx, int)?.into())
+    }
+}
+
+/// Finds a row at or below `col` with a non-zero entry in column `col`
+fn find_pivot(rows: &[Vec<Number>], col: usize) -> Option<usize> {
+    (col..rows.len()).find(|&i| !rows[i][col].is_zero())
+}
+
+/// Subtracts `factor` times `source` from `target`. Zeros are compatible
+/// with any unit, so e.g. `[[1 m, 0], [0, 1 m]]` can be reduced.
+fn subtract_row<I: Interrupt>(
+    target: &mut [Number],
+    source: &[Number],
+    factor: &Number,
+    int: &I,
+) -> Result<(), FendError> {
+    for (x, y) in target.iter_mut().zip(source) {
+        let product = factor.clone().mul(y.clone(), int)?;
+        if product.is_zero() {
+            continue;
+        }
+        *x = if x.is_zero() {
+            -product
+        } else {
+            x.clone().sub(product, int)?
+        };
+    }
+    Ok(())
+}
+
+fn width(spans: &[Span]) -> usize {
+    spans.iter().map(|s| s.string.chars().count()).sum()
+}
+
+impl ValueTrait for Matrix {
+    fn type_name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn format(&self, indent: usize, spans: &mut Vec<Span>) {
+        let col_widths: Vec<usize> = (0..self.num_cols())
+            .map(|c| {
+                self.cells
+                    .iter()
+                    .map(|row| width(&row[c]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for (i, row) in self.cells.iter().enumerate() {
+            if i != 0 {
+                spans.push(Span {
+                    string: format!("\n{}", " ".repeat(indent)),
+                    kind: SpanKind::Whitespace,
+                });
+            }
+            spans.push(Span {
+                string: "[".to_string(),
+                kind: SpanKind::Matrix,
+            });
+            for (j, (cell, col_width)) in row.iter().zip(&col_widths).enumerate() {
+                // right-align each column, with two spaces between columns
+                let padding = col_width - width(cell) + if j == 0 { 0 } else { 2 };
+                if padding > 0 {
+                    spans.push(Span {
+                        string: " ".repeat(padding),
+                        kind: SpanKind::Whitespace,
+                    });
+                }
+                spans.extend(cell.iter().cloned());
+            }
+            spans.push(Span {
+                string: "]".to_string(),
+                kind: SpanKind::Matrix,
+            });
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::{Value, ValueTrait};
+use crate::error::{FendError, Interrupt};
+use crate::num::{FormattingStyle, Number, RoundingMode};
+use crate::{Span, SpanKind};
+use std::cmp::Ordering;
+
+/// A quantity shown in several units from largest to smallest, e.g.
+/// `6 ft 1.6 in`, which is the result of converting to `ft+in`. All
+/// units except the last one only hold whole numbers.
+#[derive(Clone, Debug)]
+pub(crate) struct MixedUnits {
+    spans: Vec<Span>,
+}
+
+impl MixedUnits {
+    pub(crate) fn new<I: Interrupt>(
+        num: &Number,
+        larger_units: &[Number],
+        smallest_unit: &Number,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        // e.g. `ft+ft` or `in+ft`
+        let mut units = larger_units.iter().chain(std::iter::once(smallest_unit));
+        if let Some(mut larger) = units.next() {
+            for smaller in units {
+                if larger.compare(smaller, int)? != Ordering::Greater {
+                    return Err(FendError::MixedUnitsOutOfOrder);
+                }
+                larger = smaller;
+            }
+        }
+        let abs = num.clone().abs(int)?;
+        let negative = num.compare(&abs, int)? == Ordering::Less;
+        let mut remaining = abs.convert_to(smallest_unit.clone(), int)?;
+        let approximate = Value::Num(Box::new(remaining.clone()))
+            .format_to_plain_string(0, ctx, int)?
+            .starts_with("approx. ");
+        if approximate {
+            // round first, so that e.g. `5.99999 ft` isn't shown as `5 ft 12 in`
+            remaining = remaining.round(Some(1.into()), RoundingMode::Nearest, int)?;
+        }
+        let mut parts = vec![];
+        for unit in larger_units {
+            let part = remaining.clone().convert_to(unit.clone(), int)?.round(
+                None,
+                RoundingMode::Trunc,
+                int,
+            )?;
+            remaining = remaining.sub(part.clone(), int)?;
+            parts.push(part);
+        }
+        parts.push(remaining);
+        let mut spans = vec![];
+        if approximate {
+            spans.push(Span {
+                string: "approx. ".to_string(),
+                kind: SpanKind::Ident,
+            });
+        }
+        // leave out units that are zero, unless all of them are
+        if parts.iter().any(|part| !part.is_zero()) {
+            parts.retain(|part| !part.is_zero());
+        } else {
+            parts.drain(..parts.len() - 1);
+        }
+        let mut first = true;
+        for part in parts {
+            let part = if negative && first { -part } else { part };
+            let part = if approximate {
+                part.with_format(FormattingStyle::DecimalPlaces(1))
+            } else {
+                part
+            };
+            if !first {
+                spans.push(Span {
+                    string: " ".to_string(),
+                    kind: SpanKind::Whitespace,
+                });
+            }
+            let mut part_spans = vec![];
+            Value::Num(Box::new(part)).format(0, &mut part_spans, ctx, int)?;
+            // `approx.` is only shown once, at the start
+            spans.extend(part_spans.into_iter().filter(|s| s.string != "approx. "));
+            first = false;
+        }
+        Ok(Self { spans })
+    }
+}
+
+impl ValueTrait for MixedUnits {
+    fn type_name(&self) -> &'static str {
+        "mixed units"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
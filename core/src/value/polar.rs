@@ -0,0 +1,46 @@
+use super::{Value, ValueTrait};
+use crate::error::{FendError, Interrupt};
+use crate::ident::Ident;
+use crate::num::Number;
+use crate::scope::Scope;
+use crate::{Span, SpanKind};
+use std::sync::Arc;
+
+/// A complex number shown in polar form, e.g. `5 ∠ 30°`, which is
+/// the result of `to polar`. The angle is always shown in degrees.
+#[derive(Clone, Debug)]
+pub(crate) struct PolarForm {
+    spans: Vec<Span>,
+}
+
+impl PolarForm {
+    pub(crate) fn new<I: Interrupt>(
+        num: Number,
+        scope: Option<Arc<Scope>>,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let degrees =
+            crate::ast::resolve_identifier(&Ident::new_str("°"), scope, ctx, int)?.expect_num()?;
+        let magnitude = num.clone().abs(int)?;
+        let angle = num.arg(int)?.convert_to(degrees, int)?;
+        let mut spans = vec![];
+        Value::Num(Box::new(magnitude)).format(0, &mut spans, ctx, int)?;
+        spans.push(Span {
+            string: " \u{2220} ".to_string(),
+            kind: SpanKind::Other,
+        });
+        Value::Num(Box::new(angle)).format(0, &mut spans, ctx, int)?;
+        Ok(Self { spans })
+    }
+}
+
+impl ValueTrait for PolarForm {
+    fn type_name(&self) -> &'static str {
+        "complex number"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
@@ -0,0 +1,293 @@
+use super::{ApplyMulHandling, Value, ValueTrait};
+use crate::ast::{Bop, Expr};
+use crate::error::{FendError, Interrupt};
+use crate::ident::Ident;
+use crate::interrupt::test_int;
+use crate::num::{polynomial, Number};
+use crate::scope::Scope;
+use crate::{Span, SpanKind};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// A polynomial in one variable, e.g. `x^2 - 2x + 1`. Like matrices,
+/// coefficients are formatted when the polynomial is created.
+#[derive(Clone, Debug)]
+pub(crate) struct Polynomial {
+    variable: Ident,
+    // lowest degree first, without trailing zeroes
+    coefficients: Vec<Number>,
+    spans: Vec<Span>,
+}
+
+impl Polynomial {
+    fn new<I: Interrupt>(
+        variable: Ident,
+        mut coefficients: Vec<Number>,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        while coefficients.last().map_or(false, Number::is_zero) {
+            coefficients.pop();
+        }
+        let spans = format_terms(&variable, &coefficients, ctx, int)?;
+        Ok(Self {
+            variable,
+            coefficients,
+            spans,
+        })
+    }
+
+    /// Evaluates `expr` as a polynomial. The first undefined identifier
+    /// becomes the variable, so `x^2 - 1` works as long as `x` is not
+    /// otherwise defined. Lambdas like `\x.x^2 - 1` are also accepted.
+    fn evaluate_expr<I: Interrupt>(
+        expr: Expr,
+        scope: Option<Arc<Scope>>,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let value = match crate::ast::evaluate(expr.clone(), scope.clone(), ctx, int) {
+            Err(FendError::IdentifierNotFound(name)) => {
+                let variable = Self::new(name.clone(), vec![0.into(), 1.into()], ctx, int)?;
+                let scope = Scope::with_variable(
+                    name,
+                    Expr::Literal(variable.into()),
+                    scope.clone(),
+                    scope,
+                );
+                crate::ast::evaluate(expr, Some(Arc::new(scope)), ctx, int)?
+            }
+            Ok(Value::Fn(param, body, fn_scope)) => {
+                let variable = Self::new(param.clone(), vec![0.into(), 1.into()], ctx, int)?;
+                Value::Fn(param, body, fn_scope).apply(
+                    Expr::Literal(variable.into()),
+                    ApplyMulHandling::OnlyApply,
+                    scope,
+                    ctx,
+                    int,
+                )?
+            }
+            res => res?,
+        };
+        match value {
+            Value::Num(n) => Self::new("x".into(), vec![*n], ctx, int),
+            value => match value.as_dyn::<Self>() {
+                Some(p) => Ok(p.clone()),
+                None => Err(FendError::ExpectedAPolynomial),
+            },
+        }
+    }
+
+    pub(crate) fn poly_value<I: Interrupt>(
+        expr: Expr,
+        scope: Option<Arc<Scope>>,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        Ok(Self::evaluate_expr(expr, scope, ctx, int)?.into())
+    }
+
+    /// Returns a list of the distinct real roots, in ascending order
+    pub(crate) fn roots_value<I: Interrupt>(
+        expr: Expr,
+        scope: Option<Arc<Scope>>,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let polynomial = Self::evaluate_expr(expr, scope, ctx, int)?;
+        let roots = polynomial::real_roots(polynomial.coefficients, int)?;
+        Ok(Value::List(
+            roots.into_iter().map(|x| Value::Num(Box::new(x))).collect(),
+        ))
+    }
+
+    /// Returns true if evaluating `lhs <bop> rhs` should be handled by
+    /// `Polynomial::bop`
+    pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+        lhs.as_dyn::<Self>().is_some() || rhs.as_dyn::<Self>().is_some()
+    }
+
+    fn coefficients_of(value: Value, variable: &Ident) -> Result<Vec<Number>, FendError> {
+        if let Value::Num(n) = value {
+            return Ok(vec![*n]);
+        }
+        match value.as_dyn::<Self>() {
+            Some(p) if p.variable.as_str() == variable.as_str() => Ok(p.coefficients.clone()),
+            Some(_) => Err(FendError::PolynomialVariableMismatch),
+            None => Err(FendError::UnsupportedPolynomialOperation),
+        }
+    }
+
+    /// Evaluates `lhs <bop> rhs` where at least one operand is a polynomial
+    pub(crate) fn bop<I: Interrupt>(
+        bop: Bop,
+        lhs: Value,
+        rhs: Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let variable = match lhs.as_dyn::<Self>().or_else(|| rhs.as_dyn::<Self>()) {
+            Some(p) => p.variable.clone(),
+            None => return Err(FendError::UnsupportedPolynomialOperation),
+        };
+        let a = Self::coefficients_of(lhs, &variable)?;
+        let coefficients = match (bop, rhs) {
+            (Bop::Plus, b) => add(a, Self::coefficients_of(b, &variable)?, int)?,
+            (Bop::Minus, b) => {
+                let b = Self::coefficients_of(b, &variable)?;
+                add(a, b.into_iter().map(|x| -x).collect(), int)?
+            }
+            (Bop::Mul, b) => mul(&a, &Self::coefficients_of(b, &variable)?, int)?,
+            (Bop::Div, Value::Num(n)) => a
+                .into_iter()
+                .map(|x| x.div((*n).clone(), int))
+                .collect::<Result<_, _>>()?,
+            (Bop::Pow, exponent) => {
+                let mut exponent = exponent.expect_num()?.try_as_usize(int)?;
+                let mut base = a;
+                let mut result = vec![1.into()];
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        result = mul(&result, &base, int)?;
+                    }
+                    exponent >>= 1;
+                    if exponent > 0 {
+                        base = mul(&base, &base, int)?;
+                    }
+                }
+                result
+            }
+            _ => return Err(FendError::UnsupportedPolynomialOperation),
+        };
+        Ok(Self::new(variable, coefficients, ctx, int)?.into())
+    }
+
+    /// Applying a polynomial to a number evaluates it, while
+    /// applying it to another polynomial multiplies them
+    /// (e.g. `x(x - 1)`)
+    pub(crate) fn apply<I: Interrupt>(
+        &self,
+        arg: Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        match arg {
+            Value::Num(x) => Ok(Value::Num(Box::new(polynomial::evaluate(
+                &self.coefficients,
+                &x,
+                int,
+            )?))),
+            arg => Self::bop(Bop::Mul, self.clone().into(), arg, ctx, int),
+        }
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        if self.variable.as_str() != rhs.variable.as_str()
+            || self.coefficients.len() != rhs.coefficients.len()
+        {
+            return Ok(false);
+        }
+        for (a, b) in self.coefficients.iter().zip(&rhs.coefficients) {
+            if a.compare(b, int)? != Ordering::Equal {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn add<I: Interrupt>(
+    mut a: Vec<Number>,
+    mut b: Vec<Number>,
+    int: &I,
+) -> Result<Vec<Number>, FendError> {
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+    let mut b = b.into_iter();
+    let mut result = vec![];
+    for x in a {
+        test_int(int)?;
+        result.push(match b.next() {
+            Some(y) => x.add(y, int)?,
+            None => x,
+        });
+    }
+    Ok(result)
+}
+
+fn mul<I: Interrupt>(a: &[Number], b: &[Number], int: &I) -> Result<Vec<Number>, FendError> {
+    let mut result: Vec<Option<Number>> = vec![None; (a.len() + b.len()).saturating_sub(1)];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            test_int(int)?;
+            let product = x.clone().mul(y.clone(), int)?;
+            result[i + j] = Some(match result[i + j].take() {
+                Some(sum) => sum.add(product, int)?,
+                None => product,
+            });
+        }
+    }
+    Ok(result
+        .into_iter()
+        .map(|x| x.unwrap_or_else(|| 0.into()))
+        .collect())
+}
+
+fn format_terms<I: Interrupt>(
+    variable: &Ident,
+    coefficients: &[Number],
+    ctx: &crate::Context,
+    int: &I,
+) -> Result<Vec<Span>, FendError> {
+    let mut spans = vec![];
+    for (degree, c) in coefficients.iter().enumerate().rev() {
+        if c.is_zero() {
+            continue;
+        }
+        let negative = matches!(c.compare(&0.into(), int), Ok(Ordering::Less));
+        let sign = match (spans.is_empty(), negative) {
+            (true, false) => "",
+            (true, true) => "-",
+            (false, false) => " + ",
+            (false, true) => " - ",
+        };
+        if !sign.is_empty() {
+            spans.push(Span {
+                string: sign.to_string(),
+                kind: SpanKind::Other,
+            });
+        }
+        let c = if negative { -c.clone() } else { c.clone() };
+        if degree == 0 || !c.is_unitless_one() {
+            Value::Num(Box::new(c)).format(0, &mut spans, ctx, int)?;
+        }
+        if degree > 0 {
+            spans.push(Span {
+                string: if degree == 1 {
+                    variable.as_str().to_string()
+                } else {
+                    format!("{}^{}", variable.as_str(), degree)
+                },
+                kind: SpanKind::Ident,
+            });
+        }
+    }
+    if spans.is_empty() {
+        spans.push(Span {
+            string: "0".to_string(),
+            kind: SpanKind::Number,
+        });
+    }
+    Ok(spans)
+}
+
+impl ValueTrait for Polynomial {
+    fn type_name(&self) -> &'static str {
+        "polynomial"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
@@ -0,0 +1,297 @@
+use super::{Value, ValueTrait};
+use crate::ast::{Bop, Expr};
+use crate::error::{FendError, Interrupt};
+use crate::num::{vector, Number};
+use crate::scope::Scope;
+use crate::{Span, SpanKind};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+const UNIT_NAMES: [&str; 3] = ["i", "j", "k"];
+
+/// A quaternion `w + xi + yj + zk`. Like matrices, components are
+/// formatted when the quaternion is created.
+#[derive(Clone, Debug)]
+pub(crate) struct Quaternion {
+    // `[w, x, y, z]`
+    components: [Number; 4],
+    spans: Vec<Span>,
+}
+
+impl Quaternion {
+    fn new<I: Interrupt>(
+        components: [Number; 4],
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let spans = format_components(&components, ctx, int)?;
+        Ok(Self { components, spans })
+    }
+
+    fn unit(index: usize) -> [Number; 4] {
+        let mut components = [0.into(), 0.into(), 0.into(), 0.into()];
+        components[index] = 1.into();
+        components
+    }
+
+    /// Returns the quaternion unit with the given name (`i`, `j` or `k`)
+    pub(crate) fn unit_value<I: Interrupt>(
+        name: &str,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Option<Value>, FendError> {
+        Ok(match UNIT_NAMES.iter().position(|&n| n == name) {
+            Some(idx) => Some(Self::new(Self::unit(idx + 1), ctx, int)?.into()),
+            None => None,
+        })
+    }
+
+    /// Evaluates the argument of `quat`. Within it, `i`, `j` and `k` refer
+    /// to the quaternion units, so e.g. `quat(1 + 2i + 3j + 4k)` works.
+    /// A list of four components like `quat(1, 2, 3, 4)` is also accepted.
+    pub(crate) fn quat_value<I: Interrupt>(
+        expr: Expr,
+        scope: Option<Arc<Scope>>,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let quaternion_units = ctx.quaternion_units;
+        ctx.quaternion_units = true;
+        let value = crate::ast::evaluate(expr, scope, ctx, int);
+        ctx.quaternion_units = quaternion_units;
+        let value = value?;
+        let components = match value {
+            Value::List(list) => {
+                if list.len() != 4 {
+                    return Err(FendError::WrongNumberOfArguments {
+                        function: "quat",
+                        expected: "1 or 4",
+                        found: list.len(),
+                    });
+                }
+                let mut components = Self::unit(0);
+                for (c, value) in components.iter_mut().zip(list) {
+                    *c = value.expect_num()?;
+                }
+                components
+            }
+            value => Self::components_of(value)?,
+        };
+        Ok(Self::new(components, ctx, int)?.into())
+    }
+
+    /// Returns true if evaluating `lhs <bop> rhs` should be handled by
+    /// `Quaternion::bop`
+    pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+        lhs.as_dyn::<Self>().is_some() || rhs.as_dyn::<Self>().is_some()
+    }
+
+    fn components_of(value: Value) -> Result<[Number; 4], FendError> {
+        if let Value::Num(n) = value {
+            let mut components = Self::unit(0);
+            components[0] = *n;
+            return Ok(components);
+        }
+        match value.as_dyn::<Self>() {
+            Some(q) => Ok(q.components.clone()),
+            None => Err(FendError::ExpectedAQuaternion),
+        }
+    }
+
+    /// Evaluates `lhs <bop> rhs` where at least one operand is a quaternion
+    pub(crate) fn bop<I: Interrupt>(
+        bop: Bop,
+        lhs: Value,
+        rhs: Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let a = Self::components_of(lhs)?;
+        let components = match bop {
+            Bop::Plus | Bop::Minus => {
+                let b = Self::components_of(rhs)?;
+                let mut result = Self::unit(0);
+                for (r, (x, y)) in result.iter_mut().zip(a.into_iter().zip(b)) {
+                    *r = if bop == Bop::Plus {
+                        x.add(y, int)?
+                    } else {
+                        x.sub(y, int)?
+                    };
+                }
+                result
+            }
+            Bop::Mul => hamilton_product(&a, &Self::components_of(rhs)?, int)?,
+            Bop::Div => hamilton_product(&a, &inverse(Self::components_of(rhs)?, int)?, int)?,
+            Bop::Pow => {
+                let exponent = rhs.expect_num()?.try_as_i64(int)?;
+                let mut base = if exponent < 0 { inverse(a, int)? } else { a };
+                let mut exponent = exponent.unsigned_abs();
+                let mut result = Self::unit(0);
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        result = hamilton_product(&result, &base, int)?;
+                    }
+                    exponent >>= 1;
+                    if exponent > 0 {
+                        base = hamilton_product(&base, &base, int)?;
+                    }
+                }
+                result
+            }
+            _ => return Err(FendError::UnsupportedQuaternionOperation),
+        };
+        Ok(Self::new(components, ctx, int)?.into())
+    }
+
+    pub(crate) fn conjugate_value<I: Interrupt>(
+        &self,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        Ok(Self::new(conjugate(self.components.clone()), ctx, int)?.into())
+    }
+
+    pub(crate) fn norm<I: Interrupt>(&self, int: &I) -> Result<Number, FendError> {
+        vector::norm(self.components.to_vec(), int)
+    }
+
+    /// Rotates a 3D vector by the given quaternion, computing `q v q^-1`.
+    /// The quaternion doesn't need to be normalised.
+    pub(crate) fn rotate_value<I: Interrupt>(arg: Value, int: &I) -> Result<Value, FendError> {
+        let args = arg.into_list();
+        if args.len() != 2 {
+            return Err(FendError::WrongNumberOfArguments {
+                function: "rotate",
+                expected: "2",
+                found: args.len(),
+            });
+        }
+        let mut args = args.into_iter();
+        let q = Self::components_of(args.next().unwrap())?;
+        let v = args.next().unwrap().into_list();
+        if v.len() != 3 {
+            return Err(FendError::ExpectedA3dVector);
+        }
+        let mut point = Self::unit(0);
+        for (c, value) in point.iter_mut().skip(1).zip(v) {
+            *c = value.expect_num()?;
+        }
+        let rotated =
+            hamilton_product(&hamilton_product(&q, &point, int)?, &inverse(q, int)?, int)?;
+        Ok(Value::List(
+            rotated
+                .into_iter()
+                .skip(1)
+                .map(|x| Value::Num(Box::new(x)))
+                .collect(),
+        ))
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        for (a, b) in self.components.iter().zip(&rhs.components) {
+            if a.compare(b, int)? != Ordering::Equal {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn hamilton_product<I: Interrupt>(
+    a: &[Number; 4],
+    b: &[Number; 4],
+    int: &I,
+) -> Result<[Number; 4], FendError> {
+    // each component is a sum of four products `a[i] * b[j]`, given as
+    // `(i, j, negate)`
+    const TERMS: [[(usize, usize, bool); 4]; 4] = [
+        [(0, 0, false), (1, 1, true), (2, 2, true), (3, 3, true)],
+        [(0, 1, false), (1, 0, false), (2, 3, false), (3, 2, true)],
+        [(0, 2, false), (1, 3, true), (2, 0, false), (3, 1, false)],
+        [(0, 3, false), (1, 2, false), (2, 1, true), (3, 0, false)],
+    ];
+    let mut result = Quaternion::unit(0);
+    for (r, terms) in result.iter_mut().zip(TERMS) {
+        let mut sum = Number::from(0);
+        for (i, j, negate) in terms {
+            let product = a[i].clone().mul(b[j].clone(), int)?;
+            sum = if negate {
+                sum.sub(product, int)?
+            } else {
+                sum.add(product, int)?
+            };
+        }
+        *r = sum;
+    }
+    Ok(result)
+}
+
+fn conjugate(components: [Number; 4]) -> [Number; 4] {
+    let [w, x, y, z] = components;
+    [w, -x, -y, -z]
+}
+
+fn inverse<I: Interrupt>(components: [Number; 4], int: &I) -> Result<[Number; 4], FendError> {
+    let norm_squared = vector::dot(components.to_vec(), components.to_vec(), int)?;
+    if norm_squared.is_zero() {
+        return Err(FendError::DivideByZero);
+    }
+    let mut result = conjugate(components);
+    for c in &mut result {
+        *c = c.clone().div(norm_squared.clone(), int)?;
+    }
+    Ok(result)
+}
+
+fn format_components<I: Interrupt>(
+    components: &[Number; 4],
+    ctx: &crate::Context,
+    int: &I,
+) -> Result<Vec<Span>, FendError> {
+    let mut spans = vec![];
+    for (idx, c) in components.iter().enumerate() {
+        let negative = match c.compare(&0.into(), int)? {
+            Ordering::Equal => continue,
+            ordering => ordering == Ordering::Less,
+        };
+        let sign = match (spans.is_empty(), negative) {
+            (true, false) => "",
+            (true, true) => "-",
+            (false, false) => " + ",
+            (false, true) => " - ",
+        };
+        if !sign.is_empty() {
+            spans.push(Span {
+                string: sign.to_string(),
+                kind: SpanKind::Other,
+            });
+        }
+        let c = if negative { -c.clone() } else { c.clone() };
+        if idx == 0 || !c.is_unitless_one() {
+            Value::Num(Box::new(c)).format(0, &mut spans, ctx, int)?;
+        }
+        if idx > 0 {
+            spans.push(Span {
+                string: UNIT_NAMES[idx - 1].to_string(),
+                kind: SpanKind::Number,
+            });
+        }
+    }
+    if spans.is_empty() {
+        spans.push(Span {
+            string: "0".to_string(),
+            kind: SpanKind::Number,
+        });
+    }
+    Ok(spans)
+}
+
+impl ValueTrait for Quaternion {
+    fn type_name(&self) -> &'static str {
+        "quaternion"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
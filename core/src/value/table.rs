@@ -0,0 +1,48 @@
+use super::ValueTrait;
+use crate::{Span, SpanKind};
+
+/// A two-column table, e.g. the result of `table(1..3, \n. n miles to km)`
+#[derive(Clone, Debug)]
+pub(crate) struct Table {
+    rows: Vec<(Vec<Span>, Vec<Span>)>,
+}
+
+impl Table {
+    pub(crate) fn new(rows: Vec<(Vec<Span>, Vec<Span>)>) -> Self {
+        Self { rows }
+    }
+}
+
+fn width(spans: &[Span]) -> usize {
+    spans.iter().map(|s| s.string.chars().count()).sum()
+}
+
+impl ValueTrait for Table {
+    fn type_name(&self) -> &'static str {
+        "table"
+    }
+
+    fn format(&self, indent: usize, spans: &mut Vec<Span>) {
+        let left_width = self
+            .rows
+            .iter()
+            .map(|(left, _)| width(left))
+            .max()
+            .unwrap_or(0);
+        for (i, (left, right)) in self.rows.iter().enumerate() {
+            if i != 0 {
+                spans.push(Span {
+                    string: format!("\n{}", " ".repeat(indent)),
+                    kind: SpanKind::Whitespace,
+                });
+            }
+            spans.extend(left.iter().cloned());
+            spans.push(Span {
+                string: " ".repeat(left_width - width(left) + 1),
+                kind: SpanKind::Whitespace,
+            });
+            spans.push(Span::from_string("| ".to_string()));
+            spans.extend(right.iter().cloned());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::{Value, ValueTrait};
+use crate::ast::Bop;
+use crate::error::{FendError, Interrupt};
+use crate::num::uncertain::Uncertain;
+use crate::num::Number;
+use crate::Span;
+
+/// A value with an uncertainty, e.g. `5.0 ± 0.1`. Like matrices, the
+/// value is formatted when it is created.
+#[derive(Clone, Debug)]
+pub(crate) struct UncertainValue {
+    inner: Uncertain,
+    spans: Vec<Span>,
+}
+
+impl UncertainValue {
+    fn new<I: Interrupt>(
+        inner: Uncertain,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Self, FendError> {
+        let spans = inner.format(ctx, int)?;
+        Ok(Self { inner, spans })
+    }
+
+    /// Evaluates `value ± uncertainty`
+    pub(crate) fn plus_minus<I: Interrupt>(
+        value: Value,
+        uncertainty: Value,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        if value.as_dyn::<Self>().is_some() {
+            return Err(FendError::UnsupportedUncertaintyOperation);
+        }
+        let inner = Uncertain::new(value.expect_num()?, uncertainty.expect_num()?, int)?;
+        Ok(Self::new(inner, ctx, int)?.into())
+    }
+
+    /// Returns true if evaluating `lhs <bop> rhs` should be handled by
+    /// `UncertainValue::bop`
+    pub(crate) fn is_operand(lhs: &Value, rhs: &Value) -> bool {
+        lhs.as_dyn::<Self>().is_some() || rhs.as_dyn::<Self>().is_some()
+    }
+
+    fn operand(value: Value) -> Result<Uncertain, FendError> {
+        if let Value::Num(n) = value {
+            return Ok(Uncertain::exact(*n));
+        }
+        match value.as_dyn::<Self>() {
+            Some(u) => Ok(u.inner.clone()),
+            None => Err(FendError::UnsupportedUncertaintyOperation),
+        }
+    }
+
+    /// Evaluates `lhs <bop> rhs` where at least one operand has an
+    /// uncertainty. The operands are assumed to be independent, so e.g.
+    /// `x * x` has a smaller uncertainty than `x^2`.
+    pub(crate) fn bop<I: Interrupt>(
+        bop: Bop,
+        lhs: Value,
+        rhs: Value,
+        ctx: &mut crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        if bop == Bop::PlusMinus {
+            return Err(FendError::UnsupportedUncertaintyOperation);
+        }
+        let args = [Self::operand(lhs)?, Self::operand(rhs)?];
+        let inner = Uncertain::propagate(
+            &args,
+            |mut values| {
+                let b = values.pop().unwrap();
+                let a = values.pop().unwrap();
+                a.bop(bop, b, ctx, int)
+            },
+            int,
+        )?;
+        Ok(Self::new(inner, ctx, int)?.into())
+    }
+
+    /// Applies a function of a single number, e.g. `sin` or `sqrt`
+    pub(crate) fn apply_fn<I: Interrupt>(
+        &self,
+        ctx: &mut crate::Context,
+        mut f: impl FnMut(Number, &mut crate::Context) -> Result<Number, FendError>,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let inner = Uncertain::propagate(
+            std::slice::from_ref(&self.inner),
+            |mut values| f(values.pop().unwrap(), ctx),
+            int,
+        )?;
+        Ok(Self::new(inner, ctx, int)?.into())
+    }
+
+    pub(crate) fn convert_to<I: Interrupt>(
+        &self,
+        unit: Number,
+        ctx: &crate::Context,
+        int: &I,
+    ) -> Result<Value, FendError> {
+        let inner = self.inner.clone().convert_to(unit, int)?;
+        Ok(Self::new(inner, ctx, int)?.into())
+    }
+
+    pub(crate) fn equals<I: Interrupt>(&self, rhs: &Self, int: &I) -> Result<bool, FendError> {
+        self.inner.equals(&rhs.inner, int)
+    }
+}
+
+impl ValueTrait for UncertainValue {
+    fn type_name(&self) -> &'static str {
+        "uncertain value"
+    }
+
+    fn format(&self, _indent: usize, spans: &mut Vec<Span>) {
+        spans.extend(self.spans.iter().cloned());
+    }
+}
@@ -0,0 +1,385 @@
+use crate::units::builtin;
+use crate::{Span, SpanKind};
+
+// Spells out results in words, e.g. "3.14 m^2" becomes
+// "three point one four meters squared". This is intended
+// for screen readers, which often read symbols poorly.
+
+const DIGITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+const TEENS: &[&str] = &[
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: &[&str] = &[
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: &[&str] = &[
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+];
+
+fn digit_word(ch: char) -> Option<&'static str> {
+    ch.to_digit(10).map(|d| DIGITS[d as usize])
+}
+
+/// Spells each character individually, e.g. "0x1f" becomes "zero x one f"
+fn spell_chars(s: &str) -> String {
+    let words: Vec<String> = s
+        .chars()
+        .map(|ch| digit_word(ch).map_or_else(|| ch.to_string(), ToString::to_string))
+        .collect();
+    words.join(" ")
+}
+
+fn below_one_thousand(n: usize) -> String {
+    let mut words = vec![];
+    if n >= 100 {
+        words.push(format!("{} hundred", DIGITS[n / 100]));
+    }
+    let rest = n % 100;
+    if rest >= 20 {
+        match (rest / 10, rest % 10) {
+            (tens, 0) => words.push(TENS[tens].to_string()),
+            (tens, ones) => words.push(format!("{}-{}", TENS[tens], DIGITS[ones])),
+        }
+    } else if rest >= 10 {
+        words.push(TEENS[rest - 10].to_string());
+    } else if rest > 0 || words.is_empty() {
+        words.push(DIGITS[rest].to_string());
+    }
+    words.join(" ")
+}
+
+/// Spells out a string of decimal digits, e.g. "1234" becomes
+/// "one thousand two hundred thirty-four"
+fn integer_words(digits: &str) -> String {
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return DIGITS[0].to_string();
+    }
+    if digits.len() > SCALES.len() * 3 {
+        return scientific_words(digits);
+    }
+    let mut groups = vec![];
+    let mut end = digits.len();
+    while end > 0 {
+        let start = end.saturating_sub(3);
+        groups.push(digits[start..end].parse::<usize>().unwrap_or(0));
+        end = start;
+    }
+    let mut words = vec![];
+    for (scale, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        words.push(below_one_thousand(group));
+        if !SCALES[scale].is_empty() {
+            words.push(SCALES[scale].to_string());
+        }
+    }
+    words.join(" ")
+}
+
+/// Reads an integer that is too large for scale words in scientific
+/// notation, e.g. "one point two times ten to the power of forty"
+fn scientific_words(digits: &str) -> String {
+    let exponent = integer_words(&(digits.len() - 1).to_string());
+    let (first, rest) = digits.trim_end_matches('0').split_at(1);
+    if rest.is_empty() && first == "1" {
+        return format!("ten to the power of {}", exponent);
+    }
+    let mantissa = if rest.is_empty() {
+        decimal_words(first)
+    } else {
+        decimal_words(&format!("{}.{}", first, rest))
+    };
+    format!("{} times ten to the power of {}", mantissa, exponent)
+}
+
+/// Spells out a decimal number like "3.1(6)", reading
+/// digits after the decimal point individually
+fn decimal_words(s: &str) -> String {
+    let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let mut res = integer_words(integer);
+    if fraction.is_empty() {
+        return res;
+    }
+    res.push_str(" point");
+    for ch in fraction.chars() {
+        if let Some(word) = digit_word(ch) {
+            res.push(' ');
+            res.push_str(word);
+        } else if ch == ')' {
+            res.push_str(" recurring");
+        }
+    }
+    res
+}
+
+fn number_token_words(token: &str) -> String {
+    if let Some(rest) = token.strip_prefix('-') {
+        return format!("minus {}", number_token_words(rest));
+    }
+    if let Some(rest) = token.strip_suffix('i') {
+        if rest.is_empty() {
+            return "i".to_string();
+        }
+        return format!("{} i", number_token_words(rest));
+    }
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        return format!(
+            "{} over {}",
+            number_token_words(numerator),
+            number_token_words(denominator)
+        );
+    }
+    if let Some((mantissa, exponent)) = token.split_once('e') {
+        if !mantissa.is_empty() && !exponent.is_empty() {
+            return format!(
+                "{} times ten to the power of {}",
+                number_token_words(mantissa),
+                number_token_words(exponent)
+            );
+        }
+    }
+    // symbolic results like "3π" or "2√2"
+    if let Some(coefficient) = token.strip_suffix('\u{3c0}') {
+        if coefficient.is_empty() {
+            return "pi".to_string();
+        }
+        return format!("{} pi", number_token_words(coefficient));
+    }
+    if let Some((coefficient, radicand)) = token.split_once('\u{221a}') {
+        let root = format!("the square root of {}", number_token_words(radicand));
+        if coefficient.is_empty() {
+            return root;
+        }
+        return format!("{} times {}", number_token_words(coefficient), root);
+    }
+    if token
+        .chars()
+        .all(|ch| ch.is_ascii_digit() || ch == '.' || ch == '(' || ch == ')')
+    {
+        return decimal_words(token);
+    }
+    // e.g. numbers in other bases
+    spell_chars(token)
+}
+
+fn number_words(s: &str) -> String {
+    let words: Vec<String> = s
+        .split_whitespace()
+        .map(|token| match token {
+            "+" => "plus".to_string(),
+            "-" => "minus".to_string(),
+            token => number_token_words(token),
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn is_simple_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|ch| ch.is_alphabetic() || ch == '_')
+}
+
+/// Finds the full name of a unit, following aliases and
+/// abbreviations (e.g. `m` -> `meter`, `km` -> `kilometer`)
+fn long_unit_name(name: &str, plural: bool, depth: usize) -> Option<String> {
+    if depth > 5 {
+        return None;
+    }
+    if let Some((singular, plural_name, def)) = builtin::query_unit(name, false, true) {
+        let target = def.split_once('@').map_or(def, |(_, target)| target);
+        let target = target.strip_prefix('=').unwrap_or(target);
+        if is_simple_ident(target) && target != name {
+            if let Some(res) = long_unit_name(target, plural, depth + 1) {
+                return Some(res);
+            }
+        }
+        return Some(if plural { plural_name } else { singular }.replace('_', " "));
+    }
+    for (idx, _) in name.char_indices().skip(1) {
+        let (prefix, rest) = name.split_at(idx);
+        if let Some((_, _, def)) = builtin::query_unit(prefix, true, true) {
+            if let Some(prefix) = def.strip_prefix("sp@") {
+                if let Some(unit) = long_unit_name(rest, plural, depth + 1) {
+                    return Some(format!("{}{}", prefix, unit));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Temperatures are read with "degrees", e.g. "five degrees Celsius"
+fn temperature_scale_words(name: &str, plural: bool) -> Option<String> {
+    let scale = match name {
+        "celsius" => "Celsius",
+        "fahrenheit" => "Fahrenheit",
+        "rankine" => "Rankine",
+        _ => return None,
+    };
+    let degrees = if plural { "degrees" } else { "degree" };
+    Some(format!("{} {}", degrees, scale))
+}
+
+fn unit_words(s: &str, plural: bool) -> String {
+    let mut tokens: Vec<String> = vec![];
+    for token in s
+        .split_whitespace()
+        .map(|token| token.trim_matches(|ch| ch == '(' || ch == ')'))
+    {
+        // merge units whose names contain spaces, like "light year"
+        if let Some(last) = tokens.last_mut() {
+            let merged = format!("{}_{}", last, token);
+            if builtin::query_unit(&merged, false, true).is_some() {
+                *last = merged;
+                continue;
+            }
+        }
+        tokens.push(token.to_string());
+    }
+    // only the last unit before any `/` is plural,
+    // e.g. "kilogram meters per second squared"
+    let plural_idx = tokens
+        .iter()
+        .position(|token| token == "/")
+        .unwrap_or(tokens.len())
+        .checked_sub(1);
+    let mut words = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        if token == "/" {
+            words.push("per".to_string());
+            continue;
+        }
+        let (name, exponent) = token.split_once('^').unwrap_or((token, ""));
+        let plural = plural && plural_idx == Some(i);
+        let name = long_unit_name(name, plural, 0).unwrap_or_else(|| name.to_string());
+        words.push(temperature_scale_words(&name, plural).unwrap_or(name));
+        match exponent {
+            "" => (),
+            "2" => words.push("squared".to_string()),
+            "3" => words.push("cubed".to_string()),
+            exponent => words.push(format!("to the power of {}", number_words(exponent))),
+        }
+    }
+    words.join(" ")
+}
+
+fn is_singular(number: &str) -> bool {
+    number == "1" || number == "-1"
+}
+
+/// Spells out the given result spans in words
+pub(crate) fn spell_out(spans: &[Span]) -> String {
+    let mut words = vec![];
+    let mut last_number = None;
+    // currency symbols like `$` come before the number,
+    // but should be read after it
+    let mut prefix_unit = None;
+    for span in spans {
+        let s = span.string.as_str();
+        match span.kind {
+            SpanKind::Number => {
+                words.push(number_words(s));
+                last_number = Some(s);
+                if let Some(unit) = prefix_unit.take() {
+                    words.push(unit_words(unit, !is_singular(s)));
+                }
+            }
+            SpanKind::Ident if s == "approx. " => words.push("approximately".to_string()),
+            SpanKind::Ident if s == "$" || s == "\u{a3}" => prefix_unit = Some(s),
+            SpanKind::Ident => {
+                let plural = !last_number.map_or(false, is_singular);
+                words.push(unit_words(s, plural));
+            }
+            // matrices are read row by row
+            SpanKind::Matrix if s == "[" => words.push("row".to_string()),
+            SpanKind::Matrix => words.push(",".to_string()),
+            SpanKind::Other => {
+                words.push(s.replace('[', "list: ").replace(']', ""));
+            }
+            _ => words.push(s.to_string()),
+        }
+    }
+    let res = words.join(" ");
+    res.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" ,", ",")
+        .trim_end_matches(',')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(integer_words("0"), "zero");
+        assert_eq!(integer_words("15"), "fifteen");
+        assert_eq!(integer_words("40"), "forty");
+        assert_eq!(
+            integer_words("1234"),
+            "one thousand two hundred thirty-four"
+        );
+        assert_eq!(integer_words("1000001"), "one million one");
+        assert_eq!(
+            integer_words(&format!("1{}", "0".repeat(100))),
+            "ten to the power of one hundred"
+        );
+        assert_eq!(
+            integer_words(&format!("12{}", "0".repeat(40))),
+            "one point two times ten to the power of forty-one"
+        );
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(decimal_words("3.14"), "three point one four");
+        assert_eq!(decimal_words("0.(3)"), "zero point three recurring");
+    }
+
+    #[test]
+    fn symbolic() {
+        assert_eq!(number_words("5\u{3c0}/2"), "five pi over two");
+        assert_eq!(
+            number_words("2\u{221a}2"),
+            "two times the square root of two"
+        );
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(unit_words(" m^2", true), "meters squared");
+        assert_eq!(unit_words(" km / h", true), "kilometers per hour");
+        assert_eq!(unit_words(" kg", false), "kilogram");
+        assert_eq!(unit_words(" light years", true), "light years");
+        assert_eq!(unit_words(" \u{b0}C", true), "degrees Celsius");
+        assert_eq!(unit_words(" \u{b0}F", false), "degree Fahrenheit");
+    }
+}
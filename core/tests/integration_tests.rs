@@ -87,17 +87,17 @@ fn version() {
 
 #[test]
 fn pi() {
-    test_eval("pi", "approx. 3.1415926535");
+    test_eval("pi", "π");
 }
 
 #[test]
 fn pi_times_two() {
-    test_eval("pi * 2", "approx. 6.2831853071");
+    test_eval("pi * 2", "2π");
 }
 
 #[test]
 fn two_pi() {
-    test_eval("2 pi", "approx. 6.2831853071");
+    test_eval("2 pi", "2π");
 }
 
 #[test]
@@ -381,7 +381,7 @@ fn inverse_sin() {
 
 #[test]
 fn inverse_sin_point_five() {
-    test_eval("sin^-1 0.5", "approx. 0.5235987755");
+    test_eval("sin^-1 0.5", "π/6");
 }
 
 #[test]
@@ -532,7 +532,7 @@ fn large_simple_subtraction() {
 
 #[test]
 fn three_pi_minus_two_pi() {
-    test_eval("3pi - 2pi", "approx. 3.1415926535");
+    test_eval("3pi - 2pi", "π");
 }
 
 #[test]
@@ -550,7 +550,7 @@ fn large_simple_subtraction_2() {
 
 #[test]
 fn sqrt_half() {
-    test_eval("sqrt (1/2)", "approx. 0.7071067814");
+    test_eval("sqrt (1/2)", "√2/2");
 }
 
 #[test]
@@ -565,7 +565,7 @@ fn sqrt_1() {
 
 #[test]
 fn sqrt_2() {
-    test_eval("sqrt 2", "approx. 1.4142135619");
+    test_eval("sqrt 2", "√2");
 }
 
 #[test]
@@ -1189,10 +1189,7 @@ fn powers_17() {
 
 #[test]
 fn powers_18() {
-    test_eval(
-        "5.2*10^15*300^(3/2)",
-        "approx. 27019992598076723515.9873962402",
-    );
+    test_eval("5.2*10^15*300^(3/2)", "15600000000000000000√3");
 }
 
 #[test]
@@ -2154,12 +2151,12 @@ fn units_12() {
 
 #[test]
 fn units_13() {
-    test_eval("5pi", "approx. 15.7079632679");
+    test_eval("5pi", "5π");
 }
 
 #[test]
 fn units_14() {
-    test_eval("5 pi/2", "approx. 7.8539816339");
+    test_eval("5 pi/2", "5π/2");
 }
 
 #[test]
@@ -2442,7 +2439,7 @@ fn abs_2_i() {
 
 #[test]
 fn abs_1_plus_i() {
-    test_eval("abs (1 + i)", "approx. 1.4142135619");
+    test_eval("abs (1 + i)", "√2");
 }
 
 #[test]
@@ -3003,7 +3000,7 @@ fn cos_minus_5_pi_over_3() {
 
 #[test]
 fn tau() {
-    test_eval("tau", "approx. 6.2831853071");
+    test_eval("tau", "2π");
 }
 
 #[test]
@@ -3013,12 +3010,12 @@ fn sin_tau_over_two() {
 
 #[test]
 fn greek_pi_symbol() {
-    test_eval("π", "approx. 3.1415926535");
+    test_eval("π", "π");
 }
 
 #[test]
 fn greek_tau_symbol() {
-    test_eval("τ", "approx. 6.2831853071");
+    test_eval("τ", "2π");
 }
 
 #[test]
@@ -3043,7 +3040,7 @@ fn tan_2pi() {
 
 #[test]
 fn asin_1() {
-    test_eval("asin 1", "approx. 1.5707963267");
+    test_eval("asin 1", "π/2");
 }
 
 #[test]
@@ -3068,7 +3065,7 @@ fn asin_minus_one_point_zero_one() {
 
 #[test]
 fn acos_0() {
-    test_eval("acos 0", "approx. 1.5707963267");
+    test_eval("acos 0", "π/2");
 }
 
 #[test]
@@ -3093,7 +3090,7 @@ fn acos_minus_one_point_zero_one() {
 
 #[test]
 fn atan_1() {
-    test_eval("atan 1", "approx. 0.7853981633");
+    test_eval("atan 1", "π/4");
 }
 
 #[test]
@@ -3360,7 +3357,7 @@ fn lambda_2() {
 fn lambda_3() {
     test_eval(
         "(cis: (cis (pi/3))) (x: cos x + i * (sin x))",
-        "approx. 0.5 + 0.8660254037i",
+        "0.5 + i√3/2",
     );
 }
 
@@ -3501,7 +3498,7 @@ fn cis_minus_two_pi() {
 
 #[test]
 fn cis_pi_over_six() {
-    test_eval("cis (pi/6)", "approx. 0.8660254037 + 0.5i");
+    test_eval("cis (pi/6)", "√3/2 + 0.5i");
 }
 
 #[test]
@@ -4412,7 +4409,7 @@ fn kg_to_unitless() {
     expect_error(
         "kg to unitless",
         Some(
-            "cannot convert from kg to unitless: units 'kilogram' and 'unitless' are incompatible",
+            "cannot convert from kg to unitless: mass (kilogram) and dimensionless (unitless) are incompatible",
         ),
     );
 }
@@ -4747,7 +4744,7 @@ fn triple_string_concatenation() {
 
 #[test]
 fn number_to_string() {
-    test_eval_simple("\"pi = \" + (pi to string)", "pi = approx. 3.1415926535");
+    test_eval_simple("\"pi = \" + (pi to string)", "pi = π");
 }
 
 #[test]
@@ -5041,7 +5038,6 @@ fn unicode_escape_aaa_uppercase() {
 }
 
 #[test]
-#[ignore]
 fn today() {
     let mut context = Context::new();
     context.set_current_time_v1(1617517099000, 0);
@@ -5052,16 +5048,34 @@ fn today() {
 }
 
 #[test]
-#[ignore]
 fn today_with_tz() {
     let mut context = Context::new();
-    context.set_current_time_v1(1619943083155, 43200);
+    // 2021-05-02 08:11 UTC is already the evening in New Zealand (UTC+12)
+    context.set_current_time_v1(1619943083155, -43200);
     assert_eq!(
         evaluate("today", &mut context).unwrap().get_main_result(),
         "Sunday, 2 May 2021"
     );
 }
 
+#[test]
+fn current_time_from_public_api() {
+    let mut context = Context::new();
+    // Friday, 2 May 2025 09:30 UTC
+    context.set_current_time_v1(1_746_178_200_000, 0);
+    let mut eval = |input| {
+        evaluate(input, &mut context)
+            .unwrap()
+            .get_main_result()
+            .to_string()
+    };
+    assert_eq!(eval("now"), "Friday, 2 May 2025 09:30");
+    assert_eq!(eval("today + 36 hours"), "Saturday, 3 May 2025 12:00");
+    assert_eq!(eval("next friday"), "Friday, 9 May 2025");
+    assert_eq!(eval("3 weeks from today"), "Friday, 23 May 2025");
+    assert_eq!(eval("now in Asia/Tokyo"), "Friday, 2 May 2025 18:30 JST");
+}
+
 #[test]
 fn acre_foot_to_m_3() {
     test_eval("acre foot to m^3", "1233.48183754752 m^3");
@@ -5424,6 +5438,27 @@ fn add_days_to_date() {
     );
 }
 
+#[test]
+fn far_range_dates() {
+    test_eval_simple("'-0044-03-15' to date", "Thursday, 15 March 45 BC");
+    test_eval_simple("'0000-02-29' to date", "Tuesday, 29 February 1 BC");
+    test_eval_simple("('0000-12-31' to date) + 1 day", "Monday, 1 January 1");
+    test_eval_simple("('1582-10-04' to date) + 1 day", "Tuesday, 5 October 1582");
+    test_eval_simple("'12345-06-07' to date", "Thursday, 7 June 12345");
+    test_eval_simple(
+        "('2020-05-04' to date) + 1000000000 days",
+        "Sunday, 8 May 2739927",
+    );
+    expect_error(
+        "('2147483647-12-31' to date) + 1 day",
+        Some("date is out of range"),
+    );
+    expect_error(
+        "'-0001-02-29' to date",
+        Some("failed to convert '-0001-02-29' to a date"),
+    );
+}
+
 #[test]
 fn fancy_syntax() {
     test_eval("(\u{3bb}x.x) 5", "5");
@@ -5458,7 +5493,7 @@ fn implicit_unit_fudging_2() {
 fn implicit_unit_fudging_3() {
     expect_error(
         "0'1 + 5",
-        Some("cannot convert from unitless to ': units 'unitless' and 'meter' are incompatible"),
+        Some("cannot convert from unitless to ': dimensionless (unitless) and length (meter) are incompatible"),
     );
 }
 
@@ -5471,6 +5506,2441 @@ fn implicit_unit_fudging_4() {
 fn implicit_unit_fudging_5() {
     expect_error(
         "5'1 + 5kg",
-        Some("cannot convert from kg to ': units 'kilogram' and 'meter' are incompatible"),
+        Some("cannot convert from kg to ': mass (kilogram) and length (meter) are incompatible"),
+    );
+}
+
+#[test]
+fn lists() {
+    test_eval("[1, 2, 3 kg]", "[1, 2, 3 kg]");
+}
+
+#[test]
+fn empty_list() {
+    test_eval("[]", "[]");
+}
+
+#[test]
+fn list_in_parens() {
+    test_eval_simple("(1, 2)", "[1, 2]");
+}
+
+#[test]
+fn max_of_numbers() {
+    test_eval("max(1, 3, 2)", "3");
+}
+
+#[test]
+fn max_of_list() {
+    test_eval("max [1, 5, 2]", "5");
+}
+
+#[test]
+fn max_with_units() {
+    test_eval("max(3 ft, 1 m)", "1 m");
+}
+
+#[test]
+fn min_of_numbers() {
+    test_eval("min(4, -2, 7)", "-2");
+}
+
+#[test]
+fn min_with_units() {
+    test_eval("min(3 ft, 1 m)", "3 ft");
+}
+
+#[test]
+fn min_of_list_variable() {
+    test_eval_simple("x = [4, 2, 9]; min x", "2");
+}
+
+#[test]
+fn min_is_still_minutes() {
+    test_eval("5 min", "5 mins");
+}
+
+#[test]
+fn max_of_empty_list() {
+    expect_error("max []", Some("expected at least one argument"));
+}
+
+#[test]
+fn max_of_no_arguments() {
+    expect_error("max()", Some("expected at least one argument"));
+    expect_error("min()", Some("expected at least one argument"));
+}
+
+#[test]
+fn arguments_without_spaces() {
+    test_eval("max(1,2)", "2");
+    test_eval("min(1,2)", "1");
+    test_eval("[1,2,3]", "[1, 2, 3]");
+    test_eval("clamp(5,1,3)", "3");
+    test_eval_simple("f = \\(x, y). x - y; f(1,2)", "-1");
+}
+
+#[test]
+fn digit_separators_outside_of_lists() {
+    test_eval("max(1,2) + 1,000", "1002");
+}
+
+#[test]
+fn digit_separators_inside_parentheses() {
+    test_eval("(1,000) * 2", "2000");
+    test_eval("sqrt(1,000,000)", "1000");
+    test_eval("max(1,000,2)", "1000");
+    test_eval("[1,000, 2,500]", "[1000, 2500]");
+    test_eval("[1,2345]", "[1, 2345]");
+}
+
+#[test]
+fn max_incompatible_units() {
+    expect_error(
+        "max(1 kg, 1 m)",
+        Some("cannot convert from kg to m: mass (kilogram) and length (meter) are incompatible"),
+    );
+}
+
+#[test]
+fn clamp_within_bounds() {
+    test_eval("clamp(5, 1, 10)", "5");
+}
+
+#[test]
+fn clamp_below_lower_bound() {
+    test_eval("clamp(-5, 1, 10)", "1");
+}
+
+#[test]
+fn clamp_with_units() {
+    test_eval("clamp(10 ft, 1 m, 2 m) to 2dp", "approx. 6.56 ft");
+}
+
+#[test]
+fn clamp_wrong_number_of_arguments() {
+    expect_error(
+        "clamp(1, 2)",
+        Some("clamp expects 3 arguments, but 2 were given"),
+    );
+}
+
+#[test]
+fn clamp_bounds_out_of_order() {
+    expect_error(
+        "clamp(1, 3, 2)",
+        Some("the lower bound cannot be larger than the upper bound"),
+    );
+}
+
+#[test]
+fn round_half_away_from_zero() {
+    test_eval("round 2.5", "3");
+    test_eval("round(-2.5)", "-3");
+}
+
+#[test]
+fn floor_negative() {
+    test_eval("floor(-3.2)", "-4");
+}
+
+#[test]
+fn ceil_negative() {
+    test_eval("ceil(-0.5)", "0");
+}
+
+#[test]
+fn trunc_negative() {
+    test_eval("trunc(-3.7)", "-3");
+}
+
+#[test]
+fn round_to_decimal_places() {
+    test_eval("round(3.14159, 2)", "3.14");
+}
+
+#[test]
+fn round_to_negative_decimal_places() {
+    test_eval("round(1234, -2)", "1200");
+    test_eval("round(1250, -2)", "1300");
+    test_eval("floor(1299 m, -2)", "1200 m");
+}
+
+#[test]
+fn round_pi_to_decimal_places() {
+    test_eval("round(pi, 3)", "3.142");
+}
+
+#[test]
+fn round_with_unit_granularity() {
+    test_eval("round(3.7 m, 0.5 m)", "3.5 m");
+}
+
+#[test]
+fn round_with_different_unit_granularity() {
+    test_eval("round(1.234 m, 1 cm)", "1.23 m");
+}
+
+#[test]
+fn floor_with_unitless_granularity() {
+    test_eval("floor(3.7 m, 0.5)", "3.5 m");
+}
+
+#[test]
+fn round_keeps_unit() {
+    test_eval("round(2.5 kg)", "3 kg");
+}
+
+#[test]
+fn round_too_many_arguments() {
+    expect_error(
+        "round(1, 2, 3)",
+        Some("round expects 1 or 2 arguments, but 3 were given"),
+    );
+}
+
+#[test]
+fn round_incompatible_granularity() {
+    expect_error(
+        "round(1 kg, 1 m)",
+        Some("cannot convert from kg to m: mass (kilogram) and length (meter) are incompatible"),
+    );
+}
+
+#[test]
+fn evaluate_pure_does_not_modify_context() {
+    let mut context = Context::new();
+    let (result, delta) = fend_core::evaluate_pure("foo = 5", &context);
+    assert_eq!(result.unwrap().get_main_result(), "5");
+    assert!(!delta.is_empty());
+    assert!(evaluate("foo", &mut context).is_err());
+    delta.apply(&mut context);
+    assert_eq!(
+        evaluate("foo * 2", &mut context).unwrap().get_main_result(),
+        "10"
+    );
+}
+
+#[test]
+fn evaluate_pure_revert_delta() {
+    let mut context = Context::new();
+    evaluate("foo = 5", &mut context).unwrap();
+    let (_, delta) = fend_core::evaluate_pure("foo = 7; qux = foo + 1", &context);
+    delta.apply(&mut context);
+    assert_eq!(
+        evaluate("foo + qux", &mut context)
+            .unwrap()
+            .get_main_result(),
+        "15"
+    );
+    delta.revert(&mut context);
+    assert_eq!(
+        evaluate("foo", &mut context).unwrap().get_main_result(),
+        "5"
+    );
+    assert!(evaluate("qux", &mut context).is_err());
+}
+
+#[test]
+fn evaluate_pure_custom_unit() {
+    let mut context = Context::new();
+    let (result, delta) = fend_core::evaluate_pure("unit zarp = 3 m", &context);
+    assert!(result.is_ok());
+    assert!(!delta.is_empty());
+    assert!(evaluate("2 zarp", &mut context).is_err());
+    delta.apply(&mut context);
+    assert_eq!(
+        evaluate("2 zarp to m", &mut context)
+            .unwrap()
+            .get_main_result(),
+        "6 m"
+    );
+    delta.revert(&mut context);
+    assert!(evaluate("2 zarp", &mut context).is_err());
+}
+
+#[test]
+fn evaluate_pure_settings() {
+    let mut context = Context::new();
+    let (result, delta) = fend_core::evaluate_pure("set precision 50", &context);
+    assert!(result.is_ok());
+    assert_eq!(context.precision(), 10);
+    delta.apply(&mut context);
+    assert_eq!(context.precision(), 50);
+    delta.revert(&mut context);
+    assert_eq!(context.precision(), 10);
+}
+
+#[test]
+fn evaluate_pure_random_seed() {
+    let mut seeded = Context::new();
+    evaluate("random_seed(5)", &mut seeded).unwrap();
+    let expected = evaluate("random()", &mut seeded).unwrap();
+
+    let mut context = Context::new();
+    let (result, delta) = fend_core::evaluate_pure("random_seed(5)", &context);
+    assert!(result.is_ok());
+    assert!(!delta.is_empty());
+    assert!(evaluate("random()", &mut context).is_err());
+    delta.apply(&mut context);
+    assert_eq!(evaluate("random()", &mut context).unwrap(), expected);
+}
+
+#[test]
+fn inclusive_range() {
+    test_eval_simple("1..5", "[1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn range_with_variable() {
+    test_eval_simple("n = 3; 1..n", "[1, 2, 3]");
+}
+
+#[test]
+fn range_with_fractional_start() {
+    test_eval_simple("1.5..4", "[1.5, 2.5, 3.5]");
+}
+
+#[test]
+fn empty_range() {
+    test_eval_simple("5..1", "[]");
+}
+
+#[test]
+fn max_of_range() {
+    test_eval("max(1..10)", "10");
+}
+
+#[test]
+fn conversion_table() {
+    test_eval_simple(
+        "table(1..3, \\n. n miles to km)",
+        "1 | 1.609344 km\n2 | 3.218688 km\n3 | 4.828032 km",
+    );
+}
+
+#[test]
+fn conversion_table_alignment() {
+    test_eval_simple(
+        "table([1, 10, 100], \\x. x kg to g)",
+        "1   | 1000 g\n10  | 10000 g\n100 | 100000 g",
+    );
+}
+
+#[test]
+fn table_wrong_number_of_arguments() {
+    expect_error(
+        "table(1)",
+        Some("table expects 2 arguments, but 1 were given"),
+    );
+}
+
+#[test]
+fn random_without_rng() {
+    expect_error("random()", Some("random numbers are not available"));
+}
+
+#[test]
+fn seeded_random() {
+    test_eval_simple("random_seed 42; random()", "approx. 0.7415648787");
+}
+
+#[test]
+fn seeded_random_is_reproducible() {
+    let mut context = Context::new();
+    context.set_random_seed(5);
+    let first = evaluate("random()", &mut context).unwrap();
+    context.set_random_seed(5);
+    let second = evaluate("random()", &mut context).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn seeded_randint() {
+    test_eval_simple("random_seed 42; randint(1, 6)", "6");
+}
+
+#[test]
+fn randint_negative_range() {
+    test_eval_simple("random_seed 42; randint(-3, -1)", "-1");
+}
+
+#[test]
+fn randint_non_integer() {
+    expect_error(
+        "random_seed 42; randint(1.5, 3)",
+        Some("cannot convert fraction to integer"),
+    );
+}
+
+#[test]
+fn seeded_random_choice() {
+    test_eval_simple(r#"random_seed 7; random_choice ["a", "b", "c"]"#, "a");
+}
+
+#[test]
+fn random_choice_empty_list() {
+    expect_error(
+        "random_seed 7; random_choice []",
+        Some("expected at least one argument"),
     );
 }
+
+#[test]
+fn totient_of_36() {
+    test_eval_simple("totient 36", "12");
+}
+
+#[test]
+fn totient_of_prime() {
+    test_eval_simple("totient 97", "96");
+}
+
+#[test]
+fn totient_of_zero() {
+    expect_error("totient 0", Some("expected a positive integer"));
+}
+
+#[test]
+fn divisors_of_12() {
+    test_eval_simple("divisors 12", "[1, 2, 3, 4, 6, 12]");
+}
+
+#[test]
+fn divisors_of_1() {
+    test_eval_simple("divisors 1", "[1]");
+}
+
+#[test]
+fn num_divisors_of_36() {
+    test_eval_simple("num_divisors 36", "9");
+}
+
+#[test]
+fn sigma_of_12() {
+    test_eval_simple("sigma 12", "28");
+}
+
+#[test]
+fn divisors_and_sigma_of_2_to_the_63() {
+    test_eval_simple("sigma (2^63)", "18446744073709551615");
+    test_eval_simple("num_divisors (2^63)", "64");
+    test_eval_simple("max(divisors(2^63))", "9223372036854775808");
+}
+
+#[test]
+fn small_fibonacci_numbers() {
+    test_eval_simple("fib 0", "0");
+    test_eval_simple("fib 1", "1");
+    test_eval_simple("fib 2", "1");
+    test_eval_simple("fib 10", "55");
+}
+
+#[test]
+fn large_fibonacci_number() {
+    test_eval_simple("fib 100", "354224848179261915075");
+}
+
+#[test]
+fn fibonacci_of_fraction() {
+    expect_error("fib 2.5", Some("cannot convert fraction to integer"));
+}
+
+#[test]
+fn dimension_algebra() {
+    test_eval_simple("length^2 / time", "length^2 / time");
+    test_eval_simple("mass * length / time^2", "length * mass / time^2");
+    test_eval_simple("1 / time", "1 / time");
+    test_eval_simple("time^-1", "1 / time");
+}
+
+#[test]
+fn dimension_of_number() {
+    test_eval_simple("dimension of (3 m^2/s)", "length^2 / time");
+    test_eval_simple("dimension of (5 N)", "length * mass / time^2");
+    test_eval_simple("dimension of (20 \u{b0}C)", "temperature");
+    test_eval_simple("dimension of 5", "dimensionless");
+}
+
+#[test]
+fn dimension_variable_comparison() {
+    test_eval_simple(
+        "dimension = length^2 / time; dimension of (3 m^2/s) == dimension",
+        "true",
+    );
+    test_eval_simple(
+        "dimension = length^2 / time; dimension of (3 m/s) == dimension",
+        "false",
+    );
+}
+
+#[test]
+fn dimension_inequality() {
+    test_eval_simple("length != time", "true");
+}
+
+#[test]
+fn dimension_times_number() {
+    expect_error("length * 2", Some("expected a dimension"));
+}
+
+#[test]
+fn equality_of_numbers() {
+    test_eval_simple("1 m == 100 cm", "true");
+    test_eval_simple("2 != 3", "true");
+    test_eval_simple("3! == 6", "true");
+}
+
+#[test]
+fn equality_of_strings_and_bools() {
+    test_eval_simple(r#""foo" == "foo""#, "true");
+    test_eval_simple("true == false", "false");
+}
+
+#[test]
+fn unit_categories() {
+    let categories = fend_core::get_unit_categories();
+    let length = categories.iter().find(|c| c.name() == "length").unwrap();
+    assert!(length.units().contains(&"mile"));
+    assert!(categories.iter().any(|c| c.name() == "temperature"));
+}
+
+#[test]
+fn mean_preserves_units() {
+    test_eval_simple("mean [1 m, 200 cm]", "1.5 m");
+}
+
+#[test]
+fn mean_of_arguments() {
+    test_eval_simple("mean(1, 2, 3, 4)", "2.5");
+    test_eval_simple("average(1..10)", "5.5");
+}
+
+#[test]
+fn mean_of_empty_list() {
+    expect_error("mean []", Some("expected at least one argument"));
+}
+
+#[test]
+fn mean_of_incompatible_units() {
+    expect_error(
+        "mean [1 m, 2 s]",
+        Some("cannot convert from s to m: time (second) and length (meter) are incompatible"),
+    );
+}
+
+#[test]
+fn median_of_odd_and_even_lists() {
+    test_eval_simple("median(3, 1, 2)", "2");
+    test_eval_simple("median(4, 1, 3, 2)", "2.5");
+    test_eval_simple("median [1 m, 50 cm]", "0.75 m");
+}
+
+#[test]
+fn mode_of_list() {
+    test_eval_simple("mode(1, 2, 2, 3, 3)", "2");
+    test_eval_simple("mode [1 kg, 1000 g, 2 kg]", "1 kg");
+}
+
+#[test]
+fn variance_and_stdev() {
+    test_eval_simple("variance [1 m, 3 m]", "2 m^2");
+    test_eval_simple("stdev [1 m, 3 m]", "√2 m");
+    test_eval_simple("stdev(2, 4, 4, 4, 5, 5, 7, 9)", "4√14/7");
+}
+
+#[test]
+fn variance_of_single_value() {
+    expect_error(
+        "variance [1]",
+        Some("variance expects at least 2 arguments, but 1 were given"),
+    );
+}
+
+#[test]
+fn percentiles() {
+    test_eval_simple("percentile([1, 2, 3, 4, 5], 50)", "3");
+    test_eval_simple("percentile([1, 2, 3, 4], 90)", "3.7");
+    test_eval_simple("percentile([1, 2, 3, 4], 90%)", "3.7");
+    test_eval_simple("percentile([1, 2, 3, 4], 0)", "1");
+    test_eval_simple("percentile([1, 2, 3, 4], 100)", "4");
+}
+
+#[test]
+fn percentile_out_of_range() {
+    expect_error(
+        "percentile([1, 2], 150)",
+        Some("percentile must be between 0 and 100"),
+    );
+}
+
+#[track_caller]
+fn test_eval_verbose(input: &str, expected: &str) {
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate(input, &mut context)
+            .unwrap()
+            .get_main_result_verbose(),
+        expected
+    );
+}
+
+#[test]
+fn verbose_output() {
+    test_eval_verbose("3.14 m^2", "three point one four meters squared");
+    test_eval_verbose("5 km/h", "five kilometers per hour");
+    test_eval_verbose("1 kg", "one kilogram");
+    test_eval_verbose("-2.5 kg", "minus two point five kilograms");
+    test_eval_verbose("1234", "one thousand two hundred thirty-four");
+    test_eval_verbose("1/3 to fraction", "one over three");
+    test_eval_verbose("2+3i", "two plus three i");
+    test_eval_verbose("$5", "five USD");
+    test_eval_verbose("1e100", "ten to the power of one hundred");
+    test_eval_verbose("5 \u{b0}C", "five degrees Celsius");
+}
+
+#[test]
+fn verbose_output_approx() {
+    test_eval_verbose(
+        "sqrt 2 to decimal",
+        "approximately one point four one four two one three five six one nine",
+    );
+}
+
+#[test]
+fn verbose_output_symbolic() {
+    test_eval_verbose("sqrt 8", "two times the square root of two");
+    test_eval_verbose("5pi/2", "five pi over two");
+}
+
+#[test]
+fn matrix_display() {
+    test_eval_simple("[[1, 2], [3, 4]]", "[1  2]\n[3  4]");
+    test_eval_simple("[[1, -2], [30, 4]]", "[ 1  -2]\n[30   4]");
+    test_eval_simple("[[1, 2], [3]]", "[[1, 2], [3]]");
+}
+
+#[test]
+fn matrix_arithmetic() {
+    test_eval_simple(
+        "[[1, 2], [3, 4]] + [[10, 20], [30, 40]]",
+        "[11  22]\n[33  44]",
+    );
+    test_eval_simple("[[1, 2], [3, 4]] - [[1, 1], [1, 1]]", "[0  1]\n[2  3]");
+    test_eval_simple("[[1, 2], [3, 4]] * [[5, 6], [7, 8]]", "[19  22]\n[43  50]");
+    test_eval_simple("2 * [[1, 2], [3, 4]]", "[2  4]\n[6  8]");
+    test_eval_simple("[[1, 2], [3, 4]] / 2", "[0.5  1]\n[1.5  2]");
+    test_eval_simple("[[1, 1], [1, 0]]^10", "[89  55]\n[55  34]");
+    test_eval_simple("[[0, 1], [1, 0]] * [[1], [2]]", "[2]\n[1]");
+}
+
+#[test]
+fn matrix_functions() {
+    test_eval_simple("transpose [[1, 2, 3], [4, 5, 6]]", "[1  4]\n[2  5]\n[3  6]");
+    test_eval_simple("det [[1, 2], [3, 4]]", "-2");
+    test_eval_simple("determinant [[0, 1], [1, 0]]", "-1");
+    test_eval_simple("det [[2, 0, 1], [1, 3, 2], [1, 1, 1]]", "0");
+    test_eval_simple("inverse [[1, 2], [3, 4]]", "[ -2     1]\n[1.5  -0.5]");
+    test_eval_simple("[[1, 2], [3, 4]]^-1", "[ -2     1]\n[1.5  -0.5]");
+    test_eval_simple("[[1, 2], [3, 4]] == [[1, 2], [3, 4]]", "true");
+    test_eval_simple("[[1, 2], [3, 4]] != [[1, 2], [3, 5]]", "true");
+}
+
+#[test]
+fn matrix_example_without_spaces() {
+    test_eval_simple("[[1,2],[3,4]]", "[1  2]\n[3  4]");
+    test_eval_simple("[[1,2],[3,4]] + [[1,2],[3,4]]", "[2  4]\n[6  8]");
+    test_eval_simple("[[1,2],[3,4]] * [[1,2],[3,4]]", "[ 7  10]\n[15  22]");
+    test_eval_simple("transpose([[1,2],[3,4]])", "[1  3]\n[2  4]");
+    test_eval_simple("det([[1,2],[3,4]])", "-2");
+    test_eval_simple("inverse([[1,2],[3,4]])", "[ -2     1]\n[1.5  -0.5]");
+}
+
+#[test]
+fn determinant_with_units_and_zeros() {
+    test_eval_simple("det [[1 m,0],[0,1 m]]", "1 m^2");
+    test_eval_simple("det [[1 m, 0], [1 m, 1 m]]", "1 m^2");
+    test_eval_simple("det [[0, 1 m], [1 m, 0]]", "-1 m^2");
+}
+
+#[test]
+fn matrix_errors() {
+    expect_error("inverse [[1, 2], [2, 4]]", Some("matrix is not invertible"));
+    expect_error(
+        "[[1, 2]] + [[1], [2]]",
+        Some("matrix dimensions do not match"),
+    );
+    expect_error("det [[1, 2]]", Some("expected a square matrix"));
+    expect_error("transpose 5", Some("expected a matrix"));
+}
+
+#[test]
+fn vector_products() {
+    test_eval_simple("dot([1, 2, 3], [4, 5, 6])", "32");
+    test_eval_simple("dot([1 m, 2 m], [3 N, 4 N])", "11 m N");
+    test_eval_simple("cross([1, 0, 0], [0, 1, 0])", "[0, 0, 1]");
+    test_eval_simple("cross([1, 2, 3], [4, 5, 6])", "[-3, 6, -3]");
+}
+
+#[test]
+fn vector_norm() {
+    test_eval_simple("norm [3, 4]", "5");
+    test_eval_simple("norm [3 m, 4 m]", "5 m");
+    test_eval_simple("norm [1, 1]", "√2");
+}
+
+#[test]
+fn vector_scalar_multiplication() {
+    test_eval_simple("2 * [1 m, 2 m]", "[2 m, 4 m]");
+    test_eval_simple("[1, 2] * 3", "[3, 6]");
+    test_eval_simple("[3 kg, 6 kg] / 3", "[1 kg, 2 kg]");
+}
+
+#[test]
+fn vector_errors() {
+    expect_error(
+        "dot([1, 2], [1])",
+        Some("vectors must have the same length"),
+    );
+    expect_error(
+        "cross([1, 2], [3, 4])",
+        Some("expected a 3-dimensional vector"),
+    );
+    expect_error("dot([1, 2])", Some("expected a vector"));
+    expect_error(
+        "dot([1], [2], [3])",
+        Some("dot expects 2 arguments, but 3 were given"),
+    );
+}
+
+#[test]
+fn factorial_quotients() {
+    test_eval("100! / 98!", "9900");
+    test_eval_simple("98! / 100!", "approx. 0.0001010101");
+    test_eval_simple("98! / 100! to fraction", "1/9900");
+    test_eval("5! / 5!", "1");
+    test_eval_simple("1000000! / 999998!", "999999000000");
+    test_eval_simple("0x10! / 0xe!", "0xf0");
+    test_eval_simple("(\\x.x)! / 2!", "\\x.(x!/2)");
+    expect_error("3.5! / 2!", Some("3.5 is not an integer"));
+}
+
+#[test]
+fn large_factorial() {
+    test_eval_simple("30!", "265252859812191058636308480000000");
+}
+
+#[test]
+fn large_integer_powers() {
+    test_eval_simple("2^100", "1267650600228229401496703205376");
+    test_eval_simple("(2/3)^5 to fraction", "32/243");
+    test_eval_simple("(-3)^3", "-27");
+}
+
+#[test]
+fn polynomial_roots() {
+    test_eval_simple("roots(x^3 - 6x^2 + 11x - 6)", "[1, 2, 3]");
+    test_eval_simple("roots(2x^2 - 3x + 1)", "[0.5, 1]");
+    test_eval_simple("roots((x - 1)^2 (x + 2))", "[-2, 1]");
+    test_eval_simple("roots(x^5 - x)", "[-1, 0, 1]");
+    test_eval_simple("roots(x^2 + 1)", "[]");
+    test_eval_simple(
+        "roots(x^2 - 2)",
+        "[approx. -1.4142135623, approx. 1.4142135623]",
+    );
+    test_eval_simple(
+        "roots(x^3 - 3x + 1)",
+        "[approx. -1.8793852415, approx. 0.3472963553, approx. 1.5320888862]",
+    );
+    test_eval_simple("roots(\\t. t^2 - 4)", "[-2, 2]");
+}
+
+#[test]
+fn polynomial_arithmetic() {
+    test_eval_simple("poly(x^3 - 6x^2 + 11x - 6)", "x^3 - 6x^2 + 11x - 6");
+    test_eval_simple("poly((x - 1)^2)", "x^2 - 2x + 1");
+    test_eval_simple("poly(6 - x)", "-x + 6");
+    test_eval_simple("poly(x(x - 1))", "x^2 - x");
+    test_eval_simple("poly(x/2 + 1)", "0.5x + 1");
+    test_eval_simple("poly(x - x)", "0");
+    test_eval_simple("poly(x + 1) == poly(1 + x)", "true");
+}
+
+#[test]
+fn polynomial_evaluation() {
+    test_eval_simple("p = poly(x^2 - 2x + 1); p(3)", "4");
+    test_eval_simple("p = poly(x^3 - 6x^2 + 11x - 6); p(4)", "6");
+}
+
+#[test]
+fn polynomial_errors() {
+    expect_error(
+        "roots(0)",
+        Some("every number is a root of the zero polynomial"),
+    );
+    expect_error(
+        "poly(1/x)",
+        Some("this operation is not supported for polynomials"),
+    );
+    expect_error(
+        "poly(x) + poly(\\y.y)",
+        Some("polynomials must be in the same variable"),
+    );
+}
+
+#[test]
+fn complex_argument() {
+    test_eval("arg(3+4i)", "approx. 0.927295218");
+    test_eval("arg(i)", "π/2");
+    test_eval("arg(-i)", "-π/2");
+    test_eval("arg(-1) to degrees", "180 degrees");
+    test_eval_simple("arg(-1-i) to °", "-135°");
+    test_eval_simple("arg(2) to °", "0°");
+    expect_error("arg(0)", Some("the argument of zero is undefined"));
+}
+
+#[test]
+fn complex_conjugate_and_abs() {
+    test_eval_simple("conj(3+4i)", "3 - 4i");
+    test_eval_simple("conjugate(3-4i)", "3 + 4i");
+    test_eval_simple("abs(3+4i)", "5");
+    test_eval_simple("abs(-2i)", "2");
+}
+
+#[test]
+fn polar_form_input() {
+    test_eval("2 ∠ 90°", "2i");
+    test_eval("3 ∠ 180°", "-3");
+    test_eval("5 ∠ 30°", "5√3/2 + 2.5i");
+    test_eval("5 ∠ -30°", "5√3/2 - 2.5i");
+    test_eval("5 ∠ 30° * 2", "5√3 + 5i");
+    test_eval("1 ∠ 0 + 1 ∠ 90°", "1 + i");
+    test_eval("2 e^(i pi/2)", "approx. 2i");
+    test_eval("exp(i pi/2)", "approx. i");
+    for input in ["2^(1+i)", "e = 2; e^i"] {
+        expect_error(
+            input,
+            Some("exponentiation is not supported for complex numbers"),
+        );
+    }
+    expect_error(
+        "(-2)^i",
+        Some("exponentiation is not supported for complex numbers"),
+    );
+}
+
+#[test]
+fn polar_form_output() {
+    test_eval_simple("3+4i to polar", "5 ∠ approx. 53.1301023541°");
+    test_eval_simple("2i to polar", "2 ∠ 90°");
+    test_eval_simple("-2 to polar", "2 ∠ 180°");
+    test_eval_simple("(3+4i) V to polar", "5 V ∠ approx. 53.1301023541°");
+}
+
+#[test]
+fn evaluate_relexed_input() {
+    let mut context = Context::new();
+    let lexed = fend_core::lex("1 + 2", &context);
+    assert_eq!(
+        fend_core::evaluate_lexed(&lexed, &mut context)
+            .unwrap()
+            .get_main_result(),
+        "3"
+    );
+    let lexed = fend_core::relex(&lexed, "1 kg + 20 kg to g", &context);
+    assert_eq!(lexed.input(), "1 kg + 20 kg to g");
+    assert_eq!(
+        fend_core::evaluate_lexed(&lexed, &mut context)
+            .unwrap()
+            .get_main_result(),
+        "21000 g"
+    );
+    let lexed = fend_core::relex(&lexed, "1 + \"abc", &context);
+    assert_eq!(
+        fend_core::evaluate_lexed(&lexed, &mut context).unwrap_err(),
+        "unterminated string literal"
+    );
+}
+
+#[test]
+fn quaternion_construction() {
+    test_eval_simple("quat(1 + 2i + 3j + 4k)", "1 + 2i + 3j + 4k");
+    test_eval_simple("quat(1, 2, 3, 4)", "1 + 2i + 3j + 4k");
+    test_eval_simple("quat(2 - 3.5j)", "2 - 3.5j");
+    test_eval_simple("quat(0)", "0");
+    test_eval_simple("quat(i) == quat(i)", "true");
+    test_eval_simple("quat(i) == quat(j)", "false");
+    // `j` and `k` keep their usual meaning outside of `quat`
+    test_eval_simple("2 k", "2000");
+}
+
+#[test]
+fn quaternion_arithmetic() {
+    test_eval_simple("quat(i) * quat(j)", "k");
+    test_eval_simple("quat(j) * quat(i)", "-k");
+    test_eval_simple("quat(i) * quat(j) * quat(k)", "-1");
+    test_eval_simple("quat(k)^2", "-1");
+    test_eval_simple("quat(1 + j)^-2", "-0.5j");
+    test_eval_simple("quat(1 + i) / quat(1 + i)", "1");
+    test_eval_simple("quat(1 + k) * 2", "2 + 2k");
+    test_eval_simple("2 quat(j) + 1", "1 + 2j");
+    test_eval_simple("1 - quat(k)", "1 - k");
+    test_eval_simple("-quat(1 - i)", "-1 + i");
+}
+
+#[test]
+fn quaternion_functions() {
+    test_eval_simple("conj(quat(1 + 2i + 3j + 4k))", "1 - 2i - 3j - 4k");
+    test_eval_simple("q = quat(1 + 2i + 3j + 4k); q * conj(q)", "30");
+    test_eval_simple("norm(quat(1 + i + j + k))", "2");
+    test_eval_simple("abs(quat(1 + i + j + k))", "2");
+    test_eval_simple("rotate(quat(1 + k), [1, 0, 0])", "[0, 1, 0]");
+    test_eval_simple("rotate(quat(i), [1, 2, 3])", "[1, -2, -3]");
+}
+
+#[test]
+fn quaternion_errors() {
+    expect_error(
+        "quat(i) mod 2",
+        Some("this operation is not supported for quaternions"),
+    );
+    expect_error(
+        "quat([1, 2])",
+        Some("quat expects 1 or 4 arguments, but 2 were given"),
+    );
+    expect_error("quat(\\x.x)", Some("expected a quaternion"));
+    expect_error("quat(0)^-1", Some("division by zero"));
+    expect_error(
+        "rotate(quat(i), [1, 2])",
+        Some("expected a 3-dimensional vector"),
+    );
+}
+
+#[test]
+fn currency_info_object() {
+    test_eval_simple(
+        "currencyinfo(\"JPY\")",
+        "{\n    code: JPY,\n    symbol: \u{a5},\n    name: Japanese yen,\n    decimal_places: 0\n}",
+    );
+    test_eval_simple("decimal_places of (currencyinfo(\"usd\"))", "2");
+    expect_error("currencyinfo(\"XYZ\")", Some("unknown currency code 'XYZ'"));
+    expect_error("currencyinfo(5)", Some("expected a string"));
+}
+
+#[test]
+fn currency_info_api() {
+    let jpy = fend_core::get_currency_info("jpy").unwrap();
+    assert_eq!(jpy.code(), "JPY");
+    assert_eq!(jpy.symbol(), "\u{a5}");
+    assert_eq!(jpy.name(), "Japanese yen");
+    assert_eq!(jpy.decimal_places(), 0);
+    assert!(fend_core::get_currency_info("XYZ").is_none());
+    assert!(fend_core::get_currencies()
+        .iter()
+        .any(|c| c.code() == "EUR" && c.decimal_places() == 2));
+}
+
+#[test]
+fn uncertainty_notation() {
+    test_eval_simple("5.0 ± 0.1", "5.0 ± 0.1");
+    test_eval_simple("5 ± 0.15", "5.0 ± 0.2");
+    test_eval_simple("1234 ± 56", "1230 ± 60");
+    test_eval_simple("5.06 ± 0.096", "5.1 ± 0.1");
+    test_eval_simple("-5 ± 0.1", "-5.0 ± 0.1");
+    test_eval_simple("(5 ± 0.1) == (5 ± 0.1)", "true");
+}
+
+#[test]
+fn uncertainty_propagation() {
+    test_eval_simple("(5 ± 0.1) * 2", "10.0 ± 0.2");
+    test_eval_simple("2 (5 ± 0.1)", "10.0 ± 0.2");
+    test_eval_simple("(3 ± 0.3) + (4 ± 0.4)", "7.0 ± 0.5");
+    test_eval_simple("10 - (5 ± 0.1)", "5.0 ± 0.1");
+    test_eval_simple("-(5 ± 0.1)", "-5.0 ± 0.1");
+    test_eval_simple("(10 ± 1) / (2 ± 0.1)", "5.0 ± 0.6");
+    test_eval_simple("(2 ± 0.1)^2", "4.0 ± 0.4");
+    test_eval_simple("sqrt(16 ± 0.4)", "4.00 ± 0.05");
+    test_eval_simple("sin(1 ± 0.01)", "0.841 ± 0.005");
+    test_eval_simple("(5 ± 0.1) * sqrt(4)", "10.0 ± 0.2");
+}
+
+#[test]
+fn uncertainty_with_units() {
+    test_eval_simple("(5 ± 0.1) m", "5.0 m ± 0.1 m");
+    test_eval_simple("5 m ± 1 cm", "5.00 m ± 0.01 m");
+    test_eval_simple("(5 m ± 1 cm) to mm", "5000 mm ± 10 mm");
+    expect_error(
+        "5 ± 0.1 ± 0.1",
+        Some("this operation is not supported for values with uncertainties"),
+    );
+}
+
+#[test]
+fn dual_units() {
+    let mut ctx = Context::new();
+    ctx.show_dual_units();
+    for (input, expected) in [
+        ("5 m", "5 m (approx. 16.4 feet)"),
+        ("3 km", "3 km (approx. 1.86 miles)"),
+        ("12 inch", "12 inches (30.48 cm)"),
+        ("20 °C", "20 °C (68 °F)"),
+        ("70 kg", "70 kg (approx. 154.32 lbs)"),
+        ("5 m/s", "5 m / s (approx. 11.18 mph)"),
+        ("5", "5"),
+        ("5 m^2", "5 m^2"),
+    ] {
+        assert_eq!(
+            evaluate(input, &mut ctx).unwrap().get_main_result(),
+            expected
+        );
+    }
+    let result = evaluate("2 lb", &mut ctx).unwrap();
+    let secondary: Vec<_> = result
+        .get_main_result_spans()
+        .filter(|s| s.kind() == fend_core::SpanKind::Secondary)
+        .map(|s| s.string().to_string())
+        .collect();
+    assert_eq!(secondary, [" (approx. 907.18 g)"]);
+    assert_eq!(
+        evaluate("5 m", &mut Context::new())
+            .unwrap()
+            .get_main_result(),
+        "5 m"
+    );
+}
+
+#[test]
+fn significant_figures_tracking() {
+    let mut ctx = Context::new();
+    ctx.track_significant_figures();
+    for (input, expected) in [
+        ("2.50 * 2.00", "5.00"),
+        ("2.50 * 2", "5"),
+        ("12.3 + 1.234", "13.5"),
+        ("1.0 - 0.99", "0.0"),
+        ("1200 * 1.5", "1800"),
+        ("0.0012 * 3.000", "0.0036"),
+        ("9.96 * 1.00", "9.96"),
+        ("9.96 * 1.0", "10"),
+        ("3.14159 / 2.0", "1.6"),
+        ("2.50 m * 4.0", "10 m"),
+        ("12.0 inch to cm", "30.5 cm"),
+        ("sqrt(2.00)", "approx. 1.41"),
+        ("1/3", "0.3"),
+        ("2.50 to 4 dp", "2.5"),
+        ("0x10 * 2.0", "0x20"),
+        ("pi * 2.0", "approx. 6.3"),
+    ] {
+        assert_eq!(
+            evaluate(input, &mut ctx).unwrap().get_main_result(),
+            expected,
+            "{}",
+            input
+        );
+    }
+    assert_eq!(
+        evaluate("2.50 * 2.00", &mut Context::new())
+            .unwrap()
+            .get_main_result(),
+        "5"
+    );
+}
+
+#[test]
+fn symbolic_radicals() {
+    test_eval("sqrt 8", "2√2");
+    test_eval("sqrt 72", "6√2");
+    test_eval("sqrt 16", "4");
+    test_eval("sqrt (3/4)", "√3/2");
+    test_eval("sqrt 8 * sqrt 2", "4");
+    test_eval("sqrt 6 * sqrt 10", "2√15");
+    test_eval("sqrt 2 ^ 2", "2");
+    test_eval("8^(3/2)", "16√2");
+    test_eval("sqrt 12 + sqrt 3", "3√3");
+    test_eval("1 / sqrt 2", "√2/2");
+    test_eval("sqrt 2 + sqrt 3", "approx. 3.1462643695");
+    test_eval("tan(pi/3)", "√3");
+    test_eval("sin(45°)", "√2/2");
+    test_eval_simple("sqrt 8 to decimal", "approx. 2.8284271243");
+    test_eval_simple("sqrt 8 to 3 dp", "approx. 2.828");
+    test_eval_simple(
+        "sqrt 8 to float",
+        "approx. 2.8284271243028342723846435546875",
+    );
+}
+
+#[test]
+fn symbolic_pi() {
+    test_eval("pi/3", "π/3");
+    test_eval("7pi/3", "7π/3");
+    test_eval("-pi/2", "-π/2");
+    test_eval("pi * pi", "approx. 9.869604401");
+    test_eval("4pi + 1", "approx. 13.5663706143");
+    test_eval_simple("pi/3 to float", "approx. 1.047197551196597746");
+    test_eval_simple("pi to decimal", "approx. 3.1415926535");
+    test_eval_simple("pi to hex", "approx. 3.243f6a8885");
+    test_eval_simple("pi to 2 dp", "approx. 3.14");
+    test_eval_simple("255 to hex", "ff");
+}
+
+#[test]
+fn exact_trig_at_special_angles() {
+    test_eval("sin(30°)", "0.5");
+    test_eval("cos(45°)", "√2/2");
+    test_eval("cos(150°)", "-√3/2");
+    test_eval("tan(30°)", "√3/3");
+    test_eval("tan(120°)", "-√3");
+    test_eval("sin(390°)", "0.5");
+    test_eval("sin(15°)", "approx. 0.2588190451");
+    test_eval("asin(-√2/2)", "-π/4");
+    test_eval("acos(-1/2)", "2π/3");
+    test_eval("acos 1", "0");
+    test_eval("atan(√3/3)", "π/6");
+    test_eval("atan 2", "approx. 1.1071487177");
+    test_eval_simple("asin(1/2) to degrees", "30 degrees");
+    test_eval_simple("acos(√3/2) to degrees", "30 degrees");
+    test_eval_simple("arg(-1-i)", "-3π/4");
+}
+
+#[test]
+fn set_precision() {
+    test_eval_simple(
+        "set precision 30; pi",
+        "approx. 3.141592653589793238462643383279",
+    );
+    test_eval_simple(
+        "set precision 30; sqrt 2",
+        "approx. 1.414213562373095048801688724209",
+    );
+    test_eval_simple(
+        "set precision 30; sin 1",
+        "approx. 0.84147098480789650665250232163",
+    );
+    test_eval_simple(
+        "set precision 30; e",
+        "approx. 2.718281828459045235360287471352",
+    );
+    test_eval_simple(
+        "set precision 30; ln 10",
+        "approx. 2.302585092994045684017991454684",
+    );
+    test_eval_simple("set precision 30; cos(60°)", "approx. 0.5");
+    test_eval_simple("set precision 30; 1/4", "0.25");
+    test_eval_simple("set precision 3; pi", "approx. 3.141");
+    expect_error(
+        "set precision 1001",
+        Some("precision must be an integer between 0 and 1000"),
+    );
+    expect_error(
+        "set precision 1.5",
+        Some("precision must be an integer between 0 and 1000"),
+    );
+    expect_error("set foo 3", Some("unknown setting 'foo'"));
+}
+
+#[test]
+fn set_precision_from_context() {
+    let mut ctx = Context::new();
+    ctx.set_precision(20);
+    assert_eq!(
+        evaluate("pi", &mut ctx).unwrap().get_main_result(),
+        "approx. 3.14159265358979323846"
+    );
+}
+
+#[test]
+fn templates_with_placeholders() {
+    test_eval_simple("tpl = ?a * ?b / 100; tpl(a=250, b=12)", "30");
+    test_eval_simple("tpl = ?a * ?b / 100; tpl(b=12, a=250)", "30");
+    test_eval_simple("tpl = ?a * ?b / 100; tpl(250)(12)", "30");
+    test_eval_simple("tpl = ?a * ?b / 100; tpl", "\\a.\\b.((a*b)/100)");
+    test_eval_simple("tpl = ?a * ?b / 100; tpl(a=250)", "\\b.((a*b)/100)");
+    test_eval_simple("tpl = ?a * (?b + ?a); tpl(a=2, b=3)", "10");
+    test_eval_simple("tpl = ?x m + ?y cm; tpl(x=1, y=50) to cm", "150 cm");
+    test_eval_simple("(?a + 1) * 2", "\\a.(((a+1))*2)");
+    expect_error(
+        "tpl = ?a * ?b / 100; tpl(a=2, c=1)",
+        Some("unknown argument 'c'"),
+    );
+    expect_error(
+        "tpl = ?a * ?b / 100; tpl(b=2)",
+        Some("missing argument 'a'"),
+    );
+    expect_error(
+        "tpl = ?a * ?b / 100; tpl(a=1, a=2)",
+        Some("argument 'a' was given more than once"),
+    );
+}
+
+#[test]
+fn lambda_named_arguments() {
+    test_eval_simple("f = \\x.\\y.x - y; f(y=1, x=5)", "4");
+    test_eval_simple("f = x: y: x - y; f(x=5)(3)", "2");
+    test_eval_simple("(\\x.x^2)(x=3)", "9");
+}
+
+#[test]
+fn lambda_parameter_lists_with_defaults() {
+    test_eval_simple("f = \\(x, y=2). x^y; f(3)", "9");
+    test_eval_simple("f = \\(x, y=2). x^y; f(3, 3)", "27");
+    test_eval_simple("f = \\(x, y=2). x^y; f(x=3)", "9");
+    test_eval_simple("f = \\(x, y=2). x^y; f(3, y=4)", "81");
+    test_eval_simple("f = \\(x, y=2). x^y; f(y=3, x=2)", "8");
+    test_eval_simple("f = \\(a, b=a*2). a + b; f 5", "15");
+    test_eval_simple("f = λ(x). x * 2; f 4 m", "8 m");
+    test_eval_simple("\\(x, y=2). x^y", "\\(x, y=2).(x^y)");
+    expect_error("f = \\(x, y=2). x^y; f(y=4)", Some("missing argument 'x'"));
+    expect_error(
+        "f = \\(x, y=2). x^y; f(1, 2, 3)",
+        Some("too many arguments: expected at most 2"),
+    );
+    expect_error(
+        "f = \\(x, y=2). x^y; f(x=1, 2)",
+        Some("positional arguments must come before named arguments"),
+    );
+}
+
+#[test]
+fn custom_unit_definitions() {
+    test_eval_simple("unit furlong = 220 yards; 1 furlong to m", "201.168 m");
+    test_eval_simple(
+        "unit furlong furlongs = 220 yards; 3 furlongs",
+        "3 furlongs",
+    );
+    test_eval_simple(
+        "unit furlong furlongs = 220 yards; 8 furlongs to miles",
+        "1 mile",
+    );
+    test_eval_simple("unit long flop flops; 5 kiloflops / s", "5 kiloflops / s");
+    test_eval_simple("unit short Q = 3 m; 2 kQ to km", "6 km");
+    test_eval_simple("unit widget widgets; 4 widgets * 2", "8 widgets");
+    test_eval_simple("unit foot = 2 m; 1 foot to m", "2 m");
+    test_eval_simple("unit score = 20; 3 score to 1", "60");
+    expect_error("unit widget; 1 kilowidget", None);
+}
+
+#[test]
+fn count_units_and_rates() {
+    test_eval("3 times per week * 6 months", "approx. 78.266185453 times");
+    test_eval("45 min per item * 32 items", "1440 mins");
+    test_eval("12 occurrences / (4 days)", "3 occurrences / day");
+    test_eval_simple("takes 45 min per item; 32 items", "24 hours");
+    test_eval_simple("takes 45 min per item; 1 item", "45 minutes");
+    test_eval_simple("takes 20 s per item; 2 items", "40 seconds");
+    test_eval_simple("takes 30 min per item; 1000 items", "500 hours");
+    test_eval_simple("takes 45 min per item; 32 items to min", "1440 mins");
+    test_eval_simple("takes 45 min per item; 32 items to hours", "24 hours");
+    test_eval_simple("takes $3 per item; 12 items", "$36");
+    test_eval_simple("takes 45 min per item", "45 mins / item");
+    test_eval_simple("takes 45 min per item; 32 m", "32 m");
+}
+
+#[test]
+fn rates_only_apply_to_the_current_calculation() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        evaluate("takes 45 min per item; 2 items", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "1.5 hours"
+    );
+    assert_eq!(
+        evaluate("2 items", &mut ctx).unwrap().get_main_result(),
+        "2 items"
+    );
+}
+
+#[test]
+fn define_units_in_bulk() {
+    let mut ctx = Context::new();
+    ctx.define_units("# team units\nunit furlong furlongs = 220 yards\n\nunit long flop flops\n")
+        .unwrap();
+    assert_eq!(
+        evaluate("8 furlongs to miles", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "1 mile"
+    );
+    assert_eq!(
+        evaluate("3 kiloflops", &mut ctx).unwrap().get_main_result(),
+        "3 kiloflops"
+    );
+    assert_eq!(
+        ctx.define_units("unit a = 1\n2 + 2"),
+        Err("line 2: expected a unit definition like `unit furlong = 220 yards`".to_string())
+    );
+    assert_eq!(
+        ctx.define_units("unit b = 2 foo"),
+        Err("line 1: unknown identifier 'foo'".to_string())
+    );
+}
+
+#[test]
+fn define_variables_in_bulk() {
+    let mut ctx = Context::new();
+    ctx.define_variables(&[("f2c", "x => (x - 32) * 5/9"), ("boiling_point", "f2c 212")])
+        .unwrap();
+    assert_eq!(
+        evaluate("f2c 50", &mut ctx).unwrap().get_main_result(),
+        "10"
+    );
+    assert_eq!(
+        evaluate("boiling_point", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "100"
+    );
+    assert_eq!(
+        ctx.define_variables(&[("a", "1"), ("b", "2 foo")]),
+        Err("b: unknown identifier 'foo'".to_string())
+    );
+    assert_eq!(evaluate("a", &mut ctx).unwrap().get_main_result(), "1");
+    for name in ["", "1x", "to", "a b", "c+d"] {
+        assert_eq!(
+            ctx.define_variables(&[(name, "1")]),
+            Err(format!("{}: not a valid variable name", name))
+        );
+    }
+}
+
+#[test]
+fn exchange_rate_source() {
+    struct TestRates;
+    impl fend_core::ExchangeRateSource for TestRates {
+        fn units_per_euro(&self, currency: &str) -> Option<f64> {
+            match currency {
+                "USD" => Some(1.25),
+                "GBP" => Some(0.8),
+                "XTS" => Some(2.0),
+                "JPY" => Some(f64::NAN),
+                _ => None,
+            }
+        }
+    }
+    let mut ctx = Context::new();
+    ctx.set_exchange_rate_source(TestRates);
+    let mut eval = |input: &str| {
+        evaluate(input, &mut ctx)
+            .unwrap()
+            .get_main_result()
+            .to_string()
+    };
+    assert_eq!(eval("100 USD to EUR"), "80 EUR");
+    assert_eq!(eval("100 usd to GBP"), "64 GBP");
+    assert_eq!(eval("$5 to EUR"), "4 EUR");
+    assert_eq!(eval("3 XTS to USD"), "1.875 USD");
+    // invalid or missing rates fall back to the built-in rates
+    assert_eq!(eval("132.98 JPY to EUR"), "1 EUR");
+    assert_eq!(eval("1 BTU to J"), "1055.05585 J");
+}
+
+#[test]
+fn percent_of_total_and_normalize() {
+    test_eval_simple("percentoftotal [1, 3]", "[25%, 75%]");
+    test_eval_simple("percent_of_total(2, 2, 4)", "[25%, 25%, 50%]");
+    test_eval_simple("percentoftotal [1 m, 100 cm, 2 m]", "[25%, 25%, 50%]");
+    test_eval_simple("percentoftotal [$30, $90]", "[25%, 75%]");
+    test_eval_simple("normalize [2, 6]", "[0.25, 0.75]");
+    test_eval_simple("normalize [30 cm, 0.7 m]", "[0.3, 0.7]");
+    expect_error("normalize []", Some("expected at least one argument"));
+    expect_error("normalize [1, -1]", Some("division by zero"));
+    expect_error(
+        "percentoftotal [1 m, 2 s]",
+        Some("cannot convert from s to m: time (second) and length (meter) are incompatible"),
+    );
+}
+
+#[test]
+fn disabled_language_features() {
+    let mut ctx = Context::new();
+    for feature in [
+        fend_core::LanguageFeature::Lambdas,
+        fend_core::LanguageFeature::Assignments,
+        fend_core::LanguageFeature::Strings,
+        fend_core::LanguageFeature::Dates,
+    ] {
+        ctx.disable_language_feature(feature);
+    }
+    let mut eval = |input: &str| match evaluate(input, &mut ctx) {
+        Ok(res) => res.get_main_result().to_string(),
+        Err(msg) => msg,
+    };
+    // built-in functions and units still work
+    assert_eq!(eval("sqrt(16 m^2) to cm"), "400 cm");
+    assert_eq!(eval("5 feet to m"), "1.524 m");
+    assert_eq!(eval("\\x.x^2"), "lambdas are disabled");
+    assert_eq!(eval("(x: x + 1) 2"), "lambdas are disabled");
+    assert_eq!(eval("?a * 2"), "lambdas are disabled");
+    assert_eq!(eval("a = 5"), "assignments are disabled");
+    assert_eq!(eval("unit furlong = 220 yards"), "assignments are disabled");
+    assert_eq!(eval("\"hello\""), "strings are disabled");
+    assert_eq!(eval("[1, 'a']"), "strings are disabled");
+    assert_eq!(eval("today"), "dates are disabled");
+    assert_eq!(eval("5 to date"), "dates are disabled");
+    assert_eq!(eval("14:30 + 1h"), "dates are disabled");
+    assert_eq!(eval("'14:30' to time"), "dates are disabled");
+    assert_eq!(eval("next friday"), "dates are disabled");
+    assert_eq!(eval("date(2024, 5, 1)"), "dates are disabled");
+    assert_eq!(
+        eval("between('2024-01-01', '2024-05-01')"),
+        "dates are disabled"
+    );
+}
+
+#[test]
+fn cryptocurrency_units() {
+    test_eval_simple("0.05 BTC to satoshis", "5000000 satoshis");
+    test_eval_simple("20 gwei to ETH", "0.00000002 ETH");
+    test_eval_simple("1 ether to wei", "1000000000000000000 wei");
+    test_eval_simple("5 dogecoins to DOGE", "5 DOGE");
+    expect_error("1 BTC to USD", Some("no exchange rate available for BTC"));
+    expect_error("100 BTC to USD", Some("no exchange rate available for BTC"));
+    expect_error(
+        "5 EUR to satoshis",
+        Some("no exchange rate available for BTC"),
+    );
+    expect_error(
+        "1 ETH/month to USD/year",
+        Some("no exchange rate available for ETH"),
+    );
+    expect_error("1 BTC to ETH", Some("no exchange rate available for BTC"));
+    expect_error(
+        "1 BTC to kg",
+        Some("cannot convert from BTC to kg: BTC (BTC) and mass (kilogram) are incompatible"),
+    );
+}
+
+#[test]
+fn cryptocurrency_exchange_rates() {
+    struct TestRates;
+    impl fend_core::ExchangeRateSource for TestRates {
+        fn units_per_euro(&self, currency: &str) -> Option<f64> {
+            match currency {
+                "USD" => Some(1.25),
+                "BTC" => Some(0.00002),
+                "ETH" => Some(0.0005),
+                _ => None,
+            }
+        }
+    }
+    let mut ctx = Context::new();
+    ctx.set_exchange_rate_source(TestRates);
+    let mut eval = |input: &str| {
+        evaluate(input, &mut ctx)
+            .unwrap()
+            .get_main_result()
+            .to_string()
+    };
+    assert_eq!(eval("0.05 BTC to USD"), "3125 USD");
+    assert_eq!(eval("100000 satoshis to EUR"), "50 EUR");
+    assert_eq!(eval("1 eth to EUR"), "2000 EUR");
+    assert_eq!(eval("1e9 gwei to USD"), "2500 USD");
+}
+
+#[test]
+fn evaluation_limits() {
+    use std::time::Duration;
+
+    let mut ctx = Context::new();
+    ctx.set_limits(fend_core::Limits {
+        max_recursion: Some(20),
+        max_memory: Some(1000),
+        max_output_len: Some(100),
+        ..fend_core::Limits::default()
+    });
+    let mut eval = |input: &str| match evaluate(input, &mut ctx) {
+        Ok(res) => res.get_main_result().to_string(),
+        Err(e) => e,
+    };
+    assert_eq!(eval("(((1 + 2)))"), "3");
+    assert_eq!(eval("30!"), "265252859812191058636308480000000");
+    assert_eq!(
+        eval(&format!("{}1{}", "(".repeat(30), ")".repeat(30))),
+        "maximum recursion depth exceeded"
+    );
+    assert_eq!(
+        eval("(\\x.x x) (\\x.x x)"),
+        "maximum recursion depth exceeded"
+    );
+    assert_eq!(eval("2^100000"), "memory limit exceeded");
+    assert_eq!(eval("1e1000000"), "memory limit exceeded");
+    assert_eq!(eval("1e1000000 + 1 +"), "memory limit exceeded");
+    assert_eq!(eval("100!"), "the result is too long");
+    assert_eq!(eval("x = 1..3000000; 1"), "memory limit exceeded");
+    assert_eq!(eval("[1, 2]"), "[1, 2]");
+    assert_eq!(eval("1 + 1"), "2");
+    assert_eq!(ctx.get_error_kind(), None);
+    let _ = evaluate("1e1000000", &mut ctx);
+    assert_eq!(ctx.get_error_kind(), Some(fend_core::ErrorKind::Evaluation));
+    assert_eq!(fend_core::locate_syntax_error("1e1000000 +* 2", &ctx), None);
+
+    let mut ctx = Context::new();
+    ctx.set_limits(fend_core::Limits {
+        max_time: Some(Duration::ZERO),
+        ..fend_core::Limits::default()
+    });
+    assert_eq!(
+        evaluate("3^1000000", &mut ctx).unwrap_err(),
+        "time limit exceeded"
+    );
+    ctx.set_limits(fend_core::Limits {
+        max_time: Some(Duration::MAX),
+        ..fend_core::Limits::default()
+    });
+    assert_eq!(evaluate("1 + 1", &mut ctx).unwrap().get_main_result(), "2");
+}
+
+#[test]
+fn to_human() {
+    test_eval_simple("123456789 bytes to human", "approx. 117.7 MiB");
+    test_eval_simple("0.000013 s to human", "13 µs");
+    test_eval_simple("100000 s to human", "approx. 1.157 days");
+    test_eval_simple("-2500 m to human", "-2.5 km");
+    test_eval_simple("3.6e9 J to human", "3.6 GJ");
+    test_eval_simple("0 s to human", "0 s");
+    test_eval_simple("3 m/s to human", "3 m / s");
+    test_eval_simple("5 to human", "5");
+}
+
+#[test]
+fn locate_syntax_errors() {
+    let ctx = Context::new();
+    let locate = |input: &str| {
+        fend_core::locate_syntax_error(input, &ctx).map(|range| input[range].to_string())
+    };
+    assert_eq!(locate("5 @ 3"), Some("@".to_string()));
+    assert_eq!(locate("1 + \"abc"), Some("\"abc".to_string()));
+    assert_eq!(locate("2 * * 3"), Some("*".to_string()));
+    assert_eq!(locate("1 to to"), Some("to".to_string()));
+    assert_eq!(locate("12.3.4"), Some(".4".to_string()));
+    assert_eq!(locate("a = "), Some("".to_string()));
+    assert_eq!(fend_core::locate_syntax_error("a = ", &ctx), Some(4..4));
+    assert_eq!(locate("1 + 2) * 3"), None);
+    assert_eq!(locate("unknown_ident + 1"), None);
+}
+
+#[test]
+fn incomplete_input() {
+    let ctx = Context::new();
+    assert!(fend_core::is_incomplete("2 *", &ctx));
+    assert!(fend_core::is_incomplete("1 + 2 +  ", &ctx));
+    assert!(fend_core::is_incomplete("5 km to", &ctx));
+    assert!(fend_core::is_incomplete("(1 + 2", &ctx));
+    assert!(fend_core::is_incomplete("[1, 2, (3", &ctx));
+    assert!(fend_core::is_incomplete("a =", &ctx));
+    assert!(fend_core::is_incomplete("f = \\x.", &ctx));
+    assert!(!fend_core::is_incomplete("", &ctx));
+    assert!(!fend_core::is_incomplete("(1 + 2) * 3", &ctx));
+    assert!(!fend_core::is_incomplete("1 + 2) * 3", &ctx));
+    assert!(!fend_core::is_incomplete("5!", &ctx));
+    assert!(!fend_core::is_incomplete("2 * \"abc", &ctx));
+}
+
+#[test]
+fn debug_parse_shows_tokens_and_tree() {
+    let ctx = Context::new();
+    assert_eq!(
+        fend_core::debug_parse("2x + \\y.y", &ctx).unwrap(),
+        "tokens:
+  Num 2
+  Ident x
+  Symbol +
+  Symbol \\
+  Ident y
+  Symbol .
+  Ident y
+expression:
+  Bop Plus
+    ApplyMul
+      Literal 2
+      Ident x
+    Fn y
+      Ident y
+"
+    );
+    assert_eq!(
+        fend_core::debug_parse("1 +* 2", &ctx).unwrap(),
+        "tokens:
+  Num 1
+  Symbol +
+  Symbol *
+  Num 2
+expression:
+  error: unexpected input found
+"
+    );
+    assert_eq!(
+        fend_core::debug_parse("\"abc", &ctx),
+        Err("unterminated string literal".to_string())
+    );
+}
+
+#[test]
+fn error_kinds() {
+    use fend_core::ErrorKind;
+
+    fn kind(input: &str, ctx: &mut Context) -> Option<ErrorKind> {
+        let _ = evaluate(input, ctx);
+        ctx.get_error_kind()
+    }
+
+    let mut ctx = Context::new();
+    assert_eq!(kind("1 + 1", &mut ctx), None);
+    assert_eq!(kind("2 * * 3", &mut ctx), Some(ErrorKind::Syntax));
+    assert_eq!(kind("1 + \"abc", &mut ctx), Some(ErrorKind::Syntax));
+    assert_eq!(kind("1/0", &mut ctx), Some(ErrorKind::Evaluation));
+    assert_eq!(kind("5 kg to m", &mut ctx), Some(ErrorKind::Evaluation));
+    assert_eq!(kind("unknown_ident", &mut ctx), Some(ErrorKind::Evaluation));
+    assert_eq!(kind("", &mut ctx), None);
+
+    ctx.set_limits(fend_core::Limits {
+        max_time: Some(std::time::Duration::from_millis(1)),
+        ..fend_core::Limits::default()
+    });
+    assert_eq!(kind("10^1000000!", &mut ctx), Some(ErrorKind::Interrupted));
+}
+
+#[test]
+fn did_you_mean_corrections() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        evaluate("5 metr + 2 metr to feet", &mut ctx).unwrap_err(),
+        "unknown identifier 'metr', did you mean 'meter'?"
+    );
+    let correction = ctx.get_correction().unwrap();
+    assert_eq!(correction.original(), "metr");
+    assert_eq!(correction.replacement(), "meter");
+    assert_eq!(correction.corrected_input(), "5 meter + 2 meter to feet");
+    let corrected_input = correction.corrected_input().to_string();
+    assert!(evaluate(&corrected_input, &mut ctx).is_ok());
+    assert_eq!(ctx.get_correction(), None);
+
+    assert_eq!(
+        evaluate("sqtr(16)", &mut ctx).unwrap_err(),
+        "unknown identifier 'sqtr', did you mean 'sqrt'?"
+    );
+    assert_eq!(ctx.get_correction().unwrap().corrected_input(), "sqrt(16)");
+
+    evaluate("distance = 5", &mut ctx).unwrap();
+    assert_eq!(
+        evaluate("distanse * 2", &mut ctx).unwrap_err(),
+        "unknown identifier 'distanse', did you mean 'distance'?"
+    );
+
+    assert_eq!(
+        evaluate("xyz", &mut ctx).unwrap_err(),
+        "unknown identifier 'xyz'"
+    );
+    assert_eq!(ctx.get_correction(), None);
+}
+
+#[test]
+fn mixed_unit_conversions() {
+    test_eval_simple("1.87 m to ft+in", "approx. 6 ft 1.6 inches");
+    test_eval_simple("-1.87 m to ft+inch", "approx. -6 ft 1.6 inches");
+    test_eval_simple("70 inches to ft + in", "5 ft 10 inches");
+    test_eval_simple("5000 s to hr+min+s", "1 hr 23 mins 20 s");
+    test_eval_simple("3600 s to hr+min+s", "1 hr");
+    test_eval_simple("0 m to ft+in", "0 inches");
+    test_eval_simple("1 mile to km+m", "1 km 609.344 m");
+    test_eval_simple("2 ft to ft + 1 inch", "approx. 2.0833333333 ft");
+    expect_error(
+        "1 kg to ft+in",
+        Some(
+            "cannot convert from kg to inches: mass (kilogram) and length (meter) are incompatible",
+        ),
+    );
+    let out_of_order =
+        Some("mixed units must go from largest to smallest without repeating, e.g. `ft+in`");
+    expect_error("1 m to ft+ft", out_of_order);
+    expect_error("1 m to inch+ft", out_of_order);
+    expect_error("1 h to hr+min+hour", out_of_order);
+}
+
+#[test]
+fn convert_to_simplest_unit() {
+    test_eval_simple("5 kg * 3 m/s^2 to simplest", "15 N");
+    test_eval_simple("2 V * 3 A to simplest", "6 W");
+    test_eval_simple("1 kg m^2 / s^2 to simplest", "1 J");
+    test_eval_simple("(5 kg * 3 m/s^2) / (2 m^2) to simplest", "7.5 Pa");
+    test_eval_simple("5 m * 3 m to simplest", "15 m^2");
+    test_eval_simple("5 kJ to simplest", "5 kJ");
+    test_eval_simple("3 to simplest", "3");
+}
+
+#[test]
+fn incompatible_units_show_dimensions() {
+    expect_error(
+        "1 m to J",
+        Some(
+            "cannot convert from m to J: length (meter) and energy (kilogram meter^2 / second^2) are incompatible",
+        ),
+    );
+    expect_error(
+        "1 N to W",
+        Some(
+            "cannot convert from N to W: force (kilogram meter / second^2) and power (kilogram meter^2 / second^3) are incompatible",
+        ),
+    );
+    expect_error(
+        "1 kg + 1 s",
+        Some("cannot convert from s to kg: time (second) and mass (kilogram) are incompatible"),
+    );
+    expect_error(
+        "1 m to kg^0.5",
+        Some("cannot convert from m to kg^0.5: units 'meter' and 'kilogram^0.5' are incompatible"),
+    );
+}
+
+#[test]
+fn convert_to_si_and_imperial() {
+    test_eval_simple("60 mph to si", "26.8224 m / s");
+    test_eval_simple("60 mph to SI", "26.8224 m / s");
+    test_eval_simple("60 mph to imperial", "88 ft / s");
+    test_eval_simple("1 kWh to si", "3600000 kg m^2 / s^2");
+    test_eval_simple("1 acre to si", "4046.8564224 m^2");
+    test_eval_simple("68 °F to si", "293.15 K");
+    test_eval_simple("20 °C to imperial", "68 °F");
+    test_eval_simple("1 V to si", "1 kg m^2 s^-3 A^-1");
+    test_eval_simple("5 to si", "5");
+}
+
+#[test]
+fn temperature_differences() {
+    test_eval_simple("70 °F + 10 °F", "80 °F");
+    test_eval_simple("20 °C - 68 °F", "0 deltaC");
+    test_eval_simple("30 °C - 50 °F", "20 deltaC");
+    test_eval_simple("100 °F - 0 °C", "68 deltaF");
+    test_eval_simple("20 °C - 10 °C to deltaF", "18 deltaF");
+    test_eval_simple("20 °C - 5 K", "15 °C");
+    test_eval_simple("70 °F - 10 deltaF", "60 °F");
+    test_eval_simple("20 °C + 9 deltaF", "25 °C");
+    test_eval_simple("10 deltaC to K", "10 K");
+}
+
+#[test]
+fn reciprocal_unit_conversions() {
+    test_eval_simple("30 mpg to L/100km", "approx. 7.8404861111 L/100km");
+    test_eval_simple("20 km/L to L/100km", "5 L/100km");
+    test_eval_simple("7.8 L/(100 km) to mpg", "approx. 30.1557158119 mpg");
+    test_eval_simple("2 Hz to s", "0.5 s");
+    expect_error(
+        "5 m to L/100km",
+        Some(
+            "cannot convert from m to L/100km: length (meter) and area (meter^2) are incompatible",
+        ),
+    );
+    expect_error("0 mpg to L/100km", Some("division by zero"));
+}
+
+#[test]
+fn adding_power_levels() {
+    // 100 mW + 100 mW = 200 mW
+    test_eval_simple("20 dBm + 20 dBm", "approx. 23.0102999566 dBm");
+    test_eval_simple("20 dBW + 20 dBm", "approx. 20.0043407747 dBW");
+    test_eval_simple("0 dBW + 30 dBm", "approx. 3.0102999566 dBW");
+    test_eval_simple("23 dBm - 20 dBm", "approx. 3 dB");
+    test_eval_simple("30 dBm - 0 dBW", "approx. 0 dB");
+}
+
+#[test]
+fn logarithmic_units() {
+    test_eval("20 dBm to mW", "approx. 100 mW");
+    test_eval("100 mW to dBm", "approx. 20 dBm");
+    test_eval("1 kW to dBW", "approx. 30 dBW");
+    test_eval("30 dBm to dBW", "approx. 0 dBW");
+    test_eval("-3 dBm to mW", "approx. 0.5011872336 mW");
+    test_eval("10 dBm to dBm", "approx. 10 dBm");
+    test_eval("100 to dB", "approx. 20 dB");
+    test_eval("20 dB to unitless", "approx. 100");
+    test_eval("20 dBm + 3 dB", "approx. 23 dBm");
+    test_eval("20 dBm - 3 dB", "approx. 17 dBm");
+    expect_error("0 W to dBm", Some("0 must lie in the interval (0, ∞)"));
+    expect_error(
+        "1 m to dBm",
+        Some("cannot convert from m to mW: length (meter) and power (kilogram meter^2 / second^3) are incompatible"),
+    );
+}
+
+#[test]
+fn degrees_as_default_angle_unit() {
+    let mut ctx = Context::new();
+    ctx.use_degrees();
+    for (input, expected) in [
+        ("sin 30", "0.5"),
+        ("cos 60", "0.5"),
+        ("tan 45", "1"),
+        ("sin (pi/6 rad)", "0.5 rad"),
+        ("sin(30°)", "0.5"),
+        ("asin 0.5", "30°"),
+        ("atan 1", "45°"),
+        ("sin (asin 0.5)", "0.5"),
+    ] {
+        assert_eq!(
+            evaluate(input, &mut ctx).unwrap().get_main_result(),
+            expected,
+            "{}",
+            input
+        );
+    }
+    test_eval("sin 30", "approx. -0.988031624");
+}
+
+#[test]
+fn degrees_minutes_seconds() {
+    test_eval("48°51'24\"", "approx. 48.8566666666°");
+    test_eval("48° 51' 24\"", "approx. 48.8566666666°");
+    test_eval("48°51′24″", "approx. 48.8566666666°");
+    test_eval("10°30' + 5°45'", "16.25°");
+    test_eval("5' 3\"", "5.25'");
+    test_eval_simple("48°51'24\" to dms", "48° 51' 24\"");
+    test_eval_simple("48.8567 to dms", "48° 51' 24.12\"");
+    test_eval_simple("-2.3522° to dms", "-2° 21' 7.92\"");
+    test_eval("pi/4 radians to dms", "45°");
+    test_eval_simple("1 radian to dms", "approx. 57° 17' 44.8\"");
+    test_eval("sin(30°0'0\")", "0.5");
+}
+
+#[test]
+fn data_transfer_rates() {
+    test_eval("5 MB/s to Mbit/s", "40 Mbit / s");
+    test_eval("3 Gbit to GB", "0.375 GB");
+    test_eval(
+        "700 MB / (20 Mbit/s) to minutes",
+        "approx. 4.6666666666 minutes",
+    );
+    test_eval_simple("download_time(700 MB, 20 Mbit/s)", "approx. 4.666 mins");
+    test_eval_simple("download_time(4.7 GB, 1 MB/s)", "approx. 1.305 h");
+    test_eval_simple("download_time(1 kB, 1 Mbps)", "8 ms");
+    expect_error(
+        "download_time(1 GB)",
+        Some("download_time expects 2 arguments, but 1 were given"),
+    );
+    expect_error(
+        "download_time(1 m, 1 Mbps)",
+        Some("cannot convert from m / Mbps to s: length * time / information (meter second / bit) and time (second) are incompatible"),
+    );
+}
+
+#[test]
+fn units_of_dimension() {
+    test_eval_simple("units of power", "watt, W, horsepower, hp, mbh");
+    test_eval_simple(
+        "units of information",
+        "bit, byte, b, B, kbit, Mbit, Gbit, Tbit, octet",
+    );
+    test_eval_simple(
+        "units of (km/h)",
+        "light, mph, kph, kmh, fpm, fps, ipy, knot",
+    );
+    expect_error(
+        "units of (1 / byte)",
+        Some("there are no known units of 1 / information"),
+    );
+    expect_error(
+        "units of (m^5)",
+        Some("there are no known units of length^5"),
+    );
+
+    let mut ctx = Context::new();
+    ctx.define_units("unit long flop flops").unwrap();
+    assert_eq!(
+        fend_core::get_units_of_dimension("power", &mut ctx).unwrap(),
+        ["watt", "W", "horsepower", "hp", "mbh"]
+    );
+    assert_eq!(
+        fend_core::get_units_of_dimension("flop", &mut ctx).unwrap(),
+        ["flop"]
+    );
+    assert!(fend_core::get_units_of_dimension("mass", &mut ctx)
+        .unwrap()
+        .contains(&"pound".to_string()));
+}
+
+#[test]
+fn custom_prefix_definitions() {
+    test_eval_simple("prefix lakh = 1e5; 5 lakh", "500000");
+    test_eval_simple(
+        "prefix lakh = 1e5; unit long rupee rupees; 3 lakhrupees to rupees",
+        "300000 rupees",
+    );
+    test_eval_simple(
+        "prefix lakh = 1e5; prefix crore = 100 lakh; unit long rupee rupees; 2 crorerupees to lakhrupees",
+        "200 lakhrupees",
+    );
+    test_eval_simple("prefix lakh = 1e5; 2 lakhmeters to km", "200 km");
+    test_eval_simple(
+        "prefix short L = 1e5; unit short Rs; 3 LRs to Rs",
+        "300000 Rs",
+    );
+    test_eval_simple("prefix = 5; prefix * 2", "10");
+    expect_error("prefix lakh = 5 m", Some("expected a unitless number"));
+    // long prefixes only apply to units defined as `long`
+    expect_error("prefix lakh = 1e5; unit widget; 1 lakhwidget", None);
+
+    let mut ctx = Context::new();
+    ctx.define_units("prefix myria = 1e4\nunit long flop flops\n")
+        .unwrap();
+    assert_eq!(
+        evaluate("2 myriaflops to flops", &mut ctx)
+            .unwrap()
+            .get_main_result(),
+        "20000 flops"
+    );
+}
+
+#[test]
+fn unit_exponent_styles() {
+    let mut ctx = Context::new();
+    let eval =
+        |input, ctx: &mut Context| evaluate(input, ctx).unwrap().get_main_result().to_string();
+    assert_eq!(eval("9.8 m/s^2", &mut ctx), "9.8 m / s^2");
+    ctx.set_unit_exponent_style(fend_core::UnitExponentStyle::NegativeExponents);
+    assert_eq!(eval("9.8 m/s^2", &mut ctx), "9.8 m s^-2");
+    assert_eq!(eval("5 km/h", &mut ctx), "5 km h^-1");
+    assert_eq!(eval("3 m^2", &mut ctx), "3 m^2");
+    ctx.set_unit_exponent_style(fend_core::UnitExponentStyle::Superscript);
+    assert_eq!(eval("9.8 m/s^2", &mut ctx), "9.8 m / s²");
+    assert_eq!(eval("5 kg / m / s^2", &mut ctx), "5 kg m⁻¹ s⁻²");
+    assert_eq!(eval("4 m^(1/2)", &mut ctx), "4 m^0.5");
+    assert_eq!(eval("10 m^10", &mut ctx), "10 m¹⁰");
+    // errors aren't affected
+    assert_eq!(
+        evaluate("1 m^2 to s", &mut ctx),
+        Err(
+            "cannot convert from m^2 to s: area (meter^2) and time (second) are incompatible"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn molar_mass_of_chemical_formulas() {
+    test_eval("molar_mass(\"C6H12O6\")", "180.156 g / mol");
+    test_eval("molar_mass(\"H2O\")", "18.015 g / mol");
+    test_eval("molar_mass(\"Ca(OH)2\")", "74.092 g / mol");
+    test_eval("molar_mass(\"K4[Fe(CN)6]\")", "368.345 g / mol");
+    test_eval("molar_mass(\"CuSO4·5H2O\")", "249.677 g / mol");
+    test_eval(
+        "10 g / molar_mass(\"H2O\") to mol",
+        "approx. 0.555092978 mol",
+    );
+    expect_error("molar_mass(\"Xx2\")", Some("unknown chemical element 'Xx'"));
+    expect_error(
+        "molar_mass(\"H2O)\")",
+        Some("invalid chemical formula 'H2O)'"),
+    );
+    expect_error("molar_mass(\"\")", Some("invalid chemical formula ''"));
+    expect_error("molar_mass(18)", Some("expected a string"));
+}
+
+#[test]
+fn subtract_dates() {
+    test_eval("'2025-01-01' - '2024-03-15'", "292 days");
+    test_eval(
+        "('2024-03-15' to date) - ('2025-01-01' to date)",
+        "-292 days",
+    );
+    test_eval("('2025-01-01' to date) - '2024-12-25'", "7 days");
+    test_eval_simple(
+        "('2025-01-01' to date) - 10 days",
+        "Sunday, 22 December 2024",
+    );
+    test_eval(
+        "('2025-01-01' - '2024-03-15') to weeks",
+        "approx. 41.7142857142 weeks",
+    );
+    expect_error(
+        "'abc' - '2024-03-15'",
+        Some("failed to convert 'abc' to a date"),
+    );
+    expect_error("'2025-01-01' - 5", Some("invalid operands for subtraction"));
+    expect_error("('2025-01-01' to date) - 5", Some("expected a number"));
+}
+
+#[test]
+fn subtract_dates_with_dates_disabled() {
+    let mut ctx = Context::new();
+    ctx.disable_language_feature(fend_core::LanguageFeature::Dates);
+    assert_eq!(
+        evaluate("'2025-01-01' - '2024-03-15'", &mut ctx).unwrap_err(),
+        "dates are disabled"
+    );
+}
+
+#[test]
+fn durations() {
+    test_eval_simple(
+        "2 weeks 3 days 4 hours to duration",
+        "2 weeks 3 days 4 hours",
+    );
+    test_eval_simple(
+        "100000.5 s to duration",
+        "1 day 3 hours 46 minutes 40.5 seconds",
+    );
+    test_eval_simple("0 s to duration", "0 seconds");
+    test_eval("(2 weeks 3 days 4 hours to duration) to hours", "412 hours");
+    test_eval_simple("d = 90 min to duration; d + 45 min", "2 hours 15 minutes");
+    test_eval_simple("d = 90 min to duration; 3 d", "4 hours 30 minutes");
+    test_eval_simple("d = 90 min to duration; d / 2", "45 minutes");
+    test_eval_simple("-(3 weeks to duration)", "-3 weeks");
+    test_eval("d = 90 min to duration; d / (30 min to duration)", "3");
+    test_eval("(1 day to duration) == (24 hours to duration)", "true");
+    expect_error(
+        "5 m to duration",
+        Some("cannot convert from m to seconds: length (meter) and time (second) are incompatible"),
+    );
+    expect_error(
+        "(1 hour to duration) * (1 hour to duration)",
+        Some("this operation is not supported for durations"),
+    );
+}
+
+#[test]
+fn durations_with_dates() {
+    test_eval_simple(
+        "('2025-01-01' to date) + (3 weeks to duration)",
+        "Wednesday, 22 January 2025",
+    );
+    test_eval_simple(
+        "(3 weeks to duration) + ('2025-01-01' to date)",
+        "Wednesday, 22 January 2025",
+    );
+    test_eval_simple(
+        "('2025-01-01' to date) - (3 weeks to duration)",
+        "Wednesday, 11 December 2024",
+    );
+    expect_error(
+        "(3 weeks to duration) - ('2025-01-01' to date)",
+        Some("this operation is not supported for durations"),
+    );
+}
+
+#[test]
+fn times_of_day() {
+    test_eval_simple("14:30 + 2h15m", "16:45");
+    test_eval_simple("14:30 + 2h 15m", "16:45");
+    test_eval_simple("23:30 + 90 min", "01:00");
+    test_eval_simple("9:05 - 30 min", "08:35");
+    test_eval_simple("'14:30:15' to time", "14:30:15");
+    test_eval_simple("17:00 - 9:30", "7 hours 30 minutes");
+    test_eval_simple("(2 hours to duration) + 14:30", "16:30");
+    test_eval("hour of 14:30", "14");
+    test_eval("14:30 == 14:30", "true");
+    expect_error(
+        "'25:00' to time",
+        Some("failed to convert '25:00' to a time"),
+    );
+}
+
+#[test]
+fn compound_durations() {
+    test_eval("1h30m to min", "90 mins");
+    test_eval("5m30s", "5.5 mins");
+    test_eval("3 min 2 m", "5 mins");
+    test_eval("5 m", "5 m");
+}
+
+#[test]
+fn dates_with_times() {
+    test_eval_simple(
+        "('2025-01-01' to date) + 36 hours",
+        "Thursday, 2 January 2025 12:00",
+    );
+    test_eval_simple(
+        "('2025-01-01' to date) + 48 hours",
+        "Friday, 3 January 2025",
+    );
+    test_eval_simple(
+        "('2025-01-01' to date) + 14:30",
+        "Wednesday, 1 January 2025 14:30",
+    );
+    test_eval_simple(
+        "('2025-01-01 22:00' to datetime) + 5h30m",
+        "Thursday, 2 January 2025 03:30",
+    );
+    test_eval_simple(
+        "('2025-01-03 12:00' to datetime) - ('2025-01-01' to date)",
+        "2 days 12 hours",
+    );
+    test_eval_simple(
+        "('2025-01-01T08:00' to datetime) - ('2025-01-01 17:45' to datetime)",
+        "-9 hours 45 minutes",
+    );
+    test_eval_simple("('2025-01-01 22:00' to datetime) to time", "22:00");
+    test_eval_simple(
+        "('2025-01-01 22:00' to datetime) to date",
+        "Wednesday, 1 January 2025",
+    );
+    expect_error(
+        "14:30 - ('2025-01-01' to date)",
+        Some("invalid operands for subtraction"),
+    );
+    expect_error(
+        "'2025-01-01' to datetime",
+        Some("failed to convert '2025-01-01' to a date and time"),
+    );
+}
+
+#[test]
+fn time_zones() {
+    test_eval_simple("3pm", "15:00");
+    test_eval_simple("12am", "00:00");
+    test_eval_simple("3pm EST", "15:00 EST");
+    test_eval_simple("3pm EST to PST", "12:00 PST");
+    test_eval_simple("12pm UTC in IST", "17:30 IST");
+    test_eval_simple("9:30am JST to Europe/Paris", "01:30 CET");
+    test_eval_simple(
+        "('2025-07-01 12:00' to datetime) UTC to America/New_York",
+        "Tuesday, 1 July 2025 08:00 EDT",
+    );
+    test_eval_simple(
+        "('2025-01-15 12:00' to datetime) in Europe/London",
+        "Wednesday, 15 January 2025 12:00 GMT",
+    );
+    test_eval_simple(
+        "('2025-07-15 12:00' to datetime) PDT to Asia/Tokyo",
+        "Wednesday, 16 July 2025 04:00 JST",
+    );
+    test_eval_simple(
+        "('2025-03-09 06:59' to datetime) UTC to America/New_York",
+        "Sunday, 9 March 2025 01:59 EST",
+    );
+    test_eval_simple(
+        "('2025-03-09 07:00' to datetime) UTC to America/New_York",
+        "Sunday, 9 March 2025 03:00 EDT",
+    );
+    test_eval_simple(
+        "('2025-12-25 09:00' to datetime) in Australia/Sydney",
+        "Thursday, 25 December 2025 20:00 AEDT",
+    );
+    expect_error("5 to EST", Some("expected a date or time"));
+    expect_error("now in Asia/Tokyo", Some("unable to get the current date"));
+}
+
+#[test]
+fn iso_8601_dates() {
+    test_eval_simple(
+        "'2024-05-01T13:00:00Z' to datetime",
+        "Wednesday, 1 May 2024 13:00 UTC",
+    );
+    test_eval_simple(
+        "'2024-05-01T13:00:00+09:00' to datetime",
+        "Wednesday, 1 May 2024 13:00 UTC+09:00",
+    );
+    test_eval_simple(
+        "('2024-05-01T13:00:00+09:00' to datetime) to UTC",
+        "Wednesday, 1 May 2024 04:00 UTC",
+    );
+    test_eval_simple("('2024-05-01' to date) to iso", "2024-05-01");
+    test_eval_simple("('-44-03-15' to date) to iso", "-0044-03-15");
+    test_eval_simple(
+        "('2024-05-01 13:00' to datetime) to iso",
+        "2024-05-01T13:00:00",
+    );
+    test_eval_simple("'2024-05-01T13:00:00Z' to iso", "2024-05-01T13:00:00Z");
+    test_eval_simple(
+        "'2024-05-01T13:00+0200' to iso",
+        "2024-05-01T13:00:00+02:00",
+    );
+    test_eval_simple(
+        "(('2024-05-01T13:00:00Z' to datetime) in America/New_York) to iso",
+        "2024-05-01T09:00:00-04:00",
+    );
+    test_eval_simple("14:30 to iso", "14:30:00");
+    test_eval_simple("3pm EST to iso", "15:00:00-05:00");
+    expect_error("5 to iso", Some("expected a date or time"));
+    expect_error(
+        "'2024-05-01T13:00:00Y' to datetime",
+        Some("failed to convert '2024-05-01T13:00:00Y' to a date and time"),
+    );
+}
+
+#[test]
+fn custom_date_formats() {
+    test_eval_simple("format('2024-05-01' to date, '%Y-%m-%d')", "2024-05-01");
+    test_eval_simple("format('2024-05-01', '%d/%m/%Y')", "01/05/2024");
+    test_eval_simple(
+        "format('2024-05-01 13:00' to datetime, '%A %e %B %Y at %I:%M %p')",
+        "Wednesday  1 May 2024 at 01:00 PM",
+    );
+    test_eval_simple("format(3pm EST, '%H:%M %Z (%z)')", "15:00 EST (-0500)");
+    test_eval_simple(
+        "format('2024-05-01T13:00:00Z', '%FT%T%z')",
+        "2024-05-01T13:00:00+0000",
+    );
+    expect_error("format(5, '%Y')", Some("expected a date or time"));
+    expect_error(
+        "format(14:30, '%Y')",
+        Some("'%Y' can only be used to format a date"),
+    );
+    expect_error("format(14:30, '%q')", Some("unknown date format '%q'"));
+    expect_error(
+        "format(14:30)",
+        Some("format expects 2 arguments, but 1 were given"),
+    );
+}
+
+#[test]
+fn relative_dates() {
+    test_eval_simple(
+        "3 weeks from ('2024-05-01' to date)",
+        "Wednesday, 22 May 2024",
+    );
+    test_eval_simple(
+        "36 hours from ('2024-05-01' to date)",
+        "Thursday, 2 May 2024 12:00",
+    );
+    expect_error("next friday", Some("unable to get the current date"));
+    expect_error("last Monday", Some("unable to get the current date"));
+    expect_error("3 weeks from today", Some("unable to get the current date"));
+    expect_error("next week", Some("unknown identifier 'next'"));
+}
+
+#[test]
+fn date_function() {
+    test_eval_simple("date(2024, 5, 1)", "Wednesday, 1 May 2024");
+    test_eval_simple("date('2024-05-01')", "Wednesday, 1 May 2024");
+    test_eval_simple("date(2024, 5, 1) + 3 days", "Saturday, 4 May 2024");
+    test_eval_simple("date(2024, 12, 25) - date(2024, 5, 1)", "238 days");
+    test_eval_simple("month of (date(2024, 5, 1))", "May");
+    expect_error("date(2023, 2, 29)", Some("2023-02-29 is not a valid date"));
+    expect_error("date(2024, 13, 1)", Some("2024-13-01 is not a valid date"));
+    expect_error(
+        "date(2024, 5)",
+        Some("date expects 3 arguments, but 2 were given"),
+    );
+}
+
+#[test]
+fn date_object_members() {
+    test_eval_simple("year of (date(2024, 5, 1))", "2024");
+    test_eval_simple("year of ('-44-03-15' to date)", "-44");
+    test_eval_simple("day of (date(2024, 5, 1))", "1");
+    test_eval_simple("day_of_year of (date(2024, 12, 31))", "366");
+    test_eval_simple("week_number of (date(2024, 5, 1))", "18");
+    test_eval_simple("week_number of (date(2021, 1, 3))", "53");
+    test_eval_simple("week_number of (date(2024, 12, 30))", "1");
+    test_eval_simple("is_leap_year of (date(2024, 1, 1))", "true");
+    test_eval_simple("is_leap_year of (date(2100, 1, 1))", "false");
+    test_eval_simple("week_number of ('2024-05-01 13:00' to datetime)", "18");
+}
+
+#[test]
+fn add_months_and_years_to_dates() {
+    test_eval_simple("date(2024, 5, 1) + 3 months", "Thursday, 1 August 2024");
+    test_eval_simple("date(2024, 1, 31) + 1 month", "Thursday, 29 February 2024");
+    test_eval_simple("date(2023, 1, 31) + 1 month", "Tuesday, 28 February 2023");
+    test_eval_simple("date(2024, 2, 29) + 1 year", "Friday, 28 February 2025");
+    test_eval_simple("date(2024, 2, 29) - 2 years", "Monday, 28 February 2022");
+    test_eval_simple("date(2024, 5, 1) + 1 decade", "Monday, 1 May 2034");
+    test_eval_simple("3 months from date(2024, 5, 1)", "Thursday, 1 August 2024");
+    test_eval_simple(
+        "('2024-01-31 13:00' to datetime) + 1 month",
+        "Thursday, 29 February 2024 13:00",
+    );
+}
+
+#[test]
+fn intervals_between_dates() {
+    test_eval_simple(
+        "between('1990-06-15', '2024-05-01')",
+        "33 years 10 months 16 days",
+    );
+    test_eval_simple(
+        "between(date(2024, 1, 31), date(2024, 3, 1))",
+        "1 month 1 day",
+    );
+    test_eval_simple(
+        "years between('1990-06-15', '2024-05-01')",
+        "approx. 33.8770491803 years",
+    );
+    test_eval_simple(
+        "between('1990-06-15', '2024-05-01') to months",
+        "approx. 406.5333333333 months",
+    );
+    test_eval_simple("between('1990-06-15', '2024-05-01') to days", "12374 days");
+    test_eval_simple("years between('2020-03-01', '2024-03-01')", "4 years");
+    test_eval_simple(
+        "years between('2024-05-01', '1990-06-15')",
+        "approx. -33.8770491803 years",
+    );
+    test_eval_simple("years of (between('1990-06-15', '2024-05-01'))", "33");
+    test_eval_simple("days of (between('1990-06-15', '2024-05-01'))", "16");
+    expect_error("between(5, 6)", Some("expected a date"));
+    expect_error(
+        "years between('1990-06-15', today)",
+        Some("unable to get the current date"),
+    );
+}
+
+#[test]
+fn comparison_operators() {
+    test_eval_simple("3 < 5", "true");
+    test_eval_simple("5 <= 5", "true");
+    test_eval_simple("3 > 5", "false");
+    test_eval_simple("1 km >= 999 m", "true");
+    test_eval_simple("2 + 2 > 3", "true");
+    test_eval_simple("3 hours < 200 minutes", "true");
+    test_eval_simple("date(2024, 1, 1) < date(2024, 5, 1)", "true");
+    test_eval_simple("date(2024, 1, 1) >= date(2024, 5, 1)", "false");
+    test_eval_simple("date(2024, 5, 1) == date(2024, 5, 1)", "true");
+    test_eval_simple("date(2024, 5, 1) != date(2024, 5, 2)", "true");
+    test_eval_simple(
+        "('2024-05-01 09:00' to datetime) > date(2024, 5, 1)",
+        "true",
+    );
+    test_eval_simple("9:30 < 14:00", "true");
+    test_eval_simple("(3 < 5) == true", "true");
+    expect_error("'a' < 'b'", Some("invalid operands for comparison"));
+    expect_error(
+        "date(2024, 5, 1) < 5",
+        Some("invalid operands for comparison"),
+    );
+    expect_error("1 m < 1 s", None);
+}
+
+#[test]
+fn alternative_calendars() {
+    test_eval_simple("date(2024, 5, 1) to julian", "18 April 2024 (Julian)");
+    test_eval_simple("'2024-04-23' to hebrew", "15 Nisan 5784");
+    test_eval_simple("date(2024, 3, 11) to islamic", "1 Ramadan 1445 AH");
+    test_eval_simple(
+        "('2024-03-11 18:00' to datetime) to hebrew",
+        "1 Adar II 5784",
+    );
+    test_eval_simple("'15 Nisan 5784' to date", "Tuesday, 23 April 2024");
+    test_eval_simple("date('1 Shawwal 1445 AH')", "Wednesday, 10 April 2024");
+    test_eval_simple(
+        "'5 October 1582 (Julian)' to date",
+        "Friday, 15 October 1582",
+    );
+    test_eval_simple(
+        "(date(2024, 5, 1) to hebrew) to gregorian",
+        "Wednesday, 1 May 2024",
+    );
+    test_eval_simple(
+        "(date(2024, 5, 1) to hebrew) to islamic",
+        "22 Shawwal 1445 AH",
+    );
+    test_eval_simple("'15 Nisan 5784' - '1 Tishrei 5784'", "220 days");
+    expect_error(
+        "'30 Iyar 5784' to date",
+        Some("failed to convert '30 Iyar 5784' to a date"),
+    );
+    expect_error("5 to hebrew", Some("expected a date"));
+}
+
+#[test]
+fn add_negative_amounts_and_weeks_to_dates() {
+    test_eval_simple("date(2024, 5, 1) + (-3 days)", "Sunday, 28 April 2024");
+    test_eval_simple("date(2024, 5, 1) - (-3 days)", "Saturday, 4 May 2024");
+    test_eval_simple("-3 days + date(2024, 5, 1)", "Sunday, 28 April 2024");
+    test_eval_simple("date(2024, 5, 1) + 2 weeks", "Wednesday, 15 May 2024");
+    test_eval_simple("date(2024, 5, 1) + (-1 week)", "Wednesday, 24 April 2024");
+    test_eval_simple("date(2024, 5, 1) + 1 fortnight", "Wednesday, 15 May 2024");
+    test_eval_simple("date(2024, 5, 1) + (-2 months)", "Friday, 1 March 2024");
+    test_eval_simple("date(2024, 2, 29) + (-1 year)", "Tuesday, 28 February 2023");
+    test_eval_simple("date(2024, 5, 1) + 1000000 days", "Thursday, 29 March 4762");
+    test_eval_simple(
+        "date(2024, 5, 1) + (-1000000 days)",
+        "Tuesday, 4 June 715 BC",
+    );
+}
+
+#[test]
+fn holidays_and_workdays() {
+    test_eval_simple("easter(2025)", "Sunday, 20 April 2025");
+    test_eval_simple("easter 2024 - 2 days", "Friday, 29 March 2024");
+    test_eval_simple("is_holiday(date(2025, 12, 25))", "true");
+    test_eval_simple("is_holiday('2025-12-26')", "false");
+    test_eval_simple(
+        "add_workdays(date(2025, 12, 24), 1)",
+        "Friday, 26 December 2025",
+    );
+    test_eval_simple(
+        "add_workdays('2025-01-02', -1)",
+        "Tuesday, 31 December 2024",
+    );
+    expect_error("is_holiday(5)", Some("expected a date"));
+
+    let mut context = Context::new();
+    context
+        .set_holidays(&["easter-2", "easter+1", "2025-05-05"])
+        .unwrap();
+    let mut eval = |input| {
+        evaluate(input, &mut context)
+            .unwrap()
+            .get_main_result()
+            .to_string()
+    };
+    assert_eq!(eval("is_holiday('2025-04-18')"), "true");
+    assert_eq!(eval("is_holiday('2025-05-05')"), "true");
+    assert_eq!(eval("is_holiday('2025-12-25')"), "false");
+    assert_eq!(
+        eval("add_workdays('2025-04-17', 1)"),
+        "Tuesday, 22 April 2025"
+    );
+    assert_eq!(
+        context.set_holidays(&["12-25", "christmas"]),
+        Err(
+            "invalid holiday 'christmas', expected e.g. '12-25', '2025-05-05' or 'easter+1'"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn moon_phases_and_julian_days() {
+    test_eval_simple("julian_day(date(2000, 1, 1))", "2451544.5");
+    test_eval_simple("julian_day('2000-01-01T12:00:00Z')", "2451545");
+    test_eval_simple("julian_day('2024-05-01 06:00')", "2460431.75");
+    test_eval_simple(
+        "julian_day(('2024-05-01 08:00' to datetime) CEST)",
+        "2460431.75",
+    );
+    test_eval_simple("julian_day(date(-4713, 11, 24))", "-0.5");
+    test_eval_simple("moon_phase(date(2024, 4, 23))", "Full Moon");
+    test_eval_simple("moon_phase(date(2024, 5, 8))", "New Moon");
+    test_eval_simple("moon_phase('2024-04-15')", "First Quarter");
+    test_eval_simple("moon_phase('2024-05-01')", "Last Quarter");
+    test_eval_simple("moon_phase('2024-05-04')", "Waning Crescent");
+    test_eval_simple(
+        "illumination of (moon_phase(date(2024, 4, 23)))",
+        "approx. 99.4438164596%",
+    );
+    test_eval_simple(
+        "age of (moon_phase(date(2024, 4, 23)))",
+        "approx. 14.0636215277 days",
+    );
+    expect_error("moon_phase(5)", Some("expected a date"));
+    expect_error("julian_day(12:00)", Some("expected a date"));
+}
+
+#[test]
+fn count_weekdays_between_dates() {
+    test_eval_simple("fridays between('2025-01-01', '2025-12-31')", "52");
+    test_eval_simple("Wednesdays between('2025-01-01', '2025-12-31')", "53");
+    test_eval_simple("mondays between(date(2024, 5, 1), date(2024, 5, 31))", "4");
+    test_eval_simple("sundays between('2024-05-31', '2024-05-01')", "4");
+    test_eval_simple("mondays between('2024-05-06', '2024-05-06')", "1");
+    expect_error("fridays 5", Some("'Friday' is not a function or number"));
+}
+
+#[test]
+fn completions() {
+    let mut context = Context::new();
+    evaluate("unit furlong = 220 yards", &mut context).unwrap();
+    evaluate("width = 3 m", &mut context).unwrap();
+    let (position, completions) = fend_core::get_completions("5 kilome", &context);
+    assert_eq!(position, 8);
+    let inserted = completions.iter().map(|c| c.insert()).collect::<Vec<_>>();
+    assert_eq!(inserted, ["ter", "tre"]);
+    let (_, completions) = fend_core::get_completions("(furl", &context);
+    assert_eq!(completions[0].display(), "furlong");
+    let (_, completions) = fend_core::get_completions("2 * wid", &context);
+    assert_eq!(completions[0].display(), "width");
+    let (_, completions) = fend_core::get_completions_for_prefix("wid");
+    assert!(completions.is_empty());
+    assert!(fend_core::get_completions("5 + ", &context).1.is_empty());
+}
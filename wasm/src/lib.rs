@@ -4,6 +4,7 @@
 #![allow(clippy::unused_unit)]
 
 use instant::Instant;
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 struct TimeoutInterrupt {
@@ -29,6 +30,36 @@ impl fend_core::Interrupt for TimeoutInterrupt {
 #[wasm_bindgen]
 pub fn initialise() {}
 
+thread_local! {
+    // names and expressions that are defined in every new context
+    static ALIASES: RefCell<Vec<(String, String)>> = RefCell::new(Vec::new());
+}
+
+/// Takes a '\0'-separated string of alternating names and expressions, like
+/// `"f2c\0x => (x - 32) * 5/9"`, which are defined before every calculation.
+/// Returns an error message, or an empty string if all aliases are valid.
+#[wasm_bindgen(js_name = setAliases)]
+pub fn set_aliases(aliases: &str) -> String {
+    if aliases.is_empty() {
+        ALIASES.with(|a| a.borrow_mut().clear());
+        return "".to_string();
+    }
+    let parts = aliases.split('\0').collect::<Vec<_>>();
+    if parts.len() % 2 != 0 {
+        return "Error: expected a name and an expression for every alias".to_string();
+    }
+    let aliases = parts
+        .chunks(2)
+        .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+        .collect::<Vec<_>>();
+    // check the aliases before they are used in any calculations
+    if let Err(msg) = fend_core::Context::new().define_variables(&aliases) {
+        return format!("Error: {}", msg);
+    }
+    ALIASES.with(|a| *a.borrow_mut() = aliases);
+    "".to_string()
+}
+
 // These two functions should be merged at some point, but that would be a breaking
 // API change.
 
@@ -50,6 +81,10 @@ fn create_context() -> fend_core::Context {
         date.get_timezone_offset() as i64 * 60,
     );
     ctx.set_random_u32_fn(random_u32);
+    ALIASES.with(|aliases| {
+        // the aliases were already checked by `set_aliases`
+        let _ = ctx.define_variables(&aliases.borrow());
+    });
     ctx
 }
 